@@ -1,4 +1,5 @@
 use crate::guards::UserErrorMessage;
+use crate::methods::{ErrorResponse, FieldError};
 use okapi::{
     openapi3::{MediaType, RequestBody},
     Map,
@@ -163,11 +164,16 @@ impl<'r, T: Validate + FromForm<'r>> FromForm<'r> for Validated<T> {
 
 #[catch(400)]
 pub fn general_catcher(req: &Request) -> Value {
-    json!({
-        "code": "error.general",
-        "message": "Bad Request. The request could not be understood by the server due to malformed syntax.",
-        "errors": req.local_cache(|| CachedValidationErrors(None)).0.as_ref(),
-    })
+    match req.local_cache(|| CachedValidationErrors(None)).0.as_ref() {
+        // Failed validations are answered as an input error naming each field.
+        Some(errors) => json!(ErrorResponse::field_errors(FieldError::from_validation(
+            errors
+        ))),
+        None => json!({
+            "code": "error.general",
+            "message": "Bad Request. The request could not be understood by the server due to malformed syntax.",
+        }),
+    }
 }
 
 #[catch(401)]
@@ -175,6 +181,11 @@ pub fn not_authorized() -> Value {
     json!({"code": "error.unauthorized", "message": "Not authorized to make request"})
 }
 
+#[catch(403)]
+pub fn forbidden() -> Value {
+    json!({"code": "error.forbidden", "message": "The request was refused, such as for a missing CSRF token."})
+}
+
 #[catch(404)]
 pub fn not_found() -> Value {
     json!({"code": "error.not_found", "message": "The requested route was not found."})
@@ -13,6 +13,7 @@ pub struct Model {
     pub auth: Json,
     pub clock_history: Json,
     pub level: Json,
+    pub stores: Option<Json>,
     pub tenant_id: String,
     pub account_type: Json,
     pub created_at: DateTime,
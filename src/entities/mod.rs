@@ -2,15 +2,23 @@
 
 pub mod prelude;
 
+pub mod audit_log;
 pub mod authrecord;
 pub mod customer;
 pub mod employee;
+pub mod gift_card;
+pub mod gift_card_redemption;
 pub mod kiosk;
+pub mod price_history;
 pub mod products;
 pub mod promotion;
+pub mod purchase_order;
 pub mod sea_orm_active_enums;
 pub mod session;
+pub mod stocktake;
 pub mod store;
 pub mod supplier;
 pub mod tenants;
 pub mod transactions;
+pub mod webhook;
+pub mod webhook_delivery;
@@ -1,12 +1,20 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.3
 
+pub use super::audit_log::Entity as AuditLog;
 pub use super::customer::Entity as Customer;
 pub use super::employee::Entity as Employee;
+pub use super::gift_card::Entity as GiftCard;
+pub use super::gift_card_redemption::Entity as GiftCardRedemption;
 pub use super::kiosk::Entity as Kiosk;
+pub use super::price_history::Entity as PriceHistory;
 pub use super::products::Entity as Products;
 pub use super::promotion::Entity as Promotion;
+pub use super::purchase_order::Entity as PurchaseOrder;
 pub use super::session::Entity as Session;
+pub use super::stocktake::Entity as Stocktake;
 pub use super::store::Entity as Store;
 pub use super::supplier::Entity as Supplier;
 pub use super::tenants::Entity as Tenants;
 pub use super::transactions::Entity as Transactions;
+pub use super::webhook::Entity as Webhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
@@ -13,6 +13,7 @@ pub struct Model {
     pub employee_id: String,
     pub expiry: DateTime,
     pub variant: Json,
+    pub created_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -12,9 +12,13 @@ pub struct Model {
     pub contact: Json,
     #[sea_orm(column_type = "Text")]
     pub code: String,
+    pub operating_hours: Option<Json>,
+    pub timezone: String,
+    pub layout: Option<Json>,
     pub tenant_id: String,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
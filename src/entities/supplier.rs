@@ -13,6 +13,8 @@ pub struct Model {
     pub tenant_id: String,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub version: i32,
+    pub phone: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
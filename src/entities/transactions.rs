@@ -22,6 +22,10 @@ pub struct Model {
     pub tenant_id: String,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub version: i32,
+    pub deleted_at: Option<DateTime>,
+    pub attachments: Option<Json>,
+    pub change_given: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
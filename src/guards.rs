@@ -1,8 +1,12 @@
 use crate::methods::common::Error;
-use crate::{cookie_status_wrapper, Db, ErrorResponse, Session};
-use futures::TryStreamExt;
+use crate::{cookie_status_wrapper, Db, ErrorResponse, Session, CSRF_COOKIE, CSRF_HEADER};
+use flate2::write::GzEncoder;
+use log::{debug, info, warn};
 use okapi::openapi3::{MediaType, RefOr, Response, Responses};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method};
 use rocket::request::{FromRequest, Outcome};
+use rocket::response::content::RawJson;
 use rocket::response::Responder;
 use rocket::serde::json::Json;
 use rocket::{
@@ -11,18 +15,370 @@ use rocket::{
     request::{local_cache, Request},
     response,
 };
-use rocket::form::{FromForm, Options};
+use rocket::{Build, Orbit, Rocket};
 use rocket_db_pools::Connection;
 use rocket_okapi::gen::OpenApiGenerator;
 use rocket_okapi::request::{OpenApiFromRequest, RequestHeaderInput};
 use rocket_okapi::response::OpenApiResponderInner;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// The correlation ID of the current request, as assigned by [`RequestTracing`].
 #[derive(Copy, Clone, Debug)]
 pub struct RequestId(pub Option<Uuid>);
 
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Assigns every request a correlation ID, taken from the `X-Request-Id` header
+/// where provided or generated otherwise, and logs the request as it opens and closes.
+/// The ID is echoed back in the response headers and in the body of any error.
+pub struct RequestTracing;
+
+#[rocket::async_trait]
+impl Fairing for RequestTracing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Assign correlation IDs to requests",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let id = request
+            .headers()
+            .get_one(REQUEST_ID_HEADER)
+            .and_then(|v| Uuid::parse_str(v).ok())
+            .unwrap_or_else(Uuid::new_v4);
+
+        request.local_cache(|| RequestId(Some(id)));
+        request.local_cache(Instant::now);
+
+        debug!(
+            "{} {} started, request_id={}",
+            request.method(),
+            request.uri().path(),
+            id
+        );
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut rocket::Response<'r>) {
+        if let Some(id) = request.local_cache(|| RequestId(None)).0 {
+            response.set_header(Header::new(REQUEST_ID_HEADER, id.to_string()));
+
+            info!(
+                "{} {} completed with {} in {}ms, request_id={}",
+                request.method(),
+                request.uri().path(),
+                response.status(),
+                request.local_cache(Instant::now).elapsed().as_millis(),
+                id
+            );
+        }
+    }
+}
+
+static IN_FLIGHT_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+static PENDING_INTENTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Marks a stock alteration as pending until dropped, such that shutdown waits
+/// for it to be written, see [`GracefulShutdown`].
+pub struct PendingIntent(());
+
+impl PendingIntent {
+    pub fn begin() -> Self {
+        PENDING_INTENTS.fetch_add(1, Ordering::SeqCst);
+        PendingIntent(())
+    }
+}
+
+impl Drop for PendingIntent {
+    fn drop(&mut self) {
+        PENDING_INTENTS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Holds the server open on shutdown until in-flight requests have been answered
+/// and pending stock alterations written, such that a restart does not cut off
+/// a transaction part way through adjusting stock. Waits at most `timeout`, read
+/// in seconds from `SHUTDOWN_TIMEOUT`, by default 30.
+pub struct GracefulShutdown {
+    pub timeout: Duration,
+}
+
+impl GracefulShutdown {
+    pub fn from_env() -> Self {
+        GracefulShutdown {
+            timeout: Duration::from_secs(
+                std::env::var("SHUTDOWN_TIMEOUT")
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(30),
+            ),
+        }
+    }
+
+    fn outstanding() -> (usize, usize) {
+        (
+            IN_FLIGHT_REQUESTS.load(Ordering::SeqCst),
+            PENDING_INTENTS.load(Ordering::SeqCst),
+        )
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for GracefulShutdown {
+    fn info(&self) -> Info {
+        Info {
+            name: "Drain requests and stock alterations on shutdown",
+            kind: Kind::Request | Kind::Response | Kind::Shutdown,
+        }
+    }
+
+    async fn on_request(&self, _: &mut Request<'_>, _: &mut Data<'_>) {
+        IN_FLIGHT_REQUESTS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn on_response<'r>(&self, _: &'r Request<'_>, _: &mut rocket::Response<'r>) {
+        IN_FLIGHT_REQUESTS.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    async fn on_shutdown(&self, _: &Rocket<Orbit>) {
+        let deadline = Instant::now() + self.timeout;
+        let mut interval = rocket::tokio::time::interval(Duration::from_millis(100));
+        let mut last = None;
+
+        loop {
+            let (requests, intents) = Self::outstanding();
+
+            if requests == 0 && intents == 0 {
+                info!("Drained all requests and stock alterations, shutting down");
+                return;
+            }
+
+            if Instant::now() >= deadline {
+                warn!(
+                    "Shutting down with {} request(s) and {} stock alteration(s) outstanding",
+                    requests, intents
+                );
+                return;
+            }
+
+            if last != Some((requests, intents)) {
+                info!(
+                    "Draining {} request(s) and {} stock alteration(s) before shutting down",
+                    requests, intents
+                );
+                last = Some((requests, intents));
+            }
+
+            interval.tick().await;
+        }
+    }
+}
+
+/// Gzips response bodies of at least `threshold` bytes for clients which accept it,
+/// such as the listings of a store's inventory. Smaller bodies are sent as they are,
+/// as are streamed ones. The threshold is read from `COMPRESSION_THRESHOLD`, by
+/// default 1 KiB.
+pub struct Compression {
+    pub threshold: usize,
+}
+
+impl Compression {
+    pub fn from_env() -> Self {
+        Compression {
+            threshold: std::env::var("COMPRESSION_THRESHOLD")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(1024),
+        }
+    }
+}
+
+/// Whether an `Accept-Encoding` header value permits gzip, i.e. `gzip, deflate`
+/// but not `gzip;q=0`.
+pub fn accepts_gzip(accept_encoding: &str) -> bool {
+    accept_encoding.split(',').any(|coding| {
+        let mut parts = coding.split(';').map(|p| p.trim());
+        let name = parts.next().unwrap_or_default();
+        let rejected = parts.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+
+        (name.eq_ignore_ascii_case("gzip") || name == "*") && !rejected
+    })
+}
+
+#[rocket::async_trait]
+impl Fairing for Compression {
+    fn info(&self) -> Info {
+        Info {
+            name: "Gzip large responses",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut rocket::Response<'r>) {
+        let accepted = request.headers().get("Accept-Encoding").any(accepts_gzip);
+
+        if !accepted
+            || response.headers().contains("Content-Encoding")
+            || response
+                .body()
+                .preset_size()
+                .is_none_or(|size| size < self.threshold)
+        {
+            return;
+        }
+
+        let body = match response.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(err) => {
+                warn!("Unable to read response body for compression: {}", err);
+                return;
+            }
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let compressed = match encoder.write_all(&body).and_then(|_| encoder.finish()) {
+            Ok(compressed) => compressed,
+            Err(err) => {
+                warn!("Unable to compress response body: {}", err);
+                response.set_sized_body(body.len(), std::io::Cursor::new(body));
+                return;
+            }
+        };
+
+        response.set_header(Header::new("Content-Encoding", "gzip"));
+        response.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+        response.set_sized_body(compressed.len(), std::io::Cursor::new(compressed));
+    }
+}
+
+/// Permits browsers on the listed origins, such as a web based point of sale, to
+/// call the API with the session cookie. Origins are read as a comma separated list
+/// from `CORS_ALLOWED_ORIGINS`, falling back to `ACCESS_ORIGIN`, with `*` allowing
+/// any. Neither being set leaves the API to same-origin callers only.
+///
+/// Preflight `OPTIONS` requests to any path are answered with `204 No Content`.
+pub struct Cors {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: String,
+    pub allowed_headers: String,
+    /// How long, in seconds, browsers may cache the answer to a preflight.
+    pub max_age: u64,
+}
+
+impl Cors {
+    pub fn new(allowed_origins: Vec<String>) -> Self {
+        Cors {
+            allowed_origins,
+            allowed_methods: "GET, POST, PUT, PATCH, DELETE, OPTIONS".to_string(),
+            allowed_headers: format!(
+                "Content-Type, Authorization, If-None-Match, {}, {}",
+                REQUEST_ID_HEADER, CSRF_HEADER
+            ),
+            max_age: 3600,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let origins = std::env::var("CORS_ALLOWED_ORIGINS")
+            .or_else(|_| std::env::var("ACCESS_ORIGIN"))
+            .unwrap_or_default();
+
+        let mut cors = Cors::new(
+            origins
+                .split(',')
+                .map(|origin| origin.trim().trim_end_matches('/').to_string())
+                .filter(|origin| !origin.is_empty())
+                .collect(),
+        );
+
+        if let Ok(methods) = std::env::var("CORS_ALLOWED_METHODS") {
+            cors.allowed_methods = methods;
+        }
+
+        if let Ok(headers) = std::env::var("CORS_ALLOWED_HEADERS") {
+            cors.allowed_headers = headers;
+        }
+
+        if let Some(max_age) = std::env::var("CORS_MAX_AGE")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            cors.max_age = max_age;
+        }
+
+        cors
+    }
+
+    pub fn allows(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+}
+
+#[rocket::options("/<_..>")]
+fn preflight() -> Status {
+    Status::NoContent
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "Add CORS headers to responses",
+            kind: Kind::Ignite | Kind::Response,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        Ok(rocket.mount("/", rocket::routes![preflight]))
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut rocket::Response<'r>) {
+        // Responses differ by origin, so must not be shared between them by caches.
+        response.adjoin_header(Header::new("Vary", "Origin"));
+
+        let origin = match request.headers().get_one("Origin") {
+            Some(origin) if self.allows(origin) => origin.to_string(),
+            _ => return,
+        };
+
+        // The origin is echoed rather than `*`, which browsers refuse with credentials.
+        response.set_header(Header::new("Access-Control-Allow-Origin", origin));
+        response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+        response.set_header(Header::new(
+            "Access-Control-Expose-Headers",
+            format!("ETag, {}", REQUEST_ID_HEADER),
+        ));
+
+        if request.method() == Method::Options {
+            response.set_header(Header::new(
+                "Access-Control-Allow-Methods",
+                self.allowed_methods.clone(),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Headers",
+                self.allowed_headers.clone(),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Max-Age",
+                self.max_age.to_string(),
+            ));
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct JsonValidation<T>(pub T);
 
@@ -62,6 +418,77 @@ where
     }
 }
 
+/// Whether a request authenticated by the session cookie may proceed. Requests
+/// which change state, when made by a browser, must echo the [`CSRF_COOKIE`] in
+/// the [`CSRF_HEADER`], which a forged request from another site cannot read.
+///
+/// Browsers send an `Origin` with every such request, which other clients, being
+/// unable to have cookies attached on their behalf, need not.
+pub fn passes_csrf_check(request: &Request<'_>) -> bool {
+    if matches!(
+        request.method(),
+        Method::Get | Method::Head | Method::Options
+    ) || request.headers().get_one("Origin").is_none()
+    {
+        return true;
+    }
+
+    match (
+        request.cookies().get(CSRF_COOKIE),
+        request.headers().get_one(CSRF_HEADER),
+    ) {
+        (Some(cookie), Some(token)) => {
+            let expected = cookie.value().as_bytes();
+            let given = token.as_bytes();
+
+            // Compared in full, such that the time taken does not reveal the token.
+            expected.len() == given.len()
+                && expected
+                    .iter()
+                    .zip(given)
+                    .fold(0, |acc, (a, b)| acc | (a ^ b))
+                    == 0
+        }
+        _ => false,
+    }
+}
+
+/// Guards a request against cross-site forgery, see [`passes_csrf_check`]. Every
+/// request authenticated by a [`Session`] is guarded, failing with `403 Forbidden`.
+pub struct CsrfProtected;
+
+impl<'r> OpenApiFromRequest<'r> for CsrfProtected {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for CsrfProtected {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if passes_csrf_check(request) {
+            return Outcome::Success(CsrfProtected);
+        }
+
+        warn!(
+            "{} {} rejected, missing or mismatched {}",
+            request.method(),
+            request.uri().path(),
+            CSRF_HEADER
+        );
+
+        let error = ErrorResponse::forbidden("Missing or mismatched CSRF token.");
+
+        Outcome::Error((error.status(), error))
+    }
+}
+
 impl<'r> OpenApiFromRequest<'r> for Session {
     fn from_request_input(
         _gen: &mut OpenApiGenerator,
@@ -94,7 +521,15 @@ impl<'r> FromRequest<'r> for Session {
         };
 
         match cookie_status_wrapper(&db, cookies).await {
-            Ok(session) => Outcome::Success(session),
+            Ok(session) => {
+                if let Outcome::Error(e) = request.guard::<CsrfProtected>().await {
+                    return Outcome::Error(e);
+                }
+
+                // Retained so later failures in the request can be logged against the session.
+                request.local_cache(|| Some(session.clone()));
+                Outcome::Success(session)
+            }
             Err(_) => Outcome::Forward(Status::Unauthorized),
         }
     }
@@ -110,7 +545,7 @@ impl<T> From<Result<T, Error>> for Convert<T> {
 
 impl<K: Serialize + JsonSchema> OpenApiResponderInner for Convert<K> {
     fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
-        let mut responses = Responses::default();
+        let mut responses = Error::responses(gen)?;
 
         // Define response for successful response
         let success_schema = gen.json_schema::<K>();
@@ -125,29 +560,89 @@ impl<K: Serialize + JsonSchema> OpenApiResponderInner for Convert<K> {
             ..Default::default()
         };
 
-        // Define response for internal server error
-        let internal_error_response = Response {
-            description: "Internal Server Error".to_string(),
-            ..Default::default()
-        };
-
-        // Insert responses into the map
+        // Insert alongside those of the errors which may be returned
         responses.responses.insert("200".to_string(), RefOr::from(success_response));
-        responses.responses.insert("500".to_string(), RefOr::from(internal_error_response));
 
         Ok(responses)
     }
 }
 
 impl<'r, 'o: 'r, K: Serialize> Responder<'r, 'o> for Convert<K> {
-    fn respond_to(self, r: &'r Request<'_>) -> response::Result<'o>
-    where
-        K: Serialize,
-    {
+    fn respond_to(self, r: &'r Request<'_>) -> response::Result<'o> {
         Responder::respond_to(self.0, r)
     }
 }
 
+/// A [`Convert`] response carrying an `ETag`, the hash of its serialized body.
+/// Requests whose `If-None-Match` names the current tag are answered with an
+/// empty `304 Not Modified`, sparing clients which poll for changes the body.
+pub struct Tagged<T>(pub Convert<T>);
+
+impl<T> From<Result<T, Error>> for Tagged<T> {
+    fn from(value: Result<T, Error>) -> Self {
+        Tagged(value.into())
+    }
+}
+
+/// The entity tag of a serialized body, quoted as sent in the `ETag` header.
+pub fn entity_tag(body: &str) -> String {
+    let digest = Sha256::digest(body.as_bytes());
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// Whether an `If-None-Match` header value names `tag`, or any tag by `*`.
+pub fn matches_entity_tag(if_none_match: &str, tag: &str) -> bool {
+    if_none_match.split(',').map(|t| t.trim()).any(|t| {
+        // Weak tags are compared as if strong, as the body is hashed as a whole.
+        t == "*" || t.trim_start_matches("W/") == tag
+    })
+}
+
+impl<K: Serialize + JsonSchema> OpenApiResponderInner for Tagged<K> {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Convert::<K>::responses(gen)?;
+
+        let not_modified_response = Response {
+            description: "Not Modified, the entity tag given in If-None-Match is current"
+                .to_string(),
+            ..Default::default()
+        };
+
+        responses
+            .responses
+            .insert("304".to_string(), RefOr::from(not_modified_response));
+
+        Ok(responses)
+    }
+}
+
+impl<'r, 'o: 'r, K: Serialize> Responder<'r, 'o> for Tagged<K> {
+    fn respond_to(self, r: &'r Request<'_>) -> response::Result<'o> {
+        let body = match &self.0 .0 {
+            Ok(Json(value)) => {
+                serde_json::to_string(value).map_err(|_| Status::InternalServerError)?
+            }
+            Err(_) => return self.0.respond_to(r),
+        };
+
+        let tag = entity_tag(&body);
+
+        if r.headers()
+            .get("If-None-Match")
+            .any(|value| matches_entity_tag(value, &tag))
+        {
+            return rocket::Response::build()
+                .status(Status::NotModified)
+                .header(Header::new("ETag", tag))
+                .ok();
+        }
+
+        rocket::Response::build_from(RawJson(body).respond_to(r)?)
+            .header(Header::new("ETag", tag))
+            .ok()
+    }
+}
+
 impl<'de, T: Deserialize<'de>> Into<Result<Json<T>, Error>> for Convert<T> {
     fn into(self) -> Result<Json<T>, Error> {
         match self.0 {
@@ -1,5 +1,6 @@
 #![allow(ambiguous_glob_reexports)]
 
+use ::log::{debug, info};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -76,11 +77,11 @@ impl Worker {
             let message = receiver.lock().unwrap().recv().unwrap();
             match message {
                 Message::NewJob(job) => {
-                    println!("Worker {} got a job; executing.", id);
+                    debug!("Worker {} got a job; executing.", id);
                     job.call_box();
                 }
                 Message::Terminate => {
-                    println!("Worker{} was told to terminate.", id);
+                    debug!("Worker {} was told to terminate.", id);
                     break;
                 }
             }
@@ -94,13 +95,13 @@ impl Worker {
 
 impl Drop for ThreadPool {
     fn drop(&mut self) {
-        println!("Sending terminate message to all workers.");
+        info!("Sending terminate message to all workers.");
         for _ in &mut self.workers {
             self.sender.send(Message::Terminate).unwrap();
         }
-        println!("Shutting down all workers.");
+        info!("Shutting down all workers.");
         for worker in &mut self.workers {
-            println!("Shutting down worker {}", worker.id);
+            debug!("Shutting down worker {}", worker.id);
             if let Some(thread) = worker.thread.take() {
                 thread.join().unwrap();
             }
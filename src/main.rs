@@ -2,13 +2,8 @@
 
 #[cfg(feature = "sql")]
 use pool::Db;
-use rocket::http::{Method, Status};
 #[cfg(feature = "sql")]
-use rocket::{
-    fairing::{Fairing, Info, Kind},
-    http::Header,
-    *,
-};
+use rocket::*;
 use rocket_db_pools::Database;
 use rocket_okapi::mount_endpoints_and_merged_docs;
 use rocket_okapi::swagger_ui::{make_swagger_ui, SwaggerUIConfig};
@@ -33,40 +28,6 @@ use open_stock::catchers;
 #[cfg(feature = "sql")]
 extern crate argon2;
 
-pub struct CORS;
-
-#[cfg(feature = "sql")]
-#[rocket::async_trait]
-impl Fairing for CORS {
-    fn info(&self) -> Info {
-        Info {
-            name: "Add CORS headers to responses",
-            kind: Kind::Response,
-        }
-    }
-
-    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
-        let access_origin = dotenv::var("ACCESS_ORIGIN").unwrap();
-
-        response.set_header(Header::new("Access-Control-Allow-Origin", access_origin));
-        response.set_header(Header::new(
-            "Access-Control-Allow-Methods",
-            "POST, GET, PATCH, OPTIONS",
-        ));
-        response.set_header(Header::new("Access-Control-Allow-Headers", "*"));
-        response.set_header(Header::new("Access-Control-Expose-Headers", "*"));
-        response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
-
-        if request.method() == Method::Options {
-            response.set_status(Status::Ok);
-            response.set_header(Header::new(
-                "Access-Control-Allow-Headers",
-                "Content-Type, Referer, *",
-            ));
-        }
-    }
-}
-
 #[cfg(feature = "process")]
 #[launch] // The "main" function of the program
 fn rocket() -> _ {
@@ -78,6 +39,7 @@ fn rocket() -> _ {
             "/",
             catchers![
                 catchers::not_authorized,
+                catchers::forbidden,
                 catchers::internal_server_error,
                 catchers::not_found,
                 catchers::unprocessable_entry,
@@ -85,7 +47,14 @@ fn rocket() -> _ {
             ],
         )
         .attach(Db::init())
-        .attach(CORS)
+        .attach(guards::Cors::from_env())
+        .attach(guards::Compression::from_env())
+        .attach(guards::RequestTracing)
+        .attach(guards::GracefulShutdown::from_env())
+        .manage(methods::transaction::OrderEvents::new())
+        .manage(methods::DemoMode::from_env())
+        .manage(methods::AddressVerifier::from_env())
+        .mount("/", methods::health::handlers::probe_routes())
         .mount(
             "/docs",
             make_swagger_ui(&SwaggerUIConfig {
@@ -99,14 +68,20 @@ fn rocket() -> _ {
     mount_endpoints_and_merged_docs! {
         launcher, "/api".to_owned(), openapi_settings,
         "/store" => methods::store::handlers::documented_routes(&openapi_settings),
+        "/audit" => methods::audit::handlers::documented_routes(&openapi_settings),
         "/kiosk" => methods::kiosk::handlers::documented_routes(&openapi_settings),
         "/ingress" => methods::ingress::handlers::documented_routes(&openapi_settings),
         "/product" => methods::product::handlers::documented_routes(&openapi_settings),
         "/customer" => methods::customer::handlers::documented_routes(&openapi_settings),
         "/employee" => methods::employee::handlers::documented_routes(&openapi_settings),
+        "/giftcard" => methods::gift_card::handlers::documented_routes(&openapi_settings),
         "/supplier" => methods::supplier::handlers::documented_routes(&openapi_settings),
         "/helpers" => methods::helpers::handlers::documented_routes(&openapi_settings),
+        "/reports" => methods::report::handlers::documented_routes(&openapi_settings),
+        "/search" => methods::search::handlers::documented_routes(&openapi_settings),
+        "/stocktake" => methods::stocktake::handlers::documented_routes(&openapi_settings),
         "/transaction" => methods::transaction::handlers::documented_routes(&openapi_settings),
+        "/webhook" => methods::webhook::handlers::documented_routes(&openapi_settings),
     }
 
     launcher
@@ -0,0 +1,19 @@
+use crate::guards::Convert;
+use crate::methods::employee::Action;
+use crate::pool::InternalDb;
+use crate::{check_permissions, AuditLog, ListQuery, Session};
+use okapi::openapi3::OpenApi;
+use rocket::get;
+use rocket_okapi::settings::OpenApiSettings;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
+
+pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![settings: get_all]
+}
+
+#[openapi(tag = "Audit")]
+#[get("/?<query..>")]
+pub async fn get_all(db: InternalDb, session: Session, query: ListQuery) -> Convert<Vec<AuditLog>> {
+    check_permissions!(session.clone(), Action::FetchAuditLog);
+    AuditLog::fetch_all(&query, session, &db.0).await.into()
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use log::error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+#[cfg(feature = "process")]
+use crate::entities::audit_log;
+#[cfg(feature = "process")]
+use crate::entities::prelude::AuditLog as AuditLogEntity;
+use crate::methods::{Error, Id, ListQuery};
+use crate::Session;
+#[cfg(feature = "process")]
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DbConn, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use uuid::Uuid;
+
+/// The kinds of record whose mutations are audited.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum AuditEntity {
+    Transaction,
+    Store,
+    Product,
+    Supplier,
+    Kiosk,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Purge,
+    Lock,
+    Unlock,
+}
+
+/// A single mutation of a record, including who made it and the fields it changed.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct AuditLog {
+    pub id: Id,
+    pub employee_id: Id,
+    pub entity_type: AuditEntity,
+    pub entity_id: Id,
+    pub action: AuditAction,
+    /// The changed fields, each as `{ "from": .., "to": .. }`.
+    pub diff: Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The most entries returned by a single fetch.
+const AUDIT_LOG_LIMIT: u64 = 100;
+
+#[cfg(feature = "methods")]
+impl AuditLog {
+    /// Records a mutation of the given record, diffing its state before and after.
+    ///
+    /// The mutation has already been committed by the time it is recorded, so a
+    /// failure to write the entry is logged rather than failing the request.
+    pub async fn record<T: Serialize>(
+        entity_type: AuditEntity,
+        entity_id: &str,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+        session: &Session,
+        db: &DbConn,
+    ) {
+        let model = audit_log::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            tenant_id: Set(session.tenant_id.clone()),
+            employee_id: Set(session.employee.id.clone()),
+            entity_type: Set(format!("{:?}", entity_type)),
+            entity_id: Set(entity_id.to_string()),
+            action: Set(format!("{:?}", action)),
+            diff: Set(diff(json!(before), json!(after))),
+            timestamp: Set(Utc::now().naive_utc()),
+        };
+
+        if let Err(err) = AuditLogEntity::insert(model).exec(db).await {
+            error!(
+                "Unable to record {:?} of {:?} {}: {}",
+                action, entity_type, entity_id, err
+            );
+        }
+    }
+
+    pub async fn fetch_all(
+        query: &ListQuery,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<AuditLog>, Error> {
+        let select =
+            AuditLogEntity::find().filter(audit_log::Column::TenantId.eq(session.tenant_id));
+
+        let logs = query
+            .apply(
+                select,
+                &[
+                    ("employee_id", audit_log::Column::EmployeeId),
+                    ("entity_type", audit_log::Column::EntityType),
+                    ("entity_id", audit_log::Column::EntityId),
+                    ("action", audit_log::Column::Action),
+                    ("timestamp", audit_log::Column::Timestamp),
+                ],
+            )?
+            .order_by_desc(audit_log::Column::Timestamp)
+            .limit(AUDIT_LOG_LIMIT)
+            .all(db)
+            .await?;
+
+        Ok(logs.into_iter().map(|l| l.into()).collect())
+    }
+}
+
+/// Compares the top-level fields of two serialized records, yielding
+/// `{ field: { "from": .., "to": .. } }` for each field which differs.
+fn diff(before: Value, after: Value) -> Value {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let changes: Map<String, Value> = before
+        .keys()
+        .chain(after.keys().filter(|k| !before.contains_key(*k)))
+        .filter_map(|key| {
+            let from = before.get(key).unwrap_or(&Value::Null);
+            let to = after.get(key).unwrap_or(&Value::Null);
+
+            (from != to).then(|| (key.clone(), json!({ "from": from, "to": to })))
+        })
+        .collect();
+
+    Value::Object(changes)
+}
+
+#[cfg(feature = "process")]
+impl From<audit_log::Model> for AuditLog {
+    fn from(val: audit_log::Model) -> Self {
+        AuditLog {
+            id: val.id,
+            employee_id: val.employee_id,
+            entity_type: serde_json::from_value(json!(val.entity_type)).unwrap(),
+            entity_id: val.entity_id,
+            action: serde_json::from_value(json!(val.action)).unwrap(),
+            diff: val.diff,
+            timestamp: DateTime::from_naive_utc_and_offset(val.timestamp, Utc),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fmt::Display;
 
 use super::{Employee as EmployeeObj, ProductExchange};
@@ -6,19 +7,29 @@ use crate::entities::employee::Entity as Employee;
 #[cfg(feature = "process")]
 use crate::entities::session::Entity as SessionEntity;
 
+#[cfg(feature = "process")]
+use crate::guards::RequestId;
 use crate::{example_employee, session, AccountType, Employee as EmployeeStruct, EmployeeInput};
 
 #[cfg(feature = "process")]
 use crate::entities;
-use crate::methods::{stml::Order, Access, Action, Attendance, EmployeeAuth};
+use crate::methods::{stml::Order, Access, Action, Attendance, EmployeeAuth, StoreAccess};
 use chrono::{DateTime, Days, Utc};
 use lazy_static::lazy_static;
-use okapi::openapi3::Responses;
+use log::{error, warn};
+use okapi::openapi3::{MediaType, RefOr, Response, Responses};
+#[cfg(feature = "process")]
+use rand::{rngs::OsRng, RngCore};
 use regex::Regex;
 use rocket::http::{Cookie, SameSite};
 use rocket::time::OffsetDateTime;
 #[cfg(feature = "process")]
-use rocket::{http::CookieJar, serde::json::Json, Responder};
+use rocket::{
+    http::{CookieJar, Status},
+    response::Responder,
+    serde::json::Json,
+    Request,
+};
 use rocket_okapi::gen::OpenApiGenerator;
 
 use crate::session::{ActiveModel, Model};
@@ -26,11 +37,18 @@ use rocket_okapi::response::OpenApiResponderInner;
 use schemars::JsonSchema;
 use sea_orm::ActiveValue::Set;
 #[cfg(feature = "process")]
-use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QuerySelect};
+use sea_orm::{
+    ColumnTrait, ConnAcquireErr, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    SqlErr,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+#[cfg(feature = "process")]
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{
+    validate_email, validate_url, Validate, ValidationError, ValidationErrors, ValidationErrorsKind,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema, Validate)]
 pub struct Name {
@@ -39,6 +57,19 @@ pub struct Name {
     pub last: String,
 }
 
+impl Display for Name {
+    /// The names joined by spaces, omitting any which are empty.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = [&self.first, &self.middle, &self.last]
+            .into_iter()
+            .map(|n| n.trim())
+            .filter(|n| !n.is_empty())
+            .collect();
+
+        write!(f, "{}", names.join(" "))
+    }
+}
+
 impl Name {
     pub(crate) fn from_string(name: String) -> Self {
         let names: Vec<&str> = name.split(' ').collect();
@@ -54,9 +85,12 @@ impl Name {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Validate)]
 pub struct ContactInformation {
     pub name: String,
+    #[validate(custom = "validate_mobile_number")]
     pub mobile: MobileNumber,
+    #[validate(custom = "validate_email_address")]
     pub email: Email,
     pub landline: String,
+    #[validate]
     pub address: Address,
 }
 
@@ -66,21 +100,76 @@ pub struct ContactInformationInput {
     pub mobile: String,
     pub email: String,
     pub landline: String,
+    #[validate]
     pub address: Address,
 }
 
 impl ContactInformationInput {
     pub fn into_major(self) -> ContactInformation {
+        self.into_major_in(*DEFAULT_REGION)
+    }
+
+    /// Reads a mobile number dialled without a calling code as one of `region`.
+    pub fn into_major_in(self, region: Region) -> ContactInformation {
         ContactInformation {
             name: self.name,
             email: Email::from(self.email),
-            mobile: MobileNumber::from(self.mobile),
+            mobile: MobileNumber::from_region(self.mobile, region),
             landline: self.landline,
             address: self.address,
         }
     }
 }
 
+#[cfg(feature = "process")]
+impl ContactInformation {
+    /// Validates contact details found at `path` within a request body, i.e.
+    /// `contact`, reading those given without a country as within `region`.
+    pub fn ensure_valid(&self, path: &str, region: Region) -> Result<(), Error> {
+        ensure_contact_valid(&self.mobile.number, &self.address, path, region)
+    }
+}
+
+#[cfg(feature = "process")]
+impl ContactInformationInput {
+    /// Validates contact details found at `path` within a request body, i.e.
+    /// `contact`, reading those given without a country as within `region`.
+    pub fn ensure_valid(&self, path: &str, region: Region) -> Result<(), Error> {
+        ensure_contact_valid(&self.mobile, &self.address, path, region)
+    }
+}
+
+#[cfg(feature = "process")]
+fn ensure_contact_valid(
+    mobile: &str,
+    address: &Address,
+    path: &str,
+    region: Region,
+) -> Result<(), Error> {
+    let at = |field: &str| {
+        if path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", path, field)
+        }
+    };
+
+    let mut errors = match address.validate_in(region) {
+        Ok(_) => vec![],
+        Err(errors) => FieldError::from_validation_at(&at("address"), &errors),
+    };
+
+    if !mobile.trim().is_empty() && !MobileNumber::from_region(mobile.to_string(), region).valid {
+        errors.push(FieldError::new(&at("mobile"), "invalid_phone_number"));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorResponse::validation_errors(errors))
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Validate)]
 pub struct MobileNumber {
     pub number: String,
@@ -115,16 +204,330 @@ pub fn verify_phone_number_without_country_code(ph: &str) -> bool {
     RE.is_match(ph)
 }
 
+/// Country calling codes, with the least and most digits of a national number
+/// once any trunk prefix (the leading `0` dialled domestically) is removed.
+const CALLING_CODES: [(&str, usize, usize); 8] = [
+    // North American Numbering Plan
+    ("1", 10, 10),
+    // France
+    ("33", 9, 9),
+    // United Kingdom
+    ("44", 9, 10),
+    // Australia
+    ("61", 9, 9),
+    // New Zealand
+    ("64", 8, 10),
+    // Singapore
+    ("65", 8, 8),
+    // Japan
+    ("81", 9, 10),
+    // India
+    ("91", 10, 10),
+];
+
+/// Validates a number in international format, i.e. `+44 20 7946 0958` or
+/// `+64 (0)21 212 120`, by the length rules of its country calling code.
+///
+/// Numbers with a calling code not in [`CALLING_CODES`] need only be a valid
+/// E.164 length, at most 15 digits including the calling code.
+pub fn verify_phone_number_international(ph: &str) -> bool {
+    let rest = match ph.trim().strip_prefix('+') {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    if !rest
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '.' | '(' | ')'))
+    {
+        return false;
+    }
+
+    let digits: String = rest.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    match CALLING_CODES
+        .iter()
+        .find(|(code, ..)| digits.starts_with(code))
+    {
+        Some((code, min, max)) => {
+            let national = &digits[code.len()..];
+            let national = match national.strip_prefix('0') {
+                Some(n) if *code != "1" => n,
+                _ => national,
+            };
+
+            (*min..=*max).contains(&national.len())
+        }
+        None => (8..=15).contains(&digits.len()),
+    }
+}
+
+/// Reduces a phone number to its digits so differently formatted copies compare equal,
+/// i.e. `(555) 123-4567`, `555.123.4567` and `+1 555 123 4567` all become `5551234567`.
+///
+/// Numbers passing [`verify_phone_number_with_country_code`] or
+/// [`verify_phone_number_without_country_code`] keep only their final ten digits,
+/// dropping any country code or leading `1`. Other numbers keep every digit.
+pub fn normalize_phone_number(ph: &str) -> String {
+    let ph = ph.trim();
+    let digits: String = ph.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let valid = if ph.starts_with('+') {
+        verify_phone_number_with_country_code(ph)
+    } else {
+        verify_phone_number_without_country_code(ph)
+    };
+
+    if valid && digits.len() > 10 {
+        digits[digits.len() - 10..].to_string()
+    } else {
+        digits
+    }
+}
+
+/// A country whose conventions are assumed of phone numbers dialled without a
+/// calling code, and of addresses which name no country.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum Region {
+    #[default]
+    UnitedStates,
+    Canada,
+    UnitedKingdom,
+    France,
+    Australia,
+    NewZealand,
+    Singapore,
+    Japan,
+    India,
+}
+
+lazy_static! {
+    /// The region of tenants which set none in their [`TenantSettings`](crate::TenantSettings),
+    /// read from `DEFAULT_REGION` as an ISO 3166 code, i.e. `AU`, by default the United States.
+    pub static ref DEFAULT_REGION: Region = std::env::var("DEFAULT_REGION")
+        .ok()
+        .and_then(|code| Region::from_country(&code))
+        .unwrap_or_default();
+
+    static ref PO_CODES: Vec<(Region, Regex)> = [
+        (Region::UnitedStates, r"^\d{5}(-?\d{4})?$"),
+        (Region::Canada, r"^[A-Za-z]\d[A-Za-z] ?\d[A-Za-z]\d$"),
+        (Region::UnitedKingdom, r"^[A-Za-z]{1,2}\d[A-Za-z\d]? ?\d[A-Za-z]{2}$"),
+        (Region::France, r"^\d{5}$"),
+        (Region::Australia, r"^\d{4}$"),
+        (Region::NewZealand, r"^\d{4}$"),
+        (Region::Singapore, r"^\d{6}$"),
+        (Region::Japan, r"^\d{3}-?\d{4}$"),
+        (Region::India, r"^\d{3} ?\d{3}$"),
+    ]
+    .into_iter()
+    .map(|(region, pattern)| (region, Regex::new(pattern).unwrap()))
+    .collect();
+}
+
+impl Region {
+    pub const ALL: [Region; 9] = [
+        Region::UnitedStates,
+        Region::Canada,
+        Region::UnitedKingdom,
+        Region::France,
+        Region::Australia,
+        Region::NewZealand,
+        Region::Singapore,
+        Region::Japan,
+        Region::India,
+    ];
+
+    /// Reads a country given by its ISO 3166 code or English name, i.e. `NZ`
+    /// or `New Zealand`. Returns `None` for countries not listed in [`Region`].
+    pub fn from_country(country: &str) -> Option<Region> {
+        match country.trim().to_lowercase().as_str() {
+            "us" | "usa" | "united states" | "united states of america" => {
+                Some(Region::UnitedStates)
+            }
+            "ca" | "canada" => Some(Region::Canada),
+            "gb" | "uk" | "united kingdom" | "great britain" => Some(Region::UnitedKingdom),
+            "fr" | "france" => Some(Region::France),
+            "au" | "australia" => Some(Region::Australia),
+            "nz" | "new zealand" => Some(Region::NewZealand),
+            "sg" | "singapore" => Some(Region::Singapore),
+            "jp" | "japan" => Some(Region::Japan),
+            "in" | "india" => Some(Region::India),
+            _ => None,
+        }
+    }
+
+    pub fn calling_code(&self) -> &'static str {
+        match self {
+            Region::UnitedStates | Region::Canada => "1",
+            Region::UnitedKingdom => "44",
+            Region::France => "33",
+            Region::Australia => "61",
+            Region::NewZealand => "64",
+            Region::Singapore => "65",
+            Region::Japan => "81",
+            Region::India => "91",
+        }
+    }
+
+    /// The national number of `ph` as dialled within the region, without any trunk
+    /// prefix, or `None` if it is not a valid number of the region.
+    fn national_number(&self, ph: &str) -> Option<String> {
+        if matches!(self, Region::UnitedStates | Region::Canada) {
+            if !verify_phone_number_without_country_code(ph) {
+                return None;
+            }
+
+            let digits: String = ph.chars().filter(|c| c.is_ascii_digit()).collect();
+            return Some(digits[digits.len() - 10..].to_string());
+        }
+
+        if !ph
+            .trim()
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        {
+            return None;
+        }
+
+        let digits: String = ph.chars().filter(|c| c.is_ascii_digit()).collect();
+        let national = digits.strip_prefix('0').unwrap_or(&digits);
+
+        CALLING_CODES
+            .iter()
+            .find(|(code, ..)| *code == self.calling_code())
+            .filter(|(_, min, max)| (*min..=*max).contains(&national.len()))
+            .map(|_| national.to_string())
+    }
+
+    /// Whether `ph`, dialled within the region, is a valid number.
+    pub fn is_valid_phone_number(&self, ph: &str) -> bool {
+        self.national_number(ph).is_some()
+    }
+
+    /// Writes `ph` in E.164 format, i.e. `+6421212120`, reading numbers without a
+    /// calling code as dialled within the region. `None` if the number is invalid.
+    pub fn international(&self, ph: &str) -> Option<String> {
+        if ph.trim().starts_with('+') {
+            if !verify_phone_number_international(ph) {
+                return None;
+            }
+
+            // Drops any trunk prefix written after the calling code, i.e. `+64 (0)21`.
+            let digits: String = ph.chars().filter(|c| c.is_ascii_digit()).collect();
+            let trimmed = CALLING_CODES
+                .iter()
+                .filter(|(code, ..)| *code != "1")
+                .find(|(code, ..)| digits.starts_with(code))
+                .and_then(|(code, ..)| {
+                    digits[code.len()..]
+                        .strip_prefix('0')
+                        .map(|national| format!("{}{}", code, national))
+                });
+
+            return Some(format!("+{}", trimmed.unwrap_or(digits)));
+        }
+
+        self.national_number(ph)
+            .map(|national| format!("+{}{}", self.calling_code(), national))
+    }
+
+    /// Whether `po_code` is a well-formed postal code of the region.
+    pub fn is_valid_po_code(&self, po_code: &str) -> bool {
+        PO_CODES
+            .iter()
+            .find(|(region, _)| region == self)
+            .is_none_or(|(_, pattern)| pattern.is_match(po_code.trim()))
+    }
+}
+
 impl MobileNumber {
+    /// Reads a number dialled without a calling code as one of [`DEFAULT_REGION`].
     pub fn from(number: String) -> Self {
-        let valid = if number.starts_with('+') {
-            verify_phone_number_with_country_code(number.as_str())
+        Self::from_region(number, *DEFAULT_REGION)
+    }
+
+    /// Reads a number dialled without a calling code as one of `region`.
+    pub fn from_region(number: String, region: Region) -> Self {
+        let valid = if number.trim().starts_with('+') {
+            verify_phone_number_international(number.as_str())
         } else {
-            verify_phone_number_without_country_code(number.as_str())
+            region.is_valid_phone_number(number.as_str())
         };
 
         MobileNumber { number, valid }
     }
+
+    pub fn normalized(&self) -> String {
+        normalize_phone_number(&self.number)
+    }
+
+    /// The number in E.164 format, see [`Region::international`].
+    pub fn international(&self, region: Region) -> Option<String> {
+        region.international(&self.number)
+    }
+}
+
+/// Ensures a mobile number, if given, is well-formed in some [`Region`], as the
+/// tenant's region is not known whilst a request is read. The number is checked
+/// against it by [`ContactInformation::ensure_valid`]. The `valid` flag sent by
+/// the client is not trusted.
+pub fn validate_mobile_number(mobile: &MobileNumber) -> Result<(), ValidationError> {
+    if mobile.number.trim().is_empty()
+        || Region::ALL
+            .iter()
+            .any(|region| MobileNumber::from_region(mobile.number.clone(), *region).valid)
+    {
+        return Ok(());
+    }
+
+    let mut err = ValidationError::new("invalid_phone_number");
+    err.add_param("value".into(), &mobile.number);
+    Err(err)
+}
+
+/// Ensures an email address, if given, is well-formed.
+pub fn validate_email_address(email: &Email) -> Result<(), ValidationError> {
+    if email.full.trim().is_empty() || validate_email(email.full.as_str()) {
+        return Ok(());
+    }
+
+    let mut err = ValidationError::new("invalid_email");
+    err.add_param("value".into(), &email.full);
+    Err(err)
+}
+
+/// Ensures a name has at least one part which is not blank.
+pub fn validate_name(name: &Name) -> Result<(), ValidationError> {
+    if name.to_string().is_empty() {
+        return Err(ValidationError::new("empty_name"));
+    }
+
+    Ok(())
+}
+
+/// Ensures an id, such as of the employee or kiosk making a sale, is given.
+pub fn validate_id(id: &str) -> Result<(), ValidationError> {
+    if id.trim().is_empty() {
+        return Err(ValidationError::new("empty_id"));
+    }
+
+    Ok(())
+}
+
+/// Validates each of `items`, naming the first which fails. Used in place of a
+/// bare `#[validate]`, which cannot see through aliases such as [`OrderList`].
+pub fn validate_each<T: Validate>(items: &[T]) -> Result<(), ValidationError> {
+    for (index, item) in items.iter().enumerate() {
+        if let Err(errors) = item.validate() {
+            let mut err = ValidationError::new("invalid_item");
+            err.add_param("index".into(), &index);
+            err.message = Some(errors.to_string().into());
+            return Err(err);
+        }
+    }
+
+    Ok(())
 }
 
 pub type OrderList = Vec<Order>;
@@ -170,9 +573,200 @@ impl Email {
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
 pub struct Note {
+    /// Identifies the note to reply to. Notes written before replies existed
+    /// are given one when read, which is kept once they are next saved.
+    #[serde(default = "new_note_id")]
+    pub id: Id,
+    /// The note this replies to, or `None` if it begins a thread.
+    #[serde(default)]
+    pub parent_id: Option<Id>,
     pub message: String,
     pub author: String,
     pub timestamp: DateTime<Utc>,
+    /// The note's earlier wordings, oldest first, see [`Note::edit`].
+    #[serde(default)]
+    pub edits: Vec<NoteEdit>,
+    /// Set once the note is removed, after which its `message` reads `[removed]`.
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// A change to a note, recording the `message` as it read before the change.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NoteEdit {
+    pub message: String,
+    pub editor: Id,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The new wording of an edited note.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Validate)]
+pub struct NoteUpdate {
+    #[validate(custom = "validate_note_message")]
+    pub message: String,
+}
+
+/// Refuses a note with nothing written in it, whitespace alone included.
+pub fn validate_note_message(message: &str) -> Result<(), ValidationError> {
+    if message.trim().is_empty() {
+        return Err(ValidationError::new("empty_note"));
+    }
+
+    Ok(())
+}
+
+/// What a removed note reads as, its original wording is kept in its edits.
+pub const REMOVED_NOTE: &str = "[removed]";
+
+fn new_note_id() -> Id {
+    Uuid::new_v4().to_string()
+}
+
+impl Note {
+    pub fn new(message: String, author: Id) -> Self {
+        Note {
+            id: new_note_id(),
+            parent_id: None,
+            message,
+            author,
+            timestamp: Utc::now(),
+            edits: vec![],
+            removed: false,
+        }
+    }
+
+    /// Notes form part of the audit trail, so may only be changed by their
+    /// author or an employee permitted to moderate notes.
+    pub fn may_change(&self, session: &Session) -> bool {
+        self.author == session.employee.id || session.clone().has_permission(Action::ModerateNotes)
+    }
+
+    /// Rewords the note, keeping its previous wording in its edits.
+    pub fn edit(&mut self, message: String, editor: Id) -> Result<(), Error> {
+        if self.removed {
+            return Err(ErrorResponse::custom_input_error(
+                "Unable to edit a note which has been removed.",
+            ));
+        }
+
+        self.edits.push(NoteEdit {
+            message: std::mem::replace(&mut self.message, message),
+            editor,
+            timestamp: Utc::now(),
+        });
+
+        Ok(())
+    }
+
+    /// Removes the note, which then reads [`REMOVED_NOTE`]. Its wording is kept
+    /// in its edits and its replies are left in place.
+    pub fn remove(&mut self, editor: Id) -> Result<(), Error> {
+        self.edit(REMOVED_NOTE.to_string(), editor)?;
+        self.removed = true;
+
+        Ok(())
+    }
+
+    /// A new note by `author` replying to this one.
+    pub fn reply(&self, message: String, author: Id) -> Self {
+        Note {
+            parent_id: Some(self.id.clone()),
+            ..Note::new(message, author)
+        }
+    }
+}
+
+/// A note to add, replying to the note `parent_id` when given.
+#[derive(Debug, Clone, Deserialize, JsonSchema, Validate)]
+pub struct NoteInput {
+    #[validate(custom = "validate_note_message")]
+    pub message: String,
+    #[serde(default)]
+    pub parent_id: Option<Id>,
+}
+
+/// A note and the replies to it, each in the order they were written.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NoteThread {
+    pub note: Note,
+    pub replies: Vec<NoteThread>,
+}
+
+/// Arranges `notes` into threads, oldest first at every level. A reply to a
+/// note not amongst `notes` begins a thread of its own, as does the oldest of
+/// notes mistakenly replying to one another, or to themselves.
+pub fn thread_notes(notes: &[Note]) -> Vec<NoteThread> {
+    let mut sorted: Vec<&Note> = notes.iter().collect();
+    sorted.sort_by_key(|n| n.timestamp);
+
+    let mut visited = HashSet::new();
+    let mut threads = vec![];
+
+    for note in sorted.iter().filter(|n| match &n.parent_id {
+        Some(parent) => !notes.iter().any(|p| &p.id == parent),
+        None => true,
+    }) {
+        threads.push(build_thread(note, &sorted, &mut visited));
+    }
+
+    // Notes replying to one another in a cycle have no note beginning their thread.
+    for note in sorted.iter() {
+        if !visited.contains(note.id.as_str()) {
+            threads.push(build_thread(note, &sorted, &mut visited));
+        }
+    }
+
+    threads.sort_by_key(|t| t.note.timestamp);
+    threads
+}
+
+/// The whole thread containing the note `id`, from the note which began it.
+pub fn note_thread(notes: &[Note], id: &str) -> Option<NoteThread> {
+    let mut root = notes.iter().find(|n| n.id == id)?;
+
+    // Bounded, as notes mistakenly replying to one another have no note beginning
+    // their thread, in which case it is read from the last note reached.
+    for _ in 0..notes.len() {
+        match root
+            .parent_id
+            .as_ref()
+            .and_then(|parent| notes.iter().find(|n| &n.id == parent))
+        {
+            Some(parent) => root = parent,
+            None => break,
+        }
+    }
+
+    let mut sorted: Vec<&Note> = notes.iter().collect();
+    sorted.sort_by_key(|n| n.timestamp);
+
+    Some(build_thread(root, &sorted, &mut HashSet::new()))
+}
+
+/// The thread of replies to `note`, skipping any note already `visited` such
+/// that notes replying to one another are each read once.
+fn build_thread<'a>(
+    note: &'a Note,
+    sorted: &[&'a Note],
+    visited: &mut HashSet<&'a str>,
+) -> NoteThread {
+    visited.insert(note.id.as_str());
+
+    let mut replies = vec![];
+
+    for reply in sorted
+        .iter()
+        .filter(|n| n.parent_id.as_deref() == Some(note.id.as_str()))
+    {
+        if !visited.contains(reply.id.as_str()) {
+            replies.push(build_thread(reply, sorted, visited));
+        }
+    }
+
+    NoteThread {
+        note: note.clone(),
+        replies,
+    }
 }
 
 impl Display for Note {
@@ -186,7 +780,7 @@ impl Display for Note {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Validate)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct Address {
     pub street: String,
     pub street2: String,
@@ -197,6 +791,92 @@ pub struct Address {
     pub lon: f64,
 }
 
+impl Address {
+    /// Addresses which were never geocoded are left at `(0.0, 0.0)`, "null island",
+    /// such addresses cannot be used for any distance calculations.
+    pub fn is_geocoded(&self) -> bool {
+        !(self.lat == 0.0 && self.lon == 0.0)
+    }
+
+    /// The region named by the address's `country`, or `default` if it names none.
+    /// `None` for countries not listed in [`Region`].
+    pub fn region(&self, default: Region) -> Option<Region> {
+        if self.country.trim().is_empty() {
+            Some(default)
+        } else {
+            Region::from_country(&self.country)
+        }
+    }
+
+    /// Whether the postal code, if given, is well-formed in the address's region.
+    /// Codes of countries not listed in [`Region`] are not checked.
+    pub fn has_valid_po_code(&self, default: Region) -> bool {
+        self.po_code.trim().is_empty()
+            || self
+                .region(default)
+                .is_none_or(|region| region.is_valid_po_code(&self.po_code))
+    }
+}
+
+#[cfg(feature = "process")]
+impl Address {
+    /// Validates an address found at `path` within a request body, i.e.
+    /// `contact.address`, an address naming no country being read as within `region`.
+    pub fn ensure_valid(&self, path: &str, region: Region) -> Result<(), Error> {
+        self.validate_in(region).map_err(|errors| {
+            ErrorResponse::validation_errors(FieldError::from_validation_at(path, &errors))
+        })
+    }
+}
+
+impl Address {
+    /// Validates the address as [`Validate::validate`] does, further checking the
+    /// postal code of an address naming no country as one of `region`.
+    pub fn validate_in(&self, region: Region) -> Result<(), ValidationErrors> {
+        self.check(Some(region))
+    }
+
+    fn check(&self, default: Option<Region>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        for (field, value, bound) in [("lat", self.lat, 90.0), ("lon", self.lon, 180.0)] {
+            if !(-bound..=bound).contains(&value) {
+                let mut err = ValidationError::new("range");
+                err.add_param("min".into(), &-bound);
+                err.add_param("max".into(), &bound);
+                err.add_param("value".into(), &value);
+                errors.add(field, err);
+            }
+        }
+
+        let valid_po_code = match default {
+            Some(region) => self.has_valid_po_code(region),
+            None => self.country.trim().is_empty() || self.has_valid_po_code(*DEFAULT_REGION),
+        };
+
+        if !valid_po_code {
+            let mut err = ValidationError::new("invalid_po_code");
+            err.add_param("value".into(), &self.po_code);
+            errors.add("po_code", err);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Checks the coordinates are in range and the postal code is well-formed in the
+/// country the address names. The tenant's region is not known whilst a request is
+/// read, so the code of an address naming none is checked by [`Address::validate_in`].
+impl Validate for Address {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.check(None)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
 pub struct Location {
     pub store_code: String,
@@ -208,6 +888,31 @@ pub struct Location {
 
 pub type Url = String;
 
+/// The maximum number of images which may be attached to a product or variant.
+pub const MAX_IMAGES: usize = 12;
+
+/// Ensures a list of image URLs is within [`MAX_IMAGES`] and that each is a
+/// well-formed `http` or `https` URL.
+pub fn validate_image_urls(images: &[Url]) -> Result<(), ValidationError> {
+    if images.len() > MAX_IMAGES {
+        let mut err = ValidationError::new("too_many_images");
+        err.add_param("max".into(), &MAX_IMAGES);
+        return Err(err);
+    }
+
+    for image in images {
+        let scheme_valid = image.starts_with("https://") || image.starts_with("http://");
+
+        if !scheme_valid || !validate_url(image.as_str()) {
+            let mut err = ValidationError::new("invalid_image_url");
+            err.add_param("value".into(), image);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
 pub type TagList = Vec<Tag>;
 pub type Tag = String;
 pub type Id = String;
@@ -220,6 +925,7 @@ pub struct SessionRaw {
     pub expiry: DateTime<Utc>,
     pub variant: SessionVariant,
     pub tenant_id: String,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, JsonSchema, Serialize, Deserialize)]
@@ -232,6 +938,7 @@ pub enum SessionVariant {
 #[derive(Debug, Clone, JsonSchema, Validate, Serialize, Deserialize)]
 pub struct Session {
     pub id: String,
+    /// The key as presented by the client, only its hash is stored, see [`Session::hash_key`].
     pub key: String,
     pub employee: EmployeeObj,
     pub expiry: DateTime<Utc>,
@@ -258,11 +965,12 @@ impl From<Session> for session::ActiveModel {
     fn from(val: Session) -> Self {
         ActiveModel {
             id: Set(val.id),
-            key: Set(val.key),
+            key: Set(Session::hash_key(&val.key)),
             tenant_id: Set(val.tenant_id),
             employee_id: Set(val.employee.id),
             expiry: Set(val.expiry.naive_utc()),
             variant: Set(json!(val.variant)),
+            created_at: Set(Utc::now().naive_utc()),
         }
     }
 }
@@ -276,6 +984,7 @@ impl From<Model> for SessionRaw {
             expiry: DateTime::from_naive_utc_and_offset(value.expiry, Utc),
             variant: serde_json::from_value::<SessionVariant>(value.variant).unwrap(),
             tenant_id: value.tenant_id,
+            created_at: DateTime::from_naive_utc_and_offset(value.created_at, Utc),
         }
     }
 }
@@ -299,6 +1008,18 @@ impl Session {
         }
     }
 
+    /// Refuses acting upon a store the employee is not assigned to.
+    pub fn ensure_store(&self, store_id: &str) -> Result<(), Error> {
+        if self.employee.stores.permits(store_id) {
+            Ok(())
+        } else {
+            Err(ErrorResponse::custom_unauthorized(&format!(
+                "Employee is not assigned to store {}.",
+                store_id
+            )))
+        }
+    }
+
     pub fn ingestion(
         employee: EmployeeInput,
         tenant_id: String,
@@ -309,26 +1030,221 @@ impl Session {
 
         Self {
             id: Uuid::new_v4().to_string(),
-            key: Uuid::new_v4().to_string(),
+            key: Self::generate_key(),
             employee: converted_employee,
             expiry: Utc::now().checked_add_days(Days::new(1)).unwrap(),
             variant: SessionVariant::AccessToken,
             tenant_id,
         }
     }
+
+    /// The sessions to end such that the employee may begin another without
+    /// holding more than `limit` at once, their oldest first. Refresh tokens go
+    /// with the session they refresh, and do not count towards the limit.
+    pub fn sessions_to_evict(sessions: &[SessionRaw], limit: usize) -> Vec<Id> {
+        let mut access: Vec<&SessionRaw> = sessions
+            .iter()
+            .filter(|s| matches!(s.variant, SessionVariant::AccessToken))
+            .collect();
+        access.sort_by_key(|s| s.created_at);
+
+        let excess = (access.len() + 1).saturating_sub(limit.max(1));
+        let mut evicted: Vec<Id> = access.iter().take(excess).map(|s| s.id.clone()).collect();
+
+        let refresh_tokens: Vec<Id> = sessions
+            .iter()
+            .filter(|s| match &s.variant {
+                SessionVariant::RefreshToken(access_id) => evicted.contains(access_id),
+                SessionVariant::AccessToken => false,
+            })
+            .map(|s| s.id.clone())
+            .collect();
+
+        evicted.extend(refresh_tokens);
+        evicted
+    }
+}
+
+#[cfg(feature = "process")]
+impl Session {
+    /// Ends the employee's oldest sessions, see [`Session::sessions_to_evict`], such
+    /// that one more may begin within [`max_concurrent_sessions`]. Sessions which
+    /// have expired are no longer counted.
+    pub async fn make_room(
+        employee_id: &str,
+        tenant_id: &str,
+        db: &DatabaseConnection,
+    ) -> Result<(), DbErr> {
+        let sessions: Vec<SessionRaw> = SessionEntity::find()
+            .filter(entities::session::Column::EmployeeId.eq(employee_id))
+            .filter(entities::session::Column::TenantId.eq(tenant_id))
+            .filter(entities::session::Column::Expiry.gt(Utc::now().naive_utc()))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|s| s.into())
+            .collect();
+
+        let evicted = Self::sessions_to_evict(&sessions, max_concurrent_sessions());
+
+        if !evicted.is_empty() {
+            SessionEntity::delete_many()
+                .filter(entities::session::Column::Id.is_in(evicted))
+                .exec(db)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Ends every session of the employee, on every device, returning how many
+    /// there were.
+    pub async fn revoke_all(
+        employee_id: &str,
+        tenant_id: &str,
+        db: &DatabaseConnection,
+    ) -> Result<u64, DbErr> {
+        let revoked = SessionEntity::delete_many()
+            .filter(entities::session::Column::EmployeeId.eq(employee_id))
+            .filter(entities::session::Column::TenantId.eq(tenant_id))
+            .exec(db)
+            .await?;
+
+        Ok(revoked.rows_affected)
+    }
+
+    /// A new session key of [`session_key_bytes`] random bytes from the operating
+    /// system's CSPRNG, hex encoded such that it is safe within URLs and cookies.
+    pub fn generate_key() -> String {
+        let mut bytes = vec![0u8; session_key_bytes()];
+        OsRng.fill_bytes(&mut bytes);
+
+        hex::encode(bytes)
+    }
+
+    /// The form in which a session key is stored, such that the keys of live
+    /// sessions cannot be read from the database. Matches MySQL's `SHA2(key, 256)`.
+    pub fn hash_key(key: &str) -> String {
+        hex::encode(Sha256::digest(key.as_bytes()))
+    }
+}
+
+/// The least number of random bytes a session key may be made of.
+pub const MIN_SESSION_KEY_BYTES: usize = 16;
+
+/// How many random bytes make up a session key, read from `SESSION_KEY_BYTES`,
+/// by default 32. Lengths below [`MIN_SESSION_KEY_BYTES`] are raised to it.
+#[cfg(feature = "process")]
+pub fn session_key_bytes() -> usize {
+    std::env::var("SESSION_KEY_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(32)
+        .max(MIN_SESSION_KEY_BYTES)
+}
+
+/// How many sessions an employee may hold at once, read from `MAX_CONCURRENT_SESSIONS`,
+/// by default 5. Beginning another ends their oldest, see [`Session::make_room`].
+#[cfg(feature = "process")]
+pub fn max_concurrent_sessions() -> usize {
+    std::env::var("MAX_CONCURRENT_SESSIONS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(5)
+        .max(1)
+}
+
+/// The cookie carrying the session key, see [`create_cookie`].
+pub const SESSION_COOKIE: &str = "os-stock-key";
+/// The cookie carrying the CSRF token, which browsers must echo in [`CSRF_HEADER`]
+/// on requests which change state.
+pub const CSRF_COOKIE: &str = "os-stock-csrf";
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// How the session cookies are set. Cookies are `Secure` unless `COOKIE_SECURE`
+/// is `false`, as needed to sign in over plain HTTP in development, and are sent
+/// with the `SameSite` given by `COOKIE_SAME_SITE`, one of `strict`, `lax` or
+/// `none`, by default `lax`.
+#[cfg(feature = "process")]
+#[derive(Debug, Clone, Copy)]
+pub struct CookiePolicy {
+    pub secure: bool,
+    pub same_site: SameSite,
+}
+
+#[cfg(feature = "process")]
+impl CookiePolicy {
+    pub fn from_env() -> Self {
+        let secure = std::env::var("COOKIE_SECURE")
+            .map(|value| !value.eq_ignore_ascii_case("false"))
+            .unwrap_or(true);
+
+        let same_site = match std::env::var("COOKIE_SAME_SITE")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "strict" => SameSite::Strict,
+            "none" => SameSite::None,
+            _ => SameSite::Lax,
+        };
+
+        CookiePolicy { secure, same_site }
+    }
+}
+
+#[cfg(feature = "process")]
+lazy_static! {
+    pub static ref COOKIE_POLICY: CookiePolicy = CookiePolicy::from_env();
+}
+
+/// How long an access token lasts, read in minutes from `SESSION_DURATION`, by default 10.
+#[cfg(feature = "process")]
+pub fn session_duration() -> chrono::Duration {
+    chrono::Duration::minutes(
+        std::env::var("SESSION_DURATION")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(10),
+    )
+}
+
+/// The longest a remembered session lasts, read in days from `REMEMBER_SESSION_DURATION`,
+/// by default 14. It is never shorter than an ordinary session, see [`session_duration`].
+#[cfg(feature = "process")]
+pub fn remember_duration() -> chrono::Duration {
+    let days = std::env::var("REMEMBER_SESSION_DURATION")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(14);
+
+    chrono::Duration::days(days).max(session_duration())
+}
+
+/// When a session begun now expires, later if the employee asked to be remembered.
+#[cfg(feature = "process")]
+pub fn session_expiry(remember: bool) -> DateTime<Utc> {
+    let duration = if remember {
+        remember_duration()
+    } else {
+        session_duration()
+    };
+
+    Utc::now() + duration
 }
 
 #[cfg(feature = "process")]
 pub fn get_key_cookie(cookies: &CookieJar<'_>) -> Option<String> {
     cookies
-        .get("os-stock-key")
+        .get(SESSION_COOKIE)
         .map(|crumb| crumb.value().to_string())
 }
 
 #[cfg(feature = "process")]
 pub async fn verify_cookie(key: String, db: &DatabaseConnection) -> Result<Session, DbErr> {
     let session = SessionEntity::find()
-        .having(entities::session::Column::Key.eq(key.clone()))
+        .having(entities::session::Column::Key.eq(Session::hash_key(&key)))
+        .having(entities::session::Column::Expiry.gt(Utc::now().naive_utc()))
         .find_also_related(Employee)
         .one(db)
         .await?;
@@ -336,7 +1252,7 @@ pub async fn verify_cookie(key: String, db: &DatabaseConnection) -> Result<Sessi
     match session {
         Some((val, Some(e))) => Ok(Session {
             id: val.id,
-            key: val.key,
+            key,
             tenant_id: val.tenant_id,
             employee: EmployeeObj {
                 id: e.id.clone(),
@@ -350,6 +1266,10 @@ pub async fn verify_cookie(key: String, db: &DatabaseConnection) -> Result<Sessi
                 .unwrap(),
                 account_type: serde_json::from_value::<AccountType>(e.account_type).unwrap(),
                 level: serde_json::from_value::<Vec<Access<Action>>>(e.level).unwrap(),
+                stores: e
+                    .stores
+                    .and_then(|stores| serde_json::from_value::<StoreAccess>(stores).ok())
+                    .unwrap_or_default(),
                 created_at: Default::default(),
                 updated_at: Default::default(),
             },
@@ -367,19 +1287,43 @@ pub async fn verify_cookie(key: String, db: &DatabaseConnection) -> Result<Sessi
     }
 }
 
+/// The session cookie for `api_key`, lasting until the session's `expiry`. It is
+/// kept from scripts, such that a cross-site scripting flaw cannot read the key.
 #[cfg(feature = "process")]
-pub fn create_cookie(api_key: String) -> Cookie<'static> {
-    use std::time::Duration;
+pub fn create_cookie(api_key: String, expiry: DateTime<Utc>) -> Cookie<'static> {
+    session_cookie(SESSION_COOKIE, api_key, expiry, true)
+}
 
-    let now = OffsetDateTime::now_utc();
-    let expiry = now + Duration::from_secs(10 * 60);
+/// Sets the session cookie for `api_key` alongside a new CSRF token, which unlike
+/// the key is readable by the client's scripts so that it may be echoed back.
+#[cfg(feature = "process")]
+pub fn add_session_cookies(cookies: &CookieJar<'_>, api_key: String, expiry: DateTime<Utc>) {
+    cookies.add(create_cookie(api_key, expiry));
+    cookies.add(session_cookie(
+        CSRF_COOKIE,
+        Uuid::new_v4().to_string(),
+        expiry,
+        false,
+    ));
+}
 
-    Cookie::build(("os-stock-key", api_key.clone()))
-        .expires(expiry)
+#[cfg(feature = "process")]
+fn session_cookie(
+    name: &'static str,
+    value: String,
+    expiry: DateTime<Utc>,
+    http_only: bool,
+) -> Cookie<'static> {
+    let policy = *COOKIE_POLICY;
+    let remaining = (expiry - Utc::now()).num_seconds().max(0);
+
+    Cookie::build((name, value))
+        .expires(OffsetDateTime::now_utc() + rocket::time::Duration::seconds(remaining))
+        .max_age(rocket::time::Duration::seconds(remaining))
         .path("/")
-        .secure(true)
-        .same_site(SameSite::None)
-        .http_only(true)
+        .secure(policy.secure)
+        .same_site(policy.same_site)
+        .http_only(http_only)
         .build()
 }
 
@@ -403,7 +1347,7 @@ pub async fn cookie_status_wrapper(
         Some(val) => match verify_cookie(val, db).await {
             Ok(v) => Ok(v),
             Err(err) => {
-                println!("[err]: {}", err);
+                warn!("Unable to validate session cookie: {}", err);
                 Err(ErrorResponse::custom_unauthorized(
                     "Unable to validate cookie, user does not have valid session.",
                 ))
@@ -415,10 +1359,76 @@ pub async fn cookie_status_wrapper(
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ErrorResponse {
     message: String,
     code: String,
+    /// The `X-Request-Id` of the failed request, set as the error is responded with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    correlation_id: Option<String>,
+    /// The fields which failed validation, empty for errors not caused by a field.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<FieldError>,
+}
+
+/// A single field of the request which failed validation, named by its path
+/// within the body, i.e. `contact.email` or `products[0].product_cost`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Flattens the errors of a failed [`Validate::validate`], nested fields
+    /// named by their path. Rules without a message are described by their code.
+    pub fn from_validation(errors: &ValidationErrors) -> Vec<FieldError> {
+        Self::from_validation_at("", errors)
+    }
+
+    /// As [`FieldError::from_validation`], for errors found at `path` within a request body.
+    pub fn from_validation_at(path: &str, errors: &ValidationErrors) -> Vec<FieldError> {
+        let mut fields = vec![];
+        Self::flatten(path, errors, &mut fields);
+        fields.sort_by(|a, b| a.field.cmp(&b.field));
+
+        fields
+    }
+
+    fn flatten(prefix: &str, errors: &ValidationErrors, fields: &mut Vec<FieldError>) {
+        for (field, kind) in errors.errors() {
+            let path = if prefix.is_empty() {
+                field.to_string()
+            } else {
+                format!("{}.{}", prefix, field)
+            };
+
+            match kind {
+                ValidationErrorsKind::Field(errors) => {
+                    fields.extend(errors.iter().map(|error| FieldError {
+                        field: path.clone(),
+                        message: match &error.message {
+                            Some(message) => message.to_string(),
+                            None => error.code.to_string(),
+                        },
+                    }))
+                }
+                ValidationErrorsKind::Struct(errors) => Self::flatten(&path, errors, fields),
+                ValidationErrorsKind::List(items) => {
+                    for (index, errors) in items {
+                        Self::flatten(&format!("{}[{}]", path, index), errors, fields);
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[cfg(feature = "process")]
@@ -427,6 +1437,8 @@ impl ErrorResponse {
         Error::StandardError(Json(ErrorResponse {
             message: message.to_string(),
             code: "error.custom".to_string(),
+            correlation_id: None,
+            errors: vec![],
         }))
     }
 
@@ -434,6 +1446,39 @@ impl ErrorResponse {
         Error::InputError(Json(ErrorResponse {
             message: "Unable to update fields due to malformed inputs".to_string(),
             code: "error.input".to_string(),
+            correlation_id: None,
+            errors: vec![],
+        }))
+    }
+
+    /// An input error naming each field which failed, see [`FieldError`].
+    pub fn validation_errors(errors: Vec<FieldError>) -> Error {
+        Error::InputError(Json(Self::field_errors(errors)))
+    }
+
+    /// The body of [`ErrorResponse::validation_errors`], also given by the
+    /// catcher of requests rejected by the `Validated` guard.
+    pub(crate) fn field_errors(errors: Vec<FieldError>) -> ErrorResponse {
+        let fields = errors
+            .iter()
+            .map(|e| e.field.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        ErrorResponse {
+            message: format!("Invalid input for {}.", fields),
+            code: "error.input.fields".to_string(),
+            correlation_id: None,
+            errors,
+        }
+    }
+
+    pub fn custom_input_error(message: &str) -> Error {
+        Error::InputError(Json(ErrorResponse {
+            message: message.to_string(),
+            code: "error.input.custom".to_string(),
+            correlation_id: None,
+            errors: vec![],
         }))
     }
 
@@ -441,6 +1486,8 @@ impl ErrorResponse {
         Error::Unauthorized(Json(ErrorResponse {
             message: format!("User lacks {:?} permission.", action),
             code: "error.unauthorized".to_string(),
+            correlation_id: None,
+            errors: vec![],
         }))
     }
 
@@ -448,17 +1495,107 @@ impl ErrorResponse {
         Error::Unauthorized(Json(ErrorResponse {
             message: message.to_string(),
             code: "error.unauthorized.custom".to_string(),
+            correlation_id: None,
+            errors: vec![],
         }))
     }
 
+    /// The request was understood but refused, regardless of the session's
+    /// permissions, such as for a missing CSRF token.
+    pub fn forbidden(message: &str) -> Error {
+        Error::Forbidden(Json(ErrorResponse {
+            message: message.to_string(),
+            code: "error.forbidden".to_string(),
+            correlation_id: None,
+            errors: vec![],
+        }))
+    }
+
+    pub fn not_found(entity: &str, id: &str) -> Error {
+        Error::NotFound(Json(ErrorResponse {
+            message: format!("{} with id {} does not exist.", entity, id),
+            code: "error.not_found".to_string(),
+            correlation_id: None,
+            errors: vec![],
+        }))
+    }
+
+    pub fn conflict(message: &str) -> Error {
+        Error::Conflict(Json(ErrorResponse {
+            message: message.to_string(),
+            code: "error.conflict".to_string(),
+            correlation_id: None,
+            errors: vec![],
+        }))
+    }
+
+    pub fn unavailable(message: &str) -> Error {
+        Error::Unavailable(Json(ErrorResponse {
+            message: message.to_string(),
+            code: "error.unavailable".to_string(),
+            correlation_id: None,
+            errors: vec![],
+        }))
+    }
+
+    /// A conflict naming the key reported as violated by a MySQL duplicate-entry
+    /// message, see [`ErrorResponse::db_err`].
+    pub fn duplicate(detail: &str) -> Error {
+        Self::conflict(&format!(
+            "A record with the same {} already exists.",
+            conflicting_key(detail)
+        ))
+    }
+
     pub fn db_err(message: DbErr) -> Error {
+        // Every pooled connection being in use is not a fault of the request,
+        // so is reported as such rather than as a failed query.
+        if let DbErr::ConnectionAcquire(ConnAcquireErr::Timeout) = message {
+            return Self::unavailable(
+                "All database connections are in use, please try again shortly.",
+            );
+        }
+
+        if let DbErr::RecordNotFound(detail) = &message {
+            return Error::NotFound(Json(ErrorResponse {
+                message: format!("Record not found, reason: {}", detail),
+                code: "error.not_found".to_string(),
+                correlation_id: None,
+                errors: vec![],
+            }));
+        }
+
+        if let Some(SqlErr::UniqueConstraintViolation(detail)) = message.sql_err() {
+            return Self::duplicate(&detail);
+        }
+
         Error::DbError(Json(ErrorResponse {
             message: format!("SQL error, reason: {}", message),
             code: "error.database.query".to_string(),
+            correlation_id: None,
+            errors: vec![],
         }))
     }
 }
 
+/// Extracts the violated key from a MySQL duplicate-entry message, i.e.
+/// `Duplicate entry 'x' for key 'Supplier.PRIMARY'` yields `id`.
+#[cfg(feature = "process")]
+fn conflicting_key(detail: &str) -> &str {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"for key '(?:[^'.]*\.)?([^']+)'").unwrap();
+    }
+
+    match RE.captures(detail).and_then(|c| c.get(1)) {
+        Some(key) if key.as_str() == "PRIMARY" => "id",
+        Some(key) => key.as_str(),
+        None => "key",
+    }
+}
+
+/// Maps database errors by [`ErrorResponse::db_err`], such that handlers may use `?`
+/// on queries: missing records give a 404, duplicate keys a 409, an exhausted pool
+/// a 503 and anything else a 500.
 impl From<DbErr> for Error {
     fn from(value: DbErr) -> Self {
         ErrorResponse::db_err(value)
@@ -485,23 +1622,150 @@ impl<T: JsonSchema> Into<Json<T>> for Wrapper<T> {
 }
 
 #[cfg(feature = "process")]
-#[derive(Debug, Responder)]
+#[derive(Debug, Clone)]
 pub enum Error {
-    #[response(status = 500, content_type = "json")]
     StandardError(Json<ErrorResponse>),
-    #[response(status = 400, content_type = "json")]
     InputError(Json<ErrorResponse>),
-    #[response(status = 401, content_type = "json")]
     Unauthorized(Json<ErrorResponse>),
-    #[response(status = 500, content_type = "json")]
+    Forbidden(Json<ErrorResponse>),
     DbError(Json<ErrorResponse>),
-    #[response(status = 500, content_type = "text")]
+    NotFound(Json<ErrorResponse>),
+    Conflict(Json<ErrorResponse>),
+    Unavailable(Json<ErrorResponse>),
     DemoDisabled(String),
 }
 
+#[cfg(feature = "process")]
+impl Error {
+    pub fn status(&self) -> Status {
+        match self {
+            Error::StandardError(_) => Status::InternalServerError,
+            Error::InputError(_) => Status::BadRequest,
+            Error::Unauthorized(_) => Status::Unauthorized,
+            Error::Forbidden(_) => Status::Forbidden,
+            Error::DbError(_) => Status::InternalServerError,
+            Error::NotFound(_) => Status::NotFound,
+            Error::Conflict(_) => Status::Conflict,
+            Error::Unavailable(_) => Status::ServiceUnavailable,
+            Error::DemoDisabled(_) => Status::InternalServerError,
+        }
+    }
+
+    /// The body the error would be responded with, such as to report the failure
+    /// of a single item within a larger request.
+    pub fn into_body(self) -> ErrorResponse {
+        match self {
+            Error::DemoDisabled(message) => ErrorResponse {
+                message,
+                code: "error.demo_disabled".to_string(),
+                correlation_id: None,
+                errors: vec![],
+            },
+            Error::StandardError(Json(body))
+            | Error::InputError(Json(body))
+            | Error::Unauthorized(Json(body))
+            | Error::Forbidden(Json(body))
+            | Error::DbError(Json(body))
+            | Error::NotFound(Json(body))
+            | Error::Conflict(Json(body))
+            | Error::Unavailable(Json(body)) => body,
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+impl<'r, 'o: 'r> Responder<'r, 'o> for Error {
+    /// Logs the failure against the request's session, such that every handler
+    /// reports errors alike whether it returns a [`Convert`](crate::guards::Convert)
+    /// or a `Result`.
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'o> {
+        let status = self.status();
+        let correlation_id = req.local_cache(|| RequestId(None)).0;
+
+        let (tenant, employee) = match req.local_cache(|| None::<Session>) {
+            Some(session) => (session.tenant_id.as_str(), session.employee.id.as_str()),
+            None => ("-", "-"),
+        };
+        let request_id = correlation_id.map_or("-".to_string(), |id| id.to_string());
+
+        if status.code >= 500 {
+            error!(
+                "{} {} failed, request_id={} tenant={} employee={}: {:?}",
+                req.method(),
+                req.uri().path(),
+                request_id,
+                tenant,
+                employee,
+                self
+            )
+        } else {
+            warn!(
+                "{} {} failed, request_id={} tenant={} employee={}: {:?}",
+                req.method(),
+                req.uri().path(),
+                request_id,
+                tenant,
+                employee,
+                self
+            )
+        }
+
+        match self {
+            Error::DemoDisabled(message) => (status, message).respond_to(req),
+            Error::StandardError(Json(mut body))
+            | Error::InputError(Json(mut body))
+            | Error::Unauthorized(Json(mut body))
+            | Error::Forbidden(Json(mut body))
+            | Error::DbError(Json(mut body))
+            | Error::NotFound(Json(mut body))
+            | Error::Conflict(Json(mut body))
+            | Error::Unavailable(Json(mut body)) => {
+                body.correlation_id = correlation_id.map(|id| id.to_string());
+                (status, Json(body)).respond_to(req)
+            }
+        }
+    }
+}
+
 impl OpenApiResponderInner for Error {
-    fn responses(_gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
-        Ok(Responses::default())
+    /// Documents each status an error may be responded with, all of which carry
+    /// an [`ErrorResponse`] body.
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let schema = gen.json_schema::<ErrorResponse>();
+        let mut responses = Responses::default();
+
+        for (status, description) in [
+            ("400", "Bad Request, the input given was invalid"),
+            (
+                "401",
+                "Unauthorized, the session is missing or lacks access",
+            ),
+            ("403", "Forbidden, the request was refused"),
+            ("404", "Not Found"),
+            ("409", "Conflict, the entity does not permit the action"),
+            ("500", "Internal Server Error"),
+            ("503", "Service Unavailable, the database was not reached"),
+        ] {
+            let response = Response {
+                description: description.to_string(),
+                content: vec![(
+                    "application/json".to_string(),
+                    MediaType {
+                        schema: Some(schema.clone()),
+                        ..Default::default()
+                    },
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            };
+
+            responses
+                .responses
+                .insert(status.to_string(), RefOr::from(response));
+        }
+
+        Ok(responses)
     }
 }
 
@@ -1,5 +1,5 @@
 use crate::entities::customer::ActiveModel;
-use crate::{ContactInformation, Customer, CustomerInput, NoteList};
+use crate::{ContactInformation, Customer, CustomerInput, NoteList, Region};
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
 use serde_json::json;
@@ -9,14 +9,15 @@ use uuid::Uuid;
 use crate::entities::customer;
 
 impl CustomerInput {
-    pub(crate) fn into_active(self, tenant_id: String) -> ActiveModel {
+    /// Reads the contact's mobile number as one of `region`, the tenant's region.
+    pub(crate) fn into_active(self, tenant_id: String, region: Region) -> ActiveModel {
         let id = Uuid::new_v4().to_string();
 
         ActiveModel {
             id: Set(id),
             name: Set(self.name),
 
-            contact: Set(json!(self.contact.into_major())),
+            contact: Set(json!(self.contact.into_major_in(region))),
             customer_notes: Set(json!(self.customer_notes)),
 
             balance: Set(self.balance),
@@ -29,12 +30,17 @@ impl CustomerInput {
         }
     }
 
-    pub(crate) fn from_existing(self, customer: Customer, tenant_id: String) -> ActiveModel {
+    pub(crate) fn from_existing(
+        self,
+        customer: Customer,
+        tenant_id: String,
+        region: Region,
+    ) -> ActiveModel {
         ActiveModel {
             id: Set(customer.id),
             name: Set(self.name),
 
-            contact: Set(json!(self.contact.into_major())),
+            contact: Set(json!(self.contact.into_major_in(region))),
             customer_notes: Set(json!(self.customer_notes)),
             accepts_marketing: Set(self.accepts_marketing),
             tenant_id: Set(tenant_id),
@@ -1,13 +1,16 @@
 use super::{Customer, CustomerInput};
 use crate::catchers::Validated;
 use crate::guards::Convert;
-use crate::methods::{Action, ContactInformation, CustomerWithTransactionsOut, Error, Transaction};
+use crate::methods::{
+    Action, ContactInformation, CustomerWithTransactionsOut, Error, Tenant, Transaction,
+};
 use crate::pool::InternalDb;
-use crate::{check_permissions, Session};
+use crate::{check_demo, check_permissions, DemoMode, Session};
 use okapi::openapi3::OpenApi;
 use rocket::get;
 use rocket::post;
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
 
@@ -99,8 +102,9 @@ pub async fn get_by_addr(db: InternalDb, session: Session, addr: &str) -> Conver
 
 #[openapi(tag = "Customer")]
 #[post("/generate")]
-async fn generate(db: InternalDb, session: Session) -> Convert<Customer> {
+async fn generate(db: InternalDb, session: Session, demo: &State<DemoMode>) -> Convert<Customer> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Customer::generate(session, &db.0).await.into()
 }
 
@@ -113,9 +117,17 @@ async fn update(
     id: &str,
 ) -> Convert<Customer> {
     check_permissions!(session.clone(), Action::ModifyCustomer);
-    Customer::update(input_data.data(), session, id, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
         .await
-        .into()
+        .and_then(|region| input.contact.ensure_valid("contact", region))
+    {
+        return Err(e).into();
+    }
+
+    Customer::update(input, session, id, &db.0).await.into()
 }
 
 #[openapi(tag = "Customer")]
@@ -127,7 +139,17 @@ async fn update_by_input(
     input_data: Validated<Json<CustomerInput>>,
 ) -> Convert<Customer> {
     check_permissions!(session.clone(), Action::ModifyCustomer);
-    Customer::update_by_input(input_data.data(), session, id, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
+        .await
+        .and_then(|region| input.contact.ensure_valid("contact", region))
+    {
+        return Err(e).into();
+    }
+
+    Customer::update_by_input(input, session, id, &db.0)
         .await
         .into()
 }
@@ -141,7 +163,17 @@ async fn update_contact_info(
     id: &str,
 ) -> Convert<Customer> {
     check_permissions!(session.clone(), Action::ModifyCustomer);
-    Customer::update_contact_information(input_data.data(), id, session, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
+        .await
+        .and_then(|region| input.ensure_valid("", region))
+    {
+        return Err(e).into();
+    }
+
+    Customer::update_contact_information(input, id, session, &db.0)
         .await
         .into()
 }
@@ -155,7 +187,11 @@ pub async fn create(
 ) -> Result<Json<Customer>, Error> {
     check_permissions!(session.clone(), Action::CreateCustomer);
 
-    let data = Customer::insert(input_data.data(), session.clone(), &db.0).await?;
+    let input = input_data.data();
+    let region = Tenant::fetch_region(&session.tenant_id, &db.0).await?;
+    input.contact.ensure_valid("contact", region)?;
+
+    let data = Customer::insert(input, session.clone(), &db.0).await?;
     let converted: Convert<Customer> = Customer::fetch_by_id(&data.last_insert_id, session, &db.0)
         .await
         .into();
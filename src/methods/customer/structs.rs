@@ -10,6 +10,8 @@ use crate::entities::prelude::Customer as Cust;
 #[cfg(feature = "process")]
 use crate::methods::convert_addr_to_geo;
 use crate::methods::{Address, ContactInformation, Id, NoteList};
+#[cfg(feature = "process")]
+use crate::Tenant;
 use crate::{methods::Error, ContactInformationInput, Session};
 #[cfg(feature = "process")]
 use sea_orm::QueryFilter;
@@ -20,7 +22,6 @@ use sea_orm::{
     JsonValue, QuerySelect, RuntimeErr, Set, Statement,
 };
 use sea_orm::{DbErr, DeleteResult, QueryOrder};
-use sea_orm::DbErr::Query;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use validator::Validate;
@@ -85,6 +86,7 @@ pub struct CustomerWithTransactionsOut {
 pub struct CustomerInput {
     pub name: String,
 
+    #[validate]
     pub contact: ContactInformationInput,
     pub customer_notes: NoteList,
 
@@ -101,7 +103,8 @@ impl Customer {
         session: Session,
         db: &DbConn,
     ) -> Result<InsertResult<ActiveModel>, Error> {
-        let insert_crud = cust.into_active(session.tenant_id);
+        let region = Tenant::fetch_region(&session.tenant_id, db).await?;
+        let insert_crud = cust.into_active(session.tenant_id, region);
         Cust::insert(insert_crud)
             .exec(db)
             .await
@@ -147,6 +150,7 @@ impl Customer {
                 LEFT JOIN Transactions
                 ON
                     (REPLACE(JSON_EXTRACT(Transactions.customer, '$.customer_id'), '"', '')) = Customer.id
+                    AND Transactions.deleted_at IS NULL
                 WHERE
                     (LOWER(Customer.name) LIKE '%?1%' OR Customer.contact LIKE '%?1%')
                 AND Customer.tenant_id = '?2'
@@ -268,7 +272,8 @@ impl Customer {
         db: &DbConn,
     ) -> Result<Customer, Error> {
         let old_customer = Self::fetch_by_id(id, session.clone(), db).await?;
-        let customer = cust.from_existing(old_customer, session.tenant_id.clone());
+        let region = Tenant::fetch_region(&session.tenant_id, db).await?;
+        let customer = cust.from_existing(old_customer, session.tenant_id.clone(), region);
 
         Cust::update(customer).exec(db).await?;
 
@@ -299,8 +304,6 @@ impl Customer {
                     ..cust.contact
                 }));
 
-                println!("Have active model: {:?}", model);
-
                 model.update(db).await?;
 
                 Self::fetch_by_id(id, session, db).await
@@ -337,8 +340,6 @@ impl Customer {
                     ..cust.contact
                 }));
 
-                println!("Have active model: {:?}", model);
-
                 model.update(db).await?;
 
                 Self::fetch_by_id(id, session, db).await
@@ -1,7 +1,7 @@
 use crate::entities::employee::{ActiveModel, Model};
 use crate::{
     Access, AccountType, Action, Attendance, ContactInformation, Employee, EmployeeAuth,
-    EmployeeInput, History, Name,
+    EmployeeInput, History, Name, Region, StoreAccess,
 };
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
@@ -16,13 +16,12 @@ impl From<EmployeeInput> for Employee {
             id,
             rid: value.rid.to_string(),
             name: Name::from_string(value.name),
-            auth: EmployeeAuth {
-                hash: String::new(),
-            },
+            auth: EmployeeAuth::default(),
             contact: value.contact.into_major(),
             clock_history: value.clock_history,
             level: value.level,
             account_type: value.account_type,
+            stores: value.stores,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -30,21 +29,24 @@ impl From<EmployeeInput> for Employee {
 }
 
 impl EmployeeInput {
+    /// Reads the contact's mobile number as one of `region`, the tenant's region.
     pub(crate) fn into_active(
         self,
         id: String,
         rid: i32,
         tenant_id: String,
-        hash: String,
+        auth: EmployeeAuth,
+        region: Region,
     ) -> ActiveModel {
         ActiveModel {
             id: Set(id),
             rid: Set(format!("{:0>#4}", rid)),
             name: Set(json!(Name::from_string(self.name))),
-            auth: Set(json!(EmployeeAuth { hash })),
-            contact: Set(json!(self.contact.into_major())),
+            auth: Set(json!(auth)),
+            contact: Set(json!(self.contact.into_major_in(region))),
             clock_history: Set(json!(self.clock_history)),
             level: Set(json!(self.level)),
+            stores: Set(Some(json!(self.stores))),
             tenant_id: Set(tenant_id),
             account_type: Set(json!(self.account_type)),
             created_at: Set(Utc::now().naive_utc()),
@@ -52,7 +54,12 @@ impl EmployeeInput {
         }
     }
 
-    pub(crate) fn from_existing(self, employee: Employee, tenant_id: String) -> ActiveModel {
+    pub(crate) fn from_existing(
+        self,
+        employee: Employee,
+        tenant_id: String,
+        region: Region,
+    ) -> ActiveModel {
         ActiveModel {
             id: Set(employee.id),
             rid: Set(format!("{:0>#4}", self.rid)),
@@ -60,11 +67,12 @@ impl EmployeeInput {
 
             name: Set(json!(Name::from_string(self.name))),
 
-            contact: Set(json!(self.contact.into_major())),
+            contact: Set(json!(self.contact.into_major_in(region))),
             clock_history: Set(json!(self.clock_history)),
 
             level: Set(json!(self.level)),
             account_type: Set(json!(self.account_type)),
+            stores: Set(Some(json!(self.stores))),
             updated_at: Set(Utc::now().naive_utc()),
 
             ..Default::default()
@@ -84,6 +92,10 @@ impl From<Model> for Employee {
             clock_history: serde_json::from_value::<Vec<History<Attendance>>>(val.clock_history)
                 .unwrap(),
             level: serde_json::from_value::<Vec<Access<Action>>>(val.level).unwrap(),
+            stores: val
+                .stores
+                .and_then(|stores| serde_json::from_value::<StoreAccess>(stores).ok())
+                .unwrap_or_default(),
             created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
             updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
         }
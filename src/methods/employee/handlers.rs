@@ -1,19 +1,20 @@
 use crate::catchers::Validated;
 use crate::entities::session;
 use crate::guards::Convert;
-use crate::methods::{cookie_status_wrapper, Error, ErrorResponse, History, Name};
+use crate::methods::{cookie_status_wrapper, Error, ErrorResponse, History, Name, Tenant};
 use crate::pool::{Db, InternalDb};
 use crate::SessionVariant;
 use crate::{
-    check_permissions, create_cookie, example_employee, tenants, Auth, AuthenticationLog, Customer,
-    Kiosk, LogRequest, Session,
+    add_session_cookies, check_demo, check_permissions, session_expiry, tenants, Auth,
+    AuthenticationLog, DemoMode, Kiosk, LogRequest, Session, CSRF_COOKIE, SESSION_COOKIE,
 };
-use chrono::{Days, Duration as ChronoDuration, Utc};
+use chrono::Utc;
 use okapi::openapi3::OpenApi;
 use rocket::get;
 use rocket::http::CookieJar;
 use rocket::post;
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_db_pools::Connection;
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
@@ -21,7 +22,10 @@ use sea_orm::{EntityTrait, Set};
 use serde_json::json;
 use uuid::Uuid;
 
-use super::{Action, Attendance, Employee, EmployeeInput, TrackType};
+use super::{
+    Action, Attendance, CurrentSession, Employee, EmployeeInput, PasswordChange, PasswordReset,
+    Role, RoleAssignment, RoleTemplate, TrackType,
+};
 
 pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
     openapi_get_routes_spec![
@@ -41,7 +45,13 @@ pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, Ope
         generate,
         auth,
         get_status,
-        logout
+        logout,
+        reset_password,
+        change_password,
+        get_roles,
+        assign_role,
+        revoke_sessions,
+        me
     ]
 }
 
@@ -52,6 +62,21 @@ pub async fn whoami(session: Session) -> Convert<Employee> {
     Ok(session.employee).into()
 }
 
+/// The signed in employee, their permissions and tenant. Unlike other routes
+/// this responds with a 401 rather than failing the request guard when there
+/// is no valid session.
+#[openapi(tag = "Employee")]
+#[get("/auth/me")]
+pub async fn me(
+    conn: Connection<Db>,
+    cookies: &CookieJar<'_>,
+) -> Result<Json<CurrentSession>, Error> {
+    let db = conn.into_inner();
+    let session = cookie_status_wrapper(&db, cookies).await?;
+
+    Ok(Json(session.into()))
+}
+
 #[openapi(tag = "Employee")]
 #[get("/<id>")]
 pub async fn get(db: InternalDb, id: &str, session: Session) -> Convert<Employee> {
@@ -112,8 +137,9 @@ pub async fn get_by_level(db: InternalDb, session: Session, level: i32) -> Conve
 
 #[openapi(tag = "Employee")]
 #[post("/generate")]
-async fn generate(db: InternalDb, session: Session) -> Convert<Employee> {
+async fn generate(db: InternalDb, session: Session, demo: &State<DemoMode>) -> Convert<Employee> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Employee::generate(&db.0, session).await.into()
 }
 
@@ -126,9 +152,17 @@ async fn update(
     input_data: Validated<Json<Employee>>,
 ) -> Convert<Employee> {
     check_permissions!(session.clone(), Action::ModifyEmployee);
-    Employee::update(input_data.data(), session, id, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
         .await
-        .into()
+        .and_then(|region| input.contact.ensure_valid("contact", region))
+    {
+        return Err(e).into();
+    }
+
+    Employee::update(input, session, id, &db.0).await.into()
 }
 
 #[openapi(tag = "Employee")]
@@ -140,17 +174,127 @@ async fn update_by_input(
     input_data: Validated<Json<EmployeeInput>>,
 ) -> Convert<Employee> {
     check_permissions!(session.clone(), Action::ModifyEmployee);
-    Employee::update_by_input(input_data.data(), session, id, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
+        .await
+        .and_then(|region| input.contact.ensure_valid("contact", region))
+    {
+        return Err(e).into();
+    }
+
+    Employee::update_by_input(input, session, id, &db.0)
         .await
         .into()
 }
 
+/// The roles which may be assigned to an employee, and the permissions of each.
+#[openapi(tag = "Employee")]
+#[get("/roles")]
+pub async fn get_roles(session: Session) -> Convert<Vec<RoleTemplate>> {
+    check_permissions!(session.clone(), Action::FetchEmployee);
+    Ok(Role::templates()).into()
+}
+
+#[openapi(tag = "Employee")]
+#[post("/role/<id>", data = "<input_data>")]
+async fn assign_role(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    input_data: Json<RoleAssignment>,
+) -> Convert<Employee> {
+    check_permissions!(session.clone(), Action::ModifyEmployee);
+
+    let assignment = input_data.into_inner();
+    let level = assignment.role.level_with(&assignment.overrides);
+
+    // An employee may only hand out the permissions they hold themselves.
+    if level
+        .into_iter()
+        .any(|access| access.authority >= 1 && !session.clone().has_permission(access.action))
+    {
+        return Err(ErrorResponse::custom_unauthorized(
+            "Cannot grant permissions beyond your own.",
+        ))
+        .into();
+    }
+
+    Employee::assign_role(id, assignment, session, &db.0)
+        .await
+        .into()
+}
+
+/// Issues the employee a new password, which they must change upon next signing in.
+#[openapi(tag = "Employee")]
+#[post("/password/<id>", data = "<input_data>")]
+async fn reset_password(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    input_data: Validated<Json<PasswordReset>>,
+) -> Convert<Employee> {
+    check_permissions!(session.clone(), Action::ModifyEmployee);
+    Employee::set_password(id, &input_data.data().password, true, session, &db.0)
+        .await
+        .into()
+}
+
+/// Changes the employee's own password given their current one. Unlike signing in,
+/// this is permitted with an expired password so it may be rotated.
+#[openapi(tag = "Employee")]
+#[post("/auth/password/<id>", data = "<input_data>")]
+async fn change_password(
+    db: InternalDb,
+    id: &str,
+    input_data: Validated<Json<PasswordChange>>,
+) -> Result<(), Error> {
+    let input = input_data.data();
+    let session = Session::default_with_tenant(input.tenant_id.clone());
+
+    let employee = Employee::fetch_by_id(id, session.clone(), &db.0).await?;
+
+    if !employee.auth.verify_password(&input.current) {
+        return Err(ErrorResponse::custom_unauthorized(
+            "Invalid password or id.",
+        ));
+    }
+
+    Employee::set_password(id, &input.new, false, session, &db.0).await?;
+
+    Ok(())
+}
+
+/// Signs the employee out of every device, as when one is lost or they leave.
+#[openapi(tag = "Employee")]
+#[post("/<id>/sessions/revoke")]
+async fn revoke_sessions(db: InternalDb, session: Session, id: &str) -> Result<Json<u64>, Error> {
+    check_permissions!(session.clone(), Action::RevokeSessions);
+
+    let employee = Employee::fetch_by_id(id, session.clone(), &db.0).await?;
+    let revoked = Session::revoke_all(&employee.id, &session.tenant_id, &db.0).await?;
+
+    ::log::warn!(
+        "Revoked {} session(s) of employee {} at the request of {}",
+        revoked,
+        employee.id,
+        session.employee.id
+    );
+
+    Ok(Json(revoked))
+}
+
 #[openapi(tag = "Employee")]
 #[post("/logout")]
-pub async fn logout(cookies: &CookieJar<'_>,) -> Result<(), Error> {
-    match cookies.get("os-stock-key") {
+pub async fn logout(cookies: &CookieJar<'_>) -> Result<(), Error> {
+    if let Some(cookie) = cookies.get(CSRF_COOKIE) {
+        cookies.remove(cookie.clone());
+    }
+
+    match cookies.get(SESSION_COOKIE) {
         Some(cookie) => Ok(cookies.remove(cookie.clone())),
-        None => Err(ErrorResponse::create_error("Cookie not found."))
+        None => Err(ErrorResponse::create_error("Cookie not found.")),
     }
 }
 
@@ -165,6 +309,8 @@ pub async fn auth(
     let input = input_data.data();
     let default_session = Session::default_with_tenant(input.tenant_id.clone());
 
+    Kiosk::ensure_usable(&input.kiosk_id, default_session.clone(), &db.0).await?;
+
     let verified = Employee::verify(id, default_session, &input.pass, &db.0).await?;
 
     match verified {
@@ -173,11 +319,9 @@ pub async fn auth(
         )),
         true => {
             // User is authenticated, lets give them an API key to work with...
-            let api_key = Uuid::new_v4().to_string();
+            let api_key = Session::generate_key();
             let session_id = Uuid::new_v4().to_string();
-            let exp = Utc::now()
-                .checked_add_signed(ChronoDuration::minutes(10))
-                .unwrap();
+            let exp = session_expiry(input.remember);
 
             let tenant_data: Option<tenants::Model> =
                 tenants::Entity::find_by_id(input.tenant_id.clone())
@@ -186,18 +330,21 @@ pub async fn auth(
 
             match tenant_data {
                 Some(data) => {
+                    Session::make_room(id, &data.tenant_id, &db.0).await?;
+
                     session::Entity::insert(session::ActiveModel {
                         id: Set(session_id.to_string()),
-                        key: Set(api_key.clone()),
+                        key: Set(Session::hash_key(&api_key)),
                         employee_id: Set(id.to_string()),
                         expiry: Set(exp.naive_utc()),
                         tenant_id: Set(data.tenant_id),
                         variant: Set(json!(SessionVariant::AccessToken)),
+                        created_at: Set(Utc::now().naive_utc()),
                     })
                     .exec(&db.0)
                     .await?;
 
-                    cookies.add(create_cookie(api_key.clone()));
+                    add_session_cookies(cookies, api_key.clone(), exp);
                     Ok(Json(api_key))
                 }
                 None => Err(ErrorResponse::create_error("Tenant does not exist.")),
@@ -217,6 +364,8 @@ pub async fn auth_rid(
     let input = input_data.data();
     let session = Session::default_with_tenant(input.tenant_id.clone());
 
+    Kiosk::ensure_usable(&input.kiosk_id, session.clone(), &db.0).await?;
+
     match Employee::verify_with_rid(rid, session.clone(), &input.pass, &db.0).await {
         Ok(data) => {
             let auth_log = AuthenticationLog {
@@ -225,11 +374,9 @@ pub async fn auth_rid(
             };
             Kiosk::auth_log(&input.kiosk_id, session.clone(), auth_log, &db.0).await?;
 
-            let api_key = Uuid::new_v4().to_string();
+            let api_key = Session::generate_key();
             let session_id = Uuid::new_v4().to_string();
-            let exp = Utc::now()
-                .checked_add_signed(ChronoDuration::minutes(10))
-                .unwrap();
+            let exp = session_expiry(input.remember);
 
             let tenant_data: Option<tenants::Model> =
                 tenants::Entity::find_by_id(input.tenant_id.clone())
@@ -238,18 +385,21 @@ pub async fn auth_rid(
 
             match tenant_data {
                 Some(tenant) => {
+                    Session::make_room(&data.id, &tenant.tenant_id, &db.0).await?;
+
                     session::Entity::insert(session::ActiveModel {
                         id: Set(session_id.to_string()),
-                        key: Set(api_key.clone()),
+                        key: Set(Session::hash_key(&api_key)),
                         employee_id: Set(data.id.to_string()),
                         expiry: Set(exp.naive_utc()),
                         tenant_id: Set(tenant.tenant_id),
                         variant: Set(json!(SessionVariant::AccessToken)),
+                        created_at: Set(Utc::now().naive_utc()),
                     })
                     .exec(&db.0)
                     .await?;
 
-                    cookies.add(create_cookie(api_key.clone()));
+                    add_session_cookies(cookies, api_key.clone(), exp);
                     Ok(Json(api_key))
                 }
                 None => Err(ErrorResponse::create_error("Tenant does not exist.")),
@@ -279,7 +429,11 @@ pub async fn create(
 ) -> Result<Json<Employee>, Error> {
     check_permissions!(session.clone(), Action::CreateEmployee);
 
-    let data = Employee::insert(input_data.data(), &db.0, session.clone(), None, None).await?;
+    let input = input_data.data();
+    let region = Tenant::fetch_region(&session.tenant_id, &db.0).await?;
+    input.contact.ensure_valid("contact", region)?;
+
+    let data = Employee::insert(input, &db.0, session.clone(), None, None).await?;
     let converted: Convert<Employee> = Employee::fetch_by_id(&data.last_insert_id, session, &db.0)
         .await
         .into();
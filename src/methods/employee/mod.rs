@@ -1,8 +1,10 @@
 mod conversions;
 #[cfg(feature = "process")]
 pub(crate) mod handlers;
+mod role;
 mod structs;
 
 #[cfg(feature = "process")]
 pub use handlers::*;
+pub use role::*;
 pub use structs::*;
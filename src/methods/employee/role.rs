@@ -0,0 +1,125 @@
+use enum_iterator::{all, Sequence};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{all_actions, Access, Action};
+
+/// A named bundle of permissions, assigned to an employee in place of
+/// setting each of their `Access` entries by hand.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Sequence, JsonSchema)]
+pub enum Role {
+    /// Serves customers at a kiosk.
+    Cashier,
+    /// Runs a store, its stock and its staff.
+    Manager,
+    /// May do anything.
+    Admin,
+}
+
+/// A role as offered to a client, alongside the permissions it grants.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct RoleTemplate {
+    pub role: Role,
+    pub level: Vec<Access<Action>>,
+}
+
+/// Assigns `role` to an employee, with `overrides` granting or revoking
+/// individual actions on top of it.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct RoleAssignment {
+    pub role: Role,
+    #[serde(default)]
+    pub overrides: Vec<Access<Action>>,
+}
+
+const CASHIER_ACTIONS: [Action; 12] = [
+    Action::FetchProduct,
+    Action::FetchCustomer,
+    Action::CreateCustomer,
+    Action::ModifyCustomer,
+    Action::FetchTransaction,
+    Action::CreateTransaction,
+    Action::FetchStore,
+    Action::FetchKiosk,
+    Action::FetchSupplier,
+    Action::FetchGeoLocation,
+    Action::FetchGiftCard,
+    Action::RedeemGiftCard,
+];
+
+const MANAGER_ACTIONS: [Action; 23] = [
+    Action::DeleteCustomer,
+    Action::FetchEmployee,
+    Action::ModifyEmployee,
+    Action::ModifyTransaction,
+    Action::DeleteTransaction,
+    Action::ModerateNotes,
+    Action::ReassignSalesperson,
+    Action::CreateProduct,
+    Action::ModifyProduct,
+    Action::BulkUpdatePrices,
+    Action::DeleteProduct,
+    Action::CreateStockAdjustmentIntent,
+    Action::ClearStockAdjustmentIntent,
+    Action::OverrideStock,
+    Action::ModifyStore,
+    Action::CreateSupplier,
+    Action::ModifySupplier,
+    Action::ModifyKiosk,
+    Action::ModifyKioskPreferences,
+    Action::LockKiosk,
+    Action::FetchReports,
+    Action::FetchAuditLog,
+    Action::IssueGiftCard,
+];
+
+impl Role {
+    /// Every action the role grants. A manager may do all a cashier may.
+    pub fn actions(&self) -> Vec<Action> {
+        match self {
+            Role::Cashier => CASHIER_ACTIONS.to_vec(),
+            Role::Manager => [CASHIER_ACTIONS.as_slice(), MANAGER_ACTIONS.as_slice()].concat(),
+            Role::Admin => all::<Action>().collect(),
+        }
+    }
+
+    /// The role as an employee's `level`, with every action present and only
+    /// those of the role granted.
+    pub fn level(&self) -> Vec<Access<Action>> {
+        let granted = self.actions();
+
+        all_actions()
+            .into_iter()
+            .map(|access| Access {
+                authority: granted.contains(&access.action) as i32,
+                action: access.action,
+            })
+            .collect()
+    }
+
+    /// The role's `level`, with each override replacing the entry for its action.
+    pub fn level_with(&self, overrides: &[Access<Action>]) -> Vec<Access<Action>> {
+        let mut level = self.level();
+
+        for access in overrides {
+            match level.iter_mut().find(|x| x.action == access.action) {
+                Some(entry) => entry.authority = access.authority,
+                None => level.push(access.clone()),
+            }
+        }
+
+        level
+    }
+
+    pub fn templates() -> Vec<RoleTemplate> {
+        all::<Role>()
+            .map(|role| RoleTemplate {
+                role,
+                level: role.level(),
+            })
+            .collect()
+    }
+}
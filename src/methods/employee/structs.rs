@@ -14,17 +14,23 @@ use uuid::Uuid;
 use crate::entities::employee;
 #[cfg(feature = "process")]
 use crate::entities::prelude::Employee as Epl;
-use crate::methods::{Address, ContactInformation, Email, History, Id, MobileNumber, Name};
-use crate::{ContactInformationInput, Customer, CustomerInput, Session};
+use crate::methods::{Address, ContactInformation, History, Id, Name, RoleAssignment};
+use crate::{ContactInformationInput, Session};
 
 #[cfg(feature = "process")]
 use crate::methods::convert_addr_to_geo;
+#[cfg(feature = "process")]
+use crate::Tenant;
 
 #[derive(Deserialize, Serialize, Clone, JsonSchema, Validate)]
 pub struct Auth {
     pub pass: String,
     pub kiosk_id: String,
     pub tenant_id: String,
+    /// Keeps the employee signed in for longer, as on a manager's own device
+    /// rather than a shared kiosk, see [`remember_duration`](crate::remember_duration).
+    #[serde(default)]
+    pub remember: bool,
 }
 
 #[cfg(feature = "types")]
@@ -48,17 +54,70 @@ pub struct Employee {
     pub rid: String,
     pub name: Name,
 
+    /// The password hash is never sent, only whether it must be rotated.
+    #[serde(default, serialize_with = "EmployeeAuth::serialize_status")]
+    #[schemars(with = "EmployeeAuthStatus")]
     pub auth: EmployeeAuth,
     pub contact: ContactInformation,
     pub clock_history: Vec<History<Attendance>>,
 
     pub level: Vec<Access<Action>>,
     pub account_type: AccountType,
+    #[serde(default)]
+    pub stores: StoreAccess,
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Who is signed in and what they may do, such that a client need not guess
+/// which controls to show.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct CurrentSession {
+    pub employee: Employee,
+    /// Every action the employee is permitted to perform.
+    pub permissions: Vec<Action>,
+    pub tenant_id: String,
+    pub expiry: DateTime<Utc>,
+}
+
+#[cfg(feature = "types")]
+impl From<Session> for CurrentSession {
+    fn from(session: Session) -> Self {
+        CurrentSession {
+            permissions: all::<Action>()
+                .filter(|action| session.clone().has_permission(action.clone()))
+                .collect(),
+            tenant_id: session.tenant_id,
+            expiry: session.expiry,
+            employee: session.employee,
+        }
+    }
+}
+
+/// The stores an employee may act upon.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub enum StoreAccess {
+    /// Every store of the tenant, as for managers overseeing several.
+    #[default]
+    All,
+    /// Only the listed stores.
+    Only(Vec<Id>),
+}
+
+#[cfg(feature = "types")]
+impl StoreAccess {
+    /// Whether the employee may act upon the store `store_id`.
+    pub fn permits(&self, store_id: &str) -> bool {
+        match self {
+            StoreAccess::All => true,
+            StoreAccess::Only(stores) => stores.iter().any(|id| id == store_id),
+        }
+    }
+}
+
 #[cfg(feature = "types")]
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
 pub struct Access<T> {
@@ -80,18 +139,29 @@ pub enum Action {
     DeleteEmployee,
     ModifyEmployee,
     FetchEmployee,
+    /// Sign an employee out of every device at once.
+    RevokeSessions,
 
     CreateTransaction,
     DeleteTransaction,
+    PurgeTransaction,
     ModifyTransaction,
     FetchTransaction,
+    /// Edit or remove notes written by other employees.
+    ModerateNotes,
+    /// Correct the employee credited with a sale.
+    ReassignSalesperson,
 
     CreateProduct,
     DeleteProduct,
     ModifyProduct,
+    /// Reprice every product carrying a tag at once.
+    BulkUpdatePrices,
 
     CreateStockAdjustmentIntent,
     ClearStockAdjustmentIntent,
+    /// Sell stock which is not on hand, taking it below zero.
+    OverrideStock,
 
     FetchProduct,
     CreateStore,
@@ -108,12 +178,22 @@ pub enum Action {
     DeleteKiosk,
     ModifyKiosk,
     ModifyKioskPreferences,
+    /// Lock a kiosk, preventing any sign in or sale from it, or unlock it.
+    LockKiosk,
     FetchKiosk,
 
     AccessAdminPanel,
     SuperUserDo,
     GenerateTemplateContent,
     FetchGeoLocation,
+    FetchReports,
+    FetchAuditLog,
+    CreateWebhook,
+    FetchWebhook,
+    /// Issue a gift card or store credit with an opening balance.
+    IssueGiftCard,
+    RedeemGiftCard,
+    FetchGiftCard,
 }
 
 #[cfg(feature = "types")]
@@ -126,13 +206,99 @@ pub fn all_actions() -> Vec<Access<Action>> {
         .collect::<Vec<_>>()
 }
 
+/// Passwords older than this must be changed before the employee may sign in again.
+pub const PASSWORD_MAX_AGE_DAYS: i64 = 90;
+
 #[cfg(feature = "types")]
 /// Stores a password hash, signed as a key using the users login ID.
 /// Upon logging in using a client portal, the pre-sign object is signed using the provided ID -
 /// if the hash matches that which is given, authentication can be approved.
-#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema, Validate)]
 pub struct EmployeeAuth {
     pub hash: String,
+    /// Set when the password was issued by someone else, i.e. on a reset,
+    /// such that the employee must choose their own.
+    #[serde(default)]
+    pub must_rotate: bool,
+    /// Unset for passwords made before rotation was tracked.
+    #[serde(default)]
+    pub last_changed: Option<DateTime<Utc>>,
+}
+
+/// The part of [`EmployeeAuth`] which may be shared, everything but the hash.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct EmployeeAuthStatus {
+    pub must_rotate: bool,
+    pub last_changed: Option<DateTime<Utc>>,
+}
+
+impl EmployeeAuth {
+    fn serialize_status<S>(auth: &EmployeeAuth, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        EmployeeAuthStatus {
+            must_rotate: auth.must_rotate,
+            last_changed: auth.last_changed,
+        }
+        .serialize(serializer)
+    }
+
+    /// Whether the password must be changed before it may be used to sign in.
+    pub fn needs_rotation(&self) -> bool {
+        self.must_rotate
+            || self.last_changed.is_some_and(|changed| {
+                Utc::now() - changed > chrono::Duration::days(PASSWORD_MAX_AGE_DAYS)
+            })
+    }
+}
+
+#[cfg(feature = "process")]
+impl EmployeeAuth {
+    /// Hashes `password` under a fresh salt, replacing the current one.
+    pub fn set_password(&mut self, password: &str, must_rotate: bool) -> Result<(), Error> {
+        let salt = rand::thread_rng().gen::<[u8; 16]>();
+
+        self.hash = argon2::hash_encoded(password.as_bytes(), &salt, &Config::original())
+            .map_err(|e| ErrorResponse::create_error(&e.to_string()))?;
+        self.must_rotate = must_rotate;
+        self.last_changed = Some(Utc::now());
+
+        Ok(())
+    }
+
+    /// Whether `password` is the one hashed. An unset or malformed hash matches nothing.
+    pub fn verify_password(&self, password: &str) -> bool {
+        argon2::verify_encoded(&self.hash, password.as_bytes()).unwrap_or(false)
+    }
+
+    /// Refuses a sign in with a password which is due to be rotated.
+    pub fn ensure_current(&self) -> Result<(), Error> {
+        if self.needs_rotation() {
+            return Err(ErrorResponse::custom_unauthorized(
+                "Password has expired and must be changed before signing in.",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Issues a new password for an employee, who must change it when next signing in.
+#[derive(Deserialize, Serialize, Clone, JsonSchema, Validate)]
+pub struct PasswordReset {
+    #[validate(length(min = 4))]
+    pub password: String,
+}
+
+/// Changes an employee's own password, which is possible whilst it is due to be rotated.
+#[derive(Deserialize, Serialize, Clone, JsonSchema, Validate)]
+pub struct PasswordChange {
+    pub current: String,
+    #[validate(length(min = 4))]
+    pub new: String,
+    pub tenant_id: String,
 }
 
 #[cfg(feature = "types")]
@@ -140,11 +306,14 @@ pub struct EmployeeAuth {
 pub struct EmployeeInput {
     pub name: String,
     pub rid: i32,
+    #[validate]
     pub contact: ContactInformationInput,
     pub password: Option<String>,
     pub clock_history: Vec<History<Attendance>>,
     pub level: Vec<Access<Action>>,
     pub account_type: AccountType,
+    #[serde(default)]
+    pub stores: StoreAccess,
 }
 
 impl Display for Employee {
@@ -182,9 +351,10 @@ impl Display for Employee {
     }
 }
 
-use crate::methods::Error;
+use crate::methods::{Error, ErrorResponse};
 #[cfg(feature = "process")]
 use argon2::{self, Config};
+use log::{debug, warn};
 use rand::Rng;
 use schemars::JsonSchema;
 use sea_orm::QueryOrder;
@@ -212,11 +382,11 @@ impl Employee {
             return Err(DbErr::AttrNotSet("Field `password` must be present".to_string()).into());
         }
 
-        let salt = b"randomsalt";
-        let config = Config::original();
-        let hash = argon2::hash_encoded(password.unwrap().as_bytes(), salt, &config).unwrap();
+        let mut auth = EmployeeAuth::default();
+        auth.set_password(&password.unwrap(), false)?;
 
-        let insert_crud = empl.into_active(id, rid, session.tenant_id, hash);
+        let region = Tenant::fetch_region(&session.tenant_id, db).await?;
+        let insert_crud = empl.into_active(id, rid, session.tenant_id, auth, region);
 
         Epl::insert(insert_crud)
             .exec(db)
@@ -232,7 +402,11 @@ impl Employee {
     ) -> Result<bool, Error> {
         let employee = Self::fetch_by_id(id, session, db).await?;
 
-        let is_valid = argon2::verify_encoded(&employee.auth.hash, pass.as_bytes()).unwrap();
+        let is_valid = employee.auth.verify_password(pass);
+
+        if is_valid {
+            employee.auth.ensure_current()?;
+        }
 
         Ok(is_valid)
     }
@@ -243,30 +417,33 @@ impl Employee {
         pass: &str,
         db: &DbConn,
     ) -> Result<Employee, Error> {
-        let employee = Self::fetch_by_rid(rid, session, db).await?;
+        let employee = Self::fetch_by_rid(rid, session.clone(), db).await?;
 
         let mut valid_user: Option<Employee> = None;
 
         for employee in employee {
-            println!("Validating employee");
+            let is_valid = employee.auth.verify_password(pass);
 
-            let is_valid = argon2::verify_encoded(&employee.auth.hash, pass.as_bytes())
-                .map_err(|e| DbErr::RecordNotFound(e.to_string()))?;
-
-            println!(
-                "Found employee is {}",
-                if is_valid { "Valid" } else { "Invalid" }
+            debug!(
+                "Employee {} with rid {} is {}",
+                employee.id,
+                rid,
+                if is_valid { "valid" } else { "invalid" }
             );
 
             if is_valid && valid_user.is_none() {
                 valid_user = Some(employee);
             } else if is_valid {
-                println!("User with same rid and password exists. Unsure which one to pass - insufficient information.")
+                warn!(
+                    "User with same rid and password exists. Unsure which one to pass - insufficient information. tenant={}",
+                    session.tenant_id
+                )
             }
         }
 
-        if valid_user.is_some() {
-            Ok(valid_user.unwrap())
+        if let Some(employee) = valid_user {
+            employee.auth.ensure_current()?;
+            Ok(employee)
         } else {
             Err(DbErr::Query(RuntimeErr::Internal(
                 "Unable to locate user. No user exists.".to_string(),
@@ -378,7 +555,6 @@ impl Employee {
             id: Set(id.to_string()),
             rid: Set(empl.rid),
             name: Set(json!(empl.name)),
-            auth: Set(json!(empl.auth)),
             clock_history: Set(json!(empl.clock_history)),
             level: Set(json!(empl.level)),
             ..Default::default()
@@ -396,7 +572,8 @@ impl Employee {
         db: &DbConn,
     ) -> Result<Employee, Error> {
         let old_employee = Self::fetch_by_id(id, session.clone(), db).await?;
-        let as_model = employee.from_existing(old_employee, session.tenant_id.clone());
+        let region = Tenant::fetch_region(&session.tenant_id, db).await?;
+        let as_model = employee.from_existing(old_employee, session.tenant_id.clone(), region);
 
         crate::entities::employee::Entity::update(as_model)
             .exec(db)
@@ -428,18 +605,77 @@ impl Employee {
         // When given time, re-write the track for the `update` API
         // to remove this as a limitation as this may become future
         // technical debt.
+        //
+        // The password is left as is, it is only changed through `set_password`.
         employee::ActiveModel {
             id: Set(id.to_string()),
             rid: Set(empl.rid),
             name: Set(json!(empl.name)),
-            auth: Set(json!(empl.auth)),
             contact: Set(json!(new_contact)),
             clock_history: Set(json!(empl.clock_history)),
             level: Set(json!(empl.level)),
+            stores: Set(Some(json!(empl.stores))),
             tenant_id: Set(session.clone().tenant_id),
             account_type: Set(json!(empl.account_type)),
             created_at: Set(empl.created_at.naive_utc()),
             updated_at: Set(empl.updated_at.naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+
+        Self::fetch_by_id(id, session, db).await
+    }
+
+    /// Replaces the employee's password. When set by someone else the employee
+    /// is made to rotate it upon their next sign in.
+    pub async fn set_password(
+        id: &str,
+        password: &str,
+        must_rotate: bool,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Employee, Error> {
+        let mut auth = Self::fetch_by_id(id, session.clone(), db).await?.auth;
+        auth.set_password(password, must_rotate)?;
+
+        employee::ActiveModel {
+            id: Set(id.to_string()),
+            auth: Set(json!(auth)),
+            updated_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+
+        Self::fetch_by_id(id, session, db).await
+    }
+
+    /// Sets the employee's `level` from the role's template and their overrides.
+    pub async fn assign_role(
+        id: &str,
+        assignment: RoleAssignment,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Employee, Error> {
+        // The employee must belong to the tenant, else the employee of another
+        // tenant could be granted access to this one.
+        let existing = Epl::find_by_id(id.to_string())
+            .filter(employee::Column::TenantId.eq(session.tenant_id.clone()))
+            .one(db)
+            .await?;
+
+        if existing.is_none() {
+            return Err(ErrorResponse::not_found("Employee", id));
+        }
+
+        let level = assignment.role.level_with(&assignment.overrides);
+
+        employee::ActiveModel {
+            id: Set(id.to_string()),
+            level: Set(json!(level)),
+            updated_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
         }
         .update(db)
         .await?;
@@ -482,6 +718,31 @@ impl ToString for TrackType {
     }
 }
 
+#[cfg(feature = "types")]
+impl Attendance {
+    /// Reads a stored clock history, skipping any malformed entries rather
+    /// than discarding the history as a whole.
+    pub fn history_from_value(value: serde_json::Value) -> Vec<History<Attendance>> {
+        match value {
+            serde_json::Value::Array(entries) => entries
+                .into_iter()
+                .filter_map(|entry| serde_json::from_value(entry).ok())
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(feature = "types")]
+impl Employee {
+    /// The employee's most recent attendance entry, if they have ever clocked in or out.
+    pub fn latest_attendance(&self) -> Option<&History<Attendance>> {
+        self.clock_history
+            .iter()
+            .max_by_key(|entry| entry.timestamp)
+    }
+}
+
 pub fn example_employee() -> EmployeeInput {
     EmployeeInput {
         password: Some("1232".to_string()),
@@ -497,12 +758,13 @@ pub fn example_employee() -> EmployeeInput {
                 street2: "".into(),
                 city: "Auckland".into(),
                 country: "New Zealand".into(),
-                po_code: "100".into(),
+                po_code: "1060".into(),
                 lat: -36.915500,
                 lon: 174.838740,
             },
         },
         account_type: AccountType::FrontLine,
+        stores: StoreAccess::All,
         clock_history: vec![
             History::<Attendance> {
                 item: Attendance {
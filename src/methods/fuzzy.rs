@@ -0,0 +1,75 @@
+/// The default number of single-character edits a name may differ from a query by.
+pub const DEFAULT_FUZZY_DISTANCE: usize = 2;
+
+/// The number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters needed to turn `a` into `b`, i.e. 1 for "Jonson" and
+/// "Johnson", and 1 for "Jhonson" and "Johnson".
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Distances between every prefix of `a` and of `b`.
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+/// How far `name` is from `query`, ignoring case. A name containing the query
+/// is 0 away, otherwise the distance is the least to the whole name or any one
+/// of its words, so "Jonson" is 1 away from "Carl Johnson".
+pub fn fuzzy_distance(query: &str, name: &str) -> usize {
+    let query = query.trim().to_lowercase();
+    let name = name.to_lowercase();
+
+    if name.contains(&query) {
+        return 0;
+    }
+
+    name.split_whitespace()
+        .map(|word| edit_distance(&query, word))
+        .fold(edit_distance(&query, &name), usize::min)
+}
+
+/// Filters `candidates`, pairs of a record and its name, to those within
+/// `max_distance` of `query`. Exact matches come first, followed by the
+/// remainder from most to least similar.
+pub fn fuzzy_rank<T>(
+    query: &str,
+    max_distance: usize,
+    candidates: impl IntoIterator<Item = (T, String)>,
+) -> Vec<T> {
+    let mut matches = candidates
+        .into_iter()
+        .filter_map(|(record, name)| {
+            let distance = fuzzy_distance(query, &name);
+            let exact = name.trim().eq_ignore_ascii_case(query.trim());
+
+            (distance <= max_distance).then_some((!exact, distance, name, record))
+        })
+        .collect::<Vec<_>>();
+
+    matches.sort_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+    matches.into_iter().map(|(.., record)| record).collect()
+}
@@ -0,0 +1,60 @@
+use crate::entities::gift_card::{ActiveModel, Model};
+use crate::entities::gift_card_redemption;
+use crate::methods::{from_cents, to_cents};
+use crate::{GiftCard, GiftCardInput, GiftCardRedemption};
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue::Set;
+
+impl From<Model> for GiftCard {
+    fn from(val: Model) -> Self {
+        GiftCard {
+            id: val.id,
+            code: val.code,
+            balance: from_cents(val.balance),
+            created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
+            updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
+        }
+    }
+}
+
+impl GiftCardInput {
+    pub(crate) fn into_active(self, id: String, tenant_id: String) -> ActiveModel {
+        ActiveModel {
+            id: Set(id),
+            tenant_id: Set(tenant_id),
+            code: Set(self.code),
+            balance: Set(to_cents(self.balance)),
+            created_at: Set(Utc::now().naive_utc()),
+            updated_at: Set(Utc::now().naive_utc()),
+        }
+    }
+}
+
+impl From<gift_card_redemption::Model> for GiftCardRedemption {
+    fn from(val: gift_card_redemption::Model) -> Self {
+        GiftCardRedemption {
+            id: val.id,
+            gift_card_id: val.gift_card_id,
+            transaction_id: val.transaction_id,
+            employee_id: val.employee_id,
+            amount: from_cents(val.amount),
+            balance_after: from_cents(val.balance_after),
+            timestamp: DateTime::from_naive_utc_and_offset(val.timestamp, Utc),
+        }
+    }
+}
+
+impl GiftCardRedemption {
+    pub(crate) fn into_active(self, tenant_id: String) -> gift_card_redemption::ActiveModel {
+        gift_card_redemption::ActiveModel {
+            id: Set(self.id),
+            tenant_id: Set(tenant_id),
+            gift_card_id: Set(self.gift_card_id),
+            transaction_id: Set(self.transaction_id),
+            employee_id: Set(self.employee_id),
+            amount: Set(to_cents(self.amount)),
+            balance_after: Set(to_cents(self.balance_after)),
+            timestamp: Set(self.timestamp.naive_utc()),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::{GiftCard, GiftCardBalance, GiftCardInput, GiftCardRedemption, RedemptionInput};
+use crate::catchers::Validated;
+use crate::guards::Convert;
+use crate::methods::employee::Action;
+use crate::pool::InternalDb;
+use crate::{check_permissions, Session};
+use okapi::openapi3::OpenApi;
+use rocket::serde::json::Json;
+use rocket::{get, post};
+use rocket_okapi::settings::OpenApiSettings;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
+
+pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![settings: issue, balance, redeem, get_redemptions]
+}
+
+#[openapi(tag = "Gift Card")]
+#[post("/", data = "<input_data>")]
+pub async fn issue(
+    db: InternalDb,
+    session: Session,
+    input_data: Validated<Json<GiftCardInput>>,
+) -> Convert<GiftCard> {
+    check_permissions!(session.clone(), Action::IssueGiftCard);
+    GiftCard::insert(input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Gift Card")]
+#[get("/<code>/balance")]
+pub async fn balance(db: InternalDb, session: Session, code: &str) -> Convert<GiftCardBalance> {
+    check_permissions!(session.clone(), Action::FetchGiftCard);
+    GiftCard::fetch_by_code(code, session, &db.0)
+        .await
+        .map(|card| GiftCardBalance {
+            code: card.code,
+            balance: card.balance,
+        })
+        .into()
+}
+
+/// Debits the card outside of a sale, such as when spent through another channel.
+/// Cards paid with at checkout are debited by the transaction itself.
+#[openapi(tag = "Gift Card")]
+#[post("/<code>/redeem", data = "<input_data>")]
+pub async fn redeem(
+    db: InternalDb,
+    session: Session,
+    code: &str,
+    input_data: Validated<Json<RedemptionInput>>,
+) -> Convert<GiftCardRedemption> {
+    check_permissions!(session.clone(), Action::RedeemGiftCard);
+    GiftCard::redeem(code, input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Gift Card")]
+#[get("/<code>/redemptions")]
+pub async fn get_redemptions(
+    db: InternalDb,
+    session: Session,
+    code: &str,
+) -> Convert<Vec<GiftCardRedemption>> {
+    check_permissions!(session.clone(), Action::FetchGiftCard);
+    GiftCard::fetch_redemptions(code, session, &db.0)
+        .await
+        .into()
+}
@@ -0,0 +1,9 @@
+#[cfg(feature = "process")]
+mod conversions;
+#[cfg(feature = "process")]
+pub(crate) mod handlers;
+mod structs;
+
+pub use self::structs::*;
+#[cfg(feature = "process")]
+pub use handlers::*;
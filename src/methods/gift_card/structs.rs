@@ -0,0 +1,232 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[cfg(feature = "process")]
+use crate::entities::prelude::{
+    GiftCard as GiftCardEntity, GiftCardRedemption as RedemptionEntity,
+};
+#[cfg(feature = "process")]
+use crate::entities::{gift_card, gift_card_redemption};
+use crate::methods::{from_cents, to_cents, Error, Id, Payment, PaymentMethod};
+use crate::{ErrorResponse, Session};
+#[cfg(feature = "process")]
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DbConn, EntityTrait, QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+/// A prepaid balance, either sold as a gift card or issued as store credit,
+/// which is spent by paying with [`PaymentMethod::GiftCard`].
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct GiftCard {
+    pub id: Id,
+    /// Printed on the card, unique within the tenant.
+    pub code: String,
+    pub balance: f32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct GiftCardInput {
+    #[validate(length(min = 4))]
+    pub code: String,
+    #[validate(range(min = 0.0))]
+    pub balance: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct GiftCardBalance {
+    pub code: String,
+    pub balance: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct RedemptionInput {
+    #[validate(range(min = 0.01))]
+    pub amount: f32,
+    /// The transaction the card was spent on, if any.
+    pub transaction_id: Option<Id>,
+}
+
+/// A single debit of a gift card, kept for reconciliation.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct GiftCardRedemption {
+    pub id: Id,
+    pub gift_card_id: Id,
+    pub transaction_id: Option<Id>,
+    pub employee_id: Id,
+    pub amount: f32,
+    pub balance_after: f32,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The amount paid with each gift card across `payments`, by code.
+pub fn gift_card_payments(payments: &[Payment]) -> Vec<(String, f32)> {
+    let mut totals: Vec<(String, f32)> = vec![];
+
+    for payment in payments {
+        if let PaymentMethod::GiftCard(code) = &payment.payment_method {
+            match totals.iter_mut().find(|(c, _)| c == code) {
+                Some((_, total)) => *total += payment.amount.quantity,
+                None => totals.push((code.clone(), payment.amount.quantity)),
+            }
+        }
+    }
+
+    totals
+}
+
+#[cfg(feature = "methods")]
+impl GiftCard {
+    pub async fn insert(
+        input: GiftCardInput,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<GiftCard, Error> {
+        if Self::fetch_by_code(&input.code, session.clone(), db)
+            .await
+            .is_ok()
+        {
+            return Err(ErrorResponse::conflict(&format!(
+                "Gift card {} already exists.",
+                input.code
+            )));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let code = input.code.clone();
+
+        GiftCardEntity::insert(input.into_active(id, session.tenant_id.clone()))
+            .exec(db)
+            .await?;
+
+        Self::fetch_by_code(&code, session, db).await
+    }
+
+    pub async fn fetch_by_code<C: ConnectionTrait>(
+        code: &str,
+        session: Session,
+        db: &C,
+    ) -> Result<GiftCard, Error> {
+        let card = GiftCardEntity::find()
+            .filter(gift_card::Column::TenantId.eq(session.tenant_id))
+            .filter(gift_card::Column::Code.eq(code))
+            .one(db)
+            .await?;
+
+        match card {
+            Some(c) => Ok(c.into()),
+            None => Err(ErrorResponse::not_found("Gift card", code)),
+        }
+    }
+
+    /// Ensures each card paid with in `payments` exists and holds enough to
+    /// cover what is paid with it, before any is debited.
+    pub async fn ensure_payable(
+        payments: &[Payment],
+        session: Session,
+        db: &DbConn,
+    ) -> Result<(), Error> {
+        for (code, amount) in gift_card_payments(payments) {
+            let card = Self::fetch_by_code(&code, session.clone(), db).await?;
+
+            if to_cents(card.balance) < to_cents(amount) {
+                return Err(ErrorResponse::create_error(&format!(
+                    "Gift card {} holds {:.2}, less than the {:.2} paid with it.",
+                    code, card.balance, amount
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Debits `input.amount` from the card, refusing to overdraw it, and
+    /// records the redemption. Paying for a sale, `db` is the database transaction
+    /// recording it, such that the sale is not recorded without the card being debited.
+    pub async fn redeem<C: ConnectionTrait>(
+        code: &str,
+        input: RedemptionInput,
+        session: Session,
+        db: &C,
+    ) -> Result<GiftCardRedemption, Error> {
+        let card = Self::fetch_by_code(code, session.clone(), db).await?;
+        let cents = to_cents(input.amount);
+
+        if cents <= 0 {
+            return Err(ErrorResponse::custom_input_error(
+                "Redeemed amount must be greater than zero.",
+            ));
+        }
+
+        // Debit only while the balance covers it, such that concurrent
+        // redemptions cannot overdraw the card between the fetch and update.
+        let res = GiftCardEntity::update_many()
+            .col_expr(
+                gift_card::Column::Balance,
+                Expr::col(gift_card::Column::Balance).sub(cents),
+            )
+            .col_expr(
+                gift_card::Column::UpdatedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(gift_card::Column::Id.eq(card.id.clone()))
+            .filter(gift_card::Column::TenantId.eq(session.tenant_id.clone()))
+            .filter(gift_card::Column::Balance.gte(cents))
+            .exec(db)
+            .await?;
+
+        if res.rows_affected == 0 {
+            return Err(ErrorResponse::create_error(&format!(
+                "Gift card {} holds {:.2}, less than the {:.2} redeemed.",
+                code, card.balance, input.amount
+            )));
+        }
+
+        let balance_after = Self::fetch_by_code(code, session.clone(), db)
+            .await?
+            .balance;
+
+        let redemption = GiftCardRedemption {
+            id: Uuid::new_v4().to_string(),
+            gift_card_id: card.id,
+            transaction_id: input.transaction_id,
+            employee_id: session.employee.id.clone(),
+            amount: from_cents(cents),
+            balance_after,
+            timestamp: Utc::now(),
+        };
+
+        RedemptionEntity::insert(redemption.clone().into_active(session.tenant_id))
+            .exec(db)
+            .await?;
+
+        Ok(redemption)
+    }
+
+    /// Every redemption of the card, most recent first.
+    pub async fn fetch_redemptions(
+        code: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<GiftCardRedemption>, Error> {
+        let card = Self::fetch_by_code(code, session.clone(), db).await?;
+
+        let redemptions = RedemptionEntity::find()
+            .filter(gift_card_redemption::Column::GiftCardId.eq(card.id))
+            .filter(gift_card_redemption::Column::TenantId.eq(session.tenant_id))
+            .order_by_desc(gift_card_redemption::Column::Timestamp)
+            .all(db)
+            .await?;
+
+        Ok(redemptions.into_iter().map(|r| r.into()).collect())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::pool::InternalDb;
+use crate::{PoolStatistics, Readiness};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::{get, routes};
+use sea_orm::DatabaseConnection;
+
+/// Unauthenticated probes for load balancers and orchestration,
+/// mounted outside of the documented `/api` routes.
+pub fn probe_routes() -> Vec<rocket::Route> {
+    routes![health, ready]
+}
+
+/// Liveness, responds so long as the server is accepting requests.
+#[get("/health")]
+pub async fn health() -> Status {
+    Status::Ok
+}
+
+/// Readiness, responds with `503` if the database cannot be reached.
+#[get("/ready")]
+pub async fn ready(db: InternalDb) -> (Status, Json<Readiness>) {
+    let database = db.0.ping().await.is_ok();
+
+    let pool = match &db.0 {
+        DatabaseConnection::SqlxMySqlPoolConnection(_) => {
+            let pool = db.0.get_mysql_connection_pool();
+            let idle = pool.num_idle() as u32;
+
+            Some(PoolStatistics {
+                size: pool.size(),
+                active: pool.size().saturating_sub(idle),
+                idle,
+            })
+        }
+        _ => None,
+    };
+
+    let status = if database {
+        Status::Ok
+    } else {
+        Status::ServiceUnavailable
+    };
+
+    (
+        status,
+        Json(Readiness {
+            ready: database,
+            database,
+            pool,
+        }),
+    )
+}
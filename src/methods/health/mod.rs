@@ -0,0 +1,7 @@
+#[cfg(feature = "process")]
+pub(crate) mod handlers;
+pub mod structs;
+
+#[cfg(feature = "process")]
+pub use handlers::*;
+pub use structs::*;
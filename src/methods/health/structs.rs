@@ -0,0 +1,19 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct PoolStatistics {
+    /// Connections currently open, both in use and idle.
+    pub size: u32,
+    pub active: u32,
+    pub idle: u32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub database: bool,
+    pub pool: Option<PoolStatistics>,
+}
@@ -0,0 +1,154 @@
+#[cfg(feature = "process")]
+use std::time::Duration;
+
+#[cfg(feature = "process")]
+use log::warn;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::Address;
+#[cfg(feature = "process")]
+use crate::methods::{Error, ErrorResponse, Order, OrderType};
+
+/// How long an external provider is given to answer before the address is left unchecked.
+#[cfg(feature = "process")]
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An address as standardised by an [`AddressProvider`].
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AddressVerification {
+    /// The address as corrected by the provider, unchanged if nothing needed correcting.
+    pub address: Address,
+    /// How sure the provider is that the address is deliverable, from `0.0` to `1.0`.
+    pub confidence: f32,
+}
+
+/// Standardises and verifies addresses, such as against a postal service.
+///
+/// Providers are held as a trait object by [`AddressVerifier`], so that one may be
+/// swapped for another without changing its callers.
+#[cfg(feature = "process")]
+#[rocket::async_trait]
+pub trait AddressProvider: Send + Sync {
+    async fn standardize(&self, address: &Address) -> Result<AddressVerification, Error>;
+}
+
+/// Trusts every address as given, used when no provider is configured.
+#[cfg(feature = "process")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAddressProvider;
+
+#[cfg(feature = "process")]
+#[rocket::async_trait]
+impl AddressProvider for NoopAddressProvider {
+    async fn standardize(&self, address: &Address) -> Result<AddressVerification, Error> {
+        Ok(AddressVerification {
+            address: address.clone(),
+            confidence: 1.0,
+        })
+    }
+}
+
+/// Posts each address as JSON to an external service at `url`, which must answer
+/// with an [`AddressVerification`].
+#[cfg(feature = "process")]
+#[derive(Debug, Clone)]
+pub struct HttpAddressProvider {
+    pub url: String,
+}
+
+#[cfg(feature = "process")]
+#[rocket::async_trait]
+impl AddressProvider for HttpAddressProvider {
+    async fn standardize(&self, address: &Address) -> Result<AddressVerification, Error> {
+        let request = ureq::AgentBuilder::new()
+            .timeout(PROVIDER_TIMEOUT)
+            .build()
+            .post(&self.url);
+        let address = address.clone();
+
+        let answer = rocket::tokio::task::spawn_blocking(move || {
+            request
+                .send_json(address)
+                .map_err(|err| err.to_string())?
+                .into_json::<AddressVerification>()
+                .map_err(|err| err.to_string())
+        })
+        .await;
+
+        match answer {
+            Ok(Ok(verification)) => Ok(verification),
+            Ok(Err(err)) => Err(ErrorResponse::unavailable(&format!(
+                "Unable to verify address: {}",
+                err
+            ))),
+            Err(err) => Err(ErrorResponse::create_error(&err.to_string())),
+        }
+    }
+}
+
+/// Managed state standardising the destinations of orders as they are created,
+/// flagging any the provider is less than `threshold` sure of.
+#[cfg(feature = "process")]
+pub struct AddressVerifier {
+    provider: Box<dyn AddressProvider>,
+    pub threshold: f32,
+}
+
+#[cfg(feature = "process")]
+impl AddressVerifier {
+    pub fn new(provider: Box<dyn AddressProvider>, threshold: f32) -> Self {
+        AddressVerifier {
+            provider,
+            threshold,
+        }
+    }
+
+    /// Addresses are posted to `ADDRESS_PROVIDER_URL` when set, and otherwise trusted
+    /// as given. Those of less than `ADDRESS_CONFIDENCE_THRESHOLD` confidence, by
+    /// default `0.8`, are flagged.
+    pub fn from_env() -> Self {
+        let provider: Box<dyn AddressProvider> = match std::env::var("ADDRESS_PROVIDER_URL") {
+            Ok(url) if !url.trim().is_empty() => Box::new(HttpAddressProvider { url }),
+            _ => Box::new(NoopAddressProvider),
+        };
+
+        let threshold = std::env::var("ADDRESS_CONFIDENCE_THRESHOLD")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0.8);
+
+        AddressVerifier::new(provider, threshold)
+    }
+
+    /// Standardises the destination of each shipment in `orders`. Addresses the
+    /// provider is confident of are replaced by its correction, whilst others are
+    /// kept as given and flagged for review. Should the provider fail, the order
+    /// is left unchecked rather than rejected.
+    pub async fn verify(&self, orders: &mut [Order]) {
+        for order in orders
+            .iter_mut()
+            .filter(|o| matches!(o.order_type, OrderType::Shipment))
+        {
+            match self
+                .provider
+                .standardize(&order.destination.contact.address)
+                .await
+            {
+                Ok(verification) => {
+                    order.address_confidence = Some(verification.confidence);
+                    order.address_flagged = verification.confidence < self.threshold;
+
+                    if !order.address_flagged {
+                        order.destination.contact.address = verification.address;
+                    }
+                }
+                Err(err) => warn!(
+                    "Unable to verify the destination of order {}: {:?}",
+                    order.id, err
+                ),
+            }
+        }
+    }
+}
@@ -4,28 +4,30 @@ use crate::pool::InternalDb;
 use crate::session::ActiveModel;
 use crate::ContactInformationInput;
 use crate::{
-    all_actions, check_permissions, create_cookie, example_employee,
+    add_session_cookies, all_actions, check_demo, check_permissions, example_employee,
     methods::{
-        cookie_status_wrapper, Action, Address, Customer, Employee, Error, ErrorResponse, Product,
-        Promotion, Session, Store, Transaction,
+        cookie_status_wrapper, Action, Address, Customer, Employee, Error, ErrorResponse,
+        ListQuery, Product, Promotion, Session, Store, Transaction,
     },
     pool::Db,
-    session, AccountType, All, Distance, EmployeeInput, Kiosk, NewTenantInput, NewTenantResponse,
-    SessionRaw, SessionVariant, Tenant, TenantSettings,
+    session, session_duration, AccountType, All, DemoMode, Distance, EmployeeInput, Kiosk,
+    NewTenantInput, NewTenantResponse, SessionRaw, SessionVariant, StoreAccess, Tenant,
+    TenantSettings,
 };
 use chrono::{Days, Duration, Utc};
 use geo::point;
 use geo::VincentyDistance;
+use log::debug;
 use okapi::openapi3::OpenApi;
 use photon_geocoding::{
     filter::{ForwardFilter, PhotonLayer},
     LatLon, PhotonApiClient, PhotonFeature,
 };
-use rocket::{get, http::CookieJar, post, serde::json::Json};
+use rocket::{get, http::CookieJar, post, serde::json::Json, State};
 use rocket_db_pools::Connection;
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, Set};
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QuerySelect, Set};
 use serde_json::json;
 use std::env;
 use uuid::Uuid;
@@ -44,15 +46,11 @@ pub fn documented_routes(_settings: &OpenApiSettings) -> (Vec<rocket::Route>, Op
     ]
 }
 
-/// This route does not require authentication, but is not enabled in release mode.
+/// This route does not require authentication, but is only enabled in demo mode, see [`DemoMode`].
 #[openapi(tag = "Helpers")]
 #[post("/generate")]
-pub async fn generate_template(db: InternalDb) -> Result<Json<All>, Error> {
-    if env::var("DEMO").is_err() || env::var("DEMO").unwrap() == "0" {
-        return Err(Error::DemoDisabled(
-            "OpenStock is not in DEMO mode.".to_string(),
-        ));
-    }
+pub async fn generate_template(db: InternalDb, demo: &State<DemoMode>) -> Result<Json<All>, Error> {
+    check_demo!(demo);
 
     let tenant_id = "DEFAULT_TENANT";
     let tenant_id2 = "ALTERNATE_TENANT";
@@ -85,7 +83,11 @@ pub async fn generate_template(db: InternalDb) -> Result<Json<All>, Error> {
     let _employee2 = Employee::generate(&db.0, session2.clone()).await?;
 
     // Add other items (aggregated)
-    let stores = Store::generate(session.clone(), &db.0).await?;
+    let stores = Store::generate(session.clone(), &db.0)
+        .await?
+        .into_iter()
+        .map(|generated| generated.record)
+        .collect();
     let products = Product::generate(session.clone(), &db.0).await?;
     let customer = Customer::generate(session.clone(), &db.0).await?;
 
@@ -118,7 +120,11 @@ pub async fn generate_template(db: InternalDb) -> Result<Json<All>, Error> {
     )
     .await?;
 
-    let promotions = Promotion::generate(session, &db.0).await?;
+    let promotions = Promotion::generate(session, &db.0)
+        .await?
+        .into_iter()
+        .map(|generated| generated.record)
+        .collect();
 
     Ok(Json(All {
         employee,
@@ -160,6 +166,7 @@ pub async fn new_tenant(
         rid: 0000,
         password: Some(data.clone().password),
         account_type: AccountType::Managerial,
+        stores: StoreAccess::All,
         clock_history: vec![],
         contact: ContactInformationInput {
             name: data.clone().name,
@@ -196,7 +203,7 @@ pub async fn new_tenant(
 #[openapi(tag = "Helpers")]
 #[get("/session/<key>")]
 pub async fn assign_session_cookie(key: &str, cookies: &CookieJar<'_>) -> Result<(), Error> {
-    cookies.add(create_cookie(key.to_string()));
+    add_session_cookies(cookies, key.to_string(), Utc::now() + session_duration());
     Ok(())
 }
 
@@ -212,23 +219,19 @@ pub fn convert_addr_to_geo(address: &str) -> Result<Address, Error> {
     let result: Vec<PhotonFeature> = api.forward_search(address, None).unwrap();
 
     match result.get(0) {
-        Some(loc) => {
-            println!("{:?}", loc);
-
-            Ok(Address {
-                street: format!(
-                    "{} {}",
-                    loc.house_number.clone().unwrap_or("0".to_string()),
-                    loc.street.clone().unwrap_or("".to_string())
-                ),
-                street2: loc.district.clone().unwrap_or("".to_string()),
-                city: loc.city.clone().unwrap_or("".to_string()),
-                country: loc.country.clone().unwrap_or("".to_string()),
-                po_code: loc.postcode.clone().unwrap_or("".to_string()),
-                lat: loc.coords.lat,
-                lon: loc.coords.lon,
-            })
-        }
+        Some(loc) => Ok(Address {
+            street: format!(
+                "{} {}",
+                loc.house_number.clone().unwrap_or("0".to_string()),
+                loc.street.clone().unwrap_or("".to_string())
+            ),
+            street2: loc.district.clone().unwrap_or("".to_string()),
+            city: loc.city.clone().unwrap_or("".to_string()),
+            country: loc.country.clone().unwrap_or("".to_string()),
+            po_code: loc.postcode.clone().unwrap_or("".to_string()),
+            lat: loc.coords.lat,
+            lon: loc.coords.lon,
+        }),
         None => Err(ErrorResponse::create_error(
             "Unable to search for location.",
         )),
@@ -294,7 +297,7 @@ pub async fn distance_to_stores(
     check_permissions!(session.clone(), Action::FetchGeoLocation);
 
     let customer = Customer::fetch_by_id(id, session.clone(), &db.0).await?;
-    let stores = Store::fetch_all(session, &db.0).await?;
+    let stores = Store::fetch_all(&ListQuery::default(), session, &db.0).await?;
 
     let cust = point!(x: customer.contact.address.lat, y: customer.contact.address.lon);
 
@@ -326,7 +329,7 @@ pub async fn distance_to_stores_from_store(
     check_permissions!(session.clone(), Action::FetchGeoLocation);
 
     let store_ = Store::fetch_by_id(store_id, session.clone(), &db).await?;
-    let stores = Store::fetch_all(session, &db).await?;
+    let stores = Store::fetch_all(&ListQuery::default(), session, &db).await?;
 
     let cust = point!(x: store_.contact.address.lat, y: store_.contact.address.lon);
 
@@ -360,12 +363,12 @@ pub async fn refresh_token_create(
     let db = conn.into_inner();
     let session = cookie_status_wrapper(&db, cookies).await?;
 
-    let token_key = Uuid::new_v4().to_string();
+    let token_key = Session::generate_key();
     let token_id = Uuid::new_v4().to_string();
 
-    match session::Entity::insert(session::ActiveModel {
+    session::Entity::insert(session::ActiveModel {
         id: Set(token_id),
-        key: Set(token_key.clone()),
+        key: Set(Session::hash_key(&token_key)),
         variant: Set(json!(SessionVariant::RefreshToken(session.id))),
         employee_id: Set(session.employee.id),
         tenant_id: Set(session.tenant_id),
@@ -373,15 +376,14 @@ pub async fn refresh_token_create(
             .checked_add_signed(Duration::days(7))
             .unwrap()
             .naive_utc()),
+        created_at: Set(Utc::now().naive_utc()),
     })
     .exec(&db)
-    .await
-    {
-        // Note; we do not assign a cookie.
-        // This is a refresh token, not an access token.
-        Ok(_) => Ok(Json(token_key)),
-        Err(reason) => Err(ErrorResponse::db_err(reason)),
-    }
+    .await?;
+
+    // Note; we do not assign a cookie.
+    // This is a refresh token, not an access token.
+    Ok(Json(token_key))
 }
 
 /// Sending a refresh token through to this route,
@@ -399,14 +401,14 @@ pub async fn refresh_token_refresh(
     let db = conn.into_inner();
 
     let found_token = crate::entities::session::Entity::find()
-        .having(session::Column::Key.eq(token))
+        .having(session::Column::Key.eq(Session::hash_key(token)))
         .one(&db)
         .await?;
 
     match found_token {
         Some(e) => {
             let decoded_token: SessionRaw = e.into();
-            println!("GOT TOKEN: {:?}", decoded_token);
+            debug!("Refreshing access token for session {}", decoded_token.id);
 
             match decoded_token.variant {
                 SessionVariant::AccessToken => Err(ErrorResponse::create_error(
@@ -420,68 +422,65 @@ pub async fn refresh_token_refresh(
 
                     match access_token {
                         Some(token) => {
-                            println!("THEN GOT ACCESS TOKEN: {:?}", token);
-
                             let decoded_token_2: SessionRaw = token.into();
-                            let api_key = Uuid::new_v4().to_string();
+                            let api_key = Session::generate_key();
 
                             // Return an updated access token (we update
                             // to optimize the avoidance of a dangling token)
-                            match session::Entity::update(session::ActiveModel {
+                            session::Entity::update(session::ActiveModel {
                                 id: Set(decoded_token_2.id.to_string()),
-                                key: Set(api_key.clone()),
+                                key: Set(Session::hash_key(&api_key)),
                                 variant: Set(json!(SessionVariant::AccessToken)),
                                 ..Default::default()
                             })
                             .exec(&db)
-                            .await
-                            {
-                                Ok(_) => {
-                                    // Assign the cookie
-                                    cookies.add(create_cookie(api_key.clone()));
-
-                                    Ok(Json(api_key))
-                                }
-                                Err(reason) => Err(ErrorResponse::db_err(reason)),
-                            }
+                            .await?;
+
+                            // Assign the cookie
+                            add_session_cookies(cookies, api_key.clone(), decoded_token_2.expiry);
+
+                            Ok(Json(api_key))
                         }
                         None => {
-                            println!("ACCESS TOKEN EXPIRED! LET'S GENERATE ONE! {:?}", token);
+                            debug!(
+                                "Access token for session {} has expired, issuing a new one",
+                                decoded_token.id
+                            );
 
-                            let new_access_key = Uuid::new_v4().to_string();
+                            let new_access_key = Session::generate_key();
 
-                            let exp = Utc::now()
-                                .checked_add_signed(Duration::minutes(10))
-                                .unwrap();
+                            let exp = Utc::now().checked_add_signed(session_duration()).unwrap();
+
+                            Session::make_room(
+                                &decoded_token.employee_id,
+                                &decoded_token.tenant_id,
+                                &db,
+                            )
+                            .await?;
 
                             let access_token_to_insert = session::ActiveModel {
                                 id: Set(access_reference),
-                                key: Set(new_access_key.clone()),
+                                key: Set(Session::hash_key(&new_access_key)),
                                 employee_id: Set(decoded_token.employee_id),
                                 expiry: Set(exp.naive_utc()),
                                 tenant_id: Set(decoded_token.tenant_id),
                                 variant: Set(json!(SessionVariant::AccessToken)),
+                                created_at: Set(Utc::now().naive_utc()),
                             };
 
-                            match session::Entity::insert(access_token_to_insert)
+                            session::Entity::insert(access_token_to_insert)
                                 .exec(&db)
-                                .await
-                            {
-                                Ok(_) => {
-                                    // Assign the cookie
-                                    cookies.add(create_cookie(new_access_key.clone()));
-
-                                    Ok(Json(new_access_key))
-                                }
-                                Err(reason) => Err(ErrorResponse::db_err(reason)),
-                            }
+                                .await?;
+
+                            // Assign the cookie
+                            add_session_cookies(cookies, new_access_key.clone(), exp);
+
+                            Ok(Json(new_access_key))
                         }
                     }
                 }
             }
         }
-        None => Err(ErrorResponse::db_err(DbErr::RecordNotFound(
-            token.to_string(),
-        ))),
+        None => Err(DbErr::RecordNotFound(token.to_string()).into()),
     }
 }
@@ -1,7 +1,9 @@
+mod address;
 #[cfg(feature = "process")]
 pub(crate) mod handlers;
 pub mod structs;
 
+pub use address::*;
 #[cfg(feature = "process")]
 pub use handlers::*;
 pub use structs::*;
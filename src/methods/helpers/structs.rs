@@ -1,9 +1,49 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "process")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "process")]
+use uuid::Builder;
 use validator::Validate;
 
 use crate::{Customer, Employee, Kiosk, Product, Promotion, Store, Tenant, Transaction};
 
+/// A record seeded by a `generate` route, and whether it was `created` by
+/// this call or already existed from a previous one.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct Generated<T> {
+    pub created: bool,
+    pub record: T,
+}
+
+/// Derives the id of a demo record from its tenant and a key unique amongst
+/// its kind, so that repeatedly generating demo data yields the same records.
+#[cfg(feature = "process")]
+pub fn stable_id(tenant_id: &str, key: &str) -> String {
+    let digest = Sha256::digest(format!("{}:{}", tenant_id, key).as_bytes());
+
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest[..16]);
+
+    Builder::from_random_bytes(bytes).into_uuid().to_string()
+}
+
+/// Managed state controlling whether demo data may be seeded through the `generate` routes.
+#[derive(Debug, Clone, Copy)]
+pub struct DemoMode {
+    pub enabled: bool,
+}
+
+impl DemoMode {
+    /// Demo mode is enabled when the `DEMO` environment variable is set to anything but `0`.
+    pub fn from_env() -> Self {
+        DemoMode {
+            enabled: std::env::var("DEMO").is_ok_and(|value| value != "0"),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, JsonSchema, Validate)]
 pub struct Distance {
     pub store_id: String,
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A row of a CSV file, keyed by the (lowercased) column headers.
+#[derive(Clone, Debug)]
+pub struct CsvRecord {
+    /// The line of the file the row begins on, counting the header as line 1.
+    pub line: usize,
+    fields: HashMap<String, String>,
+}
+
+impl CsvRecord {
+    /// The trimmed value of the column, empty if absent.
+    pub fn get(&self, column: &str) -> &str {
+        self.fields.get(column).map(|v| v.trim()).unwrap_or("")
+    }
+
+    pub fn require(&self, column: &str) -> Result<&str, String> {
+        match self.get(column) {
+            "" => Err(format!("Missing value for {}", column)),
+            value => Ok(value),
+        }
+    }
+
+    /// Parses the column as a non-negative number, defaulting to zero when empty.
+    pub fn number(&self, column: &str) -> Result<f64, String> {
+        match self.get(column) {
+            "" => Ok(0.0),
+            value => match value.parse::<f64>() {
+                Ok(v) if v.is_finite() && v >= 0.0 => Ok(v),
+                _ => Err(format!(
+                    "{} must be a non-negative number, got {}",
+                    column, value
+                )),
+            },
+        }
+    }
+}
+
+/// Parses CSV text (RFC 4180) with a header row into its records.
+/// Fields may be quoted, with embedded quotes escaped by doubling them.
+pub fn parse_csv(input: &str) -> Result<Vec<CsvRecord>, String> {
+    let mut rows: Vec<(usize, Vec<String>)> = vec![];
+
+    let mut row: Vec<String> = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut row_line = 1;
+
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push((row_line, std::mem::take(&mut row)));
+
+                line += 1;
+                row_line = line;
+            }
+            _ => {
+                if c == '\n' {
+                    line += 1;
+                }
+
+                field.push(c)
+            }
+        }
+    }
+
+    if quoted {
+        return Err(format!("Unterminated quoted field on line {}", row_line));
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_line, row));
+    }
+
+    // Skip blank lines, which appear as a single empty field.
+    let mut rows = rows
+        .into_iter()
+        .filter(|(_, r)| !(r.len() == 1 && r[0].trim().is_empty()));
+
+    let headers: Vec<String> = match rows.next() {
+        Some((_, h)) => h.iter().map(|h| h.trim().to_lowercase()).collect(),
+        None => return Err("File is empty, expected a header row".to_string()),
+    };
+
+    Ok(rows
+        .map(|(line, values)| CsvRecord {
+            line,
+            fields: headers.iter().cloned().zip(values).collect(),
+        })
+        .collect())
+}
+
+/// The outcome of a bulk import, rows are processed independently such that
+/// a malformed row is rejected without aborting the remainder of the import.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct ImportSummary {
+    pub inserted: Vec<ImportedRow>,
+    pub rejected: Vec<RejectedRow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ImportedRow {
+    pub line: usize,
+    /// The identifier of the record created from the row.
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct RejectedRow {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl ImportSummary {
+    pub fn reject(&mut self, line: usize, reason: impl Into<String>) {
+        self.rejected.push(RejectedRow {
+            line,
+            reason: reason.into(),
+        });
+    }
+}
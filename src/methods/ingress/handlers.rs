@@ -1,12 +1,8 @@
-use crate::{
-    check_permissions, cookie_status_wrapper, methods::Action, methods::Error, Db, ErrorResponse,
-    Session,
-};
+use crate::{check_permissions, methods::Action, methods::Error, ErrorResponse, Session};
 use chrono::Utc;
 use okapi::openapi3::OpenApi;
 
-use rocket::{fs::TempFile, http::CookieJar, post};
-use rocket_db_pools::Connection;
+use rocket::{fs::TempFile, post};
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
 
@@ -1,8 +1,22 @@
-use crate::entities::kiosk::ActiveModel;
-use crate::{Kiosk, Session};
+use crate::entities::kiosk::{ActiveModel, Model};
+use crate::{Kiosk, KioskPreferences, Session};
+use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
 use serde_json::json;
 
+impl From<Model> for Kiosk {
+    fn from(k: Model) -> Self {
+        Kiosk {
+            id: k.id,
+            name: k.name,
+            store_id: k.store_id,
+            preferences: serde_json::from_value::<KioskPreferences>(k.preferences).unwrap(),
+            disabled: k.disabled != 0,
+            last_online: DateTime::from_naive_utc_and_offset(k.last_online, Utc),
+        }
+    }
+}
+
 impl Kiosk {
     pub(crate) fn into_active(self, session: Session) -> ActiveModel {
         ActiveModel {
@@ -1,6 +1,6 @@
 use crate::catchers::Validated;
 use crate::guards::Convert;
-use crate::methods::Error;
+use crate::methods::{AuditAction, AuditEntity, AuditLog, Error};
 use crate::pool::InternalDb;
 use crate::{check_permissions, methods::Action};
 use crate::{AuthenticationLog, Kiosk, KioskInit, KioskPreferences, Session};
@@ -14,12 +14,15 @@ pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, Ope
     openapi_get_routes_spec![
         settings:
         get,
+        get_by_store,
         initialize,
         update,
         update_preferences,
         update_online_status,
         delete,
-        auth_log
+        auth_log,
+        lock,
+        unlock
     ]
 }
 
@@ -30,6 +33,18 @@ pub async fn get(db: InternalDb, id: &str, session: Session) -> Convert<Kiosk> {
     Kiosk::fetch_by_id(id, session, &db.0).await.into()
 }
 
+#[openapi(tag = "Kiosk")]
+#[get("/store/<store_id>")]
+pub async fn get_by_store(db: InternalDb, store_id: &str, session: Session) -> Convert<Vec<Kiosk>> {
+    check_permissions!(session.clone(), Action::FetchKiosk);
+
+    if let Err(e) = session.ensure_store(store_id) {
+        return Err(e).into();
+    }
+
+    Kiosk::fetch_by_store(store_id, session, &db.0).await.into()
+}
+
 #[openapi(tag = "Kiosk")]
 #[post("/", data = "<input_data>")]
 pub async fn initialize(
@@ -101,3 +116,51 @@ pub async fn auth_log(
         .await
         .map(|_| ())
 }
+
+/// Locks or unlocks the kiosk, recording the change in the audit log.
+async fn set_locked(db: InternalDb, id: &str, locked: bool, session: Session) -> Convert<Kiosk> {
+    check_permissions!(session.clone(), Action::LockKiosk);
+
+    let before = Kiosk::fetch_by_id(id, session.clone(), &db.0).await.ok();
+
+    if let Some(kiosk) = &before {
+        if let Err(e) = session.ensure_store(&kiosk.store_id) {
+            return Err(e).into();
+        }
+    }
+
+    let updated = Kiosk::set_locked(id, locked, session.clone(), &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Kiosk,
+            id,
+            if locked {
+                AuditAction::Lock
+            } else {
+                AuditAction::Unlock
+            },
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
+}
+
+/// Locks the kiosk, such as when it is compromised or being serviced. No employee
+/// may sign in at a locked kiosk, nor may any transaction be made from it.
+#[openapi(tag = "Kiosk")]
+#[post("/<id>/lock", rank = 2)]
+pub async fn lock(db: InternalDb, id: &str, session: Session) -> Convert<Kiosk> {
+    set_locked(db, id, true, session).await
+}
+
+#[openapi(tag = "Kiosk")]
+#[post("/<id>/unlock", rank = 2)]
+pub async fn unlock(db: InternalDb, id: &str, session: Session) -> Convert<Kiosk> {
+    set_locked(db, id, false, session).await
+}
@@ -1,7 +1,7 @@
 use crate::entities::kiosk::Model;
 #[cfg(feature = "process")]
 use crate::entities::prelude::Kiosk as Ksk;
-use crate::methods::Error;
+use crate::methods::{Error, ErrorResponse};
 #[cfg(feature = "process")]
 use crate::{entities::authrecord::ActiveModel as AuthRecord, entities::kiosk::ActiveModel};
 #[cfg(feature = "process")]
@@ -11,7 +11,7 @@ use schemars::JsonSchema;
 #[cfg(feature = "process")]
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DbConn, DbErr, DeleteResult, EntityTrait, InsertResult,
-    QueryFilter, Set,
+    QueryFilter, QueryOrder, Set,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -84,18 +84,69 @@ impl Kiosk {
             .await?;
 
         match kiosk {
-            Some(k) => Ok(Kiosk {
-                id: k.id,
-                name: k.name,
-                store_id: k.store_id,
-                preferences: serde_json::from_value::<KioskPreferences>(k.preferences).unwrap(),
-                disabled: k.disabled != 0,
-                last_online: DateTime::from_naive_utc_and_offset(k.last_online, Utc),
-            }),
+            Some(k) => Ok(k.into()),
             None => Err(DbErr::RecordNotFound(id.to_string()).into()),
         }
     }
 
+    /// All kiosks registered to the store, by name.
+    pub async fn fetch_by_store(
+        store_id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Kiosk>, Error> {
+        let kiosks = Ksk::find()
+            .filter(kiosk::Column::TenantId.eq(session.tenant_id))
+            .filter(kiosk::Column::StoreId.eq(store_id))
+            .order_by_asc(kiosk::Column::Name)
+            .all(db)
+            .await?;
+
+        Ok(kiosks.into_iter().map(|k| k.into()).collect())
+    }
+
+    /// Ensures `id` names a kiosk registered to the tenant, such that records
+    /// made at a terminal can be traced back to it, that it is not locked and
+    /// that it belongs to a store the employee is assigned to.
+    pub async fn ensure_usable(id: &str, session: Session, db: &DbConn) -> Result<Kiosk, Error> {
+        let kiosk = Self::fetch_by_id(id, session.clone(), db).await.map_err(|_| {
+            ErrorResponse::custom_input_error(&format!("Kiosk {} is not registered.", id))
+        })?;
+
+        if kiosk.disabled {
+            return Err(ErrorResponse::custom_unauthorized(&format!(
+                "Kiosk {} is locked.",
+                id
+            )));
+        }
+
+        session.ensure_store(&kiosk.store_id)?;
+
+        Ok(kiosk)
+    }
+
+    /// Locks the kiosk, such that no employee may sign in at it nor any
+    /// transaction be made from it until unlocked, or unlocks it.
+    pub async fn set_locked(
+        id: &str,
+        locked: bool,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Kiosk, Error> {
+        let kiosk = Self::fetch_by_id(id, session.clone(), db).await?;
+
+        ActiveModel {
+            id: Set(kiosk.id),
+            disabled: Set(locked as i8),
+            tenant_id: Set(session.clone().tenant_id),
+            ..Default::default()
+        }
+        .update(db)
+        .await?;
+
+        Self::fetch_by_id(id, session, db).await
+    }
+
     pub async fn insert(
         kiosk: KioskInit,
         session: Session,
@@ -0,0 +1,76 @@
+use rocket::{FromForm, FromFormField};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "process")]
+use crate::methods::{Error, ErrorResponse};
+#[cfg(feature = "process")]
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Select};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, JsonSchema, FromFormField)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Sorting and filtering of a listing, read from the query string,
+/// i.e. `?sort_by=name&order=desc&filter=code:LON`.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema, FromForm)]
+pub struct ListQuery {
+    pub sort_by: Option<String>,
+    pub order: Option<SortOrder>,
+    /// Filters of the form `column:value`, matching records whose column contains the value.
+    pub filter: Vec<String>,
+}
+
+#[cfg(feature = "process")]
+impl ListQuery {
+    /// Applies the sort and filters to `select`, where `columns` lists the
+    /// names which may be sorted or filtered by. Unknown names are rejected.
+    pub fn apply<E: EntityTrait>(
+        &self,
+        mut select: Select<E>,
+        columns: &[(&str, E::Column)],
+    ) -> Result<Select<E>, Error> {
+        let column = |name: &str| {
+            columns
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, c)| *c)
+                .ok_or_else(|| {
+                    ErrorResponse::custom_input_error(&format!(
+                        "Unknown column {}, expected one of: {}",
+                        name,
+                        columns
+                            .iter()
+                            .map(|(n, _)| *n)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                })
+        };
+
+        for filter in &self.filter {
+            let (name, value) = filter.split_once(':').ok_or_else(|| {
+                ErrorResponse::custom_input_error(&format!(
+                    "Malformed filter {}, expected the form column:value",
+                    filter
+                ))
+            })?;
+
+            select = select.filter(column(name)?.contains(value));
+        }
+
+        if let Some(sort_by) = &self.sort_by {
+            let order = match self.order.unwrap_or_default() {
+                SortOrder::Asc => sea_orm::Order::Asc,
+                SortOrder::Desc => sea_orm::Order::Desc,
+            };
+
+            select = select.order_by(column(sort_by)?, order);
+        }
+
+        Ok(select)
+    }
+}
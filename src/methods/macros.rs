@@ -9,3 +9,15 @@ macro_rules! check_permissions {
         }
     };
 }
+
+#[macro_export]
+macro_rules! check_demo {
+    ($demo:expr) => {
+        if !$demo.enabled {
+            return Err($crate::methods::Error::DemoDisabled(
+                "OpenStock is not in DEMO mode.".to_string(),
+            ))
+            .into();
+        }
+    };
+}
@@ -2,27 +2,47 @@ pub(crate) mod common;
 mod payment;
 mod stml;
 
+pub mod audit;
 pub mod customer;
 pub mod employee;
+pub mod fuzzy;
+pub mod gift_card;
+pub mod health;
 pub mod helpers;
+pub mod import;
 pub mod ingress;
 pub mod kiosk;
+pub mod listing;
 pub mod macros;
 pub mod product;
+pub mod report;
+pub mod search;
+pub mod stocktake;
 pub mod store;
 pub mod supplier;
 pub mod tenant;
 pub mod transaction;
+pub mod webhook;
 
+pub use self::audit::*;
 pub use self::common::*;
 pub use self::customer::*;
 pub use self::employee::*;
+pub use self::fuzzy::*;
+pub use self::gift_card::*;
+pub use self::health::*;
 pub use self::helpers::*;
+pub use self::import::*;
 pub use self::kiosk::*;
+pub use self::listing::*;
 pub use self::payment::*;
 pub use self::product::*;
+pub use self::report::*;
+pub use self::search::*;
 pub use self::stml::*;
+pub use self::stocktake::*;
 pub use self::store::*;
 pub use self::supplier::*;
 pub use self::tenant::*;
 pub use self::transaction::*;
+pub use self::webhook::*;
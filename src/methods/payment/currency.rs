@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How amounts of a currency are written, i.e. `$4.50`, `4,50 €` or `¥450`.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct CurrencyFormat {
+    /// The ISO 4217 code of the currency, i.e. `USD`.
+    pub code: String,
+    pub symbol: String,
+    /// Whether the symbol follows the amount, separated by a space.
+    pub symbol_after: bool,
+    pub decimal_separator: char,
+    /// Digits shown after the decimal separator, none for currencies without minor units.
+    pub decimals: usize,
+}
+
+impl Default for CurrencyFormat {
+    fn default() -> Self {
+        Self::new("USD", "$", false, '.', 2)
+    }
+}
+
+impl CurrencyFormat {
+    fn new(
+        code: &str,
+        symbol: &str,
+        symbol_after: bool,
+        decimal_separator: char,
+        decimals: usize,
+    ) -> Self {
+        Self {
+            code: code.to_string(),
+            symbol: symbol.to_string(),
+            symbol_after,
+            decimal_separator,
+            decimals,
+        }
+    }
+
+    /// The format of a commonly used currency by its ISO 4217 code, if known.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let format = match code.to_uppercase().as_str() {
+            "USD" => Self::default(),
+            "NZD" => Self::new("NZD", "$", false, '.', 2),
+            "AUD" => Self::new("AUD", "$", false, '.', 2),
+            "CAD" => Self::new("CAD", "$", false, '.', 2),
+            "GBP" => Self::new("GBP", "£", false, '.', 2),
+            "EUR" => Self::new("EUR", "€", true, ',', 2),
+            "JPY" => Self::new("JPY", "¥", false, '.', 0),
+            _ => return None,
+        };
+
+        Some(format)
+    }
+
+    /// Writes `amount` in the currency, with the sign leading, i.e. `-$4.50` or `-4,50 €`.
+    pub fn format(&self, amount: f32) -> String {
+        let digits = format!("{:.*}", self.decimals, amount.abs())
+            .replace('.', &self.decimal_separator.to_string());
+        let sign = if amount < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            "-"
+        } else {
+            ""
+        };
+
+        if self.symbol_after {
+            format!("{}{} {}", sign, digits, self.symbol)
+        } else {
+            format!("{}{}{}", sign, self.symbol, digits)
+        }
+    }
+}
+
+/// Writes `amount` in the default currency format, see [`CurrencyFormat::format`].
+pub fn format_price(amount: f32) -> String {
+    CurrencyFormat::default().format(amount)
+}
@@ -1,5 +1,15 @@
+mod currency;
 mod discount;
+mod rounding;
 mod structs;
+mod tax;
+#[cfg(feature = "process")]
+mod tender;
 
+pub use currency::*;
 pub use discount::*;
+pub use rounding::*;
 pub use structs::*;
+pub use tax::*;
+#[cfg(feature = "process")]
+pub use tender::*;
@@ -0,0 +1,53 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How monetary amounts are rounded to the cent once discounts and tax
+/// have been applied.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, JsonSchema)]
+pub enum RoundingMode {
+    /// Halves round away from zero, i.e. 0.125 to 0.13.
+    #[default]
+    HalfUp,
+    /// Halves round to the nearest even cent, i.e. 0.125 to 0.12 and 0.135 to 0.14.
+    HalfEven,
+    /// Fractions of a cent are discarded, i.e. 0.129 to 0.12.
+    Down,
+}
+
+impl RoundingMode {
+    pub fn round(&self, amount: f32) -> f32 {
+        // Amounts arrive as f32, so 1.005 is really 1.00499999... Trimming the
+        // scaled value to a ten-thousandth of a cent first recovers the intended halves.
+        let cents = ((amount as f64) * 100.0 * 10_000.0).round() / 10_000.0;
+
+        let rounded = match self {
+            RoundingMode::HalfUp => cents.round(),
+            RoundingMode::HalfEven => {
+                let floor = cents.floor();
+
+                if cents - floor == 0.5 {
+                    if floor % 2.0 == 0.0 {
+                        floor
+                    } else {
+                        floor + 1.0
+                    }
+                } else {
+                    cents.round()
+                }
+            }
+            RoundingMode::Down => cents.trunc(),
+        };
+
+        (rounded / 100.0) as f32
+    }
+}
+
+/// Amounts are stored in cents, such that repeated arithmetic upon them does not drift.
+pub(crate) fn to_cents(amount: f32) -> i64 {
+    (amount as f64 * 100.0).round() as i64
+}
+
+pub(crate) fn from_cents(cents: i64) -> f32 {
+    cents as f32 / 100.0
+}
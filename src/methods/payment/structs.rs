@@ -119,6 +119,8 @@ pub enum PaymentMethod {
     Card,
     Cash,
     Transfer,
+    /// A gift card or store credit, by its code, see [`GiftCard`](crate::methods::GiftCard).
+    GiftCard(String),
     Other(String),
 }
 
@@ -128,6 +130,7 @@ impl Display for PaymentMethod {
             PaymentMethod::Card => write!(f, "CARD"),
             PaymentMethod::Cash => write!(f, "CASH"),
             PaymentMethod::Transfer => write!(f, "TRANSFER"),
+            PaymentMethod::GiftCard(code) => write!(f, "GIFTCARD[{}]", code),
             PaymentMethod::Other(value) => write!(f, "OTHER[{}]", value),
         }
     }
@@ -0,0 +1,126 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{Id, OrderBreakdown, OrderList, ProductPurchase, TenantSettings};
+
+/// The rate charged on variants whose `tax_code` matches `code`.
+///
+/// Rates are fractional, i.e. `0.15` for 15%. An `exempt` code is never taxed,
+/// irrespective of its rate.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TaxRate {
+    pub code: String,
+    pub rate: f32,
+    #[serde(default)]
+    pub exempt: bool,
+}
+
+/// The tax charged on a single product purchase within a cart.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct LineTax {
+    pub product_purchase_id: Id,
+    pub tax_code: String,
+    pub rate: f32,
+    /// The line amount after both the line and order discounts, as priced
+    /// (i.e. including tax when prices are tax-inclusive).
+    pub taxable: f32,
+    pub tax: f32,
+}
+
+/// The tax owed on a cart, where `gross` is always `net` plus `total_tax`.
+///
+/// When prices are tax-exclusive the tax is added on top of the discounted
+/// amount. When tax-inclusive the tax is back-computed from the discounted
+/// amount, which is then the `gross` the customer pays.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TaxBreakdown {
+    pub lines: Vec<LineTax>,
+    pub net: f32,
+    pub total_tax: f32,
+    pub gross: f32,
+}
+
+/// A cart priced as it would be at checkout, where `tax.gross` is the amount
+/// the customer must pay.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CartBreakdown {
+    pub orders: Vec<OrderBreakdown>,
+    pub tax: TaxBreakdown,
+}
+
+/// Finds the rate applicable to `code`. Unknown and exempt codes are charged nothing.
+pub fn tax_rate_for(rates: &[TaxRate], code: &str) -> f32 {
+    match rates.iter().find(|r| r.code == code) {
+        Some(rate) if !rate.exempt => rate.rate,
+        _ => 0.0,
+    }
+}
+
+/// Computes the tax on every line of `cart` under the tenant's `settings`,
+/// where `tax_code` resolves the tax code of a purchase (usually from its
+/// variant's stock information).
+///
+/// An order's discount is spread across its lines in proportion to their
+/// value, so the taxable amounts always sum to the order's discounted total.
+/// Discounted amounts, line taxes and the totals are rounded to the cent
+/// using the tenant's [`RoundingMode`](crate::methods::RoundingMode).
+pub fn compute_tax<F>(cart: &OrderList, settings: &TenantSettings, tax_code: F) -> TaxBreakdown
+where
+    F: Fn(&ProductPurchase) -> String,
+{
+    let rounding = settings.rounding;
+    let mut lines = vec![];
+    let mut priced = 0.0;
+
+    for order in cart {
+        let breakdown = order.breakdown(rounding);
+        let amounts = breakdown.lines.iter().map(|line| line.total);
+
+        let subtotal = breakdown.subtotal;
+        let order_net = breakdown.total;
+        let factor = if subtotal == 0.0 {
+            1.0
+        } else {
+            order_net / subtotal
+        };
+
+        priced += order_net;
+
+        for (product, amount) in order.products.iter().zip(amounts) {
+            let code = tax_code(product);
+            let rate = tax_rate_for(&settings.tax_rates, &code);
+            let taxable = amount * factor;
+            let tax = rounding.round(if settings.tax_inclusive {
+                taxable - taxable / (1.0 + rate)
+            } else {
+                taxable * rate
+            });
+
+            lines.push(LineTax {
+                product_purchase_id: product.id.clone(),
+                tax_code: code,
+                rate,
+                taxable,
+                tax,
+            });
+        }
+    }
+
+    let total_tax = rounding.round(lines.iter().map(|line| line.tax).sum::<f32>());
+    let net = rounding.round(if settings.tax_inclusive {
+        priced - total_tax
+    } else {
+        priced
+    });
+
+    TaxBreakdown {
+        lines,
+        net,
+        total_tax,
+        gross: rounding.round(net + total_tax),
+    }
+}
@@ -0,0 +1,77 @@
+use crate::methods::{from_cents, to_cents, Error, FieldError, Payment, PaymentMethod};
+use crate::ErrorResponse;
+
+/// Payments may fall short of or exceed the total by this much, absorbing rounding
+/// differences between the client and server.
+pub const TENDER_TOLERANCE: f32 = 0.1;
+
+/// Checks that `payments` settle `total`, returning the change due to the customer.
+///
+/// Every payment must move money in the direction of the transaction, that is
+/// be positive for a sale and negative for a refund, being a negative `total`.
+/// Payments may exceed the total of a sale only by as much as was tendered in
+/// cash, as change cannot be given from a card or gift card.
+pub fn settle_tender(payments: &[Payment], total: f32) -> Result<f32, Error> {
+    let refund = total < 0.0;
+    let direction = if refund { -1.0 } else { 1.0 };
+
+    for payment in payments {
+        if payment.amount.quantity * direction <= 0.0 {
+            return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+                "payment",
+                &format!(
+                    "Payment of {:.2} by {} must be {} zero.",
+                    payment.amount.quantity,
+                    payment.payment_method,
+                    if refund { "less than" } else { "greater than" }
+                ),
+            )]));
+        }
+    }
+
+    let paid = payments.iter().map(|p| p.amount.quantity).sum::<f32>() * direction;
+    let owed = total * direction;
+
+    if (paid - owed).abs() <= TENDER_TOLERANCE {
+        return Ok(0.0);
+    }
+
+    if paid < owed {
+        return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+            "payment",
+            &format!(
+                "Payment of {:.2} does not cover the total of {:.2}.",
+                paid, owed
+            ),
+        )]));
+    }
+
+    if refund {
+        return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+            "payment",
+            &format!(
+                "Refund of {:.2} exceeds the {:.2} value of the goods returned.",
+                paid, owed
+            ),
+        )]));
+    }
+
+    let change = from_cents(to_cents(paid - owed));
+    let cash = payments
+        .iter()
+        .filter(|p| matches!(p.payment_method, PaymentMethod::Cash))
+        .map(|p| p.amount.quantity)
+        .sum::<f32>();
+
+    if change > cash {
+        return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+            "payment",
+            &format!(
+                "Overpaid by {:.2}, but change can only be given from the {:.2} tendered in cash.",
+                change, cash
+            ),
+        )]));
+    }
+
+    Ok(change)
+}
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::ValidationError;
+
+/// The symbologies a variant's barcode is recognised as.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum BarcodeFormat {
+    /// 13 digits, the last being a check digit.
+    Ean13,
+    /// 12 digits, the last being a check digit.
+    UpcA,
+    /// Any printable ASCII, the check symbol is part of the encoding rather than the data.
+    Code128,
+}
+
+/// A validated barcode. On the wire a barcode remains a plain string,
+/// this type exists to determine its format and verify its check digit.
+#[cfg(feature = "types")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Barcode {
+    pub value: String,
+    pub format: BarcodeFormat,
+}
+
+/// The longest payload we accept for a Code 128 barcode.
+const CODE128_MAX_LENGTH: usize = 48;
+
+impl Barcode {
+    pub fn parse(value: &str) -> Result<Barcode, String> {
+        if value.is_empty() {
+            return Err("Barcode must not be empty".to_string());
+        }
+
+        let numeric = value.chars().all(|c| c.is_ascii_digit());
+
+        let format = match value.len() {
+            13 if numeric => BarcodeFormat::Ean13,
+            12 if numeric => BarcodeFormat::UpcA,
+            _ => BarcodeFormat::Code128,
+        };
+
+        match format {
+            BarcodeFormat::Ean13 | BarcodeFormat::UpcA => {
+                let (data, check) = value.split_at(value.len() - 1);
+                let expected = gtin_check_digit(data);
+
+                if check != expected.to_string() {
+                    return Err(format!(
+                        "Barcode {} has an invalid check digit, expected {} for {:?}",
+                        value, expected, format
+                    ));
+                }
+            }
+            BarcodeFormat::Code128 => {
+                if value.len() > CODE128_MAX_LENGTH {
+                    return Err(format!(
+                        "Barcode {} exceeds the maximum length of {} characters",
+                        value, CODE128_MAX_LENGTH
+                    ));
+                }
+
+                if !value.chars().all(|c| (' '..='~').contains(&c)) {
+                    return Err(format!(
+                        "Barcode {} contains characters which cannot be encoded in Code 128",
+                        value
+                    ));
+                }
+            }
+        }
+
+        Ok(Barcode {
+            value: value.to_string(),
+            format,
+        })
+    }
+}
+
+/// Computes the GS1 check digit for the given digits (excluding the check digit),
+/// weighting digits alternately by 3 and 1 from the right.
+fn gtin_check_digit(data: &str) -> u32 {
+    let sum: u32 = data
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { d })
+        .sum();
+
+    (10 - (sum % 10)) % 10
+}
+
+/// Validator for a variant's `barcode` field.
+pub fn validate_barcode(value: &str) -> Result<(), ValidationError> {
+    match Barcode::parse(value) {
+        Ok(_) => Ok(()),
+        Err(message) => {
+            let mut err = ValidationError::new("invalid_barcode");
+            err.message = Some(Cow::from(message));
+            Err(err)
+        }
+    }
+}
+
+impl Display for Barcode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
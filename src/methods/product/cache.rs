@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+
+use crate::methods::Product;
+
+/// The most products held at once, beyond which expired entries are purged and,
+/// should the cache remain full, new products are no longer cached.
+pub const PRODUCT_CACHE_CAPACITY: usize = 10_000;
+
+lazy_static! {
+    /// The cache in front of product lookups, see [`ProductCache`].
+    pub static ref PRODUCT_CACHE: ProductCache = ProductCache::from_env();
+}
+
+/// Holds recently fetched products in memory, such that repeated scans of
+/// popular products do not each query the database.
+///
+/// Products are kept for `PRODUCT_CACHE_TTL` seconds, with the cache disabled
+/// when the variable is absent or `0`. Entries are keyed by tenant and removed
+/// whenever the product is written, see [`ProductCache::invalidate`]. Writes made
+/// by other instances of the server are seen once the entry expires.
+pub struct ProductCache {
+    ttl: Option<Duration>,
+    /// Products by tenant and sku, alongside when they were cached.
+    products: Mutex<HashMap<(String, String), (Instant, Product)>>,
+    /// The sku of the product carrying each barcode, by tenant and barcode.
+    barcodes: Mutex<HashMap<(String, String), String>>,
+}
+
+impl ProductCache {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        ProductCache {
+            ttl,
+            products: Mutex::new(HashMap::new()),
+            barcodes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let ttl = std::env::var("PRODUCT_CACHE_TTL")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs);
+
+        Self::new(ttl)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.ttl.is_some()
+    }
+
+    /// The cached product with the given sku, if held and yet to expire.
+    pub fn get(&self, tenant_id: &str, sku: &str) -> Option<Product> {
+        let ttl = self.ttl?;
+        let mut products = self.products.lock().unwrap();
+        let key = (tenant_id.to_string(), sku.to_string());
+
+        match products.get(&key) {
+            Some((cached_at, product)) if cached_at.elapsed() < ttl => Some(product.clone()),
+            Some(_) => {
+                products.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// The cached product carrying `barcode`, if held and yet to expire.
+    pub fn get_by_barcode(&self, tenant_id: &str, barcode: &str) -> Option<Product> {
+        let sku = self
+            .barcodes
+            .lock()
+            .unwrap()
+            .get(&(tenant_id.to_string(), barcode.to_string()))
+            .cloned()?;
+
+        self.get(tenant_id, &sku)
+            .filter(|p| p.variants.iter().any(|v| v.barcode == barcode))
+    }
+
+    /// Caches `product`, indexing it by the barcode of each of its variants.
+    pub fn put(&self, tenant_id: &str, product: &Product) {
+        let ttl = match self.ttl {
+            Some(ttl) => ttl,
+            None => return,
+        };
+
+        let mut products = self.products.lock().unwrap();
+
+        if products.len() >= PRODUCT_CACHE_CAPACITY {
+            products.retain(|_, (cached_at, _)| cached_at.elapsed() < ttl);
+
+            if products.len() >= PRODUCT_CACHE_CAPACITY {
+                return;
+            }
+        }
+
+        products.insert(
+            (tenant_id.to_string(), product.sku.clone()),
+            (Instant::now(), product.clone()),
+        );
+
+        let mut barcodes = self.barcodes.lock().unwrap();
+
+        if barcodes.len() >= PRODUCT_CACHE_CAPACITY * 4 {
+            barcodes
+                .retain(|(tenant, _), sku| products.contains_key(&(tenant.clone(), sku.clone())));
+        }
+
+        for variant in &product.variants {
+            barcodes.insert(
+                (tenant_id.to_string(), variant.barcode.clone()),
+                product.sku.clone(),
+            );
+        }
+    }
+
+    /// Removes the product with the given sku, such that its next lookup reads
+    /// the product as written. Barcodes indexing it are left to miss.
+    pub fn invalidate(&self, tenant_id: &str, sku: &str) {
+        if self.ttl.is_none() {
+            return;
+        }
+
+        self.products
+            .lock()
+            .unwrap()
+            .remove(&(tenant_id.to_string(), sku.to_string()));
+    }
+}
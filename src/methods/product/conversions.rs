@@ -1,7 +1,9 @@
+use crate::entities::price_history;
+use crate::methods::{from_cents, to_cents};
 use crate::products::{ActiveModel, Model};
 use crate::{
-    Product, ProductIdentification, ProductVisibility, Session, TagList, Url, VariantCategoryList,
-    VariantInformation,
+    PriceHistoryEntry, Product, ProductIdentification, ProductVisibility, Session, TagList, Url,
+    VariantCategoryList, VariantInformation,
 };
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
@@ -54,3 +56,32 @@ impl From<Model> for Product {
         }
     }
 }
+
+impl From<price_history::Model> for PriceHistoryEntry {
+    fn from(val: price_history::Model) -> Self {
+        PriceHistoryEntry {
+            id: val.id,
+            product_sku: val.product_sku,
+            variant_code: val.variant_code,
+            old_price: from_cents(val.old_price),
+            new_price: from_cents(val.new_price),
+            changed_by: val.changed_by,
+            changed_at: DateTime::from_naive_utc_and_offset(val.changed_at, Utc),
+        }
+    }
+}
+
+impl PriceHistoryEntry {
+    pub(crate) fn into_active(self, tenant_id: String) -> price_history::ActiveModel {
+        price_history::ActiveModel {
+            id: Set(self.id),
+            tenant_id: Set(tenant_id),
+            product_sku: Set(self.product_sku),
+            variant_code: Set(self.variant_code),
+            old_price: Set(to_cents(self.old_price)),
+            new_price: Set(to_cents(self.new_price)),
+            changed_by: Set(self.changed_by),
+            changed_at: Set(self.changed_at.naive_utc()),
+        }
+    }
+}
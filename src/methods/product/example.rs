@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use crate::{
     Address, ContactInformation, DiscountValue, Email, Location, MobileNumber, Product,
-    ProductIdentification, ProductVisibility, Quantity, Stock, StockInformation, Variant,
-    VariantCategory, VariantInformation,
+    ProductIdentification, ProductVisibility, Quantity, Stock, StockInformation, UnitOfMeasure,
+    Variant, VariantCategory, VariantInformation,
 };
 
 pub fn example_products() -> Vec<Product> {
@@ -203,8 +205,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -212,8 +216,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 4.0,
                                 quantity_on_order: 2.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -221,8 +227,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -230,6 +238,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 10.99,
                     retail_price: 44.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["02".into(), "21".into()],
                     order_history: vec![],
                     barcode: "51890723908812".into(),
@@ -246,6 +256,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -273,8 +286,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 7.0,
                                 quantity_unsellable: 2.0,
                                 quantity_on_order: 4.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -282,8 +297,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 1.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -291,8 +308,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -300,6 +319,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 12.49,
                     retail_price: 46.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["02".into(), "22".into()],
                     order_history: vec![],
                     barcode: "51150723152813".into(),
@@ -316,6 +337,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -343,8 +367,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -352,8 +378,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 3.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 1.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -361,8 +389,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -372,6 +402,8 @@ pub fn example_products() -> Vec<Product> {
                     order_history: vec![],
                     marginal_price: 16.09,
                     retail_price: 49.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     barcode: "51150723159173".into(),
                     stock_information: StockInformation {
                         stock_group: "RANDOM".into(),
@@ -386,6 +418,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -479,8 +514,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 4.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -488,8 +525,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 1.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -497,8 +536,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 2.0,
                                 quantity_unsellable: 1.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -506,6 +547,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 85.99,
                     retail_price: 399.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["01".into(), "21".into()],
                     order_history: vec![],
                     barcode: "51891743988214".into(),
@@ -522,6 +565,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -549,8 +595,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 7.0,
                                 quantity_unsellable: 2.0,
                                 quantity_on_order: 4.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -558,8 +606,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 1.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -567,8 +617,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -576,6 +628,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 85.99,
                     retail_price: 399.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["02".into(), "21".into()],
                     order_history: vec![],
                     barcode: "54897443288214".into(),
@@ -592,6 +646,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -697,8 +754,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 4.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -706,8 +765,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 1.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -715,8 +776,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 2.0,
                                 quantity_unsellable: 1.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -724,6 +787,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 45.99,
                     retail_price: 139.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["01".into(), "21".into()],
                     order_history: vec![],
                     barcode: "51891265958214".into(),
@@ -740,6 +805,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -767,8 +835,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 4.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield.clone(),
@@ -776,8 +846,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 1.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany.clone(),
@@ -785,8 +857,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 2.0,
                                 quantity_unsellable: 1.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -794,6 +868,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 45.99,
                     retail_price: 139.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["01".into(), "22".into()],
                     order_history: vec![],
                     barcode: "51893261953216".into(),
@@ -810,6 +886,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -837,8 +916,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 4.0,
                                 quantity_on_order: 0.0,
                                 quantity_unsellable: 2.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: westfield,
@@ -846,8 +927,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 1.0,
                                 quantity_on_order: 1.0,
                                 quantity_unsellable: 0.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                         Stock {
                             store: albany,
@@ -855,8 +938,10 @@ pub fn example_products() -> Vec<Product> {
                                 quantity_sellable: 0.0,
                                 quantity_on_order: 2.0,
                                 quantity_unsellable: 1.0,
-                                quantity_allocated: 0.0
-                            }
+                                quantity_allocated: 0.0,
+                                quantity_reserved: 0.0
+                            },
+                            bin_location: None
                         },
                     ],
                     images: vec![
@@ -864,6 +949,8 @@ pub fn example_products() -> Vec<Product> {
                     ],
                     marginal_price: 45.99,
                     retail_price: 139.99,
+                    price_tiers: HashMap::new(),
+                    scheduled_prices: vec![],
                     variant_code: vec!["01".into(), "23".into()],
                     order_history: vec![],
                     barcode: "52496265958214".into(),
@@ -880,6 +967,9 @@ pub fn example_products() -> Vec<Product> {
                         discontinued: false,
                         non_diminishing: false,
                         shippable: true,
+                        supplier_id: None,
+                        supplier_cost: 0.0,
+                        unit: UnitOfMeasure::Each,
                         min_stock_before_alert: 2.0,
                         min_stock_level: 0.0,
                         colli: String::new(),
@@ -1,13 +1,21 @@
-use super::{Product, ProductWPromotion, Promotion, PromotionInput};
+use super::{
+    BinAssignment, BulkPriceSummary, BulkPriceUpdate, EffectivePrice, PriceChangeInput,
+    PriceHistoryEntry, Product, ProductWPromotion, Promotion, PromotionInput, VariantInformation,
+};
 use crate::catchers::Validated;
-use crate::guards::Convert;
-use crate::methods::{Action, Error};
+use crate::guards::{Convert, Tagged};
+use crate::methods::{
+    Action, Error, ErrorResponse, ImportSummary, ListQuery, DEFAULT_FUZZY_DISTANCE,
+};
 use crate::pool::InternalDb;
-use crate::{check_permissions, Session};
+use crate::{check_demo, check_permissions, DemoMode, Generated, Session};
+use chrono::{DateTime, Utc};
 use okapi::openapi3::OpenApi;
+use rocket::data::Capped;
 use rocket::get;
 use rocket::post;
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
 
@@ -18,11 +26,22 @@ pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, Ope
         get_with_associated_promotions,
         get_by_name,
         get_by_name_exact,
+        get_by_barcode,
         create,
         update,
+        discontinue,
+        reinstate,
+        assign_bin,
+        schedule_price_change,
+        cancel_price_change,
+        bulk_update_prices,
+        price_history,
+        effective_price,
         generate,
+        import,
         search_query,
         get_promotion,
+        get_all_promotions,
         get_promotion_by_query,
         create_promotion,
         update_promotion,
@@ -33,7 +52,7 @@ pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, Ope
 
 #[openapi(tag = "Product")]
 #[get("/<id>")]
-pub async fn get(session: Session, db: InternalDb, id: i32) -> Convert<Product> {
+pub async fn get(session: Session, db: InternalDb, id: i32) -> Tagged<Product> {
     check_permissions!(session.clone(), Action::FetchProduct);
     Product::fetch_by_id(&id.to_string(), session, &db.0)
         .await
@@ -54,10 +73,18 @@ pub async fn get_with_associated_promotions(
 }
 
 #[openapi(tag = "Product")]
-#[get("/name/<name>")]
-pub async fn get_by_name(db: InternalDb, session: Session, name: &str) -> Convert<Vec<Product>> {
+#[get("/name/<name>?<distance>")]
+pub async fn get_by_name(
+    db: InternalDb,
+    session: Session,
+    name: &str,
+    distance: Option<usize>,
+) -> Convert<Vec<Product>> {
     check_permissions!(session.clone(), Action::FetchProduct);
-    Product::fetch_by_name(name, session, &db.0).await.into()
+    let distance = distance.unwrap_or(DEFAULT_FUZZY_DISTANCE);
+    Product::fetch_by_name(name, distance, session, &db.0)
+        .await
+        .into()
 }
 
 /// References exact name
@@ -74,6 +101,19 @@ pub async fn get_by_name_exact(
         .into()
 }
 
+#[openapi(tag = "Product")]
+#[get("/barcode/<barcode>")]
+pub async fn get_by_barcode(
+    db: InternalDb,
+    session: Session,
+    barcode: &str,
+) -> Convert<VariantInformation> {
+    check_permissions!(session.clone(), Action::FetchProduct);
+    Product::fetch_by_barcode(barcode, session, &db.0)
+        .await
+        .into()
+}
+
 /// Will search by both name, phone and email.
 #[openapi(tag = "Product")]
 #[get("/search/<query>")]
@@ -127,11 +167,48 @@ pub async fn create(
 
 #[openapi(tag = "Product")]
 #[post("/generate")]
-async fn generate(db: InternalDb, session: Session) -> Convert<Vec<Product>> {
+async fn generate(
+    db: InternalDb,
+    session: Session,
+    demo: &State<DemoMode>,
+) -> Convert<Vec<Product>> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Product::generate(session, &db.0).await.into()
 }
 
+/// Imports products from a CSV body, see [`Product::import_csv`] for the expected columns.
+#[openapi(tag = "Product")]
+#[post("/import", data = "<csv>")]
+pub async fn import(
+    db: InternalDb,
+    session: Session,
+    csv: Capped<String>,
+) -> Result<Json<ImportSummary>, Error> {
+    check_permissions!(session.clone(), Action::CreateProduct);
+
+    if !csv.is_complete() {
+        return Err(ErrorResponse::custom_input_error(
+            "Import exceeds the maximum upload size, split it into multiple files.",
+        ));
+    }
+
+    Product::import_csv(csv.as_bytes(), session, &db.0)
+        .await
+        .map(Json)
+}
+
+#[openapi(tag = "Product")]
+#[get("/promotion?<query..>")]
+pub async fn get_all_promotions(
+    db: InternalDb,
+    session: Session,
+    query: ListQuery,
+) -> Convert<Vec<Promotion>> {
+    check_permissions!(session.clone(), Action::FetchProduct);
+    Promotion::fetch_all(&query, session, &db.0).await.into()
+}
+
 #[openapi(tag = "Product")]
 #[get("/promotion/<id>")]
 pub async fn get_promotion(db: InternalDb, session: Session, id: i32) -> Convert<Promotion> {
@@ -154,6 +231,136 @@ pub async fn get_promotion_by_query(
         .into()
 }
 
+#[openapi(tag = "Product")]
+#[post("/<sku>/discontinue/<variant_code>")]
+pub async fn discontinue(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+    variant_code: &str,
+) -> Convert<Product> {
+    check_permissions!(session.clone(), Action::ModifyProduct);
+    Product::discontinue(sku, variant_code, session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Product")]
+#[post("/<sku>/reinstate/<variant_code>")]
+pub async fn reinstate(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+    variant_code: &str,
+) -> Convert<Product> {
+    check_permissions!(session.clone(), Action::ModifyProduct);
+    Product::reinstate(sku, variant_code, session, &db.0)
+        .await
+        .into()
+}
+
+/// Moves a variant's stock at a store to another bin.
+#[openapi(tag = "Product")]
+#[post("/<sku>/bin/<variant_code>", data = "<input_data>")]
+pub async fn assign_bin(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+    variant_code: &str,
+    input_data: Validated<Json<BinAssignment>>,
+) -> Convert<Product> {
+    check_permissions!(session.clone(), Action::ModifyProduct);
+    Product::assign_bin(sku, variant_code, input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+/// Schedules a change to a variant's retail price, which it sells at from `effective_at`.
+#[openapi(tag = "Product")]
+#[post("/<sku>/price/<variant_code>", data = "<input_data>")]
+pub async fn schedule_price_change(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+    variant_code: &str,
+    input_data: Validated<Json<PriceChangeInput>>,
+) -> Convert<Product> {
+    check_permissions!(session.clone(), Action::ModifyProduct);
+    Product::schedule_price_change(sku, variant_code, input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Product")]
+#[post("/<sku>/price/<variant_code>/cancel/<change_id>")]
+pub async fn cancel_price_change(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+    variant_code: &str,
+    change_id: &str,
+) -> Convert<Product> {
+    check_permissions!(session.clone(), Action::ModifyProduct);
+    Product::cancel_price_change(sku, variant_code, change_id, session, &db.0)
+        .await
+        .into()
+}
+
+/// Adjusts the price of every variant of the products carrying any of the given tags.
+#[openapi(tag = "Product")]
+#[post("/price/bulk", data = "<input_data>")]
+pub async fn bulk_update_prices(
+    db: InternalDb,
+    session: Session,
+    input_data: Validated<Json<BulkPriceUpdate>>,
+) -> Convert<BulkPriceSummary> {
+    check_permissions!(session.clone(), Action::BulkUpdatePrices);
+    Product::bulk_update_prices(input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+/// Every change to the retail prices of the product's variants, most recent first.
+#[openapi(tag = "Product")]
+#[get("/<sku>/price-history", rank = 2)]
+pub async fn price_history(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+) -> Convert<Vec<PriceHistoryEntry>> {
+    check_permissions!(session.clone(), Action::FetchProduct);
+    PriceHistoryEntry::fetch_by_product(sku, session, &db.0)
+        .await
+        .into()
+}
+
+/// The retail price a variant sold at, at a time given in RFC 3339, i.e. for a backdated return.
+#[openapi(tag = "Product")]
+#[get("/<sku>/price/<variant_code>?<at>")]
+pub async fn effective_price(
+    db: InternalDb,
+    session: Session,
+    sku: &str,
+    variant_code: &str,
+    at: &str,
+) -> Convert<EffectivePrice> {
+    check_permissions!(session.clone(), Action::FetchProduct);
+
+    let at = match DateTime::parse_from_rfc3339(at) {
+        Ok(at) => at.with_timezone(&Utc),
+        Err(_) => {
+            return Err(ErrorResponse::custom_input_error(
+                "Time must be given in RFC 3339, i.e. 2023-08-01T00:00:00Z.",
+            ))
+            .into()
+        }
+    };
+
+    PriceHistoryEntry::effective_price(sku, variant_code, at, session, &db.0)
+        .await
+        .into()
+}
+
 #[openapi(tag = "Product")]
 #[post("/promotion/<id>", data = "<input_data>")]
 async fn update_promotion(
@@ -187,7 +394,12 @@ pub async fn create_promotion(
 
 #[openapi(tag = "Product")]
 #[post("/generate/promotion")]
-async fn generate_promotion(db: InternalDb, session: Session) -> Convert<Vec<Promotion>> {
+async fn generate_promotion(
+    db: InternalDb,
+    session: Session,
+    demo: &State<DemoMode>,
+) -> Convert<Vec<Generated<Promotion>>> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Promotion::generate(session, &db.0).await.into()
 }
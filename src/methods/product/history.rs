@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "process")]
+use crate::entities::prelude::PriceHistory as PriceHistoryEntity;
+#[cfg(feature = "process")]
+use crate::entities::price_history;
+use crate::methods::{Error, Id};
+use crate::{ErrorResponse, Product, ScheduledPriceChange, Session, VariantInformation};
+#[cfg(feature = "process")]
+use sea_orm::{ColumnTrait, ConnectionTrait, DbConn, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+/// A change to a variant's retail price, kept such that the price it sold at
+/// at any time can be found, see [`PriceHistoryEntry::price_at`].
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct PriceHistoryEntry {
+    pub id: Id,
+    pub product_sku: String,
+    pub variant_code: String,
+    pub old_price: f32,
+    pub new_price: f32,
+    /// The employee who changed the price, or who scheduled the change.
+    pub changed_by: Id,
+    /// When the new price took effect.
+    pub changed_at: DateTime<Utc>,
+}
+
+/// The retail price a variant sold at, at a given time.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct EffectivePrice {
+    pub variant_code: String,
+    pub at: DateTime<Utc>,
+    pub retail_price: f32,
+}
+
+impl PriceHistoryEntry {
+    /// The changes to the retail price of each variant of the product from `before`
+    /// to `after`, made by `employee_id` at `at`. Scheduled changes settled in between
+    /// are recorded as made by whoever scheduled them, at the time they took effect.
+    pub fn between(
+        before: &Product,
+        after: &Product,
+        employee_id: &str,
+        at: DateTime<Utc>,
+    ) -> Vec<PriceHistoryEntry> {
+        let mut entries = vec![];
+
+        for variant in &after.variants {
+            // A variant new to the product has no earlier price to record.
+            let previous = match before
+                .variants
+                .iter()
+                .find(|v| v.barcode == variant.barcode)
+            {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let mut settled: Vec<&ScheduledPriceChange> = previous
+                .scheduled_prices
+                .iter()
+                .filter(|change| {
+                    change.effective_at <= at
+                        && !variant.scheduled_prices.iter().any(|c| c.id == change.id)
+                })
+                .collect();
+            settled.sort_by_key(|change| change.effective_at);
+
+            let mut price = previous.retail_price;
+
+            for change in settled {
+                if change.retail_price != price {
+                    entries.push(Self::new(
+                        &after.sku,
+                        variant,
+                        price,
+                        change.retail_price,
+                        &change.scheduled_by,
+                        change.effective_at,
+                    ));
+                    price = change.retail_price;
+                }
+            }
+
+            if variant.retail_price != price {
+                entries.push(Self::new(
+                    &after.sku,
+                    variant,
+                    price,
+                    variant.retail_price,
+                    employee_id,
+                    at,
+                ));
+            }
+        }
+
+        entries
+    }
+
+    fn new(
+        sku: &str,
+        variant: &VariantInformation,
+        old_price: f32,
+        new_price: f32,
+        changed_by: &str,
+        changed_at: DateTime<Utc>,
+    ) -> PriceHistoryEntry {
+        PriceHistoryEntry {
+            id: Uuid::new_v4().to_string(),
+            product_sku: sku.to_string(),
+            variant_code: variant.barcode.clone(),
+            old_price,
+            new_price,
+            changed_by: changed_by.to_string(),
+            changed_at,
+        }
+    }
+
+    /// The retail price of `variant` at `at`, given the variant's `history` in any order.
+    /// Before the first recorded change the variant sold at that change's old price,
+    /// and after the last at its price now, including any change scheduled since.
+    pub fn price_at(
+        variant: &VariantInformation,
+        history: &[PriceHistoryEntry],
+        at: DateTime<Utc>,
+    ) -> f32 {
+        history
+            .iter()
+            .filter(|entry| entry.variant_code == variant.barcode && entry.changed_at > at)
+            .min_by_key(|entry| entry.changed_at)
+            .map_or_else(|| variant.retail_price_at(at), |entry| entry.old_price)
+    }
+}
+
+#[cfg(feature = "methods")]
+impl PriceHistoryEntry {
+    /// Records `entries` as part of `db`, which should be the database transaction
+    /// writing the prices they describe.
+    pub async fn record<C: ConnectionTrait>(
+        entries: Vec<PriceHistoryEntry>,
+        tenant_id: &str,
+        db: &C,
+    ) -> Result<(), Error> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        PriceHistoryEntity::insert_many(
+            entries
+                .into_iter()
+                .map(|entry| entry.into_active(tenant_id.to_string())),
+        )
+        .exec(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Every change to the retail prices of the product's variants, most recent first.
+    pub async fn fetch_by_product(
+        sku: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<PriceHistoryEntry>, Error> {
+        let entries = PriceHistoryEntity::find()
+            .filter(price_history::Column::ProductSku.eq(sku))
+            .filter(price_history::Column::TenantId.eq(session.tenant_id))
+            .order_by_desc(price_history::Column::ChangedAt)
+            .all(db)
+            .await?;
+
+        Ok(entries.into_iter().map(|e| e.into()).collect())
+    }
+
+    /// The retail price a variant of the product sold at, at `at`, such as to refund
+    /// a purchase at the price paid.
+    pub async fn effective_price(
+        sku: &str,
+        variant_code: &str,
+        at: DateTime<Utc>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<EffectivePrice, Error> {
+        let product = Product::fetch_by_id(sku, session.clone(), db).await?;
+        let variant = product
+            .variants
+            .iter()
+            .find(|v| v.barcode == variant_code)
+            .ok_or_else(|| ErrorResponse::not_found("Variant", variant_code))?;
+
+        let history = Self::fetch_by_product(sku, session, db).await?;
+
+        Ok(EffectivePrice {
+            variant_code: variant.barcode.clone(),
+            at,
+            retail_price: Self::price_at(variant, &history, at),
+        })
+    }
+}
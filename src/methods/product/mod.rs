@@ -1,11 +1,21 @@
+mod barcode;
+#[cfg(feature = "process")]
+mod cache;
 mod conversions;
 mod example;
 #[cfg(feature = "process")]
 pub(crate) mod handlers;
+mod history;
 mod structs;
+mod unit;
 mod variant;
 
+pub use barcode::*;
+#[cfg(feature = "process")]
+pub use cache::*;
 #[cfg(feature = "process")]
 pub use handlers::*;
+pub use history::*;
 pub use structs::*;
+pub use unit::*;
 pub use variant::*;
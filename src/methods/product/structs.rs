@@ -1,12 +1,17 @@
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::io::Read;
 
-use crate::{methods::Error, History, Session, TransactionType};
-use chrono::{DateTime, Utc};
+use crate::{
+    methods::Error, ErrorResponse, FieldError, History, Id, Session, Store, Transaction,
+    TransactionType,
+};
+use chrono::{DateTime, Duration, Utc};
 #[cfg(feature = "process")]
 use sea_orm::{
     sea_query::{Expr, Func},
-    ActiveModelTrait, ColumnTrait, Condition, DbConn, EntityTrait, InsertResult, QueryFilter,
-    QuerySelect, Statement,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbConn, DbErr, EntityTrait,
+    InsertResult, QueryFilter, QuerySelect, Select, Statement, TransactionTrait,
 };
 use serde::{
     de::{MapAccess, Visitor},
@@ -15,7 +20,8 @@ use serde::{
 use uuid::Uuid;
 
 use super::{
-    Promotion, PromotionBuy, PromotionGet, VariantCategoryList, VariantIdTag, VariantInformation,
+    Barcode, PriceHistoryEntry, Promotion, PromotionBuy, PromotionGet, ScheduledPriceChange,
+    VariantCategoryList, VariantIdTag, VariantInformation,
 };
 #[cfg(feature = "process")]
 use crate::entities::prelude::Products;
@@ -23,16 +29,24 @@ use crate::entities::prelude::Products;
 use crate::entities::prelude::Promotion as Promotions;
 #[cfg(feature = "process")]
 use crate::entities::products;
+#[cfg(feature = "process")]
+use crate::methods::fuzzy_rank;
+#[cfg(feature = "process")]
+use crate::methods::PRODUCT_CACHE;
 
 use crate::product::example::example_products;
 use crate::{
-    methods::{DiscountValue, TagList, Url},
+    methods::{
+        parse_csv, validate_image_urls, CsvRecord, DiscountValue, ImportSummary, ImportedRow,
+        Location, Quantity, RoundingMode, Stock, StockInformation, Tag, TagList, UnitOfMeasure,
+        Url,
+    },
     Note,
 };
 #[cfg(feature = "process")]
 use futures::future::join_all;
 use schemars::JsonSchema;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 #[cfg(feature = "types")]
 #[derive(Deserialize, Serialize, Clone, JsonSchema)]
@@ -42,6 +56,125 @@ pub enum ProductVisibility {
     ShowWhenInStock,
 }
 
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct VariantMargin {
+    pub product_sku: String,
+    pub product_name: String,
+    pub variant_id: String,
+    pub variant_name: String,
+    pub barcode: String,
+    pub retail_price: f32,
+    pub marginal_price: f32,
+    /// The absolute margin, `retail_price - marginal_price`.
+    pub margin: f32,
+    /// The margin as a percentage of cost, `None` where no cost price is recorded.
+    pub margin_percentage: Option<f32>,
+    /// Set when the variant is being sold for less than it costs.
+    pub at_loss: bool,
+}
+
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct MarginReport {
+    /// Variants currently selling at a loss, a subset of `variants`.
+    pub loss_making: Vec<VariantMargin>,
+    /// All variants, sorted by descending absolute margin.
+    pub variants: Vec<VariantMargin>,
+}
+
+/// A variant held in stock which has not sold over a period, see [`Product::dead_stock`].
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct DeadStock {
+    pub product_sku: String,
+    pub product_name: String,
+    pub variant_id: String,
+    pub variant_name: String,
+    pub barcode: String,
+    /// The sellable quantity on hand, at the store reported on if any.
+    pub quantity: f32,
+    pub marginal_price: f32,
+    /// The cost of the stock on hand, `marginal_price * quantity`.
+    pub stock_value: f32,
+}
+
+/// Moves a variant's stock at a store to another bin, see [`Product::assign_bin`].
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct BinAssignment {
+    pub store_id: Id,
+    /// The bin the stock is now kept in, `None` to clear it.
+    #[validate(length(min = 1))]
+    pub bin_location: Option<String>,
+}
+
+/// Plans a change to a variant's retail price, see [`Product::schedule_price_change`].
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct PriceChangeInput {
+    #[validate(range(min = 0.0))]
+    pub retail_price: f32,
+    /// When the price takes effect, which must be in the future.
+    pub effective_at: DateTime<Utc>,
+}
+
+/// The price of each variant a [`BulkPriceUpdate`] adjusts.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum PriceField {
+    Retail,
+    Marginal,
+}
+
+/// How a [`BulkPriceUpdate`] changes each price, i.e. `Percentage(-10.0)` for 10% off
+/// or `Absolute(2.5)` for a $2.50 rise.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum PriceAdjustment {
+    Percentage(f32),
+    Absolute(f32),
+}
+
+impl PriceAdjustment {
+    /// The adjusted price, rounded to the cent.
+    pub fn apply(&self, price: f32) -> f32 {
+        let adjusted = match self {
+            PriceAdjustment::Percentage(percentage) => price * (1.0 + percentage / 100.0),
+            PriceAdjustment::Absolute(delta) => price + delta,
+        };
+
+        RoundingMode::HalfUp.round(adjusted)
+    }
+}
+
+/// Adjusts the price of every variant of the products carrying any of `tags`,
+/// see [`Product::bulk_update_prices`].
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct BulkPriceUpdate {
+    #[validate(custom = "validate_tags")]
+    pub tags: TagList,
+    pub field: PriceField,
+    pub adjustment: PriceAdjustment,
+}
+
+fn validate_tags(tags: &[Tag]) -> Result<(), ValidationError> {
+    if tags.iter().all(|tag| tag.trim().is_empty()) {
+        return Err(ValidationError::new("empty_tags"));
+    }
+
+    Ok(())
+}
+
+/// The number of products, and of their variants, a [`BulkPriceUpdate`] repriced.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct BulkPriceSummary {
+    pub products: usize,
+    pub variants: usize,
+}
+
 #[cfg(feature = "types")]
 #[derive(Deserialize, Serialize, Clone, Default, JsonSchema, Validate)]
 pub struct ProductIdentification {
@@ -52,6 +185,10 @@ pub struct ProductIdentification {
     pub isbn: String,
 }
 
+/// The most products written by a single `INSERT`. Each product binds 16 parameters,
+/// keeping well clear of MySQL's limit of 65,535 placeholders per statement.
+pub const PRODUCT_INSERT_BATCH: usize = 500;
+
 #[cfg(feature = "types")]
 #[derive(Deserialize, Serialize, Clone, JsonSchema, Validate)]
 /// A product, containing a list of `Vec<Variant>`, an identifiable `sku` along with identifying information such as `tags`, `description` and `specifications`.
@@ -64,11 +201,13 @@ pub struct Product {
 
     pub variant_groups: VariantCategoryList,
     /// Lists all the **possible** combinations of a product in terms of its variants.
+    #[validate]
     pub variants: Vec<VariantInformation>,
 
     pub sku: String,
     pub identification: ProductIdentification,
 
+    #[validate(custom = "validate_image_urls")]
     pub images: Vec<Url>,
     pub tags: TagList,
     pub description: String,
@@ -123,15 +262,109 @@ impl Product {
         }
     }
 
+    /// Fetches the product with the given sku, from the [`PRODUCT_CACHE`] if enabled.
     pub async fn fetch_by_id(id: &str, session: Session, db: &DbConn) -> Result<Product, Error> {
+        if let Some(product) = PRODUCT_CACHE.get(&session.tenant_id, id) {
+            return Ok(product);
+        }
+
+        Self::fetch_uncached(id, session, db).await
+    }
+
+    /// Fetches the product with the given sku from the database, bypassing the cache,
+    /// as must be done to read a product before altering it.
+    pub async fn fetch_uncached(id: &str, session: Session, db: &DbConn) -> Result<Product, Error> {
         let pdt = Products::find_by_id(id.to_string())
-            .filter(products::Column::TenantId.eq(session.tenant_id))
+            .filter(products::Column::TenantId.eq(session.tenant_id.clone()))
             .one(db)
             .await?;
 
-        let p = pdt.unwrap();
+        match pdt {
+            Some(p) => {
+                let product: Product = p.into();
+                PRODUCT_CACHE.put(&session.tenant_id, &product);
+                Ok(product)
+            }
+            None => Err(ErrorResponse::not_found("Product", id)),
+        }
+    }
+
+    /// Fetches the product with the given sku, locking it until `db`, a database
+    /// transaction, is committed or rolled back. Concurrent alterations of the
+    /// product are thereby made one after another, rather than overwriting each other.
+    ///
+    /// This relies on InnoDB's row locks under its default `REPEATABLE READ` isolation.
+    /// A locking read, `SELECT ... FOR UPDATE`, sees the latest committed product rather
+    /// than the transaction's snapshot, but a plain read does neither, so every write
+    /// of a product must read it this way, see [`Product::alter`].
+    pub async fn fetch_for_update<C: ConnectionTrait>(
+        id: &str,
+        tenant_id: &str,
+        db: &C,
+    ) -> Result<Product, Error> {
+        let pdt = Self::select_for_update(id, tenant_id).one(db).await?;
+
+        match pdt {
+            Some(p) => Ok(p.into()),
+            None => Err(ErrorResponse::not_found("Product", id)),
+        }
+    }
 
-        Ok(p.into())
+    /// The locking read of [`Product::fetch_for_update`].
+    pub fn select_for_update(id: &str, tenant_id: &str) -> Select<Products> {
+        Products::find_by_id(id.to_string())
+            .filter(products::Column::TenantId.eq(tenant_id))
+            .lock_exclusive()
+    }
+
+    /// Alters the product with the given sku whilst it is locked, see
+    /// [`Product::fetch_for_update`], writing it only if `alteration` succeeds.
+    pub async fn alter<F>(
+        sku: &str,
+        session: Session,
+        db: &DbConn,
+        alteration: F,
+    ) -> Result<Product, Error>
+    where
+        F: FnOnce(&mut Product) -> Result<(), Error>,
+    {
+        let txn = db.begin().await?;
+        Self::alter_within(sku, session.clone(), &txn, alteration).await?;
+        txn.commit().await?;
+        PRODUCT_CACHE.invalidate(&session.tenant_id, sku);
+
+        Self::fetch_by_id(sku, session, db).await
+    }
+
+    /// As [`Product::alter`], but as part of `txn`, a database transaction which the
+    /// caller commits alongside its own writes. Once committed, the caller must
+    /// invalidate the product in the [`PRODUCT_CACHE`].
+    pub async fn alter_within<F, C>(
+        sku: &str,
+        session: Session,
+        txn: &C,
+        alteration: F,
+    ) -> Result<Product, Error>
+    where
+        F: FnOnce(&mut Product) -> Result<(), Error>,
+        C: ConnectionTrait,
+    {
+        let mut product = Self::fetch_for_update(sku, &session.tenant_id, txn).await?;
+        let before = product.clone();
+
+        alteration(&mut product)?;
+
+        let history =
+            PriceHistoryEntry::between(&before, &product, &session.employee.id, Utc::now());
+
+        product
+            .clone()
+            .into_active(session.clone())
+            .update(txn)
+            .await?;
+        PriceHistoryEntry::record(history, &session.tenant_id, txn).await?;
+
+        Ok(product)
     }
 
     pub async fn fetch_by_id_with_promotion(
@@ -143,9 +376,11 @@ impl Product {
             .filter(products::Column::TenantId.eq(session.tenant_id))
             .one(db)
             .await?;
-        let p = pdt.unwrap();
 
-        let product: Product = p.into();
+        let product: Product = match pdt {
+            Some(p) => p.into(),
+            None => return Err(ErrorResponse::not_found("Product", id)),
+        };
 
         let promos = Promotions::find()
             .from_raw_sql(Statement::from_sql_and_values(
@@ -295,19 +530,74 @@ impl Product {
         Ok(with_promotions)
     }
 
+    /// Finds the variant carrying the given barcode, as used when scanning at the point of sale.
+    /// Popular products are served from the [`PRODUCT_CACHE`] if enabled.
+    pub async fn fetch_by_barcode(
+        barcode: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<VariantInformation, Error> {
+        if let Some(product) = PRODUCT_CACHE.get_by_barcode(&session.tenant_id, barcode) {
+            if let Some(variant) = product.variants.into_iter().find(|v| v.barcode == barcode) {
+                return Ok(variant);
+            }
+        }
+
+        let res = products::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::MySql,
+                "SELECT * FROM `Products` WHERE ? MEMBER OF(`variants`->'$[*].barcode') AND `tenant_id` = ? LIMIT 1",
+                vec![barcode.into(), session.tenant_id.clone().into()],
+            ))
+            .one(db)
+            .await?;
+
+        let variant = res.and_then(|p| {
+            let product = Product::from(p);
+            PRODUCT_CACHE.put(&session.tenant_id, &product);
+
+            product.variants.into_iter().find(|v| v.barcode == barcode)
+        });
+
+        match variant {
+            Some(v) => Ok(v),
+            None => {
+                Err(DbErr::RecordNotFound(format!("No variant with barcode {}", barcode)).into())
+            }
+        }
+    }
+
+    /// Finds products whose name is within `max_distance` edits of `name`,
+    /// exact matches first and the remainder by similarity.
     pub async fn fetch_by_name(
         name: &str,
+        max_distance: usize,
         session: Session,
         db: &DbConn,
     ) -> Result<Vec<Product>, Error> {
+        let names: Vec<(String, String)> = products::Entity::find()
+            .select_only()
+            .column(products::Column::Sku)
+            .column(products::Column::Name)
+            .filter(products::Column::TenantId.eq(session.tenant_id.clone()))
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        let mut skus = fuzzy_rank(name, max_distance, names);
+        skus.truncate(25);
+
         let res = products::Entity::find()
-            .having(products::Column::Name.contains(name))
             .filter(products::Column::TenantId.eq(session.tenant_id))
-            .limit(25)
+            .filter(products::Column::Sku.is_in(skus.clone()))
             .all(db)
             .await?;
 
-        let mapped = res.iter().map(|p| p.clone().into()).collect();
+        let mapped = skus
+            .iter()
+            .filter_map(|sku| res.iter().find(|p| &p.sku == sku))
+            .map(|p| p.clone().into())
+            .collect();
 
         Ok(mapped)
     }
@@ -335,11 +625,482 @@ impl Product {
         id: &str,
         db: &DbConn,
     ) -> Result<Product, Error> {
-        pdt.into_active(session.clone()).update(db).await?;
+        let now = Utc::now();
+        let mut pdt = pdt;
+        let txn = db.begin().await?;
+        let before = Self::fetch_for_update(&pdt.sku, &session.tenant_id, &txn).await?;
+        pdt.settle_due_prices(&before, now);
+
+        let history = PriceHistoryEntry::between(&before, &pdt, &session.employee.id, now);
+
+        pdt.into_active(session.clone()).update(&txn).await?;
+        PriceHistoryEntry::record(history, &session.tenant_id, &txn).await?;
+        txn.commit().await?;
+        PRODUCT_CACHE.invalidate(&session.tenant_id, id);
 
         Self::fetch_by_id(id, session, db).await
     }
 
+    /// Marks a variant as discontinued. It remains sellable whilst it has stock on hand,
+    /// but may no longer be back-ordered, see [`VariantInformation::available_at`].
+    pub async fn discontinue(
+        sku: &str,
+        variant_code: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        Self::set_discontinued(sku, variant_code, true, session, db).await
+    }
+
+    /// Reverses [`Product::discontinue`].
+    pub async fn reinstate(
+        sku: &str,
+        variant_code: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        Self::set_discontinued(sku, variant_code, false, session, db).await
+    }
+
+    async fn set_discontinued(
+        sku: &str,
+        variant_code: &str,
+        discontinued: bool,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        Self::alter(sku, session, db, |product| {
+            let variant = product
+                .variants
+                .iter_mut()
+                .find(|v| v.barcode == variant_code)
+                .ok_or_else(|| ErrorResponse::not_found("Variant", variant_code))?;
+
+            variant.stock_information.discontinued = discontinued;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Sets the bin a variant is kept in at a store, creating an empty stock entry
+    /// for the store if the variant has not been stocked there before. Where the
+    /// store has a [`StoreLayout`](crate::StoreLayout), the bin must be one of its bins.
+    pub async fn assign_bin(
+        sku: &str,
+        variant_code: &str,
+        assignment: BinAssignment,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        session.ensure_store(&assignment.store_id)?;
+        let store = Store::fetch_by_id(&assignment.store_id, session.clone(), db).await?;
+        let bin_location = assignment.bin_location.map(|bin| bin.trim().to_string());
+
+        if let (Some(layout), Some(bin)) = (&store.layout, &bin_location) {
+            if !layout.contains(bin) {
+                return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+                    "bin_location",
+                    &format!("{} is not a bin of store {}.", bin, store.code),
+                )]));
+            }
+        }
+
+        Self::alter(sku, session, db, |product| {
+            let variant = product
+                .variants
+                .iter_mut()
+                .find(|v| v.barcode == variant_code)
+                .ok_or_else(|| ErrorResponse::not_found("Variant", variant_code))?;
+
+            match variant
+                .stock
+                .iter_mut()
+                .find(|s| s.store.store_id == store.id)
+            {
+                Some(stock) => stock.bin_location = bin_location,
+                None => variant.stock.push(Stock {
+                    store: Location {
+                        store_code: store.code,
+                        store_id: store.id,
+                        contact: store.contact,
+                    },
+                    quantity: Quantity {
+                        quantity_sellable: 0.0,
+                        quantity_unsellable: 0.0,
+                        quantity_on_order: 0.0,
+                        quantity_allocated: 0.0,
+                        quantity_reserved: 0.0,
+                    },
+                    bin_location,
+                }),
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Schedules a change to a variant's retail price, taking effect at `effective_at`.
+    /// Until then the variant sells at its current price, see [`VariantInformation::price_for`].
+    pub async fn schedule_price_change(
+        sku: &str,
+        variant_code: &str,
+        change: PriceChangeInput,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        let now = Utc::now();
+
+        if change.effective_at <= now {
+            return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+                "effective_at",
+                "A price change must be scheduled for the future, update the product to change its price now.",
+            )]));
+        }
+
+        let scheduled = ScheduledPriceChange {
+            id: Uuid::new_v4().to_string(),
+            retail_price: change.retail_price,
+            effective_at: change.effective_at,
+            scheduled_by: session.employee.id.clone(),
+            scheduled_at: now,
+        };
+
+        Self::alter(sku, session, db, |product| {
+            let variant = product
+                .variants
+                .iter_mut()
+                .find(|v| v.barcode == variant_code)
+                .ok_or_else(|| ErrorResponse::not_found("Variant", variant_code))?;
+
+            // Changes which have since taken effect are settled, so as not to accumulate.
+            variant.apply_due_prices(now);
+            variant.scheduled_prices.push(scheduled);
+            variant
+                .scheduled_prices
+                .sort_by_key(|change| change.effective_at);
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Cancels a scheduled price change which is yet to take effect.
+    pub async fn cancel_price_change(
+        sku: &str,
+        variant_code: &str,
+        change_id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        let now = Utc::now();
+
+        Self::alter(sku, session, db, |product| {
+            let variant = product
+                .variants
+                .iter_mut()
+                .find(|v| v.barcode == variant_code)
+                .ok_or_else(|| ErrorResponse::not_found("Variant", variant_code))?;
+
+            let change = variant
+                .scheduled_prices
+                .iter()
+                .find(|change| change.id == change_id)
+                .ok_or_else(|| ErrorResponse::not_found("Price change", change_id))?;
+
+            if change.effective_at <= now {
+                return Err(ErrorResponse::conflict(&format!(
+                    "Price change {} has already taken effect.",
+                    change_id
+                )));
+            }
+
+            variant
+                .scheduled_prices
+                .retain(|change| change.id != change_id);
+            variant.apply_due_prices(now);
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Settles the scheduled changes which have taken effect by `at` into each variant's
+    /// `retail_price`, as an update to the product is written. A price edited since
+    /// `before` was made after those changes took effect, so is kept.
+    pub fn settle_due_prices(&mut self, before: &Product, at: DateTime<Utc>) {
+        for variant in self.variants.iter_mut() {
+            let edited = !before
+                .variants
+                .iter()
+                .any(|v| v.barcode == variant.barcode && v.retail_price == variant.retail_price);
+            let price = variant.retail_price;
+
+            variant.apply_due_prices(at);
+
+            if edited {
+                variant.retail_price = price;
+            }
+        }
+    }
+
+    /// Whether the product carries any of `tags`.
+    pub fn tagged_any(&self, tags: &[Tag]) -> bool {
+        self.tags.iter().any(|tag| tags.contains(tag))
+    }
+
+    /// Applies `update` to the price of every variant, returning the number repriced.
+    /// Should any price fall below zero the product is left as it was.
+    pub fn adjust_prices(
+        &mut self,
+        update: &BulkPriceUpdate,
+        at: DateTime<Utc>,
+    ) -> Result<usize, Error> {
+        let mut adjusted = self.variants.clone();
+
+        for variant in adjusted.iter_mut() {
+            let price = match update.field {
+                PriceField::Retail => {
+                    // Changes which have since taken effect are settled first, as
+                    // the adjustment is made to the price the variant sells at.
+                    variant.apply_due_prices(at);
+                    &mut variant.retail_price
+                }
+                PriceField::Marginal => &mut variant.marginal_price,
+            };
+
+            *price = update.adjustment.apply(*price);
+
+            if *price < 0.0 {
+                return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+                    "adjustment",
+                    &format!(
+                        "Variant {} of product {} would be priced below zero.",
+                        variant.barcode, self.sku
+                    ),
+                )]));
+            }
+        }
+
+        self.variants = adjusted;
+
+        Ok(self.variants.len())
+    }
+
+    /// Adjusts the prices of every product carrying any of the update's tags, within a
+    /// single database transaction such that either every product is repriced or none are.
+    pub async fn bulk_update_prices(
+        update: BulkPriceUpdate,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<BulkPriceSummary, Error> {
+        let now = Utc::now();
+        let txn = db.begin().await?;
+
+        let products = Products::find()
+            .filter(products::Column::TenantId.eq(session.tenant_id.clone()))
+            .lock_exclusive()
+            .all(&txn)
+            .await?;
+
+        let mut summary = BulkPriceSummary::default();
+        let mut repriced = vec![];
+        let mut history = vec![];
+
+        for model in products {
+            let mut product: Product = model.into();
+
+            if !product.tagged_any(&update.tags) {
+                continue;
+            }
+
+            let before = product.clone();
+            summary.variants += product.adjust_prices(&update, now)?;
+            summary.products += 1;
+            history.append(&mut PriceHistoryEntry::between(
+                &before,
+                &product,
+                &session.employee.id,
+                now,
+            ));
+
+            repriced.push(product.sku.clone());
+            product.into_active(session.clone()).update(&txn).await?;
+        }
+
+        PriceHistoryEntry::record(history, &session.tenant_id, &txn).await?;
+
+        txn.commit().await?;
+
+        for sku in &repriced {
+            PRODUCT_CACHE.invalidate(&session.tenant_id, sku);
+        }
+
+        Ok(summary)
+    }
+
+    /// Adds received goods to the sellable stock of the variant `barcode` at the given
+    /// location, drawing down any quantity which was on order. A stock entry is created
+    /// for the location if the variant has not been stocked there before.
+    pub fn receive(
+        &mut self,
+        barcode: &str,
+        location: Location,
+        quantity: f32,
+    ) -> Result<(), Error> {
+        let variant = match self.variants.iter_mut().find(|v| v.barcode == barcode) {
+            Some(v) => v,
+            None => {
+                return Err(DbErr::RecordNotFound(format!(
+                    "Variant {} does not exist on product {}",
+                    barcode, self.sku
+                ))
+                .into())
+            }
+        };
+
+        match variant
+            .stock
+            .iter_mut()
+            .find(|s| s.store.store_code == location.store_code)
+        {
+            Some(stock) => {
+                stock.quantity.quantity_sellable += quantity;
+                stock.quantity.quantity_on_order =
+                    (stock.quantity.quantity_on_order - quantity).max(0.0);
+            }
+            None => variant.stock.push(Stock {
+                store: location,
+                quantity: Quantity {
+                    quantity_sellable: quantity,
+                    quantity_unsellable: 0.0,
+                    quantity_on_order: 0.0,
+                    quantity_allocated: 0.0,
+                    quantity_reserved: 0.0,
+                },
+                bin_location: None,
+            }),
+        }
+
+        Ok(())
+    }
+
+    /// Records a new cost from a supplier against each variant of `sku` sourced from them,
+    /// updating the variant's `marginal_price` to match.
+    pub async fn update_cost_from_supplier(
+        sku: &str,
+        supplier_id: &str,
+        cost: f32,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        Self::alter(sku, session, db, |product| {
+            let mut matched = false;
+            for variant in product.variants.iter_mut() {
+                matched |= variant.record_supplier_cost(supplier_id, cost);
+            }
+
+            if !matched {
+                return Err(DbErr::RecordNotFound(format!(
+                    "No variant of product {} is supplied by {}",
+                    sku, supplier_id
+                ))
+                .into());
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Computes the margin of every variant, ordered from most to least profitable.
+    pub async fn margin_report(session: Session, db: &DbConn) -> Result<MarginReport, Error> {
+        let products = Self::fetch_all(session, db).await?;
+
+        let mut variants: Vec<VariantMargin> = products
+            .iter()
+            .flat_map(|p| {
+                p.variants.iter().map(|v| VariantMargin {
+                    product_sku: p.sku.clone(),
+                    product_name: p.name.clone(),
+                    variant_id: v.id.clone(),
+                    variant_name: v.name.clone(),
+                    barcode: v.barcode.clone(),
+                    retail_price: v.current_price(),
+                    marginal_price: v.marginal_price,
+                    margin: v.margin(),
+                    margin_percentage: if v.marginal_price == 0.0 {
+                        None
+                    } else {
+                        Some(v.margin() / v.marginal_price * 100.0)
+                    },
+                    at_loss: v.current_price() < v.marginal_price,
+                })
+            })
+            .collect();
+
+        variants.sort_by(|a, b| b.margin.total_cmp(&a.margin));
+
+        Ok(MarginReport {
+            loss_making: variants.iter().filter(|v| v.at_loss).cloned().collect(),
+            variants,
+        })
+    }
+
+    /// Finds the variants with stock on hand which have not sold in the last `days`,
+    /// at the store `store_id` if given, ordered from the most valuable stock held.
+    /// Variants which are not diminished by sale, i.e. services, are skipped.
+    pub async fn dead_stock(
+        days: i64,
+        store_id: Option<&str>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<DeadStock>, Error> {
+        if days <= 0 {
+            return Err(ErrorResponse::custom_input_error(
+                "The period must be at least one day.",
+            ));
+        }
+
+        let since = Utc::now() - Duration::days(days);
+        let sold = Transaction::sold_variants(since, store_id, session.clone(), db).await?;
+        let products = Self::fetch_all(session, db).await?;
+
+        let mut dead: Vec<DeadStock> = products
+            .iter()
+            .flat_map(|p| {
+                p.variants
+                    .iter()
+                    .filter(|v| !v.stock_information.non_diminishing)
+                    .filter(|v| !sold.contains(&v.barcode))
+                    .filter_map(|v| {
+                        let quantity: f32 = v
+                            .stock
+                            .iter()
+                            .filter(|s| store_id.is_none_or(|id| s.store.store_id == id))
+                            .map(|s| s.quantity.quantity_sellable)
+                            .sum();
+
+                        (quantity > 0.0).then(|| DeadStock {
+                            product_sku: p.sku.clone(),
+                            product_name: p.name.clone(),
+                            variant_id: v.id.clone(),
+                            variant_name: v.name.clone(),
+                            barcode: v.barcode.clone(),
+                            quantity,
+                            marginal_price: v.marginal_price,
+                            stock_value: v.marginal_price * quantity,
+                        })
+                    })
+            })
+            .collect();
+
+        dead.sort_by(|a, b| b.stock_value.total_cmp(&a.stock_value));
+
+        Ok(dead)
+    }
+
     pub async fn fetch_all(session: Session, db: &DbConn) -> Result<Vec<Product>, Error> {
         let products = Products::find()
             .filter(products::Column::TenantId.eq(session.tenant_id))
@@ -351,19 +1112,124 @@ impl Product {
         Ok(mapped)
     }
 
+    /// Inserts the products in batches of [`PRODUCT_INSERT_BATCH`] within a single
+    /// database transaction, such that either all or none of the products are inserted.
+    /// Returns the result of the final batch.
     pub async fn insert_many(
         products: Vec<Product>,
         session: Session,
         db: &DbConn,
     ) -> Result<InsertResult<products::ActiveModel>, Error> {
-        let entities = products
+        let mut entities: Vec<products::ActiveModel> = products
             .into_iter()
-            .map(|pdt| pdt.into_active(session.clone()));
+            .map(|pdt| pdt.into_active(session.clone()))
+            .collect();
 
-        Products::insert_many(entities)
-            .exec(db)
-            .await
-            .map_err(|e| e.into())
+        let txn = db.begin().await?;
+
+        while entities.len() > PRODUCT_INSERT_BATCH {
+            let remaining = entities.split_off(PRODUCT_INSERT_BATCH);
+            Products::insert_many(entities).exec(&txn).await?;
+            entities = remaining;
+        }
+
+        let res = Products::insert_many(entities).exec(&txn).await?;
+        txn.commit().await?;
+
+        Ok(res)
+    }
+
+    /// Imports products from CSV, with one variant per row. Rows sharing a `sku` are
+    /// grouped into a single product, named after the first such row.
+    ///
+    /// Malformed rows, and rows for a `sku` which already exists, are rejected
+    /// individually whilst the remaining rows are imported.
+    pub async fn import_csv<R: Read>(
+        mut reader: R,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<ImportSummary, Error> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input).map_err(|e| {
+            ErrorResponse::custom_input_error(&format!("Unable to read import: {}", e))
+        })?;
+
+        let records = parse_csv(&input).map_err(|e| ErrorResponse::custom_input_error(&e))?;
+
+        let mut summary = ImportSummary::default();
+        // Each product alongside the lines its variants were read from.
+        let mut imported: Vec<(Product, Vec<usize>)> = vec![];
+
+        for record in records {
+            let variant = match variant_from_record(&record) {
+                Ok(variant) => variant,
+                Err(reason) => {
+                    summary.reject(record.line, reason);
+                    continue;
+                }
+            };
+
+            let sku = record.get("sku");
+
+            match imported.iter_mut().find(|(p, _)| p.sku == sku) {
+                Some((product, lines)) => {
+                    product.variants.push(variant);
+                    lines.push(record.line);
+                }
+                None => {
+                    let mut product = product_from_record(&record);
+                    product.variants.push(variant);
+                    imported.push((product, vec![record.line]));
+                }
+            }
+        }
+
+        let skus: Vec<String> = imported.iter().map(|(p, _)| p.sku.clone()).collect();
+        let existing: Vec<String> = if skus.is_empty() {
+            vec![]
+        } else {
+            Products::find()
+                .filter(products::Column::TenantId.eq(session.tenant_id.clone()))
+                .filter(products::Column::Sku.is_in(skus))
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|p| p.sku)
+                .collect()
+        };
+
+        let (duplicates, imported): (Vec<_>, Vec<_>) = imported
+            .into_iter()
+            .partition(|(p, _)| existing.contains(&p.sku));
+
+        for (product, lines) in duplicates {
+            for line in lines {
+                summary.reject(
+                    line,
+                    format!("Product with sku {} already exists", product.sku),
+                );
+            }
+        }
+
+        if imported.is_empty() {
+            return Ok(summary);
+        }
+
+        for (product, lines) in &imported {
+            for (variant, line) in product.variants.iter().zip(lines) {
+                summary.inserted.push(ImportedRow {
+                    line: *line,
+                    id: variant.id.clone(),
+                });
+            }
+        }
+
+        Self::insert_many(imported.into_iter().map(|(p, _)| p).collect(), session, db).await?;
+
+        summary.inserted.sort_by_key(|r| r.line);
+        summary.rejected.sort_by_key(|r| r.line);
+
+        Ok(summary)
     }
 
     pub async fn generate(session: Session, db: &DbConn) -> Result<Vec<Product>, Error> {
@@ -392,14 +1258,73 @@ pub struct ProductPurchase {
     pub product_variant_name: String,
 
     // Cost before discount, discount will be applied on the product cost.
+    #[validate(range(min = 0.0))]
     pub product_cost: f32,
     pub quantity: f32,
+    /// The unit `quantity` and `product_cost` are in, being the variant's stock unit if absent.
+    pub unit: Option<UnitOfMeasure>,
+    /// How much of `quantity` has been picked and fulfilled so far.
+    #[serde(default)]
+    pub fulfilled_quantity: f32,
+    /// The quantity yet to be fulfilled, derived from `fulfilled_quantity`.
+    #[serde(default)]
+    pub remaining_quantity: f32,
     pub tags: TagList,
 
     pub transaction_type: TransactionType,
+    /// One per unit purchased, those absent from input are created when read.
+    #[serde(default)]
     pub instances: Vec<ProductInstance>,
 }
 
+impl ProductPurchase {
+    /// The purchased quantity in the given stock unit, or `None` if the
+    /// purchase's unit cannot be converted into it.
+    pub fn stock_quantity(&self, stock_unit: UnitOfMeasure) -> Option<f32> {
+        match self.unit {
+            Some(unit) => unit.convert(self.quantity, stock_unit),
+            None => Some(self.quantity),
+        }
+    }
+
+    /// The quantity a single instance stands for. Measured quantities are picked
+    /// as one instance, so it stands for the whole line.
+    pub fn instance_quantity(&self) -> f32 {
+        match self.unit {
+            Some(unit) if !unit.is_countable() => self.quantity,
+            _ => 1.0,
+        }
+    }
+
+    /// Sets the fulfilled quantity, kept between none and all of the line.
+    pub fn set_fulfilled(&mut self, quantity: f32) {
+        self.fulfilled_quantity = quantity.clamp(0.0, self.quantity);
+        self.remaining_quantity = self.quantity - self.fulfilled_quantity;
+    }
+
+    pub fn is_fully_fulfilled(&self) -> bool {
+        self.remaining_quantity <= 0.0
+    }
+}
+
+/// The fields a [`ProductPurchase`] is read from.
+const PRODUCT_PURCHASE_FIELDS: &[&str] = &[
+    "id",
+    "product_code",
+    "product_sku",
+    "discount",
+    "product_name",
+    "product_variant_name",
+    "product_cost",
+    "quantity",
+    "unit",
+    "fulfilled_quantity",
+    "remaining_quantity",
+    "tags",
+    "transaction_type",
+    "instances",
+];
+
 impl<'de> Deserialize<'de> for ProductPurchase {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -432,6 +1357,8 @@ impl<'de> Deserialize<'de> for ProductPurchase {
                 let mut product_cost = None;
                 let mut transaction_type = None;
                 let mut quantity = None;
+                let mut unit = None;
+                let mut fulfilled_quantity = None;
                 let mut instances: Option<Vec<ProductInstance>> = None;
 
                 // pub transaction_type: TransactionType,
@@ -501,16 +1428,34 @@ impl<'de> Deserialize<'de> for ProductPurchase {
                             }
                             quantity = Some(map.next_value()?);
                         }
+                        "unit" => {
+                            if unit.is_some() {
+                                return Err(serde::de::Error::duplicate_field("unit"));
+                            }
+                            unit = map.next_value::<Option<UnitOfMeasure>>()?;
+                        }
+                        "fulfilled_quantity" => {
+                            if fulfilled_quantity.is_some() {
+                                return Err(serde::de::Error::duplicate_field(
+                                    "fulfilled_quantity",
+                                ));
+                            }
+                            fulfilled_quantity = Some(map.next_value::<f32>()?);
+                        }
+                        // Derived from the fulfilled quantity, so is recalculated.
+                        "remaining_quantity" => {
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
                         "instances" => {
                             if instances.is_some() {
-                                return Err(serde::de::Error::duplicate_field("quantity"));
+                                return Err(serde::de::Error::duplicate_field("instances"));
                             }
                             instances = Some(map.next_value()?);
                         }
                         _ => {
                             return Err(serde::de::Error::unknown_field(
                                 key,
-                                &["id", "quantity", "instances"],
+                                PRODUCT_PURCHASE_FIELDS,
                             ))
                         }
                     }
@@ -539,13 +1484,20 @@ impl<'de> Deserialize<'de> for ProductPurchase {
                 let tags = tags.ok_or_else(|| serde::de::Error::missing_field("tags"))?;
                 let mut instances = instances.unwrap_or_default();
 
-                while instances.len() < quantity as usize {
+                // Measured quantities, i.e. 0.25kg, are picked as a single instance.
+                let instance_count = match unit {
+                    Some(unit) if !unit.is_countable() => 1,
+                    _ => quantity as usize,
+                };
+
+                while instances.len() < instance_count {
                     instances.push(ProductInstance {
                         id: format!("{}-{}-{}", id, instances.len() + 1, Uuid::new_v4()),
                         fulfillment_status: default_fulfillment(),
                     });
                 }
-                Ok(ProductPurchase {
+
+                let mut purchase = ProductPurchase {
                     id,
                     product_code,
                     product_sku,
@@ -556,8 +1508,14 @@ impl<'de> Deserialize<'de> for ProductPurchase {
                     product_cost,
                     tags,
                     quantity,
+                    unit,
+                    fulfilled_quantity: 0.0,
+                    remaining_quantity: quantity,
                     instances,
-                })
+                };
+                purchase.set_fulfilled(fulfilled_quantity.unwrap_or_default());
+
+                Ok(purchase)
             }
         }
 
@@ -638,3 +1596,103 @@ pub type ProductCode = String;
 
 #[cfg(feature = "types")]
 pub type ProductPurchaseList = Vec<ProductPurchase>;
+
+/// Reads the product-level columns of an import row; `name`, `company`, `description` and `tags`.
+#[cfg(feature = "methods")]
+fn product_from_record(record: &CsvRecord) -> Product {
+    let sku = record.get("sku").to_string();
+
+    Product {
+        name: record.get("name").to_string(),
+        name_long: String::new(),
+        company: record.get("company").to_string(),
+        variant_groups: vec![],
+        variants: vec![],
+        identification: ProductIdentification {
+            sku: sku.clone(),
+            ..Default::default()
+        },
+        sku,
+        images: vec![],
+        tags: record
+            .get("tags")
+            .split(';')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        description: record.get("description").to_string(),
+        description_long: String::new(),
+        specifications: vec![],
+        visible: ProductVisibility::ShowWhenInStock,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// Reads and validates the variant columns of an import row. The `sku`, `name`,
+/// `barcode` and `retail_price` columns are required, all others are optional.
+#[cfg(feature = "methods")]
+fn variant_from_record(record: &CsvRecord) -> Result<VariantInformation, String> {
+    record.require("sku")?;
+    let name = record.require("name")?;
+    let barcode = Barcode::parse(record.require("barcode")?)?;
+    record.require("retail_price")?;
+    let retail_price = record.number("retail_price")?;
+
+    let supplier_id = match record.get("supplier_id") {
+        "" => None,
+        id => Some(id.to_string()),
+    };
+
+    Ok(VariantInformation {
+        id: match record.get("variant_id") {
+            "" => Uuid::new_v4().to_string(),
+            id => id.to_string(),
+        },
+        name: match record.get("variant") {
+            "" => name.to_string(),
+            variant => variant.to_string(),
+        },
+        stock: vec![],
+        stock_information: StockInformation {
+            stock_group: record.get("stock_group").to_string(),
+            sales_group: record.get("sales_group").to_string(),
+            value_stream: record.get("value_stream").to_string(),
+            min_stock_before_alert: record.number("min_stock_before_alert")?,
+            min_stock_level: record.number("min_stock_level")?,
+            brand: record.get("brand").to_string(),
+            colli: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            size_x_unit: "m".to_string(),
+            size_y_unit: "m".to_string(),
+            size_z_unit: "m".to_string(),
+            size_override_unit: "m".to_string(),
+            tax_code: record.get("tax_code").to_string(),
+            weight: record.get("weight").to_string(),
+            volume: record.get("volume").to_string(),
+            max_volume: String::new(),
+            back_order: false,
+            discontinued: false,
+            non_diminishing: false,
+            shippable: true,
+            supplier_id,
+            supplier_cost: record.number("supplier_cost")? as f32,
+            unit: UnitOfMeasure::default(),
+        },
+        images: vec![],
+        retail_price: retail_price as f32,
+        price_tiers: HashMap::new(),
+        scheduled_prices: vec![],
+        marginal_price: record.number("marginal_price")? as f32,
+        buy_min: 1.0,
+        buy_max: -1.0,
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: vec![],
+        order_history: vec![],
+        barcode: barcode.value,
+        identification: ProductIdentification::default(),
+        stock_tracking: true,
+    })
+}
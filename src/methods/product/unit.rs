@@ -0,0 +1,64 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The unit a variant is stocked in, or a purchase is sold in.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub enum UnitOfMeasure {
+    /// Individual, countable items.
+    #[default]
+    Each,
+    Gram,
+    Kilogram,
+    Millilitre,
+    Litre,
+    Centimetre,
+    Metre,
+}
+
+/// The physical quantity a unit measures, only units of the same dimension convert.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Dimension {
+    Count,
+    Mass,
+    Volume,
+    Length,
+}
+
+impl UnitOfMeasure {
+    fn dimension(&self) -> Dimension {
+        match self {
+            UnitOfMeasure::Each => Dimension::Count,
+            UnitOfMeasure::Gram | UnitOfMeasure::Kilogram => Dimension::Mass,
+            UnitOfMeasure::Millilitre | UnitOfMeasure::Litre => Dimension::Volume,
+            UnitOfMeasure::Centimetre | UnitOfMeasure::Metre => Dimension::Length,
+        }
+    }
+
+    /// The number of the dimension's base unit (grams, millilitres or centimetres) in one of this unit.
+    fn factor(&self) -> f32 {
+        match self {
+            UnitOfMeasure::Each
+            | UnitOfMeasure::Gram
+            | UnitOfMeasure::Millilitre
+            | UnitOfMeasure::Centimetre => 1.0,
+            UnitOfMeasure::Kilogram | UnitOfMeasure::Litre => 1000.0,
+            UnitOfMeasure::Metre => 100.0,
+        }
+    }
+
+    /// Whether quantities in this unit are counted in whole items rather than measured.
+    pub fn is_countable(&self) -> bool {
+        self.dimension() == Dimension::Count
+    }
+
+    /// Converts `quantity` of this unit into the unit `to`, i.e. 250 grams into 0.25 kilograms.
+    /// Returns `None` if the units measure different things.
+    pub fn convert(&self, quantity: f32, to: UnitOfMeasure) -> Option<f32> {
+        if self.dimension() != to.dimension() {
+            return None;
+        }
+
+        Some(quantity * self.factor() / to.factor())
+    }
+}
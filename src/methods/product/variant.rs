@@ -2,21 +2,24 @@ use chrono::{DateTime, Days, Utc};
 use rocket_okapi::JsonSchema;
 #[cfg(feature = "process")]
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DbConn, DbErr, EntityTrait, InsertResult, QueryFilter,
-    QuerySelect, Set,
+    ActiveModelTrait, ColumnTrait, DbConn, EntityTrait, InsertResult, QueryFilter, QuerySelect, Set,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Display;
 
 #[cfg(feature = "process")]
 use crate::entities::prelude::Promotion as Promotions;
 #[cfg(feature = "process")]
 use crate::entities::promotion;
-use crate::methods::Error;
-use crate::methods::{DiscountValue, HistoryList, Id, StockList, Url};
 #[cfg(feature = "process")]
-use crate::products;
-use crate::{ProductIdentification, Session};
+use crate::methods::stable_id;
+use crate::methods::Error;
+use crate::methods::{
+    format_price, validate_image_urls, DiscountValue, Generated, HistoryList, Id, ListQuery,
+    StockList, UnitOfMeasure, Url,
+};
+use crate::{validate_barcode, ErrorResponse, ProductIdentification, Session};
 use serde_json::json;
 use uuid::Uuid;
 use validator::Validate;
@@ -34,6 +37,7 @@ pub type VariantCategoryList = Vec<VariantCategory>;
 #[derive(Deserialize, Serialize, Clone, JsonSchema, Validate)]
 pub struct VariantCategory {
     pub category: String,
+    #[validate]
     pub variants: Vec<Variant>,
 }
 
@@ -54,11 +58,20 @@ pub struct VariantInformation {
     pub stock_information: StockInformation,
 
     /// Images specific to the variant, should take priority over product images.
+    #[validate(custom = "validate_image_urls")]
     pub images: Vec<Url>,
 
     /// Price for the good to be sold at
     pub retail_price: f32,
 
+    /// Prices for specific customer groups, keyed by the group named in a customer's `special_pricing`.
+    #[serde(default)]
+    pub price_tiers: HashMap<String, f32>,
+
+    /// Changes to the retail price yet to take effect, in the order they take effect.
+    #[serde(default)]
+    pub scheduled_prices: Vec<ScheduledPriceChange>,
+
     /// Imported/Cost price of the good to compare with
     pub marginal_price: f32,
 
@@ -78,6 +91,7 @@ pub struct VariantInformation {
     pub order_history: HistoryList,
 
     /// Barcode for product / primary identification method
+    #[validate(custom = "validate_barcode")]
     pub barcode: String,
 
     /// Further identification methods, such as isbn, sku, ...
@@ -87,12 +101,104 @@ pub struct VariantInformation {
     pub stock_tracking: bool,
 }
 
+/// A change to a variant's retail price planned in advance, see
+/// [`Product::schedule_price_change`](crate::Product::schedule_price_change).
+#[cfg(feature = "types")]
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct ScheduledPriceChange {
+    pub id: Id,
+    pub retail_price: f32,
+    pub effective_at: DateTime<Utc>,
+    pub scheduled_by: Id,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+impl VariantInformation {
+    /// The per-unit margin, being the difference between the retail and cost price.
+    pub fn margin(&self) -> f32 {
+        self.current_price() - self.marginal_price
+    }
+
+    /// The retail price in effect at `at`, being that of the latest scheduled change
+    /// to have taken effect by then, or the `retail_price` if none has.
+    pub fn retail_price_at(&self, at: DateTime<Utc>) -> f32 {
+        self.scheduled_prices
+            .iter()
+            .filter(|change| change.effective_at <= at)
+            .max_by_key(|change| change.effective_at)
+            .map_or(self.retail_price, |change| change.retail_price)
+    }
+
+    /// The retail price in effect now. Scheduled changes take effect as they are read,
+    /// so no task is needed to apply them.
+    pub fn current_price(&self) -> f32 {
+        self.retail_price_at(Utc::now())
+    }
+
+    /// Makes the price in effect at `at` the `retail_price`, dropping the scheduled
+    /// changes which have taken effect.
+    pub fn apply_due_prices(&mut self, at: DateTime<Utc>) {
+        self.retail_price = self.retail_price_at(at);
+        self.scheduled_prices
+            .retain(|change| change.effective_at > at);
+    }
+
+    /// Records `cost` as the per-unit cost last agreed with `supplier_id`, updating the
+    /// `marginal_price` to match. Returns whether the variant is sourced from them.
+    pub fn record_supplier_cost(&mut self, supplier_id: &str, cost: f32) -> bool {
+        if self.stock_information.supplier_id.as_deref() != Some(supplier_id) {
+            return false;
+        }
+
+        self.stock_information.supplier_cost = cost;
+        self.marginal_price = cost;
+
+        true
+    }
+
+    /// The price charged to a customer of the given pricing group, being the
+    /// group's tier price if one is set and the current retail price otherwise.
+    pub fn price_for(&self, group: Option<&str>) -> f32 {
+        group
+            .and_then(|g| self.price_tiers.get(g))
+            .copied()
+            .unwrap_or_else(|| self.current_price())
+    }
+
+    /// Whether the variant can be supplied regardless of its stock, such that its
+    /// stock may fall below zero, see [`VariantInformation::available_at`].
+    pub fn always_available(&self) -> bool {
+        let back_order = self.stock_information.back_order && !self.stock_information.discontinued;
+
+        !self.stock_tracking || back_order || self.stock_information.non_diminishing
+    }
+
+    /// Whether `quantity` units can be supplied from the store with the given code.
+    /// Untracked, back-orderable and non-diminishing variants are always available,
+    /// except that a discontinued variant can never be back-ordered.
+    pub fn available_at(&self, store_code: &str, quantity: f32) -> bool {
+        if self.always_available() {
+            return true;
+        }
+
+        self.stock
+            .iter()
+            .filter(|stock| stock.store.store_code == store_code)
+            .map(|stock| stock.quantity.available())
+            .sum::<f32>()
+            >= quantity
+    }
+}
+
 impl Display for VariantInformation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "\t{} ({:?}) ${}[R-:-M]${}",
-            self.name, self.variant_code, self.retail_price, self.marginal_price
+            "\t{} ({:?}) {}[R-:-M]{}",
+            self.name,
+            self.variant_code,
+            format_price(self.retail_price),
+            format_price(self.marginal_price)
         )
     }
 }
@@ -168,7 +274,15 @@ impl Promotion {
         db: &DbConn,
     ) -> Result<InsertResult<promotion::ActiveModel>, Error> {
         let id = Uuid::new_v4().to_string();
+        Self::insert_with_id(prm, id, session, db).await
+    }
 
+    async fn insert_with_id(
+        prm: PromotionInput,
+        id: String,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<InsertResult<promotion::ActiveModel>, Error> {
         let insert_crud = promotion::ActiveModel {
             id: Set(id.to_string()),
             name: Set(prm.name.to_string()),
@@ -187,10 +301,14 @@ impl Promotion {
 
     pub async fn fetch_by_id(id: &str, session: Session, db: &DbConn) -> Result<Promotion, Error> {
         let pdt = Promotions::find_by_id(id.to_string())
-            .filter(products::Column::TenantId.eq(session.tenant_id))
+            .filter(promotion::Column::TenantId.eq(session.tenant_id))
             .one(db)
             .await?;
-        let p = pdt.unwrap();
+
+        let p = match pdt {
+            Some(p) => p,
+            None => return Err(ErrorResponse::not_found("Promotion", id)),
+        };
 
         Ok(Promotion {
             id: p.id,
@@ -208,7 +326,7 @@ impl Promotion {
         db: &DbConn,
     ) -> Result<Vec<Promotion>, Error> {
         let res = Promotions::find()
-            .filter(products::Column::TenantId.eq(session.tenant_id))
+            .filter(promotion::Column::TenantId.eq(session.tenant_id))
             // Is the bought product
             .having(promotion::Column::Buy.contains(query))
             // Is the promoted product
@@ -256,9 +374,22 @@ impl Promotion {
         Self::fetch_by_id(id, session, db).await
     }
 
-    pub async fn fetch_all(session: Session, db: &DbConn) -> Result<Vec<Promotion>, Error> {
-        let stores = Promotions::find()
-            .filter(promotion::Column::TenantId.eq(session.tenant_id))
+    pub async fn fetch_all(
+        query: &ListQuery,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Promotion>, Error> {
+        let select = Promotions::find().filter(promotion::Column::TenantId.eq(session.tenant_id));
+
+        let stores = query
+            .apply(
+                select,
+                &[
+                    ("name", promotion::Column::Name),
+                    ("valid_till", promotion::Column::ValidTill),
+                    ("timestamp", promotion::Column::Timestamp),
+                ],
+            )?
             .all(db)
             .await?;
 
@@ -302,16 +433,29 @@ impl Promotion {
             .map_err(|e| e.into())
     }
 
-    pub async fn generate(session: Session, db: &DbConn) -> Result<Vec<Promotion>, Error> {
-        let promotions = example_promotions();
+    /// Seeds the example promotions under ids stable for the tenant, so repeated
+    /// calls yield the same promotions rather than accumulating duplicates.
+    pub async fn generate(
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Generated<Promotion>>, Error> {
+        let mut generated = vec![];
+
+        for prm in example_promotions() {
+            let id = stable_id(&session.tenant_id, &prm.name);
+            let created = Self::fetch_by_id(&id, session.clone(), db).await.is_err();
 
-        match Promotion::insert_many(promotions, session.clone(), db).await {
-            Ok(_) => match Promotion::fetch_all(session, db).await {
-                Ok(res) => Ok(res),
-                Err(e) => Err(e),
-            },
-            Err(e) => Err(e),
+            if created {
+                Self::insert_with_id(prm, id.clone(), session.clone(), db).await?;
+            }
+
+            generated.push(Generated {
+                created,
+                record: Self::fetch_by_id(&id, session.clone(), db).await?,
+            });
         }
+
+        Ok(generated)
     }
 }
 
@@ -320,6 +464,7 @@ impl Promotion {
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema, Validate)]
 pub struct Variant {
     pub name: String,
+    #[validate(custom = "validate_image_urls")]
     pub images: Vec<Url>,
     pub marginal_price: f32,
     pub variant_code: String,
@@ -372,6 +517,8 @@ pub struct StockInformation {
     pub back_order: bool,
 
     /// A product which is no longer source-able. Once the product's inventory is consumed it is indicated to never be replenished.
+    ///
+    /// Set by [`Product::discontinue`](crate::methods::Product::discontinue), such variants should not be suggested for restocking.
     pub discontinued: bool,
 
     /// A `non_diminishing` product is often a service rather than a product, i.e. freight. It is **not removed** from inventory upon consumption, rather attached.
@@ -379,14 +526,28 @@ pub struct StockInformation {
 
     /// A non-shippable good is one which cannot be dispatched between stores or sent to a customers home, this might be a fragile product, service, oversized good or edge case.
     pub shippable: bool,
+
+    /// The supplier this variant is sourced from, if any.
+    #[serde(default)]
+    pub supplier_id: Option<Id>,
+
+    /// The per-unit cost last agreed with `supplier_id`.
+    #[serde(default)]
+    pub supplier_cost: f32,
+
+    /// The unit the variant's stock levels are kept in.
+    #[serde(default)]
+    pub unit: UnitOfMeasure,
 }
 
 impl Display for Variant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "\t{} ({}) (MP: ${})",
-            self.name, self.variant_code, self.marginal_price
+            "\t{} ({}) (MP: {})",
+            self.name,
+            self.variant_code,
+            format_price(self.marginal_price)
         )
     }
 }
@@ -0,0 +1,133 @@
+use crate::guards::Convert;
+use crate::methods::employee::Action;
+use crate::methods::transaction::handlers::parse_range;
+use crate::methods::ErrorResponse;
+use crate::pool::InternalDb;
+use crate::{
+    check_permissions, CommissionReport, DailySummary, DeadStock, MarginReport, Product,
+    ProductSales, Session, Transaction,
+};
+use chrono::NaiveDate;
+use okapi::openapi3::OpenApi;
+use rocket::get;
+use rocket_okapi::settings::OpenApiSettings;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
+
+pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![settings: margins, daily, top_products, commissions, dead_stock]
+}
+
+#[openapi(tag = "Reports")]
+#[get("/margins")]
+pub async fn margins(db: InternalDb, session: Session) -> Convert<MarginReport> {
+    check_permissions!(session.clone(), Action::FetchReports);
+    Product::margin_report(session, &db.0).await.into()
+}
+
+/// Summarises a store's trade over a day in its local time, given as
+/// `YYYY-MM-DD`, or over the current day when no date is given.
+#[openapi(tag = "Reports")]
+#[get("/daily/<store_id>?<date>")]
+pub async fn daily(
+    db: InternalDb,
+    session: Session,
+    store_id: &str,
+    date: Option<&str>,
+) -> Convert<DailySummary> {
+    check_permissions!(session.clone(), Action::FetchReports);
+
+    if let Err(e) = session.ensure_store(store_id) {
+        return Err(e).into();
+    }
+
+    let date = match date.map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d")) {
+        Some(Ok(d)) => Some(d),
+        Some(Err(_)) => {
+            return Err(ErrorResponse::custom_input_error(
+                "Date must be given as YYYY-MM-DD.",
+            ))
+            .into()
+        }
+        None => None,
+    };
+
+    Transaction::daily_summary(store_id, date, session, &db.0)
+        .await
+        .into()
+}
+
+/// The best selling products over a window, given as RFC 3339 times, optionally
+/// only at one store. Lists the top ten unless a `limit` of up to 100 is given.
+#[openapi(tag = "Reports")]
+#[get("/top-products?<from>&<to>&<store>&<limit>")]
+pub async fn top_products(
+    db: InternalDb,
+    session: Session,
+    from: &str,
+    to: &str,
+    store: Option<&str>,
+    limit: Option<usize>,
+) -> Convert<Vec<ProductSales>> {
+    check_permissions!(session.clone(), Action::FetchReports);
+
+    if let Some(store) = store {
+        if let Err(e) = session.ensure_store(store) {
+            return Err(e).into();
+        }
+    }
+
+    let (from, to) = match parse_range(from, to) {
+        Ok(range) => range,
+        Err(e) => return Err(e).into(),
+    };
+    let limit = limit.unwrap_or(10).clamp(1, 100);
+
+    Transaction::top_products(from, to, store, limit, session, &db.0)
+        .await
+        .into()
+}
+
+/// The sales credited to each salesperson over a window, given as RFC 3339 times,
+/// and the commission they have earned.
+#[openapi(tag = "Reports")]
+#[get("/commissions?<from>&<to>")]
+pub async fn commissions(
+    db: InternalDb,
+    session: Session,
+    from: &str,
+    to: &str,
+) -> Convert<CommissionReport> {
+    check_permissions!(session.clone(), Action::FetchReports);
+
+    let (from, to) = match parse_range(from, to) {
+        Ok(range) => range,
+        Err(e) => return Err(e).into(),
+    };
+
+    Transaction::commission_report(from, to, session, &db.0)
+        .await
+        .into()
+}
+
+/// Variants in stock which have not sold over the last `days`, optionally at one
+/// store, ordered from the most valuable stock held.
+#[openapi(tag = "Reports")]
+#[get("/dead-stock?<days>&<store>")]
+pub async fn dead_stock(
+    db: InternalDb,
+    session: Session,
+    days: i64,
+    store: Option<&str>,
+) -> Convert<Vec<DeadStock>> {
+    check_permissions!(session.clone(), Action::FetchReports);
+
+    if let Some(store) = store {
+        if let Err(e) = session.ensure_store(store) {
+            return Err(e).into();
+        }
+    }
+
+    Product::dead_stock(days, store, session, &db.0)
+        .await
+        .into()
+}
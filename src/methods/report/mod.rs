@@ -0,0 +1,5 @@
+#[cfg(feature = "process")]
+pub(crate) mod handlers;
+
+#[cfg(feature = "process")]
+pub use handlers::*;
@@ -0,0 +1,27 @@
+use crate::guards::Convert;
+use crate::methods::ErrorResponse;
+use crate::pool::InternalDb;
+use crate::{SearchResult, Session};
+use okapi::openapi3::OpenApi;
+use rocket::get;
+use rocket_okapi::settings::OpenApiSettings;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
+
+pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![settings: search]
+}
+
+/// Searches the products, customers and suppliers the user may read in one request.
+/// Categories the user has no permission to fetch are omitted rather than refused.
+#[openapi(tag = "Search")]
+#[get("/?<q>")]
+pub async fn search(db: InternalDb, session: Session, q: &str) -> Convert<Vec<SearchResult>> {
+    if q.trim().is_empty() {
+        return Err(ErrorResponse::custom_input_error(
+            "A search query is required",
+        ))
+        .into();
+    }
+
+    SearchResult::search(q.trim(), session, &db.0).await.into()
+}
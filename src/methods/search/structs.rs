@@ -0,0 +1,167 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "process")]
+use crate::methods::{Action, Error, DEFAULT_FUZZY_DISTANCE};
+#[cfg(feature = "process")]
+use crate::{Customer, Product, Session, Supplier};
+#[cfg(feature = "process")]
+use sea_orm::DbConn;
+
+/// The most results returned for any one kind of record.
+pub const SEARCH_RESULTS_PER_TYPE: usize = 10;
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", content = "record")]
+pub enum SearchRecord {
+    Product(Product),
+    Customer(Customer),
+    Supplier(Supplier),
+}
+
+/// A single match of a search, where `relevance` ranges from 0 (matched on a
+/// secondary field, such as an address) to 1 (an exact match).
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+pub struct SearchResult {
+    pub relevance: f32,
+    #[serde(flatten)]
+    pub result: SearchRecord,
+}
+
+/// Scores how closely `query` matches the best of `fields`, ignoring case.
+///
+/// An exact match scores 1, a match at the start of a field 0.75, at the start
+/// of any word within it 0.5 and anywhere within it 0.25. Records matched by
+/// the database on a field not given here score 0.1.
+pub fn relevance<'a>(query: &str, fields: impl IntoIterator<Item = &'a str>) -> f32 {
+    let query = query.trim().to_lowercase();
+
+    fields
+        .into_iter()
+        .map(|field| {
+            let field = field.to_lowercase();
+
+            if query.is_empty() || !field.contains(&query) {
+                0.1
+            } else if field == query {
+                1.0
+            } else if field.starts_with(&query) {
+                0.75
+            } else if field
+                .split_whitespace()
+                .any(|word| word.starts_with(&query))
+            {
+                0.5
+            } else {
+                0.25
+            }
+        })
+        .fold(0.1, f32::max)
+}
+
+/// Ranks `results` by relevance, keeping only the best [`SEARCH_RESULTS_PER_TYPE`].
+fn rank(mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+    results.sort_by(|a, b| b.relevance.total_cmp(&a.relevance));
+    results.truncate(SEARCH_RESULTS_PER_TYPE);
+    results
+}
+
+#[cfg(feature = "process")]
+impl SearchResult {
+    /// Searches the products, customers and suppliers the session may read, by
+    /// name, phone number or barcode, returning the most relevant first.
+    pub async fn search(
+        query: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let mut results = vec![];
+
+        if session.clone().has_permission(Action::FetchProduct) {
+            let products = Product::search(query, session.clone(), db).await?;
+
+            results.extend(rank(
+                products
+                    .into_iter()
+                    .map(|product| {
+                        let fields = [product.name.as_str(), product.sku.as_str()]
+                            .into_iter()
+                            .chain(product.variants.iter().map(|v| v.barcode.as_str()))
+                            .chain(product.variants.iter().map(|v| v.name.as_str()));
+
+                        SearchResult {
+                            relevance: relevance(query, fields),
+                            result: SearchRecord::Product(product),
+                        }
+                    })
+                    .collect(),
+            ));
+        }
+
+        if session.clone().has_permission(Action::FetchCustomer) {
+            let mut customers = Customer::fetch_by_name(query, session.clone(), db).await?;
+
+            for customer in Customer::fetch_by_phone(query, session.clone(), db).await? {
+                if !customers.iter().any(|c| c.id == customer.id) {
+                    customers.push(customer);
+                }
+            }
+
+            results.extend(rank(
+                customers
+                    .into_iter()
+                    .map(|customer| SearchResult {
+                        relevance: relevance(
+                            query,
+                            [
+                                customer.name.as_str(),
+                                customer.contact.mobile.number.as_str(),
+                                customer.contact.email.full.as_str(),
+                            ],
+                        ),
+                        result: SearchRecord::Customer(customer),
+                    })
+                    .collect(),
+            ));
+        }
+
+        if session.clone().has_permission(Action::FetchSupplier) {
+            let mut suppliers =
+                Supplier::fetch_by_name(query, DEFAULT_FUZZY_DISTANCE, session.clone(), db).await?;
+
+            for supplier in Supplier::fetch_by_phone(query, session.clone(), db).await? {
+                if !suppliers.iter().any(|s| s.id == supplier.id) {
+                    suppliers.push(supplier);
+                }
+            }
+
+            results.extend(rank(
+                suppliers
+                    .into_iter()
+                    .map(|supplier| {
+                        let name = supplier.name.to_string();
+
+                        SearchResult {
+                            relevance: relevance(
+                                query,
+                                [
+                                    name.as_str(),
+                                    supplier.contact.name.as_str(),
+                                    supplier.contact.mobile.number.as_str(),
+                                ],
+                            ),
+                            result: SearchRecord::Supplier(supplier),
+                        }
+                    })
+                    .collect(),
+            ));
+        }
+
+        // A stable sort keeps products, then customers, then suppliers amongst equals.
+        results.sort_by(|a, b| b.relevance.total_cmp(&a.relevance));
+
+        Ok(results)
+    }
+}
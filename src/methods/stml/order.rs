@@ -1,10 +1,11 @@
 use std::fmt::Display;
 
 use crate::methods::{
-    ContactInformation, DiscountValue, History, HistoryList, Id, Location, NoteList,
-    ProductPurchaseList, Store, Url,
+    apply_discount, thread_notes, validate_each, Attachment, ContactInformation, DiscountValue,
+    Error, ErrorResponse, History, HistoryList, Id, Location, Note, NoteInput, NoteList,
+    NoteThread, PickStatus, ProductPurchaseList, RoundingMode, Store, Url,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -17,6 +18,7 @@ pub struct Order {
     pub destination: Location,
     pub origin: Location,
 
+    #[validate(custom = "validate_each")]
     pub products: ProductPurchaseList,
 
     pub status: OrderStatusAssignment,
@@ -31,6 +33,328 @@ pub struct Order {
 
     pub discount: DiscountValue,
     pub order_type: OrderType,
+
+    /// Goods confirmed as received at the destination, see [`OrderReceipt`].
+    #[serde(default)]
+    pub receipts: Vec<OrderReceipt>,
+    /// Whether the order's stock is reserved at its origin, rather than removed,
+    /// until the order is fulfilled or fails.
+    #[serde(default)]
+    pub stock_reserved: bool,
+    /// Files kept against the order, such as proof of delivery.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// How sure the address provider was of a shipment's destination when the
+    /// order was created, see [`AddressVerifier`](crate::AddressVerifier). `None` if
+    /// it went unchecked.
+    #[serde(default)]
+    pub address_confidence: Option<f32>,
+    /// Set when the destination should be checked before the order is shipped.
+    #[serde(default)]
+    pub address_flagged: bool,
+}
+
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct ReceivedItem {
+    /// The `id` of the `ProductPurchase` line being received.
+    pub product_purchase_id: Id,
+    pub quantity: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct ReceiptInput {
+    pub items: Vec<ReceivedItem>,
+}
+
+/// A single confirmation of goods arriving at an order's destination.
+/// An order may hold several, as a transfer can be received across multiple deliveries.
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+pub struct OrderReceipt {
+    pub product_purchase_id: Id,
+    pub quantity_sent: f32,
+    /// The quantity received in this delivery alone.
+    pub quantity_received: f32,
+    /// Set when the total received for the line does not match what was sent.
+    pub discrepancy: bool,
+    pub employee: Id,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A product line of an order priced after its own discount.
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LineBreakdown {
+    pub product_purchase_id: Id,
+    /// The cost of the line before its discount.
+    pub gross: f32,
+    /// The amount taken off by the line's discount.
+    pub discount: f32,
+    pub total: f32,
+}
+
+/// The amounts making up an order's total, before tax, see [`Order::breakdown`].
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OrderBreakdown {
+    pub order_id: Id,
+    pub lines: Vec<LineBreakdown>,
+    /// The sum of the lines, after their own discounts.
+    pub subtotal: f32,
+    /// The amount taken off by the order's discount.
+    pub order_discount: f32,
+    /// The subtotal less the order's discount.
+    pub total: f32,
+}
+
+#[cfg(feature = "types")]
+impl Order {
+    /// Prices each line after its discount, then the order after its own,
+    /// rounding to the cent with `rounding`. This is the pricing used to tax
+    /// the order at checkout, see [`compute_tax`](crate::methods::compute_tax).
+    pub fn breakdown(&self, rounding: RoundingMode) -> OrderBreakdown {
+        let lines: Vec<LineBreakdown> = self
+            .products
+            .iter()
+            .map(|product| {
+                let gross = product.product_cost * product.quantity;
+                let total = rounding.round(apply_discount(product.discount.clone(), gross));
+
+                LineBreakdown {
+                    product_purchase_id: product.id.clone(),
+                    gross,
+                    discount: rounding.round(gross - total),
+                    total,
+                }
+            })
+            .collect();
+
+        let subtotal = lines.iter().map(|line| line.total).sum::<f32>();
+        let total = rounding.round(apply_discount(self.discount.clone(), subtotal));
+
+        OrderBreakdown {
+            order_id: self.id.clone(),
+            lines,
+            subtotal,
+            order_discount: rounding.round(subtotal - total),
+            total,
+        }
+    }
+
+    /// The order's notes arranged into threads of replies, see [`thread_notes`].
+    pub fn threaded_notes(&self) -> Vec<NoteThread> {
+        thread_notes(&self.order_notes)
+    }
+
+    /// Adds a note by `author`, as a reply if the input names a `parent_id`.
+    /// Returns `None` if the parent is not one of the order's notes.
+    pub fn add_note(&mut self, input: NoteInput, author: Id) -> Option<&Note> {
+        let note = match input.parent_id {
+            Some(parent_id) => self
+                .order_notes
+                .iter()
+                .find(|n| n.id == parent_id)?
+                .reply(input.message, author),
+            None => Note::new(input.message, author),
+        };
+
+        self.order_notes.push(note);
+        self.order_notes.last()
+    }
+
+    /// The total quantity received for a product line across all receipts.
+    pub fn quantity_received(&self, product_purchase_id: &str) -> f32 {
+        self.receipts
+            .iter()
+            .filter(|r| r.product_purchase_id == product_purchase_id)
+            .map(|r| r.quantity_received)
+            .sum()
+    }
+
+    /// An order is fully received once every line has received at least the quantity sent.
+    pub fn is_fully_received(&self) -> bool {
+        self.products
+            .iter()
+            .all(|p| self.quantity_received(&p.id) >= p.quantity)
+    }
+
+    /// An order is fulfilled once every line has been fulfilled in full.
+    pub fn is_fully_fulfilled(&self) -> bool {
+        self.products.iter().all(|p| p.is_fully_fulfilled())
+    }
+
+    /// Marks every line as fulfilled in full.
+    pub fn fulfil_all(&mut self) {
+        for product in self.products.iter_mut() {
+            product.set_fulfilled(product.quantity);
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+impl Order {
+    /// Resolves the assignment for moving the order to `status`, rejecting moves
+    /// not permitted by [`OrderStatus::can_transition_to`].
+    ///
+    /// A `PartiallyFulfilled` status names the lines fulfilled by this update, which
+    /// are added to those fulfilled before it. Once every line has been fulfilled the
+    /// order becomes `Fulfilled` instead.
+    pub fn next_status(&self, status: OrderStatus) -> Result<OrderStatusAssignment, Error> {
+        if !self.status.status.can_transition_to(&status) {
+            return Err(ErrorResponse::conflict(&format!(
+                "Unable to move order {} from {} to {}.",
+                self.reference, self.status.status, status
+            )));
+        }
+
+        let (status, assigned_products) = match status {
+            OrderStatus::PartiallyFulfilled(lines) => {
+                if let Some(unknown) = lines
+                    .iter()
+                    .find(|line| !self.products.iter().any(|p| &p.id == *line))
+                {
+                    return Err(ErrorResponse::custom_input_error(&format!(
+                        "Product line {} is not part of order {}.",
+                        unknown, self.reference
+                    )));
+                }
+
+                let mut fulfilled = self.status.status.fulfilled_lines().to_vec();
+                for line in lines {
+                    if !fulfilled.contains(&line) {
+                        fulfilled.push(line);
+                    }
+                }
+
+                if self.products.iter().all(|p| fulfilled.contains(&p.id)) {
+                    (OrderStatus::Fulfilled(Utc::now()), self.product_ids())
+                } else {
+                    (
+                        OrderStatus::PartiallyFulfilled(fulfilled.clone()),
+                        fulfilled,
+                    )
+                }
+            }
+            status => (status, self.product_ids()),
+        };
+
+        Ok(OrderStatusAssignment {
+            status,
+            assigned_products,
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Records the order's initial status as the first entry of its history, such
+    /// that its timeline is complete from creation. Orders with a history are untouched.
+    pub fn seed_status_history(&mut self) {
+        if !self.status_history.is_empty() {
+            return;
+        }
+
+        self.status_history.push(History {
+            item: self.status.clone(),
+            reason: "Order created".to_string(),
+            timestamp: self.status.timestamp,
+        });
+    }
+
+    /// Moves the order to `status`, recording it in the order's history. Lines
+    /// named by a fulfilled or partially fulfilled status are fulfilled in full.
+    pub fn assign_status(&mut self, status: OrderStatusAssignment, reason: String) {
+        match &status.status {
+            OrderStatus::Fulfilled(_) => self.fulfil_all(),
+            OrderStatus::PartiallyFulfilled(lines) => {
+                for product in self.products.iter_mut().filter(|p| lines.contains(&p.id)) {
+                    product.set_fulfilled(product.quantity);
+                }
+            }
+            _ => {}
+        }
+
+        self.status = status.clone();
+        self.status_history.push(History {
+            item: status,
+            reason,
+            timestamp: self.status.timestamp,
+        });
+    }
+
+    /// Moves the order on to reflect the fulfilled quantity of each line, being
+    /// `Fulfilled` once every line is fulfilled and `PartiallyFulfilled` whilst
+    /// only some are. Returns the new assignment, or `None` if the order stays put.
+    pub fn advance_fulfillment(&self) -> Option<OrderStatusAssignment> {
+        let fulfilled: Vec<Id> = self
+            .products
+            .iter()
+            .filter(|p| p.is_fully_fulfilled())
+            .map(|p| p.id.clone())
+            .collect();
+
+        if fulfilled.is_empty() || fulfilled.as_slice() == self.status.status.fulfilled_lines() {
+            return None;
+        }
+
+        self.next_status(OrderStatus::PartiallyFulfilled(fulfilled))
+            .ok()
+    }
+
+    /// Moves an instance of the line `product_purchase_id` to `status`. Picking an
+    /// instance fulfils its share of the line and un-picking returns it, after which
+    /// the order advances as by [`Order::advance_fulfillment`].
+    pub fn pick(
+        &mut self,
+        product_purchase_id: &str,
+        product_instance_id: &str,
+        status: PickStatus,
+    ) {
+        for product in self
+            .products
+            .iter_mut()
+            .filter(|p| p.id == product_purchase_id)
+        {
+            let per_instance = product.instance_quantity();
+            let mut fulfilled = product.fulfilled_quantity;
+
+            for instance in product
+                .instances
+                .iter_mut()
+                .filter(|i| i.id == product_instance_id)
+            {
+                let fulfillment = &mut instance.fulfillment_status;
+                let was_picked = matches!(fulfillment.pick_status, PickStatus::Picked);
+
+                fulfillment.pick_history.push(History {
+                    item: fulfillment.pick_status.clone(),
+                    reason: "Standard Update Bump".to_string(),
+                    timestamp: fulfillment.last_updated,
+                });
+                fulfillment.last_updated = Utc::now();
+                fulfillment.pick_status = status.clone();
+
+                match (
+                    was_picked,
+                    matches!(fulfillment.pick_status, PickStatus::Picked),
+                ) {
+                    (false, true) => fulfilled += per_instance,
+                    (true, false) => fulfilled -= per_instance,
+                    _ => {}
+                }
+            }
+
+            product.set_fulfilled(fulfilled);
+        }
+
+        if let Some(status) = self.advance_fulfillment() {
+            self.assign_status(status, "Lines fulfilled".to_string());
+        }
+    }
+
+    fn product_ids(&self) -> Vec<Id> {
+        self.products.iter().map(|p| p.id.clone()).collect()
+    }
 }
 
 #[cfg(feature = "types")]
@@ -53,7 +377,7 @@ impl ToString for Order {
 }
 
 #[cfg(feature = "types")]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OrderState {
     pub timestamp: DateTime<Utc>,
     pub status: OrderStatus,
@@ -80,6 +404,9 @@ pub enum OrderStatus {
     Processing(DateTime<Utc>),
     /// Click-n-collect item, date represents when it was readied-for-pickup.
     InStore(DateTime<Utc>),
+    /// Split shipment with some lines delivered and others outstanding, holds the
+    /// ids of the product lines fulfilled so far.
+    PartiallyFulfilled(Vec<Id>),
     /// In-store purchase or Delivered Item, date represents when it was completed.
     Fulfilled(DateTime<Utc>),
     /// Was unable to fulfill, reason is given
@@ -93,6 +420,7 @@ impl Display for OrderStatus {
             OrderStatus::Transit(_) => "TRANSIT",
             OrderStatus::Processing(_) => "PROCESSING",
             OrderStatus::InStore(_) => "IN-STORE",
+            OrderStatus::PartiallyFulfilled(_) => "PARTIALLY-FULFILLED",
             OrderStatus::Fulfilled(_) => "FULFILLED",
             OrderStatus::Failed(_reason) => "FAILED:",
         };
@@ -108,6 +436,7 @@ impl OrderStatus {
             OrderStatus::Transit(_) => false,
             OrderStatus::Processing(_) => true,
             OrderStatus::InStore(_) => false,
+            OrderStatus::PartiallyFulfilled(_) => true,
             OrderStatus::Fulfilled(_) => false,
             OrderStatus::Failed(_) => false,
         }
@@ -119,10 +448,40 @@ impl OrderStatus {
             OrderStatus::Transit(_) => true,
             OrderStatus::Processing(_) => true,
             OrderStatus::InStore(_) => false,
+            OrderStatus::PartiallyFulfilled(_) => true,
             OrderStatus::Fulfilled(_) => false,
             OrderStatus::Failed(_) => false,
         }
     }
+
+    /// The product lines fulfilled whilst an order is partially fulfilled.
+    pub fn fulfilled_lines(&self) -> &[Id] {
+        match self {
+            OrderStatus::PartiallyFulfilled(lines) => lines,
+            _ => &[],
+        }
+    }
+
+    /// Whether an order may move from this status to `next`. Fulfilled and failed
+    /// orders are final, and an order may fail from any other status. Otherwise,
+    /// orders only move forward, with transit and partial fulfillment repeatable as
+    /// further shipments are sent and delivered.
+    pub fn can_transition_to(&self, next: &OrderStatus) -> bool {
+        use OrderStatus::*;
+
+        match (self, next) {
+            (Fulfilled(_) | Failed(_), _) => false,
+            (_, Failed(_)) => true,
+            (_, Queued(_)) => false,
+            (Queued(_), _) => true,
+            (Processing(_), Processing(_)) => false,
+            (Processing(_), _) => true,
+            (Transit(_), Transit(_) | InStore(_) | PartiallyFulfilled(_) | Fulfilled(_)) => true,
+            (InStore(_), PartiallyFulfilled(_) | Fulfilled(_)) => true,
+            (PartiallyFulfilled(_), Transit(_) | PartiallyFulfilled(_) | Fulfilled(_)) => true,
+            _ => false,
+        }
+    }
 }
 
 impl Display for OrderStatusAssignment {
@@ -141,7 +500,85 @@ impl Display for OrderStatusAssignment {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, Validate)]
 pub struct TransitInformation {
     pub shipping_company: ContactInformation,
+    /// The carrier handling the shipment, used to generate the `query_url` when known.
+    #[serde(default)]
+    pub carrier: Option<Carrier>,
     pub query_url: Url,
     pub tracking_code: String,
     pub assigned_products: Vec<Id>,
+    /// When the shipment is expected to arrive, see [`TransitInformation::estimate_delivery`].
+    #[serde(default)]
+    pub estimated_delivery: Option<DateTime<Utc>>,
+}
+
+/// Carriers whose tracking pages can be linked to from a tracking code.
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub enum Carrier {
+    NzPost,
+    AusPost,
+    Dhl,
+    FedEx,
+    Ups,
+    Usps,
+    /// Any other carrier, for which the `query_url` must be given.
+    Other,
+}
+
+/// Days taken to hand a shipment to its carrier, before it begins to travel.
+const DISPATCH_DAYS: f64 = 1.0;
+
+impl Carrier {
+    /// The typical distance a shipment with the carrier travels each day.
+    pub fn kilometres_per_day(&self) -> f64 {
+        match self {
+            Carrier::NzPost => 600.0,
+            Carrier::AusPost => 500.0,
+            Carrier::Dhl | Carrier::FedEx => 1000.0,
+            Carrier::Ups => 800.0,
+            Carrier::Usps => 600.0,
+            Carrier::Other => 400.0,
+        }
+    }
+}
+
+impl TransitInformation {
+    /// The page tracking the shipment `code` with the given carrier,
+    /// or `None` if the carrier's tracking page is not known.
+    pub fn tracking_url(carrier: Carrier, code: &str) -> Option<Url> {
+        let template = match carrier {
+            Carrier::NzPost => "https://www.nzpost.co.nz/tools/tracking/item/{}",
+            Carrier::AusPost => "https://auspost.com.au/mypost/track/details/{}",
+            Carrier::Dhl => {
+                "https://www.dhl.com/global-en/home/tracking/tracking-express.html?tracking-id={}"
+            }
+            Carrier::FedEx => "https://www.fedex.com/fedextrack/?trknbr={}",
+            Carrier::Ups => "https://www.ups.com/track?tracknum={}",
+            Carrier::Usps => "https://tools.usps.com/go/TrackConfirmAction?tLabels={}",
+            Carrier::Other => return None,
+        };
+
+        Some(template.replace("{}", code))
+    }
+
+    /// Estimates the arrival of a shipment dispatched at `dispatched` which must travel
+    /// `distance` kilometres, by the carrier's speed. Shipments with no known carrier
+    /// are assumed to travel at the speed of [`Carrier::Other`].
+    pub fn estimate_delivery(&mut self, distance: f64, dispatched: DateTime<Utc>) {
+        let speed = self.carrier.unwrap_or(Carrier::Other).kilometres_per_day();
+        let days = DISPATCH_DAYS + (distance.max(0.0) / speed).ceil();
+
+        self.estimated_delivery = dispatched.checked_add_signed(Duration::days(days as i64));
+    }
+
+    /// Sets the `query_url` from the carrier and tracking code, if the carrier is known.
+    /// Otherwise, the given `query_url` is kept.
+    pub fn populate_query_url(&mut self) {
+        if let Some(url) = self
+            .carrier
+            .and_then(|carrier| Self::tracking_url(carrier, &self.tracking_code))
+        {
+            self.query_url = url;
+        }
+    }
 }
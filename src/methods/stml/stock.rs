@@ -12,6 +12,10 @@ pub type StockList = Vec<Stock>;
 pub struct Stock {
     pub store: Location,
     pub quantity: Quantity,
+    /// Where the stock is kept within the store, such as an aisle and shelf, by
+    /// which pick lists are ordered.
+    #[serde(default)]
+    pub bin_location: Option<String>,
 }
 
 #[cfg(feature = "types")]
@@ -21,4 +25,14 @@ pub struct Quantity {
     pub quantity_unsellable: f32,
     pub quantity_on_order: f32,
     pub quantity_allocated: f32,
+    /// Held for orders which are yet to be fulfilled, still counted within `quantity_sellable`.
+    #[serde(default)]
+    pub quantity_reserved: f32,
+}
+
+impl Quantity {
+    /// The quantity which may be sold, being that on hand less any reservations.
+    pub fn available(&self) -> f32 {
+        self.quantity_sellable - self.quantity_reserved
+    }
 }
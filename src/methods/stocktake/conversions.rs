@@ -0,0 +1,38 @@
+use crate::entities::stocktake::{ActiveModel, Model};
+use crate::{StockCount, Stocktake, StocktakeReport};
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue::Set;
+use serde_json::json;
+
+impl From<Model> for Stocktake {
+    fn from(val: Model) -> Self {
+        Stocktake {
+            id: val.id,
+            store_id: val.store_id,
+            counts: serde_json::from_value::<Vec<StockCount>>(val.counts).unwrap_or_default(),
+            report: val
+                .report
+                .and_then(|report| serde_json::from_value::<StocktakeReport>(report).ok()),
+            started_by: val.started_by,
+            started_at: DateTime::from_naive_utc_and_offset(val.started_at, Utc),
+            applied_at: val
+                .applied_at
+                .map(|at| DateTime::from_naive_utc_and_offset(at, Utc)),
+        }
+    }
+}
+
+impl Stocktake {
+    pub(crate) fn into_active(self, tenant_id: String) -> ActiveModel {
+        ActiveModel {
+            id: Set(self.id),
+            tenant_id: Set(tenant_id),
+            store_id: Set(self.store_id),
+            counts: Set(json!(self.counts)),
+            report: Set(self.report.map(|report| json!(report))),
+            started_by: Set(self.started_by),
+            started_at: Set(self.started_at.naive_utc()),
+            applied_at: Set(self.applied_at.map(|at| at.naive_utc())),
+        }
+    }
+}
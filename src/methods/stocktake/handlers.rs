@@ -0,0 +1,54 @@
+use super::{Stocktake, StocktakeCounts};
+use crate::catchers::Validated;
+use crate::guards::Convert;
+use crate::methods::employee::Action;
+use crate::pool::InternalDb;
+use crate::{check_permissions, Session};
+use okapi::openapi3::OpenApi;
+use rocket::serde::json::Json;
+use rocket::{get, post};
+use rocket_okapi::settings::OpenApiSettings;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
+
+pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![settings: start, get, submit_counts, apply]
+}
+
+/// Starts a stocktake of the store, to which counts are then submitted.
+#[openapi(tag = "Stocktake")]
+#[post("/store/<store_id>")]
+pub async fn start(db: InternalDb, session: Session, store_id: &str) -> Convert<Stocktake> {
+    check_permissions!(session.clone(), Action::CreateStockAdjustmentIntent);
+    Stocktake::start(store_id, session, &db.0).await.into()
+}
+
+#[openapi(tag = "Stocktake")]
+#[get("/<id>")]
+pub async fn get(db: InternalDb, session: Session, id: &str) -> Convert<Stocktake> {
+    check_permissions!(session.clone(), Action::FetchStore);
+    Stocktake::fetch_by_id(id, session, &db.0).await.into()
+}
+
+/// Records counted quantities, a variant counted again replacing its earlier count.
+#[openapi(tag = "Stocktake")]
+#[post("/<id>/counts", data = "<input_data>", rank = 2)]
+pub async fn submit_counts(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    input_data: Validated<Json<StocktakeCounts>>,
+) -> Convert<Stocktake> {
+    check_permissions!(session.clone(), Action::CreateStockAdjustmentIntent);
+    Stocktake::submit_counts(id, input_data.data().counts, session, &db.0)
+        .await
+        .into()
+}
+
+/// Sets the store's stock to the quantities counted, closing the stocktake with a
+/// report of the variances found.
+#[openapi(tag = "Stocktake")]
+#[post("/<id>/apply", rank = 2)]
+pub async fn apply(db: InternalDb, session: Session, id: &str) -> Convert<Stocktake> {
+    check_permissions!(session.clone(), Action::CreateStockAdjustmentIntent);
+    Stocktake::apply(id, session, &db.0).await.into()
+}
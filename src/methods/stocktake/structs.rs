@@ -0,0 +1,319 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[cfg(feature = "process")]
+use crate::entities::prelude::Stocktake as StocktakeEntity;
+#[cfg(feature = "process")]
+use crate::entities::stocktake;
+use crate::methods::{Error, Id, Location, Quantity, Stock, VariantInformation};
+use crate::{ErrorResponse, Session, Store};
+#[cfg(feature = "process")]
+use sea_orm::{sea_query::Expr, ColumnTrait, DbConn, EntityTrait, QueryFilter};
+#[cfg(feature = "process")]
+use serde_json::json;
+use uuid::Uuid;
+
+/// The reason recorded against every adjustment a stocktake makes.
+pub const STOCKTAKE_REASON: &str = "Stocktake adjustment";
+
+/// A physical count of a store's stock, taken over one or more submissions of
+/// counts before being applied in a single pass.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct Stocktake {
+    pub id: Id,
+    pub store_id: Id,
+    /// The latest count of each variant, in the order first counted.
+    pub counts: Vec<StockCount>,
+    /// Set once the stocktake is applied, after which no more counts are taken.
+    pub report: Option<StocktakeReport>,
+    pub started_by: Id,
+    pub started_at: DateTime<Utc>,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// The quantity of a single variant found on hand.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct StockCount {
+    pub product_sku: String,
+    /// The barcode of the variant counted.
+    pub variant_code: String,
+    #[validate(range(min = 0.0))]
+    pub counted: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct StocktakeCounts {
+    #[validate(length(min = 1))]
+    #[validate]
+    pub counts: Vec<StockCount>,
+}
+
+/// The difference between the stock recorded for a variant and that counted.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct StockVariance {
+    pub product_sku: String,
+    pub variant_code: String,
+    pub product_name: String,
+    pub variant_name: String,
+    pub recorded: f32,
+    pub counted: f32,
+    /// Positive where more was counted than recorded, negative for shrinkage.
+    pub variance: f32,
+    /// The variance at the variant's cost price.
+    pub variance_value: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct StocktakeReport {
+    pub store_id: Id,
+    pub reason: String,
+    pub applied_at: DateTime<Utc>,
+    /// Ordered by the size of the variance, largest first, whether over or under.
+    pub lines: Vec<StockVariance>,
+    pub total_variance_value: f32,
+}
+
+impl Stocktake {
+    /// Adds `counts` to those taken so far, a variant counted again taking its
+    /// latest count.
+    pub fn record(&mut self, counts: Vec<StockCount>) {
+        for count in counts {
+            match self.counts.iter_mut().find(|c| {
+                c.product_sku == count.product_sku && c.variant_code == count.variant_code
+            }) {
+                Some(existing) => existing.counted = count.counted,
+                None => self.counts.push(count),
+            }
+        }
+    }
+}
+
+impl StockVariance {
+    /// Sets the variant's sellable stock at `location` to the `counted` quantity,
+    /// returning how far it was from what was recorded. A stock entry is created
+    /// for the location if the variant has not been stocked there before.
+    pub fn apply(
+        product_sku: &str,
+        product_name: &str,
+        variant: &mut VariantInformation,
+        location: &Location,
+        counted: f32,
+    ) -> StockVariance {
+        let recorded = match variant
+            .stock
+            .iter_mut()
+            .find(|s| s.store.store_id == location.store_id)
+        {
+            Some(stock) => {
+                let recorded = stock.quantity.quantity_sellable;
+                stock.quantity.quantity_sellable = counted;
+                recorded
+            }
+            None => {
+                variant.stock.push(Stock {
+                    store: location.clone(),
+                    quantity: Quantity {
+                        quantity_sellable: counted,
+                        quantity_unsellable: 0.0,
+                        quantity_on_order: 0.0,
+                        quantity_allocated: 0.0,
+                        quantity_reserved: 0.0,
+                    },
+                    bin_location: None,
+                });
+                0.0
+            }
+        };
+
+        let variance = counted - recorded;
+
+        StockVariance {
+            product_sku: product_sku.to_string(),
+            variant_code: variant.barcode.clone(),
+            product_name: product_name.to_string(),
+            variant_name: variant.name.clone(),
+            recorded,
+            counted,
+            variance,
+            variance_value: variance * variant.marginal_price,
+        }
+    }
+}
+
+impl StocktakeReport {
+    pub fn new(store_id: &str, mut lines: Vec<StockVariance>) -> Self {
+        lines.sort_by(|a, b| {
+            b.variance
+                .abs()
+                .total_cmp(&a.variance.abs())
+                .then_with(|| b.variance_value.abs().total_cmp(&a.variance_value.abs()))
+        });
+
+        StocktakeReport {
+            store_id: store_id.to_string(),
+            reason: STOCKTAKE_REASON.to_string(),
+            applied_at: Utc::now(),
+            total_variance_value: lines.iter().map(|l| l.variance_value).sum(),
+            lines,
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+impl Stocktake {
+    /// Starts counting the store's stock. Only one stocktake may be open at a store.
+    pub async fn start(store_id: &str, session: Session, db: &DbConn) -> Result<Stocktake, Error> {
+        session.ensure_store(store_id)?;
+        Store::fetch_by_id(store_id, session.clone(), db).await?;
+
+        let open = StocktakeEntity::find()
+            .filter(stocktake::Column::TenantId.eq(session.tenant_id.clone()))
+            .filter(stocktake::Column::StoreId.eq(store_id))
+            .filter(stocktake::Column::AppliedAt.is_null())
+            .one(db)
+            .await?;
+
+        if let Some(open) = open {
+            return Err(ErrorResponse::conflict(&format!(
+                "Stocktake {} is already open at this store.",
+                open.id
+            )));
+        }
+
+        let stocktake = Stocktake {
+            id: Uuid::new_v4().to_string(),
+            store_id: store_id.to_string(),
+            counts: vec![],
+            report: None,
+            started_by: session.employee.id.clone(),
+            started_at: Utc::now(),
+            applied_at: None,
+        };
+
+        StocktakeEntity::insert(stocktake.clone().into_active(session.tenant_id))
+            .exec(db)
+            .await?;
+
+        Ok(stocktake)
+    }
+
+    pub async fn fetch_by_id(id: &str, session: Session, db: &DbConn) -> Result<Stocktake, Error> {
+        let stocktake = StocktakeEntity::find_by_id(id.to_string())
+            .filter(stocktake::Column::TenantId.eq(session.tenant_id))
+            .one(db)
+            .await?;
+
+        match stocktake {
+            Some(s) => Ok(s.into()),
+            None => Err(ErrorResponse::not_found("Stocktake", id)),
+        }
+    }
+
+    /// Records further counts against an open stocktake.
+    pub async fn submit_counts(
+        id: &str,
+        counts: Vec<StockCount>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Stocktake, Error> {
+        let mut stocktake = Self::fetch_by_id(id, session.clone(), db).await?;
+        session.ensure_store(&stocktake.store_id)?;
+
+        if stocktake.applied_at.is_some() {
+            return Err(ErrorResponse::conflict(
+                "Stocktake has already been applied, start another to count again.",
+            ));
+        }
+
+        stocktake.record(counts);
+
+        StocktakeEntity::update_many()
+            .col_expr(
+                stocktake::Column::Counts,
+                Expr::value(json!(stocktake.counts)),
+            )
+            .filter(stocktake::Column::Id.eq(id))
+            .filter(stocktake::Column::TenantId.eq(session.tenant_id))
+            .filter(stocktake::Column::AppliedAt.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(stocktake)
+    }
+
+    /// Sets the store's stock to the quantities counted, see [`Store::apply_stocktake`],
+    /// closing the stocktake with the resulting report.
+    pub async fn apply(id: &str, session: Session, db: &DbConn) -> Result<Stocktake, Error> {
+        let stocktake = Self::fetch_by_id(id, session.clone(), db).await?;
+        session.ensure_store(&stocktake.store_id)?;
+
+        if stocktake.counts.is_empty() {
+            return Err(ErrorResponse::custom_input_error(
+                "Nothing has been counted, submit counts before applying the stocktake.",
+            ));
+        }
+
+        // Claimed before applying, such that concurrent requests cannot apply it twice.
+        let claimed = StocktakeEntity::update_many()
+            .col_expr(
+                stocktake::Column::AppliedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(stocktake::Column::Id.eq(id))
+            .filter(stocktake::Column::TenantId.eq(session.tenant_id.clone()))
+            .filter(stocktake::Column::AppliedAt.is_null())
+            .exec(db)
+            .await?;
+
+        if claimed.rows_affected == 0 {
+            return Err(ErrorResponse::conflict(
+                "Stocktake has already been applied.",
+            ));
+        }
+
+        let report = match Store::apply_stocktake(
+            &stocktake.store_id,
+            &stocktake.counts,
+            session.clone(),
+            db,
+        )
+        .await
+        {
+            Ok(report) => report,
+            Err(err) => {
+                // Reopened to be corrected, counts being absolute such that applying
+                // again is safe should the failure have come part way through.
+                StocktakeEntity::update_many()
+                    .col_expr(
+                        stocktake::Column::AppliedAt,
+                        Expr::value(None::<chrono::NaiveDateTime>),
+                    )
+                    .filter(stocktake::Column::Id.eq(id))
+                    .exec(db)
+                    .await?;
+
+                return Err(err);
+            }
+        };
+
+        StocktakeEntity::update_many()
+            .col_expr(stocktake::Column::Report, Expr::value(json!(report)))
+            .col_expr(
+                stocktake::Column::AppliedAt,
+                Expr::value(report.applied_at.naive_utc()),
+            )
+            .filter(stocktake::Column::Id.eq(id))
+            .filter(stocktake::Column::TenantId.eq(session.tenant_id.clone()))
+            .exec(db)
+            .await?;
+
+        Self::fetch_by_id(id, session, db).await
+    }
+}
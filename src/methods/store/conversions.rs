@@ -1,5 +1,8 @@
 use crate::entities::store::{ActiveModel, Model};
-use crate::{ContactInformation, Session, Store, StoreInput};
+use crate::{
+    ContactInformation, OperatingHours, Region, Session, Store, StoreInput, StoreLayout,
+    DEFAULT_REGION,
+};
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
 use serde_json::json;
@@ -7,15 +10,26 @@ use uuid::Uuid;
 
 impl From<StoreInput> for Store {
     fn from(value: StoreInput) -> Self {
+        value.into_store(*DEFAULT_REGION)
+    }
+}
+
+impl StoreInput {
+    /// Reads the contact's mobile number as one of `region`, the tenant's region.
+    pub fn into_store(self, region: Region) -> Store {
         let id = Uuid::new_v4().to_string();
 
         Store {
             id,
-            name: value.name,
-            code: value.code,
-            contact: value.contact.into_major(),
+            name: self.name,
+            code: self.code,
+            contact: self.contact.into_major_in(region),
+            operating_hours: self.operating_hours,
+            timezone: self.timezone,
+            layout: self.layout,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 0,
         }
     }
 }
@@ -27,9 +41,13 @@ impl Store {
             id: Set(self.id),
             contact: Set(json!(self.contact)),
             code: Set(self.code),
+            operating_hours: Set(self.operating_hours.map(|hours| json!(hours))),
+            timezone: Set(self.timezone.name().to_string()),
+            layout: Set(self.layout.map(|layout| json!(layout))),
             tenant_id: Set(session.tenant_id),
             created_at: Set(self.created_at.naive_utc()),
             updated_at: Set(self.updated_at.naive_utc()),
+            version: Set(self.version),
         }
     }
 }
@@ -41,8 +59,16 @@ impl From<Model> for Store {
             name: val.name,
             contact: serde_json::from_value::<ContactInformation>(val.contact).unwrap(),
             code: serde_json::from_value::<String>(serde_json::Value::String(val.code)).unwrap(),
+            operating_hours: val
+                .operating_hours
+                .and_then(|hours| serde_json::from_value::<OperatingHours>(hours).ok()),
+            timezone: val.timezone.parse().unwrap_or_default(),
+            layout: val
+                .layout
+                .and_then(|layout| serde_json::from_value::<StoreLayout>(layout).ok()),
             updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
             created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
+            version: val.version,
         }
     }
 }
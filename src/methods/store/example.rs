@@ -1,5 +1,8 @@
-use crate::{Address, ContactInformation, Email, MobileNumber, Store};
-use chrono::Utc;
+use crate::{
+    Address, ContactInformation, Email, MobileNumber, OpeningPeriod, OperatingHours, Store,
+};
+use chrono::{NaiveTime, Utc};
+use chrono_tz::Tz;
 
 pub fn example_stores() -> Vec<Store> {
     vec![
@@ -29,8 +32,12 @@ pub fn example_stores() -> Vec<Store> {
                 },
             },
             code: "001".to_string(),
+            operating_hours: Some(example_hours()),
+            timezone: Tz::Pacific__Auckland,
+            layout: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 0,
         },
         Store {
             id: "c4a1d88b-e8a0-4dcd-ade2-1eea82254816".to_string(),
@@ -58,8 +65,12 @@ pub fn example_stores() -> Vec<Store> {
                 },
             },
             code: "002".to_string(),
+            operating_hours: Some(example_hours()),
+            timezone: Tz::Pacific__Auckland,
+            layout: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 0,
         },
         Store {
             id: "a91509fa-2783-43ae-8c3c-5d5bc5cb6c95".to_string(),
@@ -87,8 +98,35 @@ pub fn example_stores() -> Vec<Store> {
                 },
             },
             code: "003".to_string(),
+            operating_hours: Some(example_hours()),
+            timezone: Tz::Pacific__Auckland,
+            layout: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 0,
         },
     ]
 }
+
+/// Trading hours of the example stores, 9am to 5pm weekdays and 10am to 4pm on
+/// Saturday.
+fn example_hours() -> OperatingHours {
+    let period = |open, close| {
+        Some(OpeningPeriod {
+            open: NaiveTime::from_hms_opt(open, 0, 0).unwrap(),
+            close: NaiveTime::from_hms_opt(close, 0, 0).unwrap(),
+        })
+    };
+
+    OperatingHours {
+        days: [
+            period(9, 17),
+            period(9, 17),
+            period(9, 17),
+            period(9, 17),
+            period(9, 17),
+            period(10, 16),
+            None,
+        ],
+    }
+}
@@ -1,24 +1,27 @@
 use crate::catchers::Validated;
-use crate::{Employee, Session, StoreInput};
+use crate::{
+    AuditAction, AuditEntity, AuditLog, Distance, Employee, ListQuery, OrderEvents,
+    OrderStatusEvent, PickList, ReceiptInput, Session, StoreInput, StoreOpenStatus, Tenant,
+    Transaction, Webhook, WebhookEvent,
+};
+use chrono::Utc;
 use okapi::openapi3::OpenApi;
-use rocket::{get, http::CookieJar, post, serde::json::Json};
-use rocket_db_pools::Connection;
+use rocket::{get, post, serde::json::Json, State};
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
 
-use crate::guards::Convert;
+use crate::guards::{Convert, Tagged};
 use crate::pool::InternalDb;
 use crate::{
-    check_permissions,
-    methods::{cookie_status_wrapper, Action, Error, ErrorResponse},
-    pool::Db,
+    check_demo, check_permissions,
+    methods::{Action, DemoMode, Error, ErrorResponse, Generated},
 };
 
 use super::Store;
 
 pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
     openapi_get_routes_spec![
-        settings: get, get_all, get_by_code, generate, update, create
+        settings: get, get_all, get_by_code, nearest, open, on_shift, pick_list, generate, update, create, confirm_receipt
     ]
 }
 
@@ -31,27 +34,109 @@ pub async fn create(
 ) -> Result<Json<Store>, Error> {
     check_permissions!(session.clone(), Action::CreateStore);
 
-    let data = Store::insert(input_data.data().into(), session.clone(), &db.0).await?;
-    let converted: Convert<Store> = Store::fetch_by_id(&data.last_insert_id, session, &db.0)
-        .await
-        .into();
-    converted.0
+    let input = input_data.data();
+    let region = Tenant::fetch_region(&session.tenant_id, &db.0).await?;
+    input.contact.ensure_valid("contact", region)?;
+
+    let data = Store::insert(input.into_store(region), session.clone(), &db.0).await?;
+    let created = Store::fetch_by_id(&data.last_insert_id, session.clone(), &db.0).await?;
+
+    AuditLog::record(
+        AuditEntity::Store,
+        &created.id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+        &session,
+        &db.0,
+    )
+    .await;
+
+    Ok(Json(created))
 }
 
 #[openapi(tag = "Store")]
-#[get("/")]
-pub async fn get_all(db: InternalDb, session: Session) -> Convert<Vec<Store>> {
+#[get("/?<query..>")]
+pub async fn get_all(db: InternalDb, session: Session, query: ListQuery) -> Convert<Vec<Store>> {
     check_permissions!(session.clone(), Action::FetchStore);
-    Store::fetch_all(session, &db.0).await.into()
+    Store::fetch_all(&query, session, &db.0).await.into()
+}
+
+#[openapi(tag = "Store")]
+#[get("/nearest?<lat>&<lon>")]
+pub async fn nearest(
+    db: InternalDb,
+    session: Session,
+    lat: f64,
+    lon: f64,
+) -> Convert<Vec<Distance>> {
+    check_permissions!(session.clone(), Action::FetchStore);
+
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(ErrorResponse::custom_input_error(
+            "Coordinates must be within [-90, 90] latitude and [-180, 180] longitude.",
+        ))
+        .into();
+    }
+
+    Store::nearest(lat, lon, session, &db.0).await.into()
 }
 
 #[openapi(tag = "Store")]
 #[get("/<id>")]
-pub async fn get(db: InternalDb, session: Session, id: &str) -> Convert<Store> {
+pub async fn get(db: InternalDb, session: Session, id: &str) -> Tagged<Store> {
     check_permissions!(session.clone(), Action::FetchStore);
     Store::fetch_by_id(id, session, &db.0).await.into()
 }
 
+/// Whether the store is trading right now, per its operating hours.
+#[openapi(tag = "Store")]
+#[get("/<id>/open", rank = 2)]
+pub async fn open(db: InternalDb, session: Session, id: &str) -> Convert<StoreOpenStatus> {
+    check_permissions!(session.clone(), Action::FetchStore);
+
+    let now = Utc::now();
+
+    Store::fetch_by_id(id, session, &db.0)
+        .await
+        .map(|store| StoreOpenStatus {
+            open: store.is_open_at(now),
+            store_id: store.id,
+            checked_at: now,
+        })
+        .into()
+}
+
+/// The employees currently clocked in at the store.
+#[openapi(tag = "Store")]
+#[get("/<id>/on-shift", rank = 2)]
+pub async fn on_shift(db: InternalDb, session: Session, id: &str) -> Convert<Vec<Employee>> {
+    check_permissions!(session.clone(), Action::FetchStore);
+    check_permissions!(session.clone(), Action::FetchEmployee);
+
+    if let Err(e) = session.ensure_store(id) {
+        return Err(e).into();
+    }
+
+    Store::on_shift(id, session, &db.0).await.into()
+}
+
+/// What remains to be picked from the store's shelves for its pending orders.
+#[openapi(tag = "Store")]
+#[get("/<id>/pick-list", rank = 2)]
+pub async fn pick_list(db: InternalDb, session: Session, id: &str) -> Convert<PickList> {
+    check_permissions!(session.clone(), Action::FetchStore);
+    check_permissions!(session.clone(), Action::FetchTransaction);
+
+    if let Err(e) = session.ensure_store(id) {
+        return Err(e).into();
+    }
+
+    Transaction::generate_pick_list(id, session, &db.0)
+        .await
+        .into()
+}
+
 #[openapi(tag = "Store")]
 #[get("/code/<code>")]
 pub async fn get_by_code(db: InternalDb, session: Session, code: &str) -> Convert<Store> {
@@ -61,8 +146,13 @@ pub async fn get_by_code(db: InternalDb, session: Session, code: &str) -> Conver
 
 #[openapi(tag = "Store")]
 #[post("/generate")]
-async fn generate(db: InternalDb, session: Session) -> Convert<Vec<Store>> {
+async fn generate(
+    db: InternalDb,
+    session: Session,
+    demo: &State<DemoMode>,
+) -> Convert<Vec<Generated<Store>>> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Store::generate(session, &db.0).await.into()
 }
 
@@ -75,7 +165,86 @@ async fn update(
     id: &str,
 ) -> Convert<Store> {
     check_permissions!(session.clone(), Action::ModifyStore);
-    Store::update(input_data.data(), session, id, &db.0)
+
+    if let Err(e) = session.ensure_store(id) {
+        return Err(e).into();
+    }
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
         .await
-        .into()
+        .and_then(|region| input.contact.ensure_valid("contact", region))
+    {
+        return Err(e).into();
+    }
+
+    let before = Store::fetch_by_id(id, session.clone(), &db.0).await.ok();
+    let updated = Store::update(input, session.clone(), id, &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Store,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
+}
+
+#[openapi(tag = "Store")]
+#[post("/receive/<order_id>", data = "<input_data>")]
+async fn confirm_receipt(
+    db: InternalDb,
+    session: Session,
+    order_id: &str,
+    input_data: Validated<Json<ReceiptInput>>,
+    events: &State<OrderEvents>,
+) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ModifyStore);
+
+    let before = Transaction::fetch_by_ref(order_id, session.clone(), &db.0)
+        .await
+        .ok()
+        .and_then(|t| {
+            t.into_iter()
+                .find(|t| t.products.iter().any(|o| o.id == order_id))
+        });
+    let updated =
+        Store::confirm_receipt(order_id, input_data.data().items, session.clone(), &db.0).await;
+
+    if let Ok(after) = &updated {
+        let previous_status = before
+            .as_ref()
+            .and_then(|t| t.products.iter().find(|o| o.id == order_id))
+            .map(|o| o.status.status.clone());
+
+        if let Some(order) = after.products.iter().find(|o| o.id == order_id) {
+            if previous_status.as_ref() != Some(&order.status.status) {
+                let event = OrderStatusEvent::from_order(order, &session.tenant_id);
+
+                Webhook::dispatch(WebhookEvent::OrderStatusChanged, &event, &session, &db.0).await;
+                events.publish(event);
+            }
+        }
+
+        AuditLog::record(
+            AuditEntity::Transaction,
+            &after.id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
 }
@@ -1,24 +1,36 @@
-use chrono::{DateTime, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc,
+};
+use chrono_tz::Tz;
 use schemars::JsonSchema;
 use sea_orm::Set;
 #[cfg(feature = "process")]
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DbConn, DbErr, EntityTrait, InsertResult, QueryFilter,
-    QuerySelect, RuntimeErr,
+    QuerySelect, RuntimeErr, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "process")]
 use crate::entities::prelude::Store as StoreEntity;
 #[cfg(feature = "process")]
-use crate::entities::store;
+use crate::entities::{employee, kiosk, store};
 
 #[cfg(feature = "process")]
-use crate::methods::convert_addr_to_geo;
+use crate::methods::{convert_addr_to_geo, PRODUCT_CACHE};
+#[cfg(feature = "process")]
+use geo::{point, HaversineDistance};
 
 use crate::methods::store::example::example_stores;
-use crate::methods::{ContactInformation, Id};
-use crate::{ContactInformationInput, methods::Error, Session};
+use crate::methods::{
+    Address, ContactInformation, Distance, Generated, Id, ListQuery, Location, ProductPurchaseList,
+    StockCount, StockVariance, StocktakeReport,
+};
+use crate::{
+    methods::Error, Attendance, AuditAction, AuditEntity, AuditLog, ContactInformationInput,
+    Employee, ErrorResponse, History, Order, OrderReceipt, OrderStatus, OrderStatusAssignment,
+    Product, ProductPurchase, ReceivedItem, Session, TrackType, Transaction,
+};
 use serde_json::json;
 use validator::Validate;
 
@@ -30,17 +42,143 @@ pub struct Store {
 
     pub contact: ContactInformation,
     pub code: String,
+    /// When the store trades, stores without hours are treated as always open.
+    #[serde(default)]
+    pub operating_hours: Option<OperatingHours>,
+    /// The IANA timezone the store trades in, i.e. `Pacific/Auckland`, such that
+    /// its local time follows daylight saving.
+    #[serde(default)]
+    #[schemars(with = "String")]
+    pub timezone: Tz,
+    /// The bins stock may be kept in, any bin being accepted if absent.
+    #[serde(default)]
+    pub layout: Option<StoreLayout>,
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+
+    /// Incremented on every update, must be echoed back when updating the store.
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[cfg(feature = "types")]
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
 pub struct StoreInput {
     pub name: String,
+    #[validate]
     pub contact: ContactInformationInput,
     pub code: String,
+    #[serde(default)]
+    pub operating_hours: Option<OperatingHours>,
+    #[serde(default)]
+    #[schemars(with = "String")]
+    pub timezone: Tz,
+    #[serde(default)]
+    pub layout: Option<StoreLayout>,
+}
+
+/// The bins a store keeps its stock in, such as aisles and shelves.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct StoreLayout {
+    /// The label of each bin, i.e. `A1-03` for the third shelf of aisle A1.
+    pub bins: Vec<String>,
+}
+
+#[cfg(feature = "types")]
+impl StoreLayout {
+    /// Whether `bin` is one of the store's bins, ignoring case and surrounding space.
+    pub fn contains(&self, bin: &str) -> bool {
+        self.bins
+            .iter()
+            .any(|b| b.trim().eq_ignore_ascii_case(bin.trim()))
+    }
+}
+
+/// The hours a store trades, in the store's local time.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct OperatingHours {
+    /// Trading hours for each weekday, starting Monday. `None` denotes a closed day.
+    pub days: [Option<OpeningPeriod>; 7],
+}
+
+/// A single day's trading hours. A period closing at or before it opens runs
+/// overnight, closing on the following day.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct OpeningPeriod {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+#[cfg(feature = "types")]
+impl OpeningPeriod {
+    fn is_overnight(&self) -> bool {
+        self.close <= self.open
+    }
+}
+
+#[cfg(feature = "types")]
+impl OperatingHours {
+    /// Whether the store is trading at the local time `local`.
+    pub fn is_open_on(&self, local: NaiveDateTime) -> bool {
+        let today = local.weekday().num_days_from_monday() as usize;
+        let yesterday = (today + 6) % 7;
+        let time = local.time();
+
+        let open_today = self.days[today].as_ref().is_some_and(|period| {
+            time >= period.open && (period.is_overnight() || time < period.close)
+        });
+
+        // Overnight hours from the previous day extend into the early morning.
+        let open_from_yesterday = self.days[yesterday]
+            .as_ref()
+            .is_some_and(|period| period.is_overnight() && time < period.close);
+
+        open_today || open_from_yesterday
+    }
+}
+
+/// Whether a store is trading at the time of the request.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct StoreOpenStatus {
+    pub store_id: Id,
+    pub open: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "types")]
+impl Store {
+    /// The store's local time at the instant `at`.
+    pub fn local_time(&self, at: DateTime<Utc>) -> NaiveDateTime {
+        at.with_timezone(&self.timezone).naive_local()
+    }
+
+    /// The instant at which the local time of the store is `local`. A time which
+    /// occurs twice as clocks go back is read as the first, whilst one skipped as
+    /// clocks go forward is read as it would have been before they did.
+    pub fn to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self.timezone.from_local_datetime(&local) {
+            LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => at.with_timezone(&Utc),
+            LocalResult::None => {
+                let offset = self.timezone.offset_from_utc_datetime(&local).fix();
+                DateTime::from_naive_utc_and_offset(
+                    local - Duration::seconds(offset.local_minus_utc() as i64),
+                    Utc,
+                )
+            }
+        }
+    }
+
+    /// Whether the store is trading at the instant `at`.
+    pub fn is_open_at(&self, at: DateTime<Utc>) -> bool {
+        self.operating_hours
+            .as_ref()
+            .is_none_or(|hours| hours.is_open_on(self.local_time(at)))
+    }
 }
 
 #[cfg(feature = "methods")]
@@ -78,7 +216,7 @@ impl Store {
 
         match store {
             Some(e) => Ok(e.into()),
-            None => Err(DbErr::RecordNotFound(id.to_string()).into()),
+            None => Err(ErrorResponse::not_found("Store", id)),
         }
     }
 
@@ -95,9 +233,23 @@ impl Store {
         }
     }
 
-    pub async fn fetch_all(session: Session, db: &DbConn) -> Result<Vec<Store>, Error> {
-        let stores = StoreEntity::find()
-            .filter(store::Column::TenantId.eq(session.tenant_id))
+    pub async fn fetch_all(
+        query: &ListQuery,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Store>, Error> {
+        let select = StoreEntity::find().filter(store::Column::TenantId.eq(session.tenant_id));
+
+        let stores = query
+            .apply(
+                select,
+                &[
+                    ("name", store::Column::Name),
+                    ("code", store::Column::Code),
+                    ("created_at", store::Column::CreatedAt),
+                    ("updated_at", store::Column::UpdatedAt),
+                ],
+            )?
             .all(db)
             .await?;
 
@@ -106,6 +258,255 @@ impl Store {
         Ok(mapped)
     }
 
+    /// Fetches the tenant's stores ordered by their haversine distance (in metres) from
+    /// the given coordinates, nearest first. Stores whose address has not been geocoded
+    /// are excluded.
+    pub async fn nearest(
+        lat: f64,
+        lon: f64,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Distance>, Error> {
+        let stores = Self::fetch_all(&ListQuery::default(), session, db).await?;
+
+        Ok(Self::by_distance(&stores, lat, lon))
+    }
+
+    /// The haversine distance (in metres) of each of `stores` from the given coordinates,
+    /// nearest first, as found by [`Store::nearest`].
+    pub fn by_distance(stores: &[Store], lat: f64, lon: f64) -> Vec<Distance> {
+        let origin = point!(x: lon, y: lat);
+
+        let mut distances: Vec<Distance> = stores
+            .iter()
+            .filter(|store| store.contact.address.is_geocoded())
+            .map(|store| {
+                let address = &store.contact.address;
+
+                Distance {
+                    distance: point!(x: address.lon, y: address.lat).haversine_distance(&origin),
+                    store_id: store.id.clone(),
+                    store_code: store.code.clone(),
+                }
+            })
+            .collect();
+
+        distances.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        distances
+    }
+
+    /// Finds the nearest store to `address` which holds enough stock to supply every
+    /// purchase in `products`, or `None` if no such store exists.
+    pub async fn nearest_to(
+        address: &Address,
+        products: &ProductPurchaseList,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Option<Store>, Error> {
+        if !address.is_geocoded() {
+            return Err(ErrorResponse::custom_input_error(
+                "Unable to find the nearest store to an address which has not been geocoded.",
+            ));
+        }
+
+        let mut catalogue: Vec<Product> = vec![];
+
+        for purchase in products {
+            if !catalogue.iter().any(|p| p.sku == purchase.product_sku) {
+                catalogue
+                    .push(Product::fetch_by_id(&purchase.product_sku, session.clone(), db).await?);
+            }
+
+            let known = catalogue.iter().any(|p| {
+                p.sku == purchase.product_sku
+                    && p.variants
+                        .iter()
+                        .any(|v| v.barcode == purchase.product_code)
+            });
+
+            if !known {
+                return Err(ErrorResponse::not_found("Variant", &purchase.product_code));
+            }
+        }
+
+        let stores = Self::fetch_all(&ListQuery::default(), session, db).await?;
+
+        Ok(Self::nearest_supplying(&stores, address, products, &catalogue).cloned())
+    }
+
+    /// The nearest of `stores` to `address` holding enough stock to supply every purchase
+    /// in `products`, whose variants are found among `catalogue`, see [`Store::nearest_to`].
+    pub fn nearest_supplying<'a>(
+        stores: &'a [Store],
+        address: &Address,
+        products: &ProductPurchaseList,
+        catalogue: &[Product],
+    ) -> Option<&'a Store> {
+        let variant = |purchase: &ProductPurchase| {
+            catalogue
+                .iter()
+                .filter(|p| p.sku == purchase.product_sku)
+                .flat_map(|p| p.variants.iter())
+                .find(|v| v.barcode == purchase.product_code)
+        };
+
+        let distance = Self::by_distance(stores, address.lat, address.lon)
+            .into_iter()
+            .find(|distance| {
+                products.iter().all(|purchase| {
+                    variant(purchase)
+                        .and_then(|v| {
+                            purchase
+                                .stock_quantity(v.stock_information.unit)
+                                .map(|quantity| v.available_at(&distance.store_code, quantity))
+                        })
+                        .unwrap_or(false)
+                })
+            })?;
+
+        stores.iter().find(|store| store.id == distance.store_id)
+    }
+
+    /// Sets the store's sellable stock of each variant counted to the quantity
+    /// counted, auditing every product adjusted. The products are locked and written
+    /// as one, such that a count of an unknown variant adjusts nothing and no sale
+    /// made meanwhile is overwritten. Returns the variance of each count, largest first.
+    pub async fn apply_stocktake(
+        store_id: &str,
+        counts: &[StockCount],
+        session: Session,
+        db: &DbConn,
+    ) -> Result<StocktakeReport, Error> {
+        session.ensure_store(store_id)?;
+        let store = Self::fetch_by_id(store_id, session.clone(), db).await?;
+
+        let location = Location {
+            store_code: store.code,
+            store_id: store.id,
+            contact: store.contact,
+        };
+
+        // Locked in the same order as sales lock them, such that neither can
+        // deadlock the other.
+        let mut skus: Vec<&str> = counts.iter().map(|c| c.product_sku.as_str()).collect();
+        skus.sort();
+        skus.dedup();
+
+        // Each product as fetched and as counted, fetched once however many of its
+        // variants were counted.
+        let txn = db.begin().await?;
+        let mut products: Vec<(Product, Product)> = vec![];
+        let mut lines = vec![];
+
+        for sku in skus {
+            let product = Product::fetch_for_update(sku, &session.tenant_id, &txn).await?;
+            products.push((product.clone(), product));
+        }
+
+        for count in counts {
+            let (_, product) = match products
+                .iter_mut()
+                .find(|(before, _)| before.sku == count.product_sku)
+            {
+                Some(entry) => entry,
+                None => return Err(ErrorResponse::not_found("Product", &count.product_sku)),
+            };
+            let name = product.name.clone();
+
+            let variant = product
+                .variants
+                .iter_mut()
+                .find(|v| v.barcode == count.variant_code)
+                .ok_or_else(|| ErrorResponse::not_found("Variant", &count.variant_code))?;
+
+            lines.push(StockVariance::apply(
+                &count.product_sku,
+                &name,
+                variant,
+                &location,
+                count.counted,
+            ));
+        }
+
+        for (_, product) in products.iter() {
+            product
+                .clone()
+                .into_active(session.clone())
+                .update(&txn)
+                .await?;
+        }
+
+        txn.commit().await?;
+
+        for (before, product) in products {
+            let sku = product.sku;
+            PRODUCT_CACHE.invalidate(&session.tenant_id, &sku);
+            let updated = Product::fetch_by_id(&sku, session.clone(), db).await?;
+
+            AuditLog::record(
+                AuditEntity::Product,
+                &sku,
+                AuditAction::Update,
+                Some(&before),
+                Some(&updated),
+                &session,
+                db,
+            )
+            .await;
+        }
+
+        Ok(StocktakeReport::new(&location.store_id, lines))
+    }
+
+    /// The employees currently clocked in at one of the store's kiosks, longest on
+    /// shift first. Employees who have never clocked in are omitted, as are any
+    /// malformed entries in a clock history.
+    pub async fn on_shift(
+        store_id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Employee>, Error> {
+        let store = Self::fetch_by_id(store_id, session.clone(), db).await?;
+
+        let kiosks: Vec<Id> = kiosk::Entity::find()
+            .filter(kiosk::Column::TenantId.eq(session.tenant_id.clone()))
+            .filter(kiosk::Column::StoreId.eq(store.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|k| k.id)
+            .collect();
+
+        let employees = employee::Entity::find()
+            .filter(employee::Column::TenantId.eq(session.tenant_id))
+            .all(db)
+            .await?;
+
+        let mut on_shift: Vec<Employee> = employees
+            .into_iter()
+            .map(|model| {
+                let history = Attendance::history_from_value(model.clock_history.clone());
+
+                employee::Model {
+                    clock_history: json!(history),
+                    ..model
+                }
+                .into()
+            })
+            .filter(|employee: &Employee| {
+                employee.latest_attendance().is_some_and(|entry| {
+                    matches!(entry.item.track_type, TrackType::In)
+                        && kiosks.contains(&entry.item.kiosk)
+                })
+            })
+            .collect();
+
+        on_shift.sort_by_key(|e| e.latest_attendance().map(|entry| entry.timestamp));
+
+        Ok(on_shift)
+    }
+
     pub async fn update(
         store: Store,
         session: Session,
@@ -125,11 +526,28 @@ impl Store {
                 let mut new_contact = store.contact.clone();
                 new_contact.address = ad;
 
+                let version = store.version;
                 let mut model = store.into_active(session.clone());
 
                 model.contact = Set(json!(new_contact));
+                model.updated_at = Set(Utc::now().naive_utc());
+                model.version = Set(version + 1);
 
-                model.update(db).await?;
+                let res = StoreEntity::update_many()
+                    .set(model)
+                    .filter(store::Column::Id.eq(id))
+                    .filter(store::Column::TenantId.eq(session.tenant_id.clone()))
+                    .filter(store::Column::Version.eq(version))
+                    .exec(db)
+                    .await?;
+
+                if res.rows_affected == 0 {
+                    // Either the store does not exist, or it was modified since it was fetched.
+                    Self::fetch_by_id(id, session, db).await?;
+                    return Err(ErrorResponse::conflict(
+                        "Store was modified by another request, fetch it again before updating.",
+                    ));
+                }
 
                 Self::fetch_by_id(id, session, db).await
             }
@@ -139,15 +557,154 @@ impl Store {
         }
     }
 
-    pub async fn generate(session: Session, db: &DbConn) -> Result<Vec<Store>, Error> {
-        let stores = example_stores();
+    /// Confirms the arrival of goods for an in-transit order at its destination store.
+    ///
+    /// Each received line increments the destination's stock by the quantity received.
+    /// Once every line has been received in full the order moves to `Fulfilled`,
+    /// otherwise it is left in transit awaiting the remaining goods. The receipt and
+    /// the stock it adds are written within one database transaction, such that a
+    /// receipt confirmed twice at once is counted once.
+    pub async fn confirm_receipt(
+        order_id: &str,
+        received_quantity: Vec<ReceivedItem>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        let mut transaction = match Transaction::fetch_by_ref(order_id, session.clone(), db)
+            .await?
+            .into_iter()
+            .find(|t| t.products.iter().any(|o| o.id == order_id))
+        {
+            Some(t) => t,
+            None => return Err(ErrorResponse::not_found("Order", order_id)),
+        };
+
+        let mut order: Order = transaction
+            .products
+            .iter()
+            .find(|o| o.id == order_id)
+            .cloned()
+            .unwrap();
+
+        session.ensure_store(&order.destination.store_id)?;
+
+        if !matches!(order.status.status, OrderStatus::Transit(_)) {
+            return Err(ErrorResponse::conflict(
+                "Order must be in transit to confirm its receipt.",
+            ));
+        }
+
+        let mut received = vec![];
+
+        for item in received_quantity {
+            if item.quantity <= 0.0 {
+                return Err(ErrorResponse::create_error(
+                    "Received quantity must be greater than zero.",
+                ));
+            }
+
+            let line = match order
+                .products
+                .iter()
+                .find(|p| p.id == item.product_purchase_id)
+            {
+                Some(line) => line.clone(),
+                None => {
+                    return Err(ErrorResponse::not_found(
+                        "Product purchase",
+                        &item.product_purchase_id,
+                    ));
+                }
+            };
+
+            let total_received = order.quantity_received(&line.id) + item.quantity;
+
+            order.receipts.push(OrderReceipt {
+                product_purchase_id: line.id.clone(),
+                quantity_sent: line.quantity,
+                quantity_received: item.quantity,
+                discrepancy: total_received != line.quantity,
+                employee: session.employee.id.clone(),
+                timestamp: Utc::now(),
+            });
+
+            received.push((line, item.quantity));
+        }
+
+        if order.is_fully_received() {
+            let new_status = OrderStatusAssignment {
+                status: OrderStatus::Fulfilled(Utc::now()),
+                assigned_products: order.products.iter().map(|p| p.id.clone()).collect(),
+                timestamp: Utc::now(),
+            };
+
+            order.fulfil_all();
+            order.status = new_status.clone();
+            order.status_history.push(History {
+                item: new_status,
+                reason: format!("Receipt confirmed by {}", session.employee.id),
+                timestamp: Utc::now(),
+            });
+        }
+
+        let transaction_id = transaction.id.clone();
+        let version = transaction.version;
+        transaction.products = transaction
+            .products
+            .into_iter()
+            .map(|o| if o.id == order.id { order.clone() } else { o })
+            .collect();
+
+        let mut model = transaction.into_active(session.tenant_id.clone());
+        model.updated_at = Set(Utc::now().naive_utc());
+
+        let txn = db.begin().await?;
+
+        // Claimed before the stock is added, such that a concurrent receipt cannot add it twice.
+        if !Transaction::write_versioned(model, version, &transaction_id, &session.tenant_id, &txn)
+            .await?
+        {
+            return Err(ErrorResponse::conflict(
+                "Transaction was modified by another request, fetch it again before updating.",
+            ));
+        }
+
+        for (line, quantity) in &received {
+            Product::alter_within(&line.product_sku, session.clone(), &txn, |product| {
+                product.receive(&line.product_code, order.destination.clone(), *quantity)
+            })
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        for (line, _) in &received {
+            PRODUCT_CACHE.invalidate(&session.tenant_id, &line.product_sku);
+        }
+
+        Transaction::fetch_by_id(&transaction_id, session, db).await
+    }
+
+    /// Seeds the example stores, which have fixed ids. Stores which already
+    /// exist are left as they are, so repeated calls yield the same stores.
+    pub async fn generate(session: Session, db: &DbConn) -> Result<Vec<Generated<Store>>, Error> {
+        let mut generated = vec![];
 
-        match Store::insert_many(stores, session.clone(), db).await {
-            Ok(_) => match Store::fetch_all(session, db).await {
-                Ok(res) => Ok(res),
-                Err(e) => Err(e),
-            },
-            Err(e) => Err(e),
+        for store in example_stores() {
+            let created = Self::fetch_by_id(&store.id, session.clone(), db)
+                .await
+                .is_err();
+
+            if created {
+                Self::insert(store.clone(), session.clone(), db).await?;
+            }
+
+            generated.push(Generated {
+                created,
+                record: Self::fetch_by_id(&store.id, session.clone(), db).await?,
+            });
         }
+
+        Ok(generated)
     }
 }
@@ -1,5 +1,9 @@
+use crate::entities::purchase_order;
 use crate::entities::supplier::{ActiveModel, Model};
-use crate::{ContactInformation, Name, Supplier, SupplierInput, Transaction};
+use crate::{
+    ContactInformation, Location, Name, PurchaseOrder, PurchaseOrderInput, PurchaseOrderItem,
+    PurchaseOrderStatus, Supplier, SupplierInput, Transaction,
+};
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
 use serde_json::json;
@@ -16,6 +20,7 @@ impl From<Model> for Supplier {
             .unwrap(),
             created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
             updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
+            version: val.version,
         }
     }
 }
@@ -30,6 +35,44 @@ impl SupplierInput {
             tenant_id: Set(tenant_id),
             created_at: Set(Utc::now().naive_utc()),
             updated_at: Set(Utc::now().naive_utc()),
+            version: Set(0),
+            phone: Set(self.contact.mobile.normalized()),
+        }
+    }
+}
+
+impl From<purchase_order::Model> for PurchaseOrder {
+    fn from(val: purchase_order::Model) -> Self {
+        PurchaseOrder {
+            id: val.id,
+            supplier_id: val.supplier_id,
+            store: serde_json::from_value::<Location>(val.store).unwrap(),
+            items: serde_json::from_value::<Vec<PurchaseOrderItem>>(val.items).unwrap(),
+            status: serde_json::from_value::<PurchaseOrderStatus>(val.status).unwrap(),
+            expected_delivery: DateTime::from_naive_utc_and_offset(val.expected_delivery, Utc),
+            created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
+            updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
+        }
+    }
+}
+
+impl PurchaseOrderInput {
+    pub(crate) fn into_active(
+        self,
+        id: String,
+        supplier_id: String,
+        tenant_id: String,
+    ) -> purchase_order::ActiveModel {
+        purchase_order::ActiveModel {
+            id: Set(id),
+            supplier_id: Set(supplier_id),
+            store: Set(json!(self.store)),
+            items: Set(json!(self.items)),
+            status: Set(json!(PurchaseOrderStatus::Placed)),
+            expected_delivery: Set(self.expected_delivery.naive_utc()),
+            tenant_id: Set(tenant_id),
+            created_at: Set(Utc::now().naive_utc()),
+            updated_at: Set(Utc::now().naive_utc()),
         }
     }
 }
@@ -25,5 +25,6 @@ pub fn example_supplier() -> SupplierInput {
         },
         contact: customer,
         transaction_history: vec![],
+        version: 0,
     }
 }
@@ -1,14 +1,19 @@
-use super::{Supplier, SupplierInput};
+use super::{PurchaseOrder, PurchaseOrderInput, Supplier, SupplierInput};
 use crate::catchers::Validated;
-use crate::guards::Convert;
+use crate::guards::{Convert, Tagged};
 use crate::methods::employee::Action;
-use crate::methods::Error;
+use crate::methods::{
+    AuditAction, AuditEntity, AuditLog, Error, ErrorResponse, ImportSummary, Tenant,
+    DEFAULT_FUZZY_DISTANCE,
+};
 use crate::pool::InternalDb;
-use crate::{check_permissions, Session};
+use crate::{check_demo, check_permissions, DemoMode, Generated, Session};
 use okapi::openapi3::OpenApi;
+use rocket::data::Capped;
 use rocket::get;
 use rocket::post;
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
 
@@ -21,22 +26,34 @@ pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, Ope
         get_by_addr,
         create,
         update,
-        generate
+        generate,
+        import,
+        get_purchase_orders,
+        create_purchase_order,
+        receive_purchase_order
     ]
 }
 
 #[openapi(tag = "Supplier")]
 #[get("/<id>")]
-pub async fn get(db: InternalDb, session: Session, id: &str) -> Convert<Supplier> {
+pub async fn get(db: InternalDb, session: Session, id: &str) -> Tagged<Supplier> {
     check_permissions!(session.clone(), Action::FetchSupplier);
     Supplier::fetch_by_id(id, session, &db.0).await.into()
 }
 
 #[openapi(tag = "Supplier")]
-#[get("/name/<name>")]
-pub async fn get_by_name(db: InternalDb, session: Session, name: &str) -> Convert<Vec<Supplier>> {
+#[get("/name/<name>?<distance>")]
+pub async fn get_by_name(
+    db: InternalDb,
+    session: Session,
+    name: &str,
+    distance: Option<usize>,
+) -> Convert<Vec<Supplier>> {
     check_permissions!(session.clone(), Action::FetchSupplier);
-    Supplier::fetch_by_name(name, session, &db.0).await.into()
+    let distance = distance.unwrap_or(DEFAULT_FUZZY_DISTANCE);
+    Supplier::fetch_by_name(name, distance, session, &db.0)
+        .await
+        .into()
 }
 
 #[openapi(tag = "Supplier")]
@@ -55,8 +72,13 @@ pub async fn get_by_addr(db: InternalDb, session: Session, addr: &str) -> Conver
 
 #[openapi(tag = "Supplier")]
 #[post("/generate")]
-async fn generate(session: Session, db: InternalDb) -> Convert<Supplier> {
+async fn generate(
+    session: Session,
+    db: InternalDb,
+    demo: &State<DemoMode>,
+) -> Convert<Generated<Supplier>> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Supplier::generate(session, &db.0).await.into()
 }
 
@@ -69,9 +91,33 @@ async fn update(
     id: &str,
 ) -> Convert<Supplier> {
     check_permissions!(session.clone(), Action::ModifySupplier);
-    Supplier::update(input_data.data(), session, id, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Tenant::fetch_region(&session.tenant_id, &db.0)
         .await
-        .into()
+        .and_then(|region| input.contact.ensure_valid("contact", region))
+    {
+        return Err(e).into();
+    }
+
+    let before = Supplier::fetch_by_id(id, session.clone(), &db.0).await.ok();
+    let updated = Supplier::update(input, session.clone(), id, &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Supplier,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
 }
 
 #[openapi(tag = "Supplier")]
@@ -83,9 +129,85 @@ pub async fn create(
 ) -> Result<Json<Supplier>, Error> {
     check_permissions!(session.clone(), Action::ModifySupplier);
 
-    let data = Supplier::insert(input_data.data(), session.clone(), &db.0).await?;
-    let converted: Convert<Supplier> = Supplier::fetch_by_id(&data.last_insert_id, session, &db.0)
+    let input = input_data.data();
+    let region = Tenant::fetch_region(&session.tenant_id, &db.0).await?;
+    input.contact.ensure_valid("contact", region)?;
+
+    let data = Supplier::insert(input, session.clone(), &db.0).await?;
+    let created = Supplier::fetch_by_id(&data.last_insert_id, session.clone(), &db.0).await?;
+
+    AuditLog::record(
+        AuditEntity::Supplier,
+        &created.id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+        &session,
+        &db.0,
+    )
+    .await;
+
+    Ok(Json(created))
+}
+
+/// Imports suppliers from a CSV body, see [`Supplier::import_csv`] for the expected columns.
+#[openapi(tag = "Supplier")]
+#[post("/import", data = "<csv>")]
+pub async fn import(
+    session: Session,
+    db: InternalDb,
+    csv: Capped<String>,
+) -> Result<Json<ImportSummary>, Error> {
+    check_permissions!(session.clone(), Action::ModifySupplier);
+
+    if !csv.is_complete() {
+        return Err(ErrorResponse::custom_input_error(
+            "Import exceeds the maximum upload size, split it into multiple files.",
+        ));
+    }
+
+    Supplier::import_csv(csv.as_bytes(), session, &db.0)
+        .await
+        .map(Json)
+}
+
+#[openapi(tag = "Supplier")]
+#[get("/<id>/orders", rank = 2)]
+pub async fn get_purchase_orders(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+) -> Convert<Vec<PurchaseOrder>> {
+    check_permissions!(session.clone(), Action::FetchSupplier);
+    Supplier::fetch_purchase_orders(id, session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Supplier")]
+#[post("/<id>/orders", data = "<input_data>")]
+pub async fn create_purchase_order(
+    session: Session,
+    db: InternalDb,
+    input_data: Validated<Json<PurchaseOrderInput>>,
+    id: &str,
+) -> Convert<PurchaseOrder> {
+    check_permissions!(session.clone(), Action::ModifySupplier);
+    Supplier::create_purchase_order(id, input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Supplier")]
+#[post("/<id>/orders/<order_id>/receive")]
+pub async fn receive_purchase_order(
+    session: Session,
+    db: InternalDb,
+    id: &str,
+    order_id: &str,
+) -> Convert<PurchaseOrder> {
+    check_permissions!(session.clone(), Action::ModifySupplier);
+    Supplier::receive_purchase_order(id, order_id, session, &db.0)
         .await
-        .into();
-    converted.0
+        .into()
 }
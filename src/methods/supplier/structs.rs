@@ -1,25 +1,35 @@
 use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use std::fmt::Display;
+use std::io::Read;
 
+#[cfg(feature = "process")]
+use crate::entities::prelude::PurchaseOrder as PurchaseOrd;
 #[cfg(feature = "process")]
 use crate::entities::prelude::Supplier as Suppl;
 #[cfg(feature = "process")]
-use crate::entities::supplier;
+use crate::entities::{purchase_order, supplier};
 use crate::methods::Error;
-use crate::Session;
+use crate::{ContactInformationInput, ErrorResponse, Product, Session};
 
-use crate::methods::{ContactInformation, Name, Transaction};
+use crate::methods::{
+    parse_csv, validate_name, Address, ContactInformation, CsvRecord, FieldError, Generated, Id,
+    ImportSummary, ImportedRow, Location, Name, Region, Transaction,
+};
 
 #[cfg(feature = "process")]
-use crate::methods::convert_addr_to_geo;
+use crate::methods::{
+    convert_addr_to_geo, fuzzy_rank, normalize_phone_number, stable_id, Tenant, PRODUCT_CACHE,
+};
 
 use crate::methods::supplier::example::example_supplier;
-use sea_orm::ActiveValue::Set;
+#[cfg(feature = "process")]
+use sea_orm::prelude::Json;
+use sea_orm::ActiveValue::{NotSet, Set};
 #[cfg(feature = "process")]
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DbConn, DbErr, EntityTrait, InsertResult, QueryFilter,
-    QuerySelect, RuntimeErr,
+    sea_query::Expr, ColumnTrait, DbConn, DbErr, EntityTrait, InsertResult, QueryFilter,
+    QuerySelect, RuntimeErr, TransactionTrait, UpdateMany,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -37,14 +47,90 @@ pub struct Supplier {
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+
+    /// Incremented on every update, must be echoed back when updating the supplier.
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[cfg(feature = "types")]
 #[derive(Serialize, Deserialize, Clone, JsonSchema, Validate)]
 pub struct SupplierInput {
+    #[validate(custom = "validate_name")]
     pub name: Name,
+    #[validate]
     pub contact: ContactInformation,
     pub transaction_history: Vec<Transaction>,
+
+    /// The version of the supplier being updated, ignored on creation.
+    #[serde(default)]
+    pub version: i32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct PurchaseOrderItem {
+    pub product_sku: String,
+    /// The barcode of the variant being ordered.
+    pub product_code: String,
+    pub quantity: f32,
+    /// The per-unit cost agreed with the supplier.
+    pub cost: f32,
+}
+
+#[cfg(feature = "methods")]
+impl PurchaseOrderItem {
+    /// Adds the line to the stock of `product` at `location`, recording its cost
+    /// against the variant should the variant be sourced from `supplier_id`.
+    pub fn receive_into(
+        &self,
+        product: &mut Product,
+        supplier_id: &str,
+        location: Location,
+    ) -> Result<(), Error> {
+        product.receive(&self.product_code, location, self.quantity)?;
+
+        if let Some(variant) = product
+            .variants
+            .iter_mut()
+            .find(|v| v.barcode == self.product_code)
+        {
+            variant.record_supplier_cost(supplier_id, self.cost);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub enum PurchaseOrderStatus {
+    Placed,
+    Received(DateTime<Utc>),
+    Cancelled(String),
+}
+
+/// An order for stock placed with a supplier, delivered to `store`.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct PurchaseOrder {
+    pub id: Id,
+    pub supplier_id: Id,
+    pub store: Location,
+    pub items: Vec<PurchaseOrderItem>,
+    pub status: PurchaseOrderStatus,
+    pub expected_delivery: DateTime<Utc>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, JsonSchema, Validate)]
+pub struct PurchaseOrderInput {
+    pub store: Location,
+    pub items: Vec<PurchaseOrderItem>,
+    pub expected_delivery: DateTime<Utc>,
 }
 
 #[cfg(feature = "methods")]
@@ -55,47 +141,133 @@ impl Supplier {
         db: &DbConn,
     ) -> Result<InsertResult<supplier::ActiveModel>, Error> {
         let id = Uuid::new_v4().to_string();
+        Self::insert_with_id(suppl, id, session, db).await
+    }
+
+    async fn insert_with_id(
+        suppl: SupplierInput,
+        id: String,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<InsertResult<supplier::ActiveModel>, Error> {
         Suppl::insert(suppl.into_active(id, session.tenant_id.clone()))
             .exec(db)
             .await
             .map_err(|e| e.into())
     }
 
+    /// Imports suppliers from CSV, one per row, with the columns `name`, `contact`,
+    /// `phone`, `email`, `landline`, `street`, `street2`, `city`, `country` and `po_code`.
+    /// Rows without a name or with an invalid phone number are rejected.
+    pub async fn import_csv<R: Read>(
+        mut reader: R,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<ImportSummary, Error> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input).map_err(|e| {
+            ErrorResponse::custom_input_error(&format!("Unable to read import: {}", e))
+        })?;
+
+        let records = parse_csv(&input).map_err(|e| ErrorResponse::custom_input_error(&e))?;
+
+        let region = Tenant::fetch_region(&session.tenant_id, db).await?;
+        let mut summary = ImportSummary::default();
+        let mut suppliers = vec![];
+
+        for record in records {
+            match supplier_from_record(&record, region) {
+                Ok(supplier) => {
+                    let id = Uuid::new_v4().to_string();
+
+                    summary.inserted.push(ImportedRow {
+                        line: record.line,
+                        id: id.clone(),
+                    });
+                    suppliers.push(supplier.into_active(id, session.tenant_id.clone()));
+                }
+                Err(reason) => summary.reject(record.line, reason),
+            }
+        }
+
+        if !suppliers.is_empty() {
+            Suppl::insert_many(suppliers).exec(db).await?;
+        }
+
+        Ok(summary)
+    }
+
     pub async fn fetch_by_id(id: &str, session: Session, db: &DbConn) -> Result<Supplier, Error> {
         let suppl = Suppl::find_by_id(id.to_string())
             .filter(supplier::Column::TenantId.eq(session.tenant_id))
             .one(db)
             .await?;
-        let s = suppl.unwrap();
 
-        Ok(s.into())
+        match suppl {
+            Some(s) => Ok(s.into()),
+            None => Err(ErrorResponse::not_found("Supplier", id)),
+        }
     }
 
+    /// Finds suppliers whose name is within `max_distance` edits of `name`,
+    /// exact matches first and the remainder by similarity.
     pub async fn fetch_by_name(
         name: &str,
+        max_distance: usize,
         session: Session,
         db: &DbConn,
     ) -> Result<Vec<Supplier>, Error> {
+        let names: Vec<(String, Json)> = supplier::Entity::find()
+            .select_only()
+            .column(supplier::Column::Id)
+            .column(supplier::Column::Name)
+            .filter(supplier::Column::TenantId.eq(session.tenant_id.clone()))
+            .into_tuple()
+            .all(db)
+            .await?;
+
+        let mut ids = fuzzy_rank(
+            name,
+            max_distance,
+            names.into_iter().filter_map(|(id, n)| {
+                serde_json::from_value::<Name>(n)
+                    .ok()
+                    .map(|n| (id, n.to_string()))
+            }),
+        );
+        ids.truncate(25);
+
         let res = supplier::Entity::find()
             .filter(supplier::Column::TenantId.eq(session.tenant_id))
-            .having(supplier::Column::Name.contains(name))
-            .limit(25)
+            .filter(supplier::Column::Id.is_in(ids.clone()))
             .all(db)
             .await?;
 
-        let mapped = res.iter().map(|s| s.clone().into()).collect();
+        let mapped = ids
+            .iter()
+            .filter_map(|id| res.iter().find(|s| &s.id == id))
+            .map(|s| s.clone().into())
+            .collect();
 
         Ok(mapped)
     }
 
+    /// Finds suppliers whose mobile number begins with `phone`, irrespective of
+    /// how either is formatted.
     pub async fn fetch_by_phone(
         phone: &str,
         session: Session,
         db: &DbConn,
     ) -> Result<Vec<Supplier>, Error> {
+        let phone = normalize_phone_number(phone);
+
+        if phone.is_empty() {
+            return Ok(vec![]);
+        }
+
         let res = supplier::Entity::find()
             .filter(supplier::Column::TenantId.eq(session.tenant_id))
-            .having(supplier::Column::Contact.contains(phone))
+            .filter(supplier::Column::Phone.starts_with(&phone))
             .limit(25)
             .all(db)
             .await?;
@@ -122,15 +294,171 @@ impl Supplier {
         Ok(mapped)
     }
 
+    pub async fn create_purchase_order(
+        supplier_id: &str,
+        order: PurchaseOrderInput,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<PurchaseOrder, Error> {
+        if Suppl::find_by_id(supplier_id.to_string())
+            .filter(supplier::Column::TenantId.eq(session.tenant_id.clone()))
+            .one(db)
+            .await?
+            .is_none()
+        {
+            return Err(ErrorResponse::not_found("Supplier", supplier_id));
+        }
+
+        if order.items.is_empty() {
+            return Err(ErrorResponse::validation_errors(vec![FieldError::new(
+                "items",
+                "A purchase order must have at least one item.",
+            )]));
+        }
+
+        let invalid: Vec<FieldError> = order
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.quantity <= 0.0)
+            .map(|(index, _)| {
+                FieldError::new(
+                    &format!("items[{}].quantity", index),
+                    "Quantity must be greater than zero.",
+                )
+            })
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(ErrorResponse::validation_errors(invalid));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        PurchaseOrd::insert(order.into_active(
+            id.clone(),
+            supplier_id.to_string(),
+            session.tenant_id.clone(),
+        ))
+        .exec(db)
+        .await?;
+
+        Self::fetch_purchase_order(supplier_id, &id, session, db).await
+    }
+
+    pub async fn fetch_purchase_order(
+        supplier_id: &str,
+        id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<PurchaseOrder, Error> {
+        let order = PurchaseOrd::find_by_id(id.to_string())
+            .filter(purchase_order::Column::TenantId.eq(session.tenant_id))
+            .filter(purchase_order::Column::SupplierId.eq(supplier_id))
+            .one(db)
+            .await?;
+
+        match order {
+            Some(o) => Ok(o.into()),
+            None => Err(ErrorResponse::not_found("Purchase order", id)),
+        }
+    }
+
+    pub async fn fetch_purchase_orders(
+        supplier_id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<PurchaseOrder>, Error> {
+        let res = PurchaseOrd::find()
+            .filter(purchase_order::Column::TenantId.eq(session.tenant_id))
+            .filter(purchase_order::Column::SupplierId.eq(supplier_id))
+            .all(db)
+            .await?;
+
+        let mapped = res.iter().map(|o| o.clone().into()).collect();
+
+        Ok(mapped)
+    }
+
+    /// Marks a placed purchase order as received, adding each line to the
+    /// sellable stock of the order's store at the cost agreed on the line,
+    /// see [`PurchaseOrderItem::receive_into`]. The order is claimed and its stock
+    /// added within one database transaction, such that an order received twice
+    /// at once is counted once, and a failure part way through adds no stock.
+    pub async fn receive_purchase_order(
+        supplier_id: &str,
+        id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<PurchaseOrder, Error> {
+        let order = Self::fetch_purchase_order(supplier_id, id, session.clone(), db).await?;
+
+        if order.status != PurchaseOrderStatus::Placed {
+            return Err(ErrorResponse::conflict(
+                "Only placed purchase orders can be received.",
+            ));
+        }
+
+        let txn = db.begin().await?;
+
+        // Claimed before receiving, such that concurrent requests cannot receive it twice.
+        let claimed = PurchaseOrd::update_many()
+            .col_expr(
+                purchase_order::Column::Status,
+                Expr::value(json!(PurchaseOrderStatus::Received(Utc::now()))),
+            )
+            .col_expr(
+                purchase_order::Column::UpdatedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(purchase_order::Column::Id.eq(id))
+            .filter(purchase_order::Column::SupplierId.eq(supplier_id))
+            .filter(purchase_order::Column::TenantId.eq(session.tenant_id.clone()))
+            .filter(Expr::cust_with_values(
+                "JSON_UNQUOTE(`status`) = ?",
+                ["Placed"],
+            ))
+            .exec(&txn)
+            .await?;
+
+        if claimed.rows_affected != 1 {
+            return Err(ErrorResponse::conflict(
+                "Purchase order has already been received.",
+            ));
+        }
+
+        for item in &order.items {
+            Product::alter_within(&item.product_sku, session.clone(), &txn, |product| {
+                item.receive_into(product, supplier_id, order.store.clone())
+            })
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        for item in &order.items {
+            PRODUCT_CACHE.invalidate(&session.tenant_id, &item.product_sku);
+        }
+
+        Self::fetch_purchase_order(supplier_id, id, session, db).await
+    }
+
     /// Generate and insert a default customer.
-    pub async fn generate(session: Session, db: &DbConn) -> Result<Supplier, Error> {
-        let cust = example_supplier();
-        // Insert & Fetch Customer
-        let r = Supplier::insert(cust, session.clone(), db).await.unwrap();
-        match Supplier::fetch_by_id(&r.last_insert_id, session, db).await {
-            Ok(cust) => Ok(cust),
-            Err(e) => Err(e),
+    /// Seeds the example supplier under an id stable for the tenant, so repeated
+    /// calls yield the same supplier rather than accumulating duplicates.
+    pub async fn generate(session: Session, db: &DbConn) -> Result<Generated<Supplier>, Error> {
+        let suppl = example_supplier();
+        let id = stable_id(&session.tenant_id, &suppl.name.to_string());
+
+        let created = Self::fetch_by_id(&id, session.clone(), db).await.is_err();
+
+        if created {
+            Self::insert_with_id(suppl, id.clone(), session.clone(), db).await?;
         }
+
+        Ok(Generated {
+            created,
+            record: Self::fetch_by_id(&id, session, db).await?,
+        })
     }
 
     pub async fn update(
@@ -152,10 +480,22 @@ impl Supplier {
                 let mut new_contact = suppl.contact.clone();
                 new_contact.address = ad;
 
+                let version = suppl.version;
                 let mut supplier = suppl.into_active(id.to_string(), session.tenant_id.clone());
                 supplier.contact = Set(json!(new_contact));
+                supplier.created_at = NotSet;
 
-                supplier.update(db).await?;
+                let res = Self::update_at_version(supplier, version, id, &session.tenant_id)
+                    .exec(db)
+                    .await?;
+
+                if res.rows_affected == 0 {
+                    // Either the supplier does not exist, or it was modified since it was fetched.
+                    Self::fetch_by_id(id, session, db).await?;
+                    return Err(ErrorResponse::conflict(
+                        "Supplier was modified by another request, fetch it again before updating.",
+                    ));
+                }
 
                 Self::fetch_by_id(id, session, db).await
             }
@@ -166,6 +506,26 @@ impl Supplier {
     }
 }
 
+#[cfg(feature = "process")]
+impl Supplier {
+    /// The write of [`Supplier::update`], matching the supplier only whilst it is at
+    /// `version` and moving it to the next, such that a stale update writes nothing.
+    pub fn update_at_version(
+        mut model: supplier::ActiveModel,
+        version: i32,
+        id: &str,
+        tenant_id: &str,
+    ) -> UpdateMany<Suppl> {
+        model.version = Set(version + 1);
+
+        Suppl::update_many()
+            .set(model)
+            .filter(supplier::Column::Id.eq(id))
+            .filter(supplier::Column::TenantId.eq(tenant_id))
+            .filter(supplier::Column::Version.eq(version))
+    }
+}
+
 impl Display for Supplier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let order_history: String = self
@@ -198,3 +558,41 @@ impl Display for Supplier {
         )
     }
 }
+
+/// Reads and validates a supplier from an import row, see [`Supplier::import_csv`],
+/// reading a phone number without a calling code as one of `region`.
+#[cfg(feature = "methods")]
+fn supplier_from_record(record: &CsvRecord, region: Region) -> Result<SupplierInput, String> {
+    let name = record.require("name")?;
+
+    let contact = ContactInformationInput {
+        name: match record.get("contact") {
+            "" => name.to_string(),
+            contact => contact.to_string(),
+        },
+        mobile: record.require("phone")?.to_string(),
+        email: record.get("email").to_string(),
+        landline: record.get("landline").to_string(),
+        address: Address {
+            street: record.get("street").to_string(),
+            street2: record.get("street2").to_string(),
+            city: record.get("city").to_string(),
+            country: record.get("country").to_string(),
+            po_code: record.get("po_code").to_string(),
+            lat: 0.0,
+            lon: 0.0,
+        },
+    }
+    .into_major_in(region);
+
+    if !contact.mobile.valid {
+        return Err(format!("Invalid phone number {}", contact.mobile.number));
+    }
+
+    Ok(SupplierInput {
+        name: Name::from_string(name.to_string()),
+        contact,
+        transaction_history: vec![],
+        version: 0,
+    })
+}
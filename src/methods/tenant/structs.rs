@@ -7,11 +7,38 @@ use sea_orm::{DbConn, DbErr, EntityTrait, InsertResult};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::Id;
+use crate::{CommissionScheme, CurrencyFormat, Id, Region, RoundingMode, TaxRate, DEFAULT_REGION};
 
 #[cfg(feature = "types")]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema, Validate)]
-pub struct TenantSettings {}
+pub struct TenantSettings {
+    /// Tax rates by code, see [`TaxRate`].
+    #[serde(default)]
+    pub tax_rates: Vec<TaxRate>,
+    /// Whether product prices already include tax (as is typical in AU/EU),
+    /// otherwise tax is added on top at checkout.
+    #[serde(default)]
+    pub tax_inclusive: bool,
+    /// Applied to each line and the final total of a transaction.
+    #[serde(default)]
+    pub rounding: RoundingMode,
+    /// How amounts are written on receipts and reports.
+    #[serde(default)]
+    pub currency: CurrencyFormat,
+    /// Paid to salespeople on their sales, see [`CommissionScheme`].
+    #[serde(default)]
+    pub commission: CommissionScheme,
+    /// The country assumed of phone numbers and addresses given without one,
+    /// being [`DEFAULT_REGION`] if unset.
+    #[serde(default)]
+    pub region: Option<Region>,
+}
+
+impl TenantSettings {
+    pub fn region(&self) -> Region {
+        self.region.unwrap_or(*DEFAULT_REGION)
+    }
+}
 
 #[cfg(feature = "types")]
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, Validate)]
@@ -37,6 +64,11 @@ impl Tenant {
         Ok(tsn.unwrap().into())
     }
 
+    /// The region of the tenant `id`, see [`TenantSettings::region`].
+    pub async fn fetch_region(id: &str, db: &DbConn) -> Result<Region, Error> {
+        Ok(Tenant::fetch_by_id(id, db).await?.settings.region())
+    }
+
     pub async fn generate(db: &DbConn, tenant_id: &str) -> Result<Tenant, Error> {
         // Create Transaction
         let tsn = example_tenant(tenant_id);
@@ -66,7 +98,7 @@ pub fn example_tenant(tenant_id: &str) -> Tenant {
     Tenant {
         tenant_id: tenant_id.to_string(),
         registration_date: Utc::now(),
-        settings: TenantSettings {},
+        settings: TenantSettings::default(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
     }
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{validate_url, Validate, ValidationError};
+
+use crate::methods::{Id, Url};
+
+/// The types of file which may be attached, photographs and scanned documents.
+pub const ATTACHMENT_CONTENT_TYPES: [&str; 5] = [
+    "image/jpeg",
+    "image/png",
+    "image/webp",
+    "image/heic",
+    "application/pdf",
+];
+
+/// The largest file which may be attached, 10 MiB.
+pub const MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The most files which may be attached to a single transaction or order.
+pub const MAX_ATTACHMENTS: usize = 20;
+
+/// A file stored elsewhere, such as a signature or photograph taken on delivery,
+/// kept as evidence against a transaction or one of its orders.
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Attachment {
+    pub id: Id,
+    pub url: Url,
+    pub content_type: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    pub uploaded_by: Id,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Deserialize, JsonSchema, Validate)]
+pub struct AttachmentInput {
+    #[validate(custom = "validate_attachment_url")]
+    pub url: Url,
+    #[validate(custom = "validate_attachment_content_type")]
+    pub content_type: String,
+    #[validate(custom = "validate_attachment_size")]
+    pub size: u64,
+}
+
+fn validate_attachment_url(url: &str) -> Result<(), ValidationError> {
+    let scheme_valid = url.starts_with("https://") || url.starts_with("http://");
+
+    if !scheme_valid || !validate_url(url) {
+        let mut err = ValidationError::new("invalid_attachment_url");
+        err.add_param("value".into(), &url);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn validate_attachment_content_type(content_type: &str) -> Result<(), ValidationError> {
+    if !ATTACHMENT_CONTENT_TYPES.contains(&content_type.to_lowercase().as_str()) {
+        let mut err = ValidationError::new("unsupported_content_type");
+        err.add_param("value".into(), &content_type);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn validate_attachment_size(size: u64) -> Result<(), ValidationError> {
+    if size == 0 || size > MAX_ATTACHMENT_SIZE {
+        let mut err = ValidationError::new("invalid_attachment_size");
+        err.add_param("max".into(), &MAX_ATTACHMENT_SIZE);
+        return Err(err);
+    }
+
+    Ok(())
+}
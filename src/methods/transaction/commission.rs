@@ -0,0 +1,206 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{
+    CustomerType, Id, Order, OrderStatus, RoundingMode, TransactionCustomer, TransactionType,
+};
+
+/// How a salesperson's commission is computed from their net sales over a period.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub enum CommissionScheme {
+    /// A fraction of all sales, i.e. `0.05` for 5%.
+    Flat(f32),
+    /// Marginal rates, each applying only to the portion of sales above its
+    /// threshold and below that of the next tier.
+    Tiered(Vec<CommissionTier>),
+}
+
+impl Default for CommissionScheme {
+    fn default() -> Self {
+        CommissionScheme::Flat(0.0)
+    }
+}
+
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct CommissionTier {
+    pub threshold: f32,
+    /// Fractional, i.e. `0.05` for 5%.
+    pub rate: f32,
+}
+
+impl CommissionScheme {
+    /// The commission earned on `sales`, nothing being earned on a net loss.
+    pub fn commission(&self, sales: f32, rounding: RoundingMode) -> f32 {
+        let sales = sales.max(0.0);
+
+        let earned = match self {
+            CommissionScheme::Flat(rate) => sales * rate,
+            CommissionScheme::Tiered(tiers) => {
+                let mut tiers = tiers.clone();
+                tiers.sort_by(|a, b| a.threshold.total_cmp(&b.threshold));
+
+                tiers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, tier)| {
+                        let ceiling = tiers.get(i + 1).map_or(f32::MAX, |next| next.threshold);
+                        let portion = sales.min(ceiling) - tier.threshold;
+
+                        portion.max(0.0) * tier.rate
+                    })
+                    .sum()
+            }
+        };
+
+        rounding.round(earned)
+    }
+}
+
+/// The sales credited to a single salesperson over a period, see
+/// [`Transaction::commission_report`](crate::Transaction::commission_report).
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SalespersonCommission {
+    /// `None` for sales recorded without a salesperson.
+    pub salesperson: Option<Id>,
+    /// Money taken for goods sold, less returns made within the same sale.
+    pub sales: f32,
+    /// Money refunded by the salesperson's refund transactions.
+    pub refunds: f32,
+    pub net_sales: f32,
+    pub commission: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommissionReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub scheme: CommissionScheme,
+    /// Ordered by net sales, highest first.
+    pub salespeople: Vec<SalespersonCommission>,
+    /// Sales recorded without a salesperson, on which no commission is paid.
+    pub unattributed: SalespersonCommission,
+}
+
+/// Tallies the sales and refunds of each salesperson as transactions are added,
+/// such that a period can be summarised in a single pass over its transactions.
+#[cfg(feature = "types")]
+#[derive(Debug, Default)]
+pub struct CommissionTally {
+    salespeople: HashMap<Option<Id>, (f32, f32)>,
+    /// The tenant's suppliers, goods bought from whom are not refunds.
+    suppliers: HashSet<Id>,
+}
+
+impl CommissionTally {
+    pub fn new(suppliers: HashSet<Id>) -> Self {
+        CommissionTally {
+            salespeople: HashMap::new(),
+            suppliers,
+        }
+    }
+
+    /// Whether an `In` transaction with `customer` refunds a customer, rather than
+    /// being a purchase order from a supplier or a transfer from another store.
+    pub fn is_refund(&self, customer: &TransactionCustomer) -> bool {
+        !matches!(customer.customer_type, CustomerType::Store)
+            && !self.suppliers.contains(&customer.customer_id)
+    }
+
+    /// Adds the orders of a transaction recorded by `salesperson`, a sale if `Out`
+    /// and a refund if `In` to a customer, see [`CommissionTally::is_refund`]. Other
+    /// transactions, and cancelled orders, are ignored.
+    pub fn add(
+        &mut self,
+        salesperson: &str,
+        transaction_type: &TransactionType,
+        customer: &TransactionCustomer,
+        orders: &[Order],
+        rounding: RoundingMode,
+    ) {
+        let refund = match transaction_type {
+            TransactionType::Out => false,
+            TransactionType::In if self.is_refund(customer) => true,
+            _ => return,
+        };
+
+        let salesperson = Some(salesperson.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
+        let total: f32 = orders
+            .iter()
+            .filter(|o| !matches!(o.status.status, OrderStatus::Failed(_)))
+            .map(|o| o.breakdown(rounding).total)
+            .sum();
+
+        let (sales, refunds) = self.salespeople.entry(salesperson).or_default();
+
+        if refund {
+            *refunds += total.abs();
+        } else {
+            *sales += total;
+        }
+    }
+
+    pub fn report(
+        self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        scheme: CommissionScheme,
+        rounding: RoundingMode,
+    ) -> CommissionReport {
+        let mut unattributed = SalespersonCommission {
+            salesperson: None,
+            sales: 0.0,
+            refunds: 0.0,
+            net_sales: 0.0,
+            commission: 0.0,
+        };
+        let mut salespeople = vec![];
+
+        for (salesperson, (sales, refunds)) in self.salespeople {
+            let sales = rounding.round(sales);
+            let refunds = rounding.round(refunds);
+            let net_sales = rounding.round(sales - refunds);
+
+            let commission = match salesperson {
+                Some(_) => scheme.commission(net_sales, rounding),
+                None => 0.0,
+            };
+
+            let entry = SalespersonCommission {
+                salesperson,
+                sales,
+                refunds,
+                net_sales,
+                commission,
+            };
+
+            match entry.salesperson {
+                Some(_) => salespeople.push(entry),
+                None => unattributed = entry,
+            }
+        }
+
+        salespeople.sort_by(|a, b| {
+            b.net_sales
+                .total_cmp(&a.net_sales)
+                .then_with(|| a.salesperson.cmp(&b.salesperson))
+        });
+
+        CommissionReport {
+            from,
+            to,
+            scheme,
+            salespeople,
+            unattributed,
+        }
+    }
+}
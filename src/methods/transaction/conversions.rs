@@ -1,12 +1,13 @@
 #[cfg(feature = "process")]
 use crate::entities::sea_orm_active_enums::TransactionType as SeaORMTType;
+use crate::methods::{from_cents, to_cents};
 use crate::transactions::{ActiveModel, Model};
 use crate::{
-    NoteList, OrderList, Payment, Session, Transaction, TransactionCustomer, TransactionInit,
-    TransactionInput, TransactionType,
+    Attachment, NoteList, OrderList, Payment, Session, Transaction, TransactionCustomer,
+    TransactionInit, TransactionInput, TransactionType,
 };
 use chrono::{DateTime, Utc};
-use sea_orm::ActiveValue::Set;
+use sea_orm::ActiveValue::{NotSet, Set};
 use serde_json::json;
 
 impl From<SeaORMTType> for TransactionType {
@@ -51,6 +52,10 @@ impl TransactionInput {
             tenant_id: Set(session.tenant_id),
             created_at: Set(Utc::now().naive_utc()),
             updated_at: Set(Utc::now().naive_utc()),
+            version: Set(0),
+            deleted_at: Set(None),
+            attachments: NotSet,
+            change_given: NotSet,
         }
     }
 }
@@ -71,6 +76,10 @@ impl TransactionInit {
             tenant_id: Set(session.tenant_id),
             created_at: Set(Utc::now().naive_utc()),
             updated_at: Set(Utc::now().naive_utc()),
+            version: Set(0),
+            deleted_at: Set(None),
+            attachments: NotSet,
+            change_given: Set(to_cents(self.change)),
         }
     }
 }
@@ -91,6 +100,10 @@ impl Transaction {
             tenant_id: Set(tenant_id),
             created_at: Set(self.created_at.naive_utc()),
             updated_at: Set(self.updated_at.naive_utc()),
+            version: Set(self.version),
+            deleted_at: Set(self.deleted_at.map(|d| d.naive_utc())),
+            attachments: Set(Some(json!(self.attachments))),
+            change_given: Set(to_cents(self.change)),
         }
     }
 }
@@ -115,6 +128,15 @@ impl From<Model> for Transaction {
 
             created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
             updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
+            version: val.version,
+            deleted_at: val
+                .deleted_at
+                .map(|d| DateTime::from_naive_utc_and_offset(d, Utc)),
+            attachments: val
+                .attachments
+                .map(|a| serde_json::from_value::<Vec<Attachment>>(a).unwrap())
+                .unwrap_or_default(),
+            change: from_cents(val.change_given),
         }
     }
 }
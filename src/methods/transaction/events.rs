@@ -0,0 +1,64 @@
+use rocket::tokio::sync::broadcast::{channel, Receiver, Sender};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{Id, Order, OrderStatus};
+
+/// Events which are not received within this many subsequent events are dropped
+/// for lagging subscribers, who will skip ahead to the most recent.
+const ORDER_EVENT_CAPACITY: usize = 1024;
+
+/// Published whenever the status of an order changes.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct OrderStatusEvent {
+    pub order_id: Id,
+    pub status: OrderStatus,
+
+    pub origin_store_id: Id,
+    pub destination_store_id: Id,
+
+    #[serde(skip)]
+    pub tenant_id: Id,
+}
+
+impl OrderStatusEvent {
+    pub fn from_order(order: &Order, tenant_id: &str) -> Self {
+        OrderStatusEvent {
+            order_id: order.id.clone(),
+            status: order.status.status.clone(),
+            origin_store_id: order.origin.store_id.clone(),
+            destination_store_id: order.destination.store_id.clone(),
+            tenant_id: tenant_id.to_string(),
+        }
+    }
+
+    /// Whether the event is visible to a subscriber of the given store.
+    pub fn concerns(&self, store_id: &str, tenant_id: &str) -> bool {
+        self.tenant_id == tenant_id
+            && (self.origin_store_id == store_id || self.destination_store_id == store_id)
+    }
+}
+
+/// Managed state distributing [`OrderStatusEvent`]s to all open order streams.
+pub struct OrderEvents(Sender<OrderStatusEvent>);
+
+impl OrderEvents {
+    pub fn new() -> Self {
+        OrderEvents(channel(ORDER_EVENT_CAPACITY).0)
+    }
+
+    pub fn publish(&self, event: OrderStatusEvent) {
+        // An error only signals that there are no subscribers.
+        let _ = self.0.send(event);
+    }
+
+    pub fn subscribe(&self) -> Receiver<OrderStatusEvent> {
+        self.0.subscribe()
+    }
+}
+
+impl Default for OrderEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    Address, ContactInformation, CustomerType, DiscountValue, Email, History, Location,
+    Address, Carrier, ContactInformation, CustomerType, DiscountValue, Email, History, Location,
     MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, Payment, PaymentAction,
     PaymentProcessor, PaymentStatus, PickStatus, Price, ProductInstance, ProductPurchase,
     TransactionCustomer, TransactionInit, TransactionType, TransitInformation,
@@ -53,6 +53,9 @@ pub fn example_transaction(customer_id: &str) -> TransactionInit {
                 discount: DiscountValue::Absolute(0),
                 product_cost: 399.99,
                 quantity: 1.0,
+                unit: None,
+                fulfilled_quantity: 1.0,
+                remaining_quantity: 0.0,
                 transaction_type: TransactionType::Out,
                 tags: vec!["Tee".into(), "Cotton".into(), "Organic".into()],
                 instances: vec![ProductInstance {
@@ -74,6 +77,9 @@ pub fn example_transaction(customer_id: &str) -> TransactionInit {
                 discount: DiscountValue::Absolute(0),
                 product_cost: 139.99,
                 quantity: 1.0,
+                unit: None,
+                fulfilled_quantity: 1.0,
+                remaining_quantity: 0.0,
                 transaction_type: TransactionType::Out,
                 tags: vec!["Tee".into(), "Cotton".into(), "Organic".into()],
                 instances: vec![ProductInstance {
@@ -103,9 +109,13 @@ pub fn example_transaction(customer_id: &str) -> TransactionInit {
         },
         order_history: vec![],
         order_notes: vec![Note {
+            id: Uuid::new_v4().to_string(),
+            parent_id: None,
             message: "Order shipped from warehouse.".into(),
             timestamp: Utc::now(),
             author: Uuid::new_v4().to_string(),
+            edits: vec![],
+            removed: false,
         }],
         reference: "TOR-19592".into(),
         creation_date: Utc::now(),
@@ -135,9 +145,11 @@ pub fn example_transaction(customer_id: &str) -> TransactionInit {
                 item: OrderStatusAssignment {
                     status: OrderStatus::Transit(Box::new(TransitInformation {
                         shipping_company: torpedo7,
-                        query_url: "https://www.fedex.com/fedextrack/?trknbr=".into(),
+                        carrier: Some(Carrier::FedEx),
+                        query_url: "https://www.fedex.com/fedextrack/?trknbr=1523123".into(),
                         tracking_code: "1523123".into(),
                         assigned_products: vec!["132522-22".to_string()],
+                        estimated_delivery: Utc::now().checked_add_days(Days::new(2)),
                     })),
                     timestamp: Utc::now().checked_add_signed(Duration::hours(2)).unwrap(),
                     assigned_products: vec!["132522-22".to_string()],
@@ -158,6 +170,11 @@ pub fn example_transaction(customer_id: &str) -> TransactionInit {
             },
         ],
         discount: DiscountValue::Absolute(0),
+        receipts: vec![],
+        stock_reserved: false,
+        attachments: vec![],
+        address_confidence: None,
+        address_flagged: false,
     };
 
     TransactionInit {
@@ -195,11 +212,18 @@ pub fn example_transaction(customer_id: &str) -> TransactionInit {
         }],
         order_date: Utc::now(),
         order_notes: vec![Note {
+            id: Uuid::new_v4().to_string(),
+            parent_id: None,
             message: "Order packaged from warehouse.".into(),
             timestamp: Utc::now(),
             author: Uuid::new_v4().to_string(),
+            edits: vec![],
+            removed: false,
         }],
         // order_history: vec![History { item: ProductExchange { method_type: TransactionType::Out, product_code: "132522".into(), variant: vec!["22".into()], quantity: 1 }, reason: "Faulty Product".into(), timestamp: Utc::now() }],
         kiosk: "...".into(),
+        assign_nearest_origin: false,
+        stock_override: None,
+        change: 0.0,
     }
 }
@@ -1,21 +1,33 @@
-use super::{Transaction, TransactionInit, TransactionInput};
+use super::{
+    BulkOrderStatusUpdate, OrderCancellation, OrderEvents, OrderStatusEvent, OrderStatusOutcome,
+    SalespersonReassignment, Transaction, TransactionInit, TransactionInput, TransactionPage,
+};
 use crate::catchers::Validated;
 use crate::guards::Convert;
 use crate::methods::employee::Action;
-use crate::methods::{Error, ErrorResponse, QuantityAlterationIntent};
+use crate::methods::{
+    gift_card_payments, settle_tender, AddressVerifier, Attachment, AttachmentInput, AuditAction,
+    AuditEntity, AuditLog, CartBreakdown, Error, ErrorResponse, GiftCard, Note, NoteInput,
+    NoteThread, NoteUpdate, OrderList, QuantityAlterationIntent, Reservation, TaxBreakdown,
+    Webhook, WebhookEvent,
+};
 use crate::pool::InternalDb;
 use crate::Session;
 use crate::{
-    apply_discount, check_permissions, Order, OrderStatus, ProductStatusUpdate, TransactionType,
-    VoidableResult,
+    check_demo, check_permissions, DemoMode, Kiosk, ListQuery, Order, OrderStatus,
+    ProductStatusUpdate, TransactionType, VoidableResult,
 };
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
 use okapi::openapi3::OpenApi;
-use rocket::get;
-use rocket::post;
+use rocket::response::stream::{Event, EventStream};
 use rocket::serde::json::Json;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{get, post, Shutdown, State};
 use rocket_okapi::settings::OpenApiSettings;
 use rocket_okapi::{openapi, openapi_get_routes_spec};
-use sea_orm::{DbErr, DeleteResult};
+use sea_orm::DeleteResult;
 
 pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
     openapi_get_routes_spec![
@@ -23,14 +35,31 @@ pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, Ope
         get,
         get_by_name,
         get_all_saved,
+        get_by_date_range,
         get_by_product_sku,
         create,
         update,
+        reassign_salesperson,
         generate,
         delete,
+        purge,
+        restore,
         deliverables_search,
+        order_stream,
+        tax,
+        breakdown,
         update_product_status,
-        update_order_status
+        update_order_status,
+        bulk_update_order_status,
+        cancel_order,
+        get_order_notes,
+        add_order_note,
+        add_order_note_by_ref,
+        edit_note,
+        delete_note,
+        get_attachments,
+        add_attachment,
+        get_receipt
     ]
 }
 
@@ -41,11 +70,69 @@ pub async fn get(db: InternalDb, session: Session, id: &str) -> Convert<Transact
     Transaction::fetch_by_id(id, session, &db.0).await.into()
 }
 
+/// The transaction as a plain text receipt, formatted for a thermal printer.
 #[openapi(tag = "Transaction")]
-#[get("/saved")]
-pub async fn get_all_saved(db: InternalDb, session: Session) -> Convert<Vec<Transaction>> {
+#[get("/<id>/receipt", rank = 2)]
+pub async fn get_receipt(db: InternalDb, session: Session, id: &str) -> Result<String, Error> {
     check_permissions!(session.clone(), Action::FetchTransaction);
-    Transaction::fetch_all_saved(session, &db.0).await.into()
+    let receipt = Transaction::render_receipt(id, session, &db.0).await?;
+    Ok(receipt.to_string())
+}
+
+/// Lists transactions ordered within a window, given as RFC 3339 times, optionally
+/// only those of a store. Each page gives the `cursor` from which the next continues.
+#[openapi(tag = "Transaction")]
+#[get("/?<from>&<to>&<store>&<cursor>")]
+pub async fn get_by_date_range(
+    db: InternalDb,
+    session: Session,
+    from: &str,
+    to: &str,
+    store: Option<&str>,
+    cursor: Option<&str>,
+) -> Convert<TransactionPage> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+
+    if let Some(store) = store {
+        if let Err(e) = session.ensure_store(store) {
+            return Err(e).into();
+        }
+    }
+
+    let (from, to) = match parse_range(from, to) {
+        Ok(range) => range,
+        Err(e) => return Err(e).into(),
+    };
+
+    Transaction::fetch_by_date_range(from, to, store, cursor, session, &db.0)
+        .await
+        .into()
+}
+
+/// Reads a range of time given as RFC 3339 times from a query string.
+pub(crate) fn parse_range(from: &str, to: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), Error> {
+    match (
+        DateTime::parse_from_rfc3339(from),
+        DateTime::parse_from_rfc3339(to),
+    ) {
+        (Ok(from), Ok(to)) => Ok((from.with_timezone(&Utc), to.with_timezone(&Utc))),
+        _ => Err(ErrorResponse::custom_input_error(
+            "Range must be given as RFC 3339 times, i.e. 2023-08-01T00:00:00Z.",
+        )),
+    }
+}
+
+#[openapi(tag = "Transaction")]
+#[get("/saved?<query..>")]
+pub async fn get_all_saved(
+    db: InternalDb,
+    session: Session,
+    query: ListQuery,
+) -> Convert<Vec<Transaction>> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+    Transaction::fetch_all_saved(&query, session, &db.0)
+        .await
+        .into()
 }
 
 #[openapi(tag = "Transaction")]
@@ -59,6 +146,26 @@ pub async fn get_by_name(
     Transaction::fetch_by_ref(name, session, &db.0).await.into()
 }
 
+#[openapi(tag = "Transaction")]
+#[post("/tax", data = "<cart>")]
+pub async fn tax(db: InternalDb, session: Session, cart: Json<OrderList>) -> Convert<TaxBreakdown> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+    Transaction::compute_tax(&cart, session, &db.0).await.into()
+}
+
+/// Prices the cart as it would be at checkout, with the discounts and totals of
+/// each order, such that clients need not compute them themselves.
+#[openapi(tag = "Transaction")]
+#[post("/breakdown", data = "<cart>")]
+pub async fn breakdown(
+    db: InternalDb,
+    session: Session,
+    cart: Json<OrderList>,
+) -> Convert<CartBreakdown> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+    Transaction::price_cart(&cart, session, &db.0).await.into()
+}
+
 #[openapi(tag = "Transaction")]
 #[get("/product/<sku>")]
 pub async fn get_by_product_sku(
@@ -78,6 +185,11 @@ pub async fn deliverables_search(
     store_id: &str,
 ) -> Convert<Vec<Order>> {
     check_permissions!(session.clone(), Action::FetchTransaction);
+
+    if let Err(e) = session.ensure_store(store_id) {
+        return Err(e).into();
+    }
+
     Transaction::fetch_deliverable_jobs(store_id, session, &db.0)
         .await
         .into()
@@ -91,6 +203,11 @@ pub async fn receivables_search(
     store_id: &str,
 ) -> Convert<Vec<Order>> {
     check_permissions!(session.clone(), Action::FetchTransaction);
+
+    if let Err(e) = session.ensure_store(store_id) {
+        return Err(e).into();
+    }
+
     Transaction::fetch_receivable_jobs(store_id, session, &db.0)
         .await
         .into()
@@ -105,9 +222,75 @@ async fn update(
     id: &str,
 ) -> Convert<Transaction> {
     check_permissions!(session.clone(), Action::ModifyTransaction);
-    Transaction::update(input_data.data(), session, id, &db.0)
+
+    let input = input_data.data();
+
+    if let Err(e) = Kiosk::ensure_usable(&input.kiosk, session.clone(), &db.0).await {
+        return Err(e).into();
+    }
+
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
         .await
-        .into()
+        .ok();
+    let updated = Transaction::update(input, session.clone(), id, &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
+}
+
+/// Credits the sale to another employee, recording the correction in the audit log.
+#[openapi(tag = "Transaction")]
+#[post("/<id>/salesperson", data = "<input_data>", rank = 2)]
+async fn reassign_salesperson(
+    db: InternalDb,
+    session: Session,
+    input_data: Validated<Json<SalespersonReassignment>>,
+    id: &str,
+) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ReassignSalesperson);
+
+    let input = input_data.data();
+
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
+        .await
+        .ok();
+    let updated = Transaction::reassign_salesperson(
+        id,
+        &input.employee_id,
+        &input.reason,
+        session.clone(),
+        &db.0,
+    )
+    .await;
+
+    if let (Ok(after), Some(before)) = (&updated, &before) {
+        if after.version != before.version {
+            AuditLog::record(
+                AuditEntity::Transaction,
+                id,
+                AuditAction::Update,
+                Some(before),
+                Some(after),
+                &session,
+                &db.0,
+            )
+            .await;
+        }
+    }
+
+    updated.into()
 }
 
 #[openapi(tag = "Transaction")]
@@ -117,6 +300,7 @@ async fn update_order_status(
     session: Session,
     refer: &str,
     status: Json<OrderStatus>,
+    events: &State<OrderEvents>,
 ) -> Result<Json<Transaction>, Error> {
     check_permissions!(session.clone(), Action::ModifyTransaction);
 
@@ -125,12 +309,417 @@ async fn update_order_status(
     match fetched_transaction.get(0) {
         Some(transaction) => {
             let data = status.0;
-            Transaction::update_order_status(transaction.id.as_str(), refer, data, session, &db.0)
-                .await
-                .map(|v| Json(v))
+            let updated = Transaction::update_order_status(
+                transaction.id.as_str(),
+                refer,
+                data,
+                session.clone(),
+                &db.0,
+            )
+            .await?;
+
+            let changed = updated
+                .products
+                .iter()
+                .filter(|o| o.reference == refer)
+                .map(|o| OrderStatusEvent::from_order(o, &session.tenant_id))
+                .collect::<Vec<_>>();
+
+            for event in changed {
+                Webhook::dispatch(WebhookEvent::OrderStatusChanged, &event, &session, &db.0).await;
+                events.publish(event);
+            }
+
+            AuditLog::record(
+                AuditEntity::Transaction,
+                &transaction.id,
+                AuditAction::Update,
+                Some(transaction),
+                Some(&updated),
+                &session,
+                &db.0,
+            )
+            .await;
+
+            Ok(Json(updated))
         }
-        None => Err(DbErr::RecordNotFound("Could not retrieve transaction".to_string()).into()),
+        None => Err(ErrorResponse::not_found("Transaction", refer)),
+    }
+}
+
+/// Sets one status on many orders at once, such as a batch handed to a courier.
+/// Each order is reported as updated or with the reason it was not, a failure
+/// leaving the other orders to be updated regardless.
+#[openapi(tag = "Transaction")]
+#[post("/status/order/bulk", data = "<input_data>")]
+async fn bulk_update_order_status(
+    db: InternalDb,
+    session: Session,
+    input_data: Validated<Json<BulkOrderStatusUpdate>>,
+    events: &State<OrderEvents>,
+) -> Result<Json<Vec<OrderStatusOutcome>>, Error> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let input = input_data.data();
+    let (outcomes, changed) = Transaction::bulk_update_order_status(
+        &input.references,
+        input.status,
+        session.clone(),
+        &db.0,
+    )
+    .await?;
+
+    for (before, after) in changed {
+        let updated = after
+            .products
+            .iter()
+            .filter(|o| {
+                outcomes
+                    .iter()
+                    .any(|outcome| outcome.updated && outcome.reference == o.reference)
+            })
+            .map(|o| OrderStatusEvent::from_order(o, &session.tenant_id))
+            .collect::<Vec<_>>();
+
+        for event in updated {
+            Webhook::dispatch(WebhookEvent::OrderStatusChanged, &event, &session, &db.0).await;
+            events.publish(event);
+        }
+
+        AuditLog::record(
+            AuditEntity::Transaction,
+            &after.id,
+            AuditAction::Update,
+            Some(&before),
+            Some(&after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    Ok(Json(outcomes))
+}
+
+#[openapi(tag = "Transaction")]
+#[post("/status/order/<refer>/cancel", data = "<input_data>")]
+async fn cancel_order(
+    db: InternalDb,
+    session: Session,
+    refer: &str,
+    input_data: Validated<Json<OrderCancellation>>,
+    events: &State<OrderEvents>,
+) -> Result<Json<Transaction>, Error> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let fetched_transaction = Transaction::fetch_by_ref(refer, session.clone(), &db.0).await?;
+
+    let transaction = fetched_transaction
+        .first()
+        .ok_or_else(|| ErrorResponse::not_found("Transaction", refer))?;
+
+    let order_id = transaction
+        .products
+        .iter()
+        .find(|o| o.reference == refer)
+        .map(|o| o.id.clone())
+        .ok_or_else(|| ErrorResponse::not_found("Order", refer))?;
+
+    let updated = Transaction::cancel_order(
+        &transaction.id,
+        &order_id,
+        &input_data.data().reason,
+        session.clone(),
+        &db.0,
+    )
+    .await?;
+
+    if updated.version == transaction.version {
+        // The order had already been cancelled, nothing has changed.
+        return Ok(Json(updated));
+    }
+
+    let changed = updated
+        .products
+        .iter()
+        .filter(|o| o.id == order_id)
+        .map(|o| OrderStatusEvent::from_order(o, &session.tenant_id))
+        .collect::<Vec<_>>();
+
+    for event in changed {
+        Webhook::dispatch(WebhookEvent::OrderStatusChanged, &event, &session, &db.0).await;
+        events.publish(event);
+    }
+
+    AuditLog::record(
+        AuditEntity::Transaction,
+        &transaction.id,
+        AuditAction::Update,
+        Some(transaction),
+        Some(&updated),
+        &session,
+        &db.0,
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// The notes of an order, arranged into threads of replies.
+#[openapi(tag = "Transaction")]
+#[get("/<id>/order/<order_id>/notes")]
+async fn get_order_notes(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    order_id: &str,
+) -> Result<Json<Vec<NoteThread>>, Error> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+
+    let transaction = Transaction::fetch_by_id(id, session, &db.0).await?;
+
+    transaction
+        .products
+        .iter()
+        .find(|o| o.id == order_id)
+        .map(|o| Json(o.threaded_notes()))
+        .ok_or_else(|| ErrorResponse::not_found("Order", order_id))
+}
+
+#[openapi(tag = "Transaction")]
+#[post("/<id>/order/<order_id>/notes", data = "<input_data>")]
+async fn add_order_note(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    order_id: &str,
+    input_data: Validated<Json<NoteInput>>,
+) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
+        .await
+        .ok();
+    let updated =
+        Transaction::add_order_note(id, order_id, input_data.data(), session.clone(), &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
+}
+
+/// Adds a note to the order `refer`, without resubmitting the whole order.
+#[openapi(tag = "Transaction")]
+#[post("/order/<refer>/note", data = "<input_data>")]
+async fn add_order_note_by_ref(
+    db: InternalDb,
+    session: Session,
+    refer: &str,
+    input_data: Validated<Json<NoteInput>>,
+) -> Result<Json<Transaction>, Error> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let before = Transaction::fetch_by_ref(refer, session.clone(), &db.0)
+        .await?
+        .into_iter()
+        .find(|t| t.products.iter().any(|o| o.reference == refer))
+        .ok_or_else(|| ErrorResponse::not_found("Order", refer))?;
+
+    let updated =
+        Transaction::add_order_note(&before.id, refer, input_data.data(), session.clone(), &db.0)
+            .await?;
+
+    AuditLog::record(
+        AuditEntity::Transaction,
+        &before.id,
+        AuditAction::Update,
+        Some(&before),
+        Some(&updated),
+        &session,
+        &db.0,
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Rewords a note of the transaction, or of the order `order_id` when given.
+#[openapi(tag = "Transaction")]
+#[post("/<id>/notes/<note_id>?<order_id>", data = "<input_data>")]
+async fn edit_note(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    note_id: &str,
+    order_id: Option<&str>,
+    input_data: Validated<Json<NoteUpdate>>,
+) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
+        .await
+        .ok();
+    let updated = Transaction::edit_note(
+        id,
+        order_id,
+        note_id,
+        input_data.data().message,
+        session.clone(),
+        &db.0,
+    )
+    .await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
+}
+
+/// Removes a note of the transaction, or of the order `order_id` when given.
+/// The note remains in the transaction's history, reading `[removed]`.
+#[openapi(tag = "Transaction")]
+#[post("/<id>/notes/<note_id>/delete?<order_id>")]
+async fn delete_note(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    note_id: &str,
+    order_id: Option<&str>,
+) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
+        .await
+        .ok();
+    let updated = Transaction::delete_note(id, order_id, note_id, session.clone(), &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
     }
+
+    updated.into()
+}
+
+/// The files attached to the transaction, or to the order `order_id` when given.
+#[openapi(tag = "Transaction")]
+#[get("/<id>/attachments?<order_id>")]
+async fn get_attachments(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    order_id: Option<&str>,
+) -> Result<Json<Vec<Attachment>>, Error> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+
+    let transaction = Transaction::fetch_by_id(id, session, &db.0).await?;
+
+    match order_id {
+        Some(order_id) => transaction
+            .products
+            .into_iter()
+            .find(|o| o.id == order_id)
+            .map(|o| Json(o.attachments))
+            .ok_or_else(|| ErrorResponse::not_found("Order", order_id)),
+        None => Ok(Json(transaction.attachments)),
+    }
+}
+
+/// Attaches a file, such as a signature or photograph, to the transaction
+/// or to the order `order_id` when given.
+#[openapi(tag = "Transaction")]
+#[post("/<id>/attachments?<order_id>", data = "<input_data>")]
+async fn add_attachment(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+    order_id: Option<&str>,
+    input_data: Validated<Json<AttachmentInput>>,
+) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
+        .await
+        .ok();
+    let updated =
+        Transaction::add_attachment(id, order_id, input_data.data(), session.clone(), &db.0).await;
+
+    if let Ok(after) = &updated {
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Update,
+            before.as_ref(),
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    updated.into()
+}
+
+/// Streams the status changes of orders to or from the given store as server-sent events.
+#[openapi(skip)]
+#[get("/orders/stream/<store_id>")]
+pub async fn order_stream(
+    session: Session,
+    events: &State<OrderEvents>,
+    store_id: String,
+    mut shutdown: Shutdown,
+) -> Result<EventStream![], Error> {
+    check_permissions!(session.clone(), Action::FetchTransaction);
+    session.ensure_store(&store_id)?;
+
+    let mut receiver = events.subscribe();
+    let tenant_id = session.tenant_id;
+
+    Ok(EventStream! {
+        loop {
+            let event = select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = &mut shutdown => break,
+            };
+
+            if event.concerns(&store_id, &tenant_id) {
+                yield Event::json(&event);
+            }
+        }
+    })
 }
 
 #[openapi(tag = "Transaction")]
@@ -148,20 +737,44 @@ async fn update_product_status(
 
     match fetched_transaction.first() {
         Some(transaction) => {
-            let converted: Convert<Transaction> =
-                Transaction::update_product_status(transaction.id.as_str(), data, session, &db.0)
-                    .await
-                    .into();
-            converted.0
+            let updated = Transaction::update_product_status(
+                transaction.id.as_str(),
+                data,
+                session.clone(),
+                &db.0,
+            )
+            .await?;
+
+            AuditLog::record(
+                AuditEntity::Transaction,
+                &transaction.id,
+                AuditAction::Update,
+                Some(transaction),
+                Some(&updated),
+                &session,
+                &db.0,
+            )
+            .await;
+
+            Ok(Json(updated))
         }
-        None => Err(DbErr::RecordNotFound("Could not retrieve transaction".to_string()).into()),
+        None => Err(ErrorResponse::not_found(
+            "Transaction",
+            &data.transaction_id,
+        )),
     }
 }
 
 #[openapi(tag = "Transaction")]
 #[post("/generate/<customer_id>")]
-async fn generate(db: InternalDb, session: Session, customer_id: &str) -> Convert<Transaction> {
+async fn generate(
+    db: InternalDb,
+    session: Session,
+    customer_id: &str,
+    demo: &State<DemoMode>,
+) -> Convert<Transaction> {
     check_permissions!(session.clone(), Action::GenerateTemplateContent);
+    check_demo!(demo);
     Transaction::generate(&db.0, customer_id, session)
         .await
         .into()
@@ -173,25 +786,78 @@ pub async fn create(
     db: InternalDb,
     session: Session,
     input_data: Validated<Json<TransactionInit>>,
+    verifier: &State<AddressVerifier>,
 ) -> Result<Json<Transaction>, Error> {
     check_permissions!(session.clone(), Action::CreateTransaction);
 
     let mut quantity_alteration_intents: Vec<QuantityAlterationIntent> = vec![];
-    let new_transaction = input_data.data();
-
-    // Make and modify the required changes to stock levels
-    new_transaction.products.iter().for_each(|order| {
-        order.products.iter().for_each(|product| {
-            quantity_alteration_intents.push(QuantityAlterationIntent {
-                variant_code: product.clone().product_code,
-                product_sku: product.clone().product_sku,
-                transaction_store_code: order.clone().origin.store_code,
-                transaction_store_id: order.clone().origin.store_id,
-                transaction_type: new_transaction.clone().transaction_type,
-                quantity_to_transact: product.clone().quantity,
-            });
-        });
-    });
+    let mut new_transaction = input_data.data();
+
+    Kiosk::ensure_usable(&new_transaction.kiosk, session.clone(), &db.0).await?;
+
+    Transaction::assign_origins(&mut new_transaction, session.clone(), &db.0).await?;
+    verifier.verify(&mut new_transaction.products).await;
+
+    // Only a manager may sell stock which is not there, and is noted as having done so.
+    if let Some(stock_override) = &new_transaction.stock_override {
+        check_permissions!(session.clone(), Action::OverrideStock);
+
+        warn!(
+            "Stock override by employee={} tenant={}: {}",
+            session.employee.id, session.tenant_id, stock_override.reason
+        );
+
+        new_transaction.order_notes.push(Note::new(
+            format!("Stock override: {}", stock_override.reason),
+            session.employee.id.clone(),
+        ));
+    }
+
+    // Reject anything which would remove stock that is not there, rather than
+    // accepting the transaction and failing whilst processing its intents.
+    if new_transaction.stock_override.is_none()
+        && matches!(
+            new_transaction.transaction_type,
+            TransactionType::Out | TransactionType::PendingOut
+        )
+    {
+        let unavailable =
+            Transaction::check_availability(&new_transaction.products, session.clone(), &db.0)
+                .await;
+
+        if !unavailable.is_empty() {
+            return Err(ErrorResponse::custom_input_error(&format!(
+                "Insufficient stock for {}.",
+                unavailable
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            )));
+        }
+    }
+
+    // Price each product for the customer before totalling.
+    Transaction::apply_price_tiers(&mut new_transaction, session.clone(), &db.0).await;
+
+    // Make and modify the required changes to stock levels. Stock for orders which are
+    // yet to be fulfilled is reserved, rather than removed, until they are.
+    let transaction_type = new_transaction.transaction_type.clone();
+
+    for order in new_transaction.products.iter_mut() {
+        let reserve = matches!(transaction_type, TransactionType::Out)
+            && matches!(
+                order.status.status,
+                OrderStatus::Queued(_) | OrderStatus::Processing(_)
+            );
+
+        order.stock_reserved = reserve;
+        quantity_alteration_intents.extend(QuantityAlterationIntent::for_order(
+            order,
+            transaction_type.clone(),
+            reserve.then_some(Reservation::Reserve),
+        ));
+    }
 
     let total_paid = new_transaction
         .payment
@@ -199,27 +865,15 @@ pub async fn create(
         .map(|payment| payment.amount.quantity)
         .sum::<f32>();
 
-    let total_cost = new_transaction
-        .products
-        .iter()
-        .map(|order| {
-            apply_discount(
-                order.discount.clone(),
-                order
-                    .products
-                    .iter()
-                    .map(|product| {
-                        apply_discount(
-                            product.discount.clone(),
-                            product.product_cost * product.quantity,
-                        )
-                    })
-                    .sum::<f32>(),
-            )
-        })
-        .sum::<f32>();
+    // The customer pays the gross, whether tax is included in or added to costs.
+    let totals =
+        Transaction::compute_tax(&new_transaction.products, session.clone(), &db.0).await?;
+    let total_cost = totals.gross;
 
-    println!("Paid: {}. Cost: {}", total_paid, total_cost);
+    debug!(
+        "Transaction totals, paid={} net={} tax={} employee={} tenant={}",
+        total_paid, totals.net, totals.total_tax, session.employee.id, session.tenant_id
+    );
 
     let insertion = match new_transaction.transaction_type {
         TransactionType::Saved => {
@@ -230,25 +884,53 @@ pub async fn create(
             // As we are removing inventory via a purchase,
             // we need to process the intents.
 
-            if (total_paid - total_cost).abs() > 0.1 {
-                return Err(ErrorResponse::create_error(
-                    "Payment amount does not match product costs.",
-                ));
-            }
+            new_transaction.change = settle_tender(&new_transaction.payment, total_cost)?;
+
+            // Gift cards are debited as the sale is recorded, but are checked up front
+            // so that a sale paid with an unknown or spent card is refused outright.
+            let gift_cards = gift_card_payments(&new_transaction.payment);
 
-            let data = Transaction::insert(new_transaction, session.clone(), &db.0).await?;
-            Transaction::process_intents(session.clone(), &db.0, quantity_alteration_intents).await;
+            if !gift_cards.is_empty() {
+                if !matches!(
+                    new_transaction.transaction_type,
+                    TransactionType::Out | TransactionType::PendingOut
+                ) {
+                    return Err(ErrorResponse::create_error(
+                        "Only sales may be paid for with a gift card.",
+                    ));
+                }
+
+                GiftCard::ensure_payable(&new_transaction.payment, session.clone(), &db.0).await?;
+            }
 
-            data
+            // Stock is altered as the sale is recorded, so that a sale whose stock was
+            // taken by another since its availability was checked is still rejected.
+            Transaction::insert_with_stock(
+                new_transaction,
+                &quantity_alteration_intents,
+                session.clone(),
+                &db.0,
+            )
+            .await?
         }
     };
 
-    let converted: Convert<Transaction> =
-        Transaction::fetch_by_id(&insertion.last_insert_id, session, &db.0)
-            .await
-            .into();
+    let created =
+        Transaction::fetch_by_id(&insertion.last_insert_id, session.clone(), &db.0).await?;
+
+    AuditLog::record(
+        AuditEntity::Transaction,
+        &created.id,
+        AuditAction::Create,
+        None,
+        Some(&created),
+        &session,
+        &db.0,
+    )
+    .await;
+    Webhook::dispatch(WebhookEvent::TransactionCreated, &created, &session, &db.0).await;
 
-    converted.0
+    Ok(Json(created))
 }
 
 #[openapi(tag = "Transaction")]
@@ -257,6 +939,81 @@ pub async fn create(
 async fn delete(db: InternalDb, session: Session, id: &str) -> Convert<()> {
     check_permissions!(session.clone(), Action::DeleteTransaction);
 
-    let voided: VoidableResult<DeleteResult> = Transaction::delete(id, session, &db.0).await.into();
-    voided.void().into()
+    let before = Transaction::fetch_by_id(id, session.clone(), &db.0)
+        .await
+        .ok();
+    let deleted = Transaction::delete(id, session.clone(), &db.0).await;
+
+    if deleted.is_ok() {
+        if let Some(transaction) = &before {
+            Webhook::dispatch(
+                WebhookEvent::TransactionVoided,
+                transaction,
+                &session,
+                &db.0,
+            )
+            .await;
+        }
+
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Delete,
+            before.as_ref(),
+            None,
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    deleted.into()
+}
+
+#[openapi(tag = "Transaction")]
+#[post("/restore/<id>")]
+async fn restore(db: InternalDb, session: Session, id: &str) -> Convert<Transaction> {
+    check_permissions!(session.clone(), Action::ModifyTransaction);
+
+    let restored = Transaction::restore(id, session.clone(), &db.0).await;
+
+    if let Ok(after) = &restored {
+        AuditLog::record(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Restore,
+            None,
+            Some(after),
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    restored.into()
+}
+
+#[openapi(tag = "Transaction")]
+#[post("/purge/<id>")]
+async fn purge(db: InternalDb, session: Session, id: &str) -> Convert<()> {
+    check_permissions!(session.clone(), Action::PurgeTransaction);
+
+    let voided: VoidableResult<DeleteResult> =
+        Transaction::purge(id, session.clone(), &db.0).await.into();
+    let purged = voided.void();
+
+    if purged.is_ok() {
+        AuditLog::record::<Transaction>(
+            AuditEntity::Transaction,
+            id,
+            AuditAction::Purge,
+            None,
+            None,
+            &session,
+            &db.0,
+        )
+        .await;
+    }
+
+    purged.into()
 }
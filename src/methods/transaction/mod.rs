@@ -1,9 +1,23 @@
+mod attachment;
+mod commission;
 mod conversions;
+#[cfg(feature = "process")]
+mod events;
 mod example;
+mod pick_list;
+mod receipt;
 #[cfg(feature = "process")]
 pub(crate) mod handlers;
 mod structs;
+mod summary;
 
+pub use attachment::*;
+pub use commission::*;
+#[cfg(feature = "process")]
+pub use events::*;
 #[cfg(feature = "process")]
 pub use handlers::*;
+pub use pick_list::*;
+pub use receipt::*;
 pub use structs::*;
+pub use summary::*;
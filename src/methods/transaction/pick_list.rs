@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{Id, Order, OrderType, UnitOfMeasure};
+
+/// The stock to pull from a store's shelves for its pending orders, see
+/// [`Transaction::generate_pick_list`](crate::Transaction::generate_pick_list).
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PickList {
+    pub store_id: Id,
+    pub generated_at: DateTime<Utc>,
+    /// Ordered by bin, such that the list can be worked through in a single walk
+    /// of the store. Lines without a bin come last.
+    pub lines: Vec<PickListLine>,
+}
+
+/// A single variant to be picked, consolidated across every order awaiting it.
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PickListLine {
+    pub sku: String,
+    pub barcode: String,
+    pub product_name: String,
+    pub variant_name: String,
+    /// The quantity yet to be picked, in `unit`.
+    pub quantity: f32,
+    pub unit: Option<UnitOfMeasure>,
+    /// Where the variant is kept within the store, `None` if not recorded.
+    pub bin_location: Option<String>,
+    /// The `reference` of each order awaiting the variant.
+    pub orders: Vec<String>,
+}
+
+impl PickList {
+    /// Consolidates what remains to be picked of the store's deliverable orders,
+    /// being shipments and pickups queued at the store. Lines already picked in full
+    /// are left out. `bins` holds the bin of each variant at the store, by barcode.
+    pub fn from_orders(store_id: &str, orders: &[Order], bins: &HashMap<String, String>) -> Self {
+        let mut lines: Vec<PickListLine> = vec![];
+
+        for order in orders.iter().filter(|o| {
+            o.origin.store_id == store_id
+                && matches!(o.order_type, OrderType::Shipment | OrderType::Pickup)
+                && o.status.status.is_queued()
+        }) {
+            for product in order.products.iter().filter(|p| !p.is_fully_fulfilled()) {
+                // Lines are only combined when in the same unit, as 500g and 2kg
+                // of a variant are picked differently.
+                let existing = lines.iter_mut().find(|line| {
+                    line.sku == product.product_sku
+                        && line.barcode == product.id
+                        && line.unit == product.unit
+                });
+
+                match existing {
+                    Some(line) => {
+                        line.quantity += product.remaining_quantity;
+
+                        if !line.orders.contains(&order.reference) {
+                            line.orders.push(order.reference.clone());
+                        }
+                    }
+                    None => lines.push(PickListLine {
+                        sku: product.product_sku.clone(),
+                        barcode: product.id.clone(),
+                        product_name: product.product_name.clone(),
+                        variant_name: product.product_variant_name.clone(),
+                        quantity: product.remaining_quantity,
+                        unit: product.unit,
+                        bin_location: bins.get(&product.id).cloned(),
+                        orders: vec![order.reference.clone()],
+                    }),
+                }
+            }
+        }
+
+        lines.sort_by(|a, b| {
+            match (&a.bin_location, &b.bin_location) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| a.sku.cmp(&b.sku))
+            .then_with(|| a.barcode.cmp(&b.barcode))
+        });
+
+        PickList {
+            store_id: store_id.to_string(),
+            generated_at: Utc::now(),
+            lines,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt::{self, Display};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{
+    apply_discount, ContactInformation, CurrencyFormat, Id, RoundingMode, Store, TaxBreakdown,
+    Transaction, TransactionType,
+};
+
+/// The width of a receipt in characters, that of a common 80mm thermal printer.
+pub const RECEIPT_WIDTH: usize = 42;
+
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReceiptLine {
+    pub name: String,
+    /// Negative for goods being returned.
+    pub quantity: f32,
+    pub unit_price: f32,
+    /// The amount taken off by the line's own discount.
+    pub discount: f32,
+    pub total: f32,
+}
+
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReceiptPayment {
+    pub method: String,
+    /// Negative for money refunded to the customer.
+    pub amount: f32,
+}
+
+/// A transaction laid out for printing, see [`Transaction::render_receipt`].
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Receipt {
+    pub transaction_id: Id,
+    pub date: DateTime<Utc>,
+    /// The date in the local time of the store the sale was made at.
+    pub local_date: NaiveDateTime,
+    /// The store the goods were sold from, taken from the first order's origin.
+    pub store: Option<ContactInformation>,
+    /// Whether the transaction returns goods to the store, rather than selling them.
+    pub refund: bool,
+    pub lines: Vec<ReceiptLine>,
+    /// The sum of the lines, after their own discounts.
+    pub subtotal: f32,
+    /// The amount taken off by discounts on whole orders.
+    pub order_discount: f32,
+    pub tax: f32,
+    pub total: f32,
+    pub payments: Vec<ReceiptPayment>,
+    /// Paid in excess of the total, returned to the customer.
+    pub change: f32,
+    /// How the amounts are written when printed.
+    pub currency: CurrencyFormat,
+}
+
+impl Receipt {
+    /// Lays out `transaction`, whose tax is given by `tax`, rounding amounts
+    /// to the cent with `rounding`.
+    pub fn new(transaction: &Transaction, tax: &TaxBreakdown, rounding: RoundingMode) -> Self {
+        let lines: Vec<ReceiptLine> = transaction
+            .products
+            .iter()
+            .flat_map(|order| order.products.iter())
+            .map(|p| {
+                let gross = p.product_cost * p.quantity;
+                let total = rounding.round(apply_discount(p.discount.clone(), gross));
+
+                ReceiptLine {
+                    name: format!("{} {}", p.product_name, p.product_variant_name)
+                        .trim()
+                        .to_string(),
+                    quantity: p.quantity,
+                    unit_price: p.product_cost,
+                    discount: rounding.round(gross - total),
+                    total,
+                }
+            })
+            .collect();
+
+        let subtotal = rounding.round(lines.iter().map(|l| l.total).sum());
+        let priced = tax.lines.iter().map(|l| l.taxable).sum::<f32>();
+
+        let payments: Vec<ReceiptPayment> = transaction
+            .payment
+            .iter()
+            .map(|p| ReceiptPayment {
+                method: p.payment_method.to_string(),
+                amount: p.amount.quantity,
+            })
+            .collect();
+
+        let paid = payments.iter().map(|p| p.amount).sum::<f32>();
+
+        Receipt {
+            transaction_id: transaction.id.clone(),
+            date: transaction.order_date,
+            local_date: transaction.order_date.naive_utc(),
+            store: transaction
+                .products
+                .first()
+                .map(|order| order.origin.contact.clone()),
+            refund: matches!(
+                transaction.transaction_type,
+                TransactionType::In | TransactionType::PendingIn
+            ),
+            lines,
+            subtotal,
+            order_discount: rounding.round(subtotal - priced),
+            tax: tax.total_tax,
+            total: tax.gross,
+            payments,
+            change: rounding.round((paid - tax.gross).max(0.0)),
+            currency: CurrencyFormat::default(),
+        }
+    }
+
+    /// Dates the receipt in the local time of `store`, where the sale was made.
+    pub fn localise(&mut self, store: &Store) {
+        self.local_date = store.local_time(self.date);
+    }
+}
+
+/// A line with `left` and `right` at either edge, shortening `left` to fit.
+fn row(left: &str, right: &str) -> String {
+    let room = RECEIPT_WIDTH.saturating_sub(right.chars().count() + 1);
+    let left: String = left.chars().take(room).collect();
+
+    format!("{:<room$} {}\n", left, right, room = room)
+}
+
+fn centred(text: &str) -> String {
+    format!("{:^width$}\n", text, width = RECEIPT_WIDTH)
+}
+
+impl Display for Receipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let money = |amount: f32| self.currency.format(amount);
+        let rule = "-".repeat(RECEIPT_WIDTH);
+
+        if let Some(store) = &self.store {
+            let address = &store.address;
+
+            write!(f, "{}", centred(&store.name))?;
+            write!(f, "{}", centred(&address.street))?;
+            if !address.street2.is_empty() {
+                write!(f, "{}", centred(&address.street2))?;
+            }
+            write!(
+                f,
+                "{}",
+                centred(&format!("{} {}", address.city, address.po_code))
+            )?;
+            if !store.landline.is_empty() {
+                write!(f, "{}", centred(&store.landline))?;
+            }
+        }
+
+        writeln!(f, "{}", rule)?;
+        if self.refund {
+            write!(f, "{}", centred("REFUND"))?;
+        }
+        writeln!(f, "Transaction {}", self.transaction_id)?;
+        writeln!(f, "{}", self.local_date.format("%d/%m/%Y %H:%M"))?;
+        writeln!(f, "{}", rule)?;
+
+        for line in &self.lines {
+            let name = if line.quantity < 0.0 {
+                format!("RETURN {}", line.name)
+            } else {
+                line.name.clone()
+            };
+
+            write!(f, "{}", row(&name, &money(line.total)))?;
+            writeln!(f, "  {} @ {}", line.quantity, money(line.unit_price))?;
+            if line.discount != 0.0 {
+                write!(f, "{}", row("  Discount", &money(-line.discount)))?;
+            }
+        }
+
+        writeln!(f, "{}", rule)?;
+        write!(f, "{}", row("Subtotal", &money(self.subtotal)))?;
+        if self.order_discount != 0.0 {
+            write!(f, "{}", row("Order discount", &money(-self.order_discount)))?;
+        }
+        write!(f, "{}", row("Tax", &money(self.tax)))?;
+        write!(f, "{}", row("TOTAL", &money(self.total)))?;
+        writeln!(f, "{}", rule)?;
+
+        for payment in &self.payments {
+            let label = if payment.amount < 0.0 {
+                format!("REFUND {}", payment.method)
+            } else {
+                payment.method.clone()
+            };
+
+            write!(f, "{}", row(&label, &money(payment.amount)))?;
+        }
+
+        if self.change > 0.0 {
+            write!(f, "{}", row("Change", &money(self.change)))?;
+        }
+
+        Ok(())
+    }
+}
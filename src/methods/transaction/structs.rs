@@ -1,7 +1,11 @@
 use core::fmt;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+#[cfg(feature = "process")]
+use futures::TryStreamExt;
+use log::{debug, warn};
 use schemars::JsonSchema;
 #[cfg(feature = "process")]
 use sea_orm::{
@@ -9,29 +13,46 @@ use sea_orm::{
     *,
 };
 use serde::{Deserialize, Serialize};
-#[cfg(feature = "process")]
-use tokio::task::JoinError;
 use uuid::Uuid;
 
+#[cfg(feature = "process")]
+use geo::{point, HaversineDistance};
 #[cfg(feature = "process")]
 use sea_orm::FromQueryResult;
 
 #[cfg(feature = "process")]
 use crate::entities::{
-    prelude::Transactions, sea_orm_active_enums::TransactionType as SeaORMTType, transactions,
+    prelude::{Supplier as SupplierEntity, Transactions},
+    sea_orm_active_enums::TransactionType as SeaORMTType,
+    supplier, transactions,
 };
+#[cfg(feature = "process")]
+use crate::guards::PendingIntent;
+#[cfg(feature = "process")]
+use crate::methods::PRODUCT_CACHE;
 use crate::transaction::example::example_transaction;
 use crate::{
+    compute_tax, gift_card_payments,
     methods::{
-        Error, History, Id, NoteList, Order, OrderList, OrderStatus, OrderStatusAssignment,
-        Payment, Product, Session, Stock, VariantInformation,
+        validate_each, validate_id, Attachment, AttachmentInput, CartBreakdown, CommissionReport,
+        CommissionTally, Customer, DailySummary, Employee, Error, ErrorResponse, FieldError,
+        GiftCard, History, Id, ListQuery, Note, NoteInput, NoteList, Order, OrderList, OrderStatus,
+        OrderStatusAssignment, Payment, PickList, Product, ProductSales, ProductSalesTally,
+        Quantity, Receipt, RedemptionInput, Session, UnitOfMeasure, VariantInformation,
+        MAX_ATTACHMENTS,
     },
-    PickStatus, ProductInstance,
+    Kiosk, Location, OrderType, PickStatus, ProductPurchase, Store, TaxBreakdown, Tenant,
 };
 #[cfg(feature = "process")]
 use sea_orm::DbConn;
 use validator::Validate;
 
+/// The longest window of order dates which may be listed at once.
+pub const MAX_DATE_RANGE_DAYS: i64 = 90;
+
+/// Transactions listed per page of a date range.
+pub const DATE_RANGE_PAGE_SIZE: u64 = 100;
+
 #[cfg(feature = "types")]
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, Validate)]
 pub struct TransactionCustomer {
@@ -56,6 +77,114 @@ pub struct QuantityAlterationIntent {
     pub transaction_store_id: String,
     pub transaction_type: TransactionType,
     pub quantity_to_transact: f32,
+    /// The unit `quantity_to_transact` is in, being the variant's stock unit if absent.
+    #[serde(default)]
+    pub unit: Option<UnitOfMeasure>,
+    /// If present, the intent moves stock through a reservation and the
+    /// `transaction_type` is not applied.
+    #[serde(default)]
+    pub reservation: Option<Reservation>,
+}
+
+#[cfg(feature = "process")]
+impl QuantityAlterationIntent {
+    /// The intents to alter stock at the order's origin by each of its products.
+    pub fn for_order(
+        order: &Order,
+        transaction_type: TransactionType,
+        reservation: Option<Reservation>,
+    ) -> Vec<QuantityAlterationIntent> {
+        order
+            .products
+            .iter()
+            .map(|product| QuantityAlterationIntent {
+                variant_code: product.product_code.clone(),
+                product_sku: product.product_sku.clone(),
+                transaction_store_code: order.origin.store_code.clone(),
+                transaction_store_id: order.origin.store_id.clone(),
+                transaction_type: transaction_type.clone(),
+                quantity_to_transact: product.quantity,
+                unit: product.unit,
+                reservation: reservation.clone(),
+            })
+            .collect()
+    }
+
+    /// The quantity to transact in `stock_unit`, `None` if it cannot be converted.
+    pub fn amount(&self, stock_unit: UnitOfMeasure) -> Option<f32> {
+        match self.unit {
+            Some(unit) => unit.convert(self.quantity_to_transact, stock_unit),
+            None => Some(self.quantity_to_transact),
+        }
+    }
+
+    /// How far applying this intent would take the stock `quantity` below zero, if at all.
+    /// Stock which leaves the store may not exceed that on hand, whilst a reservation
+    /// may not exceed that available, being on hand and not already reserved.
+    pub fn shortfall(&self, quantity: &Quantity, stock_unit: UnitOfMeasure) -> Option<f32> {
+        let amount = self.amount(stock_unit)?;
+
+        let remaining = match (&self.reservation, &self.transaction_type) {
+            (Some(Reservation::Reserve), _) => quantity.available() - amount,
+            (Some(Reservation::Fulfil), _) | (None, TransactionType::Out) => {
+                quantity.quantity_sellable - amount
+            }
+            _ => return None,
+        };
+
+        Some(-remaining).filter(|shortfall| *shortfall > 0.0)
+    }
+
+    /// Alters the stock `quantity`, kept in `stock_unit`, by this intent. Quantities in a
+    /// unit which cannot be converted into the stock unit leave the stock unaltered.
+    pub fn apply(&self, quantity: &mut Quantity, stock_unit: UnitOfMeasure) {
+        let amount = match self.amount(stock_unit) {
+            Some(amount) => amount,
+            None => return,
+        };
+
+        if let Some(reservation) = &self.reservation {
+            return reservation.apply(quantity, amount);
+        }
+
+        match self.transaction_type {
+            TransactionType::In => quantity.quantity_sellable += amount,
+            TransactionType::Out => quantity.quantity_sellable -= amount,
+            TransactionType::PendingIn => quantity.quantity_on_order += amount,
+            TransactionType::PendingOut => quantity.quantity_allocated += amount,
+            // A saved transaction or quote should not be processed, but should be shifted into
+            // a specified IN or OUT variant. As this should never happen, stock is left alone.
+            TransactionType::Saved | TransactionType::Quote => {}
+        }
+    }
+}
+
+/// The movement of stock held for an order which is yet to be fulfilled.
+#[cfg(feature = "process")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub enum Reservation {
+    /// Holds stock for the order, leaving it on hand.
+    Reserve,
+    /// The order has been fulfilled, its held stock leaves the store.
+    Fulfil,
+    /// The order will not be fulfilled, its held stock is freed.
+    Release,
+}
+
+#[cfg(feature = "process")]
+impl Reservation {
+    pub fn apply(&self, quantity: &mut Quantity, amount: f32) {
+        match self {
+            Reservation::Reserve => quantity.quantity_reserved += amount,
+            Reservation::Fulfil => {
+                quantity.quantity_reserved = (quantity.quantity_reserved - amount).max(0.0);
+                quantity.quantity_sellable -= amount;
+            }
+            Reservation::Release => {
+                quantity.quantity_reserved = (quantity.quantity_reserved - amount).max(0.0);
+            }
+        }
+    }
 }
 
 #[cfg(feature = "types")]
@@ -104,6 +233,23 @@ pub struct Transaction {
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+
+    /// Incremented on every update, must be echoed back when updating the transaction.
+    #[serde(default)]
+    pub version: i32,
+
+    /// Set when the transaction has been deleted, such transactions are hidden from all fetches
+    /// until restored.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    /// Files kept against the transaction as a whole, see [`Transaction::add_attachment`].
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+
+    /// Cash returned to the customer having paid more than was owed, see [`settle_tender`](crate::settle_tender).
+    #[serde(default)]
+    pub change: f32,
 }
 
 #[cfg(feature = "process")]
@@ -132,6 +278,7 @@ pub struct TransactionInput {
     pub customer: TransactionCustomer,
     pub transaction_type: TransactionType,
 
+    #[validate(custom = "validate_each")]
     pub products: OrderList,
     pub order_total: i64,
     pub payment: Vec<Payment>,
@@ -139,8 +286,14 @@ pub struct TransactionInput {
     pub order_date: DateTime<Utc>,
     pub order_notes: NoteList,
 
+    #[validate(custom = "validate_id")]
     pub salesperson: Id,
+    #[validate(custom = "validate_id")]
     pub kiosk: Id,
+
+    /// The version of the transaction being updated.
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[cfg(feature = "types")]
@@ -149,6 +302,7 @@ pub struct TransactionInit {
     pub customer: TransactionCustomer,
     pub transaction_type: TransactionType,
 
+    #[validate(custom = "validate_each")]
     pub products: OrderList,
     pub order_total: i64,
     pub payment: Vec<Payment>,
@@ -156,7 +310,73 @@ pub struct TransactionInit {
     pub order_date: DateTime<Utc>,
     pub order_notes: NoteList,
 
+    #[validate(custom = "validate_id")]
     pub kiosk: Id,
+
+    /// When set, each shipment order without an origin store is assigned the
+    /// nearest store to its destination which can supply it, see [`Store::nearest_to`].
+    #[serde(default)]
+    pub assign_nearest_origin: bool,
+
+    /// Permits the sale of stock which is not on hand, see [`StockOverride`].
+    #[serde(default)]
+    #[validate]
+    pub stock_override: Option<StockOverride>,
+
+    /// Computed from the payments when the transaction is created, never given by the client.
+    #[serde(skip)]
+    pub change: f32,
+}
+
+/// A manager's authorisation to sell stock the store does not have on hand,
+/// taking it below zero, such as when stock has arrived but is yet to be received.
+/// The manager and their reason are noted against the transaction.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct StockOverride {
+    #[validate(length(min = 1))]
+    pub reason: String,
+}
+
+/// A page of transactions, see [`Transaction::fetch_by_date_range`].
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TransactionPage {
+    pub transactions: Vec<Transaction>,
+    /// Continues the listing after the last of `transactions`, absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// The position of a transaction within a listing ordered by `order_date`, then `id`.
+/// Sent to clients as an opaque string, see [`TransactionCursor::encode`].
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionCursor {
+    pub order_date: NaiveDateTime,
+    pub id: Id,
+}
+
+const CURSOR_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+#[cfg(feature = "process")]
+impl TransactionCursor {
+    pub fn encode(&self) -> String {
+        hex::encode(format!(
+            "{}|{}",
+            self.order_date.format(CURSOR_DATE_FORMAT),
+            self.id
+        ))
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let decoded = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+        let (order_date, id) = decoded.split_once('|')?;
+
+        Some(TransactionCursor {
+            order_date: NaiveDateTime::parse_from_str(order_date, CURSOR_DATE_FORMAT).ok()?,
+            id: id.to_string(),
+        })
+    }
 }
 
 #[cfg(feature = "types")]
@@ -168,15 +388,106 @@ pub struct ProductStatusUpdate {
     pub new_status: PickStatus,
 }
 
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct OrderCancellation {
+    #[validate(length(min = 1))]
+    pub reason: String,
+}
+
+/// Sets one status on many orders at once, see [`Transaction::bulk_update_order_status`].
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct BulkOrderStatusUpdate {
+    /// The `reference` of each order, at most 100.
+    #[validate(length(min = 1, max = 100))]
+    pub references: Vec<String>,
+    pub status: OrderStatus,
+}
+
+/// Whether a single order of a bulk status update was updated, or else why not.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct OrderStatusOutcome {
+    pub reference: String,
+    pub updated: bool,
+    pub error: Option<ErrorResponse>,
+}
+
+/// Credits a sale to another employee, such as when one forgot to switch logins.
+#[cfg(feature = "types")]
+#[derive(Deserialize, Clone, JsonSchema, Validate)]
+pub struct SalespersonReassignment {
+    #[validate(custom = "validate_id")]
+    pub employee_id: Id,
+    #[validate(length(min = 1))]
+    pub reason: String,
+}
+
+/// A product purchase which its order's origin store cannot supply,
+/// quantities are in the variant's stock unit.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct UnavailableLine {
+    pub order_id: Id,
+    pub product_purchase_id: Id,
+    pub product_sku: String,
+    pub product_code: String,
+    pub store_code: String,
+    pub requested: f32,
+    pub available: f32,
+}
+
+impl Display for UnavailableLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) at {}: requested {}, available {}",
+            self.product_sku, self.product_code, self.store_code, self.requested, self.available
+        )
+    }
+}
+
+/// A stock alteration refused for taking a variant's stock below zero,
+/// quantities are in the variant's stock unit.
+#[cfg(feature = "types")]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct StockShortfall {
+    pub product_sku: String,
+    pub variant_code: String,
+    pub store_code: String,
+    pub required: f32,
+    pub shortfall: f32,
+}
+
+impl Display for StockShortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) at {}: required {}, short by {}",
+            self.product_sku, self.variant_code, self.store_code, self.required, self.shortfall
+        )
+    }
+}
+
 #[cfg(feature = "methods")]
 impl Transaction {
-    pub async fn insert(
-        tsn: TransactionInit,
+    pub async fn insert<C: ConnectionTrait>(
+        mut tsn: TransactionInit,
         session: Session,
-        db: &DbConn,
+        db: &C,
     ) -> Result<InsertResult<transactions::ActiveModel>, Error> {
         let id = Uuid::new_v4().to_string();
 
+        // Orders completed at the till are fulfilled in full as they are placed.
+        for order in tsn.products.iter_mut() {
+            if matches!(order.status.status, OrderStatus::Fulfilled(_)) {
+                order.fulfil_all();
+            }
+
+            order.seed_status_history();
+        }
+
         match Transactions::insert(tsn.into_active(id, session))
             .exec(db)
             .await
@@ -186,11 +497,505 @@ impl Transaction {
         }
     }
 
+    /// Sets the cost of each product in the transaction to the price applicable to its
+    /// customer, see [`VariantInformation::price_for`]. Discounts are then applied on top
+    /// of this price. Products which cannot be found retain the cost they were given.
+    pub async fn apply_price_tiers(tsn: &mut TransactionInit, session: Session, db: &DbConn) {
+        let group = match tsn.customer.customer_type {
+            CustomerType::Store => None,
+            _ => Customer::fetch_by_id(&tsn.customer.customer_id, session.clone(), db)
+                .await
+                .ok()
+                .map(|c| c.special_pricing)
+                .filter(|g| !g.is_empty()),
+        };
+
+        let products = Self::cart_products(&tsn.products, session, db).await;
+
+        for order in tsn.products.iter_mut() {
+            for purchase in order.products.iter_mut() {
+                if let Some(variant) = Self::cart_variant(&products, purchase) {
+                    // Prices are per stock unit, so are scaled to the unit sold in.
+                    let per_unit = purchase
+                        .unit
+                        .and_then(|unit| unit.convert(1.0, variant.stock_information.unit))
+                        .unwrap_or(1.0);
+
+                    purchase.product_cost = variant.price_for(group.as_deref()) * per_unit;
+                }
+            }
+        }
+    }
+
+    /// Assigns the origin of every shipment order lacking one (an empty `store_id`),
+    /// if requested by `assign_nearest_origin`. Orders given an explicit origin are untouched.
+    pub async fn assign_origins(
+        tsn: &mut TransactionInit,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<(), Error> {
+        if !tsn.assign_nearest_origin {
+            return Ok(());
+        }
+
+        for order in tsn.products.iter_mut() {
+            if !matches!(order.order_type, OrderType::Shipment) || !order.origin.store_id.is_empty()
+            {
+                continue;
+            }
+
+            let store = Store::nearest_to(
+                &order.destination.contact.address,
+                &order.products,
+                session.clone(),
+                db,
+            )
+            .await?
+            .ok_or_else(|| {
+                ErrorResponse::custom_input_error(&format!(
+                    "No store is able to supply order {}.",
+                    order.reference
+                ))
+            })?;
+
+            order.origin = Location {
+                store_code: store.code,
+                store_id: store.id,
+                contact: store.contact,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Determines which purchases in `cart` cannot be supplied by their order's origin
+    /// store. Back-orderable, non-diminishing and untracked variants are always available,
+    /// whilst products or variants which cannot be found are never available.
+    pub async fn check_availability(
+        cart: &OrderList,
+        session: Session,
+        db: &DbConn,
+    ) -> Vec<UnavailableLine> {
+        let products = Self::cart_products(cart, session, db).await;
+        let mut unavailable = vec![];
+
+        for order in cart {
+            for purchase in order.products.iter() {
+                let store_code = &order.origin.store_code;
+                let variant = Self::cart_variant(&products, purchase);
+                let quantity =
+                    variant.and_then(|v| purchase.stock_quantity(v.stock_information.unit));
+
+                if let (Some(variant), Some(quantity)) = (variant, quantity) {
+                    if variant.available_at(store_code, quantity) {
+                        continue;
+                    }
+                }
+
+                let available = variant
+                    .map(|v| {
+                        v.stock
+                            .iter()
+                            .filter(|stock| &stock.store.store_code == store_code)
+                            .map(|stock| stock.quantity.available())
+                            .sum::<f32>()
+                    })
+                    .unwrap_or(0.0);
+
+                unavailable.push(UnavailableLine {
+                    order_id: order.id.clone(),
+                    product_purchase_id: purchase.id.clone(),
+                    product_sku: purchase.product_sku.clone(),
+                    product_code: purchase.product_code.clone(),
+                    store_code: store_code.clone(),
+                    requested: quantity.unwrap_or(purchase.quantity),
+                    available,
+                });
+            }
+        }
+
+        unavailable
+    }
+
+    /// Computes the tax owed on `cart` using the tenant's tax rates, honouring
+    /// whether the tenant's prices are tax-inclusive, see [`TaxBreakdown`].
+    pub async fn compute_tax(
+        cart: &OrderList,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<TaxBreakdown, Error> {
+        let settings = Tenant::fetch_by_id(&session.tenant_id, db).await?.settings;
+        let products = Self::cart_products(cart, session, db).await;
+
+        Ok(compute_tax(cart, &settings, |purchase| {
+            Self::cart_variant(&products, purchase)
+                .map(|v| v.stock_information.tax_code.clone())
+                .unwrap_or_default()
+        }))
+    }
+
+    /// Prices `cart` as it would be at checkout, breaking each order down into its
+    /// discounts and totals alongside the tax owed, see [`CartBreakdown`].
+    pub async fn price_cart(
+        cart: &OrderList,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<CartBreakdown, Error> {
+        let rounding = Tenant::fetch_by_id(&session.tenant_id, db)
+            .await?
+            .settings
+            .rounding;
+        let tax = Self::compute_tax(cart, session, db).await?;
+
+        Ok(CartBreakdown {
+            orders: cart.iter().map(|order| order.breakdown(rounding)).collect(),
+            tax,
+        })
+    }
+
+    /// Lays out the transaction as a receipt, with the tax owed on it. Its
+    /// `Display` form suits a thermal printer.
+    pub async fn render_receipt(id: &str, session: Session, db: &DbConn) -> Result<Receipt, Error> {
+        let transaction = Transaction::fetch_by_id(id, session.clone(), db).await?;
+        let settings = Tenant::fetch_by_id(&session.tenant_id, db).await?.settings;
+        let products = Self::cart_products(&transaction.products, session.clone(), db).await;
+
+        let tax = compute_tax(&transaction.products, &settings, |purchase| {
+            Self::cart_variant(&products, purchase)
+                .map(|v| v.stock_information.tax_code.clone())
+                .unwrap_or_default()
+        });
+
+        let mut receipt = Receipt::new(&transaction, &tax, settings.rounding);
+        receipt.currency = settings.currency;
+
+        // Print the time the sale was made at the store, rather than in UTC.
+        if let Ok(kiosk) = Kiosk::fetch_by_id(&transaction.kiosk, session.clone(), db).await {
+            if let Ok(store) = Store::fetch_by_id(&kiosk.store_id, session, db).await {
+                receipt.localise(&store);
+            }
+        }
+
+        Ok(receipt)
+    }
+
+    /// Summarises the sales and refunds made at the kiosks of a store over the
+    /// local `date` of the store, or its current day when `None`.
+    pub async fn daily_summary(
+        store_id: &str,
+        date: Option<NaiveDate>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<DailySummary, Error> {
+        let store = Store::fetch_by_id(store_id, session.clone(), db).await?;
+        let date = date.unwrap_or_else(|| store.local_time(Utc::now()).date());
+        let (from, to) = DailySummary::bounds(&store, date);
+
+        let kiosks: Vec<Id> = Kiosk::fetch_by_store(store_id, session.clone(), db)
+            .await?
+            .into_iter()
+            .map(|k| k.id)
+            .collect();
+
+        let transactions: Vec<Transaction> = Transactions::find()
+            .filter(transactions::Column::TenantId.eq(session.tenant_id))
+            .filter(transactions::Column::DeletedAt.is_null())
+            .filter(transactions::Column::Kiosk.is_in(kiosks))
+            .filter(transactions::Column::OrderDate.gte(from.naive_utc()))
+            .filter(transactions::Column::OrderDate.lt(to.naive_utc()))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|t| t.into())
+            .collect();
+
+        Ok(DailySummary::new(&store, date, &transactions))
+    }
+
+    /// Lists transactions ordered between `from` and `to`, optionally only those made
+    /// at the kiosks of `store_id`, in pages of [`DATE_RANGE_PAGE_SIZE`]. The range may
+    /// be no longer than [`MAX_DATE_RANGE_DAYS`].
+    ///
+    /// Transactions are ordered by `order_date`, then `id`, with each page continuing
+    /// from the `cursor` returned by the last. As the position is kept by value rather
+    /// than by count, transactions recorded whilst paging neither shift nor repeat
+    /// those already listed; new sales are listed once paging reaches them.
+    pub async fn fetch_by_date_range(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        store_id: Option<&str>,
+        cursor: Option<&str>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<TransactionPage, Error> {
+        let mut select = Self::select_date_range(from, to, store_id, session, db).await?;
+
+        if let Some(cursor) = cursor {
+            let cursor = TransactionCursor::decode(cursor)
+                .ok_or_else(|| ErrorResponse::custom_input_error("Malformed cursor."))?;
+
+            select = select.filter(
+                Condition::any()
+                    .add(transactions::Column::OrderDate.gt(cursor.order_date))
+                    .add(
+                        Condition::all()
+                            .add(transactions::Column::OrderDate.eq(cursor.order_date))
+                            .add(transactions::Column::Id.gt(cursor.id)),
+                    ),
+            );
+        }
+
+        // One more than a page is fetched to learn whether another page follows.
+        let mut res = select
+            .order_by_asc(transactions::Column::OrderDate)
+            .order_by_asc(transactions::Column::Id)
+            .limit(DATE_RANGE_PAGE_SIZE + 1)
+            .all(db)
+            .await?;
+
+        let next_cursor = if res.len() as u64 > DATE_RANGE_PAGE_SIZE {
+            res.truncate(DATE_RANGE_PAGE_SIZE as usize);
+            res.last().map(|last| {
+                TransactionCursor {
+                    order_date: last.order_date,
+                    id: last.id.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(TransactionPage {
+            transactions: res.into_iter().map(|t| t.into()).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Ranks the `limit` best selling products of the sales made between `from`
+    /// and `to`, optionally only those made at the kiosks of `store_id`. Voided
+    /// transactions, refunds and cancelled orders are not counted.
+    pub async fn top_products(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        store_id: Option<&str>,
+        limit: usize,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<ProductSales>, Error> {
+        let rounding = Tenant::fetch_by_id(&session.tenant_id, db)
+            .await?
+            .settings
+            .rounding;
+
+        // Only the orders of each sale are read, one at a time, rather than
+        // loading every transaction in the range.
+        let mut orders = Self::select_date_range(from, to, store_id, session, db)
+            .await?
+            .filter(transactions::Column::TransactionType.eq(SeaORMTType::Out))
+            .select_only()
+            .column(transactions::Column::Products)
+            .into_tuple::<serde_json::Value>()
+            .stream(db)
+            .await?;
+
+        let mut tally = ProductSalesTally::default();
+
+        while let Some(products) = orders.try_next().await? {
+            for order in serde_json::from_value::<OrderList>(products).unwrap_or_default() {
+                tally.add(&order, rounding);
+            }
+        }
+
+        Ok(tally.ranked(limit))
+    }
+
+    /// Totals the sales credited to each salesperson between `from` and `to`, and
+    /// the commission they earn under the tenant's [`CommissionScheme`]. Refunds
+    /// are deducted from the salesperson who recorded them, whilst voided
+    /// transactions, cancelled orders, purchase orders from suppliers and transfers
+    /// between stores are not counted.
+    pub async fn commission_report(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<CommissionReport, Error> {
+        let settings = Tenant::fetch_by_id(&session.tenant_id, db).await?.settings;
+
+        let suppliers = SupplierEntity::find()
+            .filter(supplier::Column::TenantId.eq(session.tenant_id.clone()))
+            .select_only()
+            .column(supplier::Column::Id)
+            .into_tuple::<String>()
+            .all(db)
+            .await?;
+
+        let mut tally = CommissionTally::new(suppliers.into_iter().collect());
+
+        let mut transactions = Self::select_date_range(from, to, None, session.clone(), db)
+            .await?
+            .filter(
+                transactions::Column::TransactionType.is_in([SeaORMTType::Out, SeaORMTType::In]),
+            )
+            .select_only()
+            .column(transactions::Column::Salesperson)
+            .column(transactions::Column::TransactionType)
+            .column(transactions::Column::Customer)
+            .column(transactions::Column::Products)
+            .into_tuple::<(String, SeaORMTType, serde_json::Value, serde_json::Value)>()
+            .stream(db)
+            .await?;
+
+        while let Some((salesperson, transaction_type, customer, products)) =
+            transactions.try_next().await?
+        {
+            let customer = match serde_json::from_value::<TransactionCustomer>(customer) {
+                Ok(customer) => customer,
+                Err(err) => {
+                    warn!("Unable to read the customer of a transaction: {:?}", err);
+                    continue;
+                }
+            };
+            let orders = serde_json::from_value::<OrderList>(products).unwrap_or_default();
+
+            tally.add(
+                &salesperson,
+                &transaction_type.into(),
+                &customer,
+                &orders,
+                settings.rounding,
+            );
+        }
+
+        Ok(tally.report(from, to, settings.commission, settings.rounding))
+    }
+
+    /// The barcodes of every variant sold since `since`, from the store `store_id`
+    /// if given. Voided transactions, refunds and cancelled orders are not counted.
+    pub async fn sold_variants(
+        since: DateTime<Utc>,
+        store_id: Option<&str>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<HashSet<String>, Error> {
+        let mut orders = Transactions::find()
+            .filter(transactions::Column::TenantId.eq(session.tenant_id))
+            .filter(transactions::Column::DeletedAt.is_null())
+            .filter(transactions::Column::TransactionType.eq(SeaORMTType::Out))
+            .filter(transactions::Column::OrderDate.gte(since.naive_utc()))
+            .select_only()
+            .column(transactions::Column::Products)
+            .into_tuple::<serde_json::Value>()
+            .stream(db)
+            .await?;
+
+        let mut sold = HashSet::new();
+
+        while let Some(products) = orders.try_next().await? {
+            let list = serde_json::from_value::<OrderList>(products).unwrap_or_default();
+
+            for order in list.iter().filter(|o| {
+                !matches!(o.status.status, OrderStatus::Failed(_))
+                    && store_id.is_none_or(|id| o.origin.store_id == id)
+            }) {
+                sold.extend(
+                    order
+                        .products
+                        .iter()
+                        .filter(|p| matches!(p.transaction_type, TransactionType::Out))
+                        .map(|p| p.product_code.clone()),
+                );
+            }
+        }
+
+        Ok(sold)
+    }
+
+    /// Selects the transactions ordered between `from` and `to`, at the kiosks of
+    /// `store_id` if given, rejecting ranges longer than [`MAX_DATE_RANGE_DAYS`].
+    async fn select_date_range(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        store_id: Option<&str>,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Select<Transactions>, Error> {
+        if to <= from {
+            return Err(ErrorResponse::custom_input_error(
+                "The end of the range must be after its start.",
+            ));
+        }
+
+        if to - from > Duration::days(MAX_DATE_RANGE_DAYS) {
+            return Err(ErrorResponse::custom_input_error(&format!(
+                "Unable to query more than {} days of transactions at once.",
+                MAX_DATE_RANGE_DAYS
+            )));
+        }
+
+        let mut select = Transactions::find()
+            .filter(transactions::Column::TenantId.eq(session.tenant_id.clone()))
+            .filter(transactions::Column::DeletedAt.is_null())
+            .filter(transactions::Column::OrderDate.gte(from.naive_utc()))
+            .filter(transactions::Column::OrderDate.lt(to.naive_utc()));
+
+        if let Some(store_id) = store_id {
+            let kiosks: Vec<Id> = Kiosk::fetch_by_store(store_id, session, db)
+                .await?
+                .into_iter()
+                .map(|k| k.id)
+                .collect();
+
+            select = select.filter(transactions::Column::Kiosk.is_in(kiosks));
+        }
+
+        Ok(select)
+    }
+
+    /// Fetches each distinct product in the cart, keyed by sku.
+    async fn cart_products(
+        cart: &OrderList,
+        session: Session,
+        db: &DbConn,
+    ) -> HashMap<String, Option<Product>> {
+        let mut products: HashMap<String, Option<Product>> = HashMap::new();
+
+        for purchase in cart.iter().flat_map(|order| order.products.iter()) {
+            if !products.contains_key(&purchase.product_sku) {
+                let product = Product::fetch_by_id(&purchase.product_sku, session.clone(), db)
+                    .await
+                    .ok();
+                products.insert(purchase.product_sku.clone(), product);
+            }
+        }
+
+        products
+    }
+
+    fn cart_variant<'a>(
+        products: &'a HashMap<String, Option<Product>>,
+        purchase: &ProductPurchase,
+    ) -> Option<&'a VariantInformation> {
+        products
+            .get(&purchase.product_sku)
+            .and_then(|p| p.as_ref())
+            .and_then(|p| {
+                p.variants
+                    .iter()
+                    .find(|v| v.barcode == purchase.product_code)
+            })
+    }
+
     pub async fn insert_raw(
-        tsn: Transaction,
+        mut tsn: Transaction,
         session: Session,
         db: &DbConn,
     ) -> Result<InsertResult<transactions::ActiveModel>, Error> {
+        for order in tsn.products.iter_mut() {
+            order.seed_status_history();
+        }
+
         match Transactions::insert(tsn.into_active(session.tenant_id))
             .exec(db)
             .await
@@ -209,7 +1014,7 @@ impl Transaction {
             DerivableTransaction::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::MySql,
                 &format!(
-                    "SELECT * FROM Transactions WHERE Transactions.products LIKE '%{}%' AND Transactions.tenant_id = '{}'",
+                    "SELECT * FROM Transactions WHERE Transactions.products LIKE '%{}%' AND Transactions.tenant_id = '{}' AND Transactions.deleted_at IS NULL",
                     query, session.tenant_id
                 ),
                 vec![],
@@ -244,7 +1049,7 @@ impl Transaction {
             DerivableTransaction::find_by_statement(Statement::from_sql_and_values(
                 DbBackend::MySql,
                 &format!(
-                    "SELECT * FROM Transactions WHERE Transactions.products LIKE '%{}%' AND Transactions.tenant_id = '{}'",
+                    "SELECT * FROM Transactions WHERE Transactions.products LIKE '%{}%' AND Transactions.tenant_id = '{}' AND Transactions.deleted_at IS NULL",
                     query, session.tenant_id
                 ),
                 vec![],
@@ -270,6 +1075,48 @@ impl Transaction {
         Ok(mapped)
     }
 
+    /// Consolidates the lines yet to be picked across the store's deliverable orders
+    /// into a single [`PickList`], ordered by where each variant is kept.
+    pub async fn generate_pick_list(
+        store_id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<PickList, Error> {
+        // Ensures the store exists, rather than listing nothing for a mistyped id.
+        Store::fetch_by_id(store_id, session.clone(), db).await?;
+
+        let orders = Self::fetch_deliverable_jobs(store_id, session.clone(), db).await?;
+
+        let skus = orders
+            .iter()
+            .flat_map(|o| o.products.iter().map(|p| p.product_sku.clone()))
+            .collect::<HashSet<_>>();
+
+        let mut bins = HashMap::new();
+
+        for sku in skus {
+            // A product removed since it was ordered is still picked, just without a bin.
+            let product = match Product::fetch_by_id(&sku, session.clone(), db).await {
+                Ok(product) => product,
+                Err(_) => continue,
+            };
+
+            for variant in product.variants {
+                let bin = variant
+                    .stock
+                    .into_iter()
+                    .find(|s| s.store.store_id == store_id)
+                    .and_then(|s| s.bin_location);
+
+                if let Some(bin) = bin {
+                    bins.insert(variant.barcode, bin);
+                }
+            }
+        }
+
+        Ok(PickList::from_orders(store_id, &orders, &bins))
+    }
+
     pub async fn fetch_by_id(
         id: &str,
         session: Session,
@@ -277,25 +1124,49 @@ impl Transaction {
     ) -> Result<Transaction, Error> {
         let tsn = Transactions::find_by_id(id.to_string())
             .filter(transactions::Column::TenantId.eq(session.tenant_id))
+            .filter(transactions::Column::DeletedAt.is_null())
             .one(db)
             .await?;
 
-        if tsn.is_none() {
-            return Err(DbErr::Custom("Unable to query value, returns none".to_string()).into());
+        match tsn {
+            Some(t) => Ok(t.into()),
+            None => Err(ErrorResponse::not_found("Transaction", id)),
         }
-
-        Ok(tsn.unwrap().into())
     }
 
-    pub async fn fetch_all_saved(session: Session, db: &DbConn) -> Result<Vec<Transaction>, Error> {
-        let res = Transactions::find()
-            .filter(transactions::Column::TenantId.eq(session.tenant_id))
+    /// The saved transactions of the tenant, excluding those deleted, as listed by
+    /// [`Transaction::fetch_all_saved`].
+    pub fn select_saved(tenant_id: &str) -> Select<Transactions> {
+        Transactions::find()
+            .filter(transactions::Column::TenantId.eq(tenant_id))
+            .filter(transactions::Column::DeletedAt.is_null())
             .having(
                 Expr::expr(Func::lower(Expr::col(
                     transactions::Column::TransactionType,
                 )))
                 .like("%saved%".to_string()),
             )
+    }
+
+    pub async fn fetch_all_saved(
+        query: &ListQuery,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<Transaction>, Error> {
+        let select = Self::select_saved(&session.tenant_id);
+
+        let res = query
+            .apply(
+                select,
+                &[
+                    ("order_date", transactions::Column::OrderDate),
+                    ("order_total", transactions::Column::OrderTotal),
+                    ("salesperson", transactions::Column::Salesperson),
+                    ("kiosk", transactions::Column::Kiosk),
+                    ("created_at", transactions::Column::CreatedAt),
+                    ("updated_at", transactions::Column::UpdatedAt),
+                ],
+            )?
             .limit(25)
             .all(db)
             .await?;
@@ -312,6 +1183,7 @@ impl Transaction {
     ) -> Result<Vec<Transaction>, Error> {
         let res = Transactions::find()
             .filter(transactions::Column::TenantId.eq(session.tenant_id))
+            .filter(transactions::Column::DeletedAt.is_null())
             .having(
                 Expr::expr(Func::lower(Expr::col(transactions::Column::Products)))
                     .like(format!("%{}%", reference.to_lowercase())),
@@ -333,6 +1205,7 @@ impl Transaction {
     ) -> Result<Vec<Transaction>, Error> {
         let tsn = Transactions::find()
             .filter(transactions::Column::TenantId.eq(session.tenant_id))
+            .filter(transactions::Column::DeletedAt.is_null())
             .having(transactions::Column::Customer.contains(id))
             .all(db)
             .await?;
@@ -348,11 +1221,11 @@ impl Transaction {
         id: &str,
         db: &DbConn,
     ) -> Result<Transaction, Error> {
-        tsn.into_active(id.to_string(), session.clone())
-            .update(db)
-            .await?;
+        let version = tsn.version;
+        let mut model = tsn.into_active(id.to_string(), session.clone());
+        model.created_at = NotSet;
 
-        Self::fetch_by_id(id, session, db).await
+        Self::update_versioned(model, version, id, session, db).await
     }
 
     pub async fn update_value(
@@ -361,13 +1234,59 @@ impl Transaction {
         id: &str,
         db: &DbConn,
     ) -> Result<Transaction, Error> {
-        tsn.into_active(session.tenant_id.clone())
-            .update(db)
-            .await?;
+        let version = tsn.version;
+        let mut model = tsn.into_active(session.tenant_id.clone());
+        model.updated_at = Set(Utc::now().naive_utc());
+
+        Self::update_versioned(model, version, id, session, db).await
+    }
+
+    /// Writes the model only if the stored transaction is still at `version`,
+    /// so that concurrent modifications are rejected rather than overwritten.
+    async fn update_versioned(
+        model: transactions::ActiveModel,
+        version: i32,
+        id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        if !Self::write_versioned(model, version, id, &session.tenant_id, db).await? {
+            // Either the transaction does not exist, or it was modified since it was fetched.
+            Self::fetch_by_id(id, session.clone(), db).await?;
+            return Err(ErrorResponse::conflict(
+                "Transaction was modified by another request, fetch it again before updating.",
+            ));
+        }
 
         Self::fetch_by_id(id, session, db).await
     }
 
+    /// Writes the model if the stored transaction is still at `version`, returning
+    /// whether it was written. Takes any connection, such that several writes may
+    /// share a database transaction.
+    pub(crate) async fn write_versioned<C: ConnectionTrait>(
+        mut model: transactions::ActiveModel,
+        version: i32,
+        id: &str,
+        tenant_id: &str,
+        db: &C,
+    ) -> Result<bool, Error> {
+        model.version = Set(version + 1);
+
+        let res = Transactions::update_many()
+            .set(model)
+            .filter(transactions::Column::Id.eq(id))
+            .filter(transactions::Column::TenantId.eq(tenant_id))
+            .filter(transactions::Column::Version.eq(version))
+            .filter(transactions::Column::DeletedAt.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Assigns `status` to the transaction's orders referenced by `refer`, writing the
+    /// stock alterations it requires within the same database transaction.
     pub async fn update_order_status(
         id: &str,
         refer: &str,
@@ -376,37 +1295,460 @@ impl Transaction {
         db: &DbConn,
     ) -> Result<Transaction, Error> {
         let mut transaction = Transaction::fetch_by_id(id, session.clone(), db).await?;
+        let intents = transaction.apply_order_status(refer, status)?;
 
-        let new_orders = transaction
-            .clone()
-            .products
-            .into_iter()
-            .map(|mut v| {
-                if v.reference == refer {
-                    let new_status = OrderStatusAssignment {
-                        status: status.clone(),
-                        assigned_products: v.products.iter().map(|el| el.id.clone()).collect(),
-                        timestamp: Utc::now(),
-                    };
-
-                    v.status = new_status.clone();
-
-                    v.status_history.push(History {
-                        item: new_status,
-                        reason: "Supered Update".to_string(),
-                        timestamp: v.status.timestamp,
-                    });
+        Self::update_with_stock(transaction, &intents, session, db).await
+    }
+
+    /// Sets `status` on every order in `refs`, each validated as by
+    /// [`Transaction::update_order_status`]. Orders which cannot take the status, or
+    /// whose stock cannot be altered, are reported as such, whilst the rest are written
+    /// with their stock within a single database transaction. Returns the outcome of each order, in the order given, alongside
+    /// each transaction changed, as it was before and after.
+    pub async fn bulk_update_order_status(
+        refs: &[String],
+        status: OrderStatus,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<(Vec<OrderStatusOutcome>, Vec<(Transaction, Transaction)>), Error> {
+        let mut results: Vec<(String, Option<Error>)> = vec![];
+        // Each transaction as fetched, as changed, the references applied to it and
+        // the stock alterations they require.
+        let mut changes: Vec<(
+            Transaction,
+            Transaction,
+            Vec<String>,
+            Vec<QuantityAlterationIntent>,
+        )> = vec![];
+
+        for refer in refs {
+            if results.iter().any(|(r, _)| r == refer) {
+                continue;
+            }
+
+            let known = changes
+                .iter()
+                .position(|(before, ..)| before.products.iter().any(|o| &o.reference == refer));
+
+            let index = match known {
+                Some(index) => index,
+                None => {
+                    match Self::fetch_by_ref(refer, session.clone(), db)
+                        .await?
+                        .into_iter()
+                        .next()
+                    {
+                        Some(transaction) => {
+                            changes.push((transaction.clone(), transaction, vec![], vec![]));
+                            changes.len() - 1
+                        }
+                        None => {
+                            results.push((
+                                refer.clone(),
+                                Some(ErrorResponse::not_found("Order", refer)),
+                            ));
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let (_, working, applied, intents) = &mut changes[index];
+
+            // Applied to a copy, such that a failed order leaves the transaction untouched.
+            let mut candidate = working.clone();
+
+            match candidate.apply_order_status(refer, status.clone()) {
+                Ok(order_intents) => {
+                    *working = candidate;
+                    applied.push(refer.clone());
+                    intents.extend(order_intents);
+                    results.push((refer.clone(), None));
+                }
+                Err(err) => results.push((refer.clone(), Some(err))),
+            }
+        }
+
+        changes.retain(|(_, _, applied, _)| !applied.is_empty());
+
+        let txn = db.begin().await?;
+        let mut written = vec![];
+        let mut altered = vec![];
+
+        for (before, working, applied, intents) in changes {
+            let mut model = working.clone().into_active(session.tenant_id.clone());
+            model.updated_at = Set(Utc::now().naive_utc());
+
+            // Each transaction is written under a savepoint together with its stock,
+            // such that one which cannot be written is rolled back alone.
+            let savepoint = txn.begin().await?;
+
+            let outcome = if Self::write_versioned(
+                model,
+                before.version,
+                &before.id,
+                &session.tenant_id,
+                &savepoint,
+            )
+            .await?
+            {
+                Self::alter_stock_within(&intents, false, &session, &savepoint).await
+            } else {
+                Err(ErrorResponse::conflict(
+                    "Transaction was modified by another request, fetch it again before updating.",
+                ))
+            };
+
+            match outcome {
+                Ok(skus) => {
+                    savepoint.commit().await?;
+                    altered.extend(skus);
+                    written.push(before);
+                }
+                Err(err) => {
+                    savepoint.rollback().await?;
+
+                    for (refer, error) in results.iter_mut() {
+                        if applied.contains(refer) {
+                            *error = Some(err.clone());
+                        }
+                    }
                 }
+            }
+        }
+
+        txn.commit().await?;
+
+        for sku in altered.iter() {
+            PRODUCT_CACHE.invalidate(&session.tenant_id, sku);
+        }
+
+        let mut changed = vec![];
+
+        for before in written {
+            let after = Self::fetch_by_id(&before.id, session.clone(), db).await?;
+            changed.push((before, after));
+        }
 
-                v
+        let outcomes = results
+            .into_iter()
+            .map(|(reference, error)| OrderStatusOutcome {
+                updated: error.is_none(),
+                error: error.map(Error::into_body),
+                reference,
             })
-            .collect::<Vec<Order>>();
+            .collect();
+
+        Ok((outcomes, changed))
+    }
+
+    /// Assigns `status` to each of the transaction's orders referenced by `refer`,
+    /// returning the stock alterations it requires, i.e. releasing a reservation
+    /// once an order fails.
+    pub fn apply_order_status(
+        &mut self,
+        refer: &str,
+        status: OrderStatus,
+    ) -> Result<Vec<QuantityAlterationIntent>, Error> {
+        let transaction_type = self.transaction_type.clone();
+        let mut intents = vec![];
+
+        for v in self
+            .products
+            .iter_mut()
+            .filter(|order| order.reference == refer)
+        {
+            let mut status = status.clone();
+
+            if let OrderStatus::Transit(transit) = &mut status {
+                transit.populate_query_url();
+
+                let origin = &v.origin.contact.address;
+                let destination = &v.destination.contact.address;
+
+                if transit.estimated_delivery.is_none()
+                    && origin.is_geocoded()
+                    && destination.is_geocoded()
+                {
+                    let distance = point!(x: origin.lon, y: origin.lat)
+                        .haversine_distance(&point!(x: destination.lon, y: destination.lat));
+
+                    transit.estimate_delivery(distance / 1000.0, Utc::now());
+                }
+            }
+
+            let new_status = v.next_status(status)?;
+
+            let reservation = match new_status.status {
+                OrderStatus::Fulfilled(_) => Some(Reservation::Fulfil),
+                OrderStatus::Failed(_) => Some(Reservation::Release),
+                _ => None,
+            };
+
+            if let (true, Some(reservation)) = (v.stock_reserved, reservation) {
+                intents.extend(QuantityAlterationIntent::for_order(
+                    v,
+                    transaction_type.clone(),
+                    Some(reservation),
+                ));
+                v.stock_reserved = false;
+            }
+
+            v.assign_status(new_status, "Supered Update".to_string());
+        }
+
+        Ok(intents)
+    }
+
+    /// Cancels an order which is yet to ship, marking it as failed for the given `reason`.
+    /// Reserved stock is released, whilst stock already removed for the order is returned
+    /// to its origin, within the same database transaction as the cancellation. Cancelling
+    /// an order which has already failed does nothing.
+    pub async fn cancel_order(
+        transaction_id: &str,
+        order_id: &str,
+        reason: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        let mut transaction = Transaction::fetch_by_id(transaction_id, session.clone(), db).await?;
+        let transaction_type = transaction.transaction_type.clone();
+
+        let order = transaction
+            .products
+            .iter_mut()
+            .find(|order| order.id == order_id)
+            .ok_or_else(|| ErrorResponse::not_found("Order", order_id))?;
+
+        match order.status.status {
+            OrderStatus::Failed(_) => return Ok(transaction),
+            OrderStatus::Transit(_)
+            | OrderStatus::PartiallyFulfilled(_)
+            | OrderStatus::Fulfilled(_) => {
+                return Err(ErrorResponse::custom_input_error(
+                    "Unable to cancel an order which has already shipped or been fulfilled.",
+                ))
+            }
+            _ => {}
+        }
+
+        let intents = if order.stock_reserved {
+            QuantityAlterationIntent::for_order(order, transaction_type, Some(Reservation::Release))
+        } else if matches!(transaction_type, TransactionType::Out) {
+            QuantityAlterationIntent::for_order(order, TransactionType::In, None)
+        } else {
+            vec![]
+        };
+
+        let status = OrderStatusAssignment {
+            status: OrderStatus::Failed(reason.to_string()),
+            assigned_products: order.products.iter().map(|el| el.id.clone()).collect(),
+            timestamp: Utc::now(),
+        };
+
+        order.stock_reserved = false;
+        order.status = status.clone();
+        order.status_history.push(History {
+            item: status,
+            reason: format!("Cancelled by {}", session.employee.id),
+            timestamp: order.status.timestamp,
+        });
+
+        Self::update_with_stock(transaction, &intents, session, db).await
+    }
+
+    /// Credits the transaction to `employee_id`, who must belong to the same tenant,
+    /// keeping a note of the previous salesperson and the `reason` for the change.
+    pub async fn reassign_salesperson(
+        id: &str,
+        employee_id: &str,
+        reason: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        let mut transaction = Transaction::fetch_by_id(id, session.clone(), db).await?;
+
+        if transaction.salesperson == employee_id {
+            return Ok(transaction);
+        }
+
+        if let Err(err) = Employee::fetch_by_id(employee_id, session.clone(), db).await {
+            return Err(match err {
+                Error::NotFound(_) => ErrorResponse::validation_errors(vec![FieldError::new(
+                    "employee_id",
+                    &format!("No employee {} exists.", employee_id),
+                )]),
+                err => err,
+            });
+        }
 
-        transaction.products = new_orders;
+        transaction.order_notes.push(Note::new(
+            format!(
+                "Salesperson reassigned from {} to {}: {}",
+                transaction.salesperson, employee_id, reason
+            ),
+            session.employee.id.clone(),
+        ));
+        transaction.salesperson = employee_id.to_string();
 
         Self::update_value(transaction, session, id, db).await
     }
 
+    /// Adds a note by the session's employee to an order, named by its reference
+    /// or its id, replying to another of the order's notes when the input names
+    /// a `parent_id`.
+    pub async fn add_order_note(
+        transaction_id: &str,
+        order_ref: &str,
+        input: NoteInput,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        let mut transaction = Transaction::fetch_by_id(transaction_id, session.clone(), db).await?;
+        let parent_id = input.parent_id.clone();
+
+        let order = transaction
+            .products
+            .iter_mut()
+            .find(|order| order.reference == order_ref || order.id == order_ref)
+            .ok_or_else(|| ErrorResponse::not_found("Order", order_ref))?;
+
+        if order.add_note(input, session.employee.id.clone()).is_none() {
+            return Err(ErrorResponse::not_found(
+                "Note",
+                &parent_id.unwrap_or_default(),
+            ));
+        }
+
+        Self::update_value(transaction, session, transaction_id, db).await
+    }
+
+    /// The notes of the order `order_id` within the transaction, or of the
+    /// transaction itself when no order is given.
+    fn notes_mut(&mut self, order_id: Option<&str>) -> Result<&mut NoteList, Error> {
+        match order_id {
+            Some(order_id) => self
+                .products
+                .iter_mut()
+                .find(|order| order.id == order_id)
+                .map(|order| &mut order.order_notes)
+                .ok_or_else(|| ErrorResponse::not_found("Order", order_id)),
+            None => Ok(&mut self.order_notes),
+        }
+    }
+
+    /// Applies `change` to the note `note_id`, provided the session may change it.
+    async fn change_note<F>(
+        transaction_id: &str,
+        order_id: Option<&str>,
+        note_id: &str,
+        session: Session,
+        db: &DbConn,
+        change: F,
+    ) -> Result<Transaction, Error>
+    where
+        F: FnOnce(&mut Note, Id) -> Result<(), Error>,
+    {
+        let mut transaction = Transaction::fetch_by_id(transaction_id, session.clone(), db).await?;
+
+        let note = transaction
+            .notes_mut(order_id)?
+            .iter_mut()
+            .find(|note| note.id == note_id)
+            .ok_or_else(|| ErrorResponse::not_found("Note", note_id))?;
+
+        if !note.may_change(&session) {
+            return Err(ErrorResponse::custom_unauthorized(
+                "Only the author of a note, or a manager, may change it.",
+            ));
+        }
+
+        change(note, session.employee.id.clone())?;
+
+        Self::update_value(transaction, session, transaction_id, db).await
+    }
+
+    /// Rewords a note of the transaction, or of one of its orders, keeping its
+    /// previous wording in the note's edits.
+    pub async fn edit_note(
+        transaction_id: &str,
+        order_id: Option<&str>,
+        note_id: &str,
+        message: String,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        Self::change_note(
+            transaction_id,
+            order_id,
+            note_id,
+            session,
+            db,
+            |note, editor| note.edit(message, editor),
+        )
+        .await
+    }
+
+    /// Removes a note of the transaction, or of one of its orders. The note is
+    /// kept, reading `[removed]`, so its history and replies remain.
+    pub async fn delete_note(
+        transaction_id: &str,
+        order_id: Option<&str>,
+        note_id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        Self::change_note(
+            transaction_id,
+            order_id,
+            note_id,
+            session,
+            db,
+            |note, editor| note.remove(editor),
+        )
+        .await
+    }
+
+    /// Attaches a file to the order `order_id` within the transaction, or to the
+    /// transaction itself when no order is given, up to [`MAX_ATTACHMENTS`] each.
+    pub async fn add_attachment(
+        transaction_id: &str,
+        order_id: Option<&str>,
+        input: AttachmentInput,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        let mut transaction = Transaction::fetch_by_id(transaction_id, session.clone(), db).await?;
+
+        let attachments = match order_id {
+            Some(order_id) => transaction
+                .products
+                .iter_mut()
+                .find(|order| order.id == order_id)
+                .map(|order| &mut order.attachments)
+                .ok_or_else(|| ErrorResponse::not_found("Order", order_id))?,
+            None => &mut transaction.attachments,
+        };
+
+        if attachments.len() >= MAX_ATTACHMENTS {
+            return Err(ErrorResponse::custom_input_error(&format!(
+                "No more than {} files may be attached.",
+                MAX_ATTACHMENTS
+            )));
+        }
+
+        attachments.push(Attachment {
+            id: Uuid::new_v4().to_string(),
+            url: input.url,
+            content_type: input.content_type.to_lowercase(),
+            size: input.size,
+            uploaded_by: session.employee.id.clone(),
+            timestamp: Utc::now(),
+        });
+
+        Self::update_value(transaction, session, transaction_id, db).await
+    }
+
     pub async fn update_product_status(
         id: &str,
         update: ProductStatusUpdate,
@@ -415,49 +1757,17 @@ impl Transaction {
     ) -> Result<Transaction, Error> {
         let mut transaction = Transaction::fetch_by_id(id, session.clone(), db).await?;
 
-        let new_orders = transaction
-            .clone()
+        for order in transaction
             .products
-            .into_iter()
-            .map(|mut v| {
-                if v.reference == update.transaction_id {
-                    let new_products = v
-                        .products
-                        .into_iter()
-                        .map(|mut p| {
-                            if p.id == update.product_purchase_id {
-                                p.instances = p
-                                    .instances
-                                    .into_iter()
-                                    .map(|mut i| {
-                                        if i.id == update.product_instance_id {
-                                            i.fulfillment_status.pick_history.push(History {
-                                                item: i.fulfillment_status.pick_status,
-                                                reason: "Standard Update Bump".to_string(),
-                                                timestamp: i.fulfillment_status.last_updated,
-                                            });
-                                            i.fulfillment_status.last_updated = Utc::now();
-                                            i.fulfillment_status.pick_status =
-                                                update.new_status.clone();
-                                        }
-
-                                        i
-                                    })
-                                    .collect::<Vec<ProductInstance>>();
-                            }
-
-                            p
-                        })
-                        .collect();
-
-                    v.products = new_products;
-                }
-
-                v
-            })
-            .collect::<Vec<Order>>();
-
-        transaction.products = new_orders;
+            .iter_mut()
+            .filter(|order| order.reference == update.transaction_id)
+        {
+            order.pick(
+                &update.product_purchase_id,
+                &update.product_instance_id,
+                update.new_status.clone(),
+            );
+        }
 
         Self::update_value(transaction, session, id, db).await
     }
@@ -471,120 +1781,347 @@ impl Transaction {
         let tsn = example_transaction(customer_id);
 
         // Insert & Fetch Transaction
-        match Transaction::insert(tsn, session.clone(), db).await {
-            Ok(data) => match Transaction::fetch_by_id(&data.last_insert_id, session, db).await {
-                Ok(res) => Ok(res),
-                Err(e) => Err(e),
-            },
-            Err(e) => Err(e),
-        }
+        let data = Transaction::insert(tsn, session.clone(), db).await?;
+        Transaction::fetch_by_id(&data.last_insert_id, session, db).await
     }
 
+    /// Applies the stock alterations, grouped by product such that each product is
+    /// fetched and written once however many lines of it the intents cover. A cart
+    /// of 50 lines across 10 products therefore takes 20 round trips rather than 100,
+    /// and lines of the same product can no longer overwrite one another's changes.
+    /// Products are processed concurrently, each within its own database transaction,
+    /// returning the products as altered. Should any product's alteration fail, i.e.
+    /// for taking its stock below zero, its error is returned naming the sku, whilst
+    /// the other products remain altered. Alterations which must be written alongside
+    /// other writes are applied by `Transaction::alter_stock_within` instead.
     pub async fn process_intents(
         session: Session,
         db: &DbConn,
         intents: Vec<QuantityAlterationIntent>,
-    ) -> Vec<Result<Product, JoinError>> {
-        let intent_processor = intents
-            .iter()
-            .map(|intent| async {
-                let intent = intent.clone();
-                let database = db.clone();
-                let session = session.clone();
-
-                println!("{}", session.employee);
-
-                tokio::spawn(async move {
-                    let db_ = database.clone();
-                    let session_clone = session.clone();
-
-                    match Product::fetch_by_id(&intent.product_sku, session.clone(), &db_).await {
-                        Ok(mut val) => {
-                            let variants: Vec<VariantInformation> = val
-                                .variants
-                                .iter_mut()
-                                .map(|var| {
-                                    let stock_info: Vec<Stock> = if var.barcode
-                                        == intent.variant_code
-                                    {
-                                        var.stock
-                                            .iter_mut()
-                                            .map(|stock| {
-                                                if stock.store.store_code
-                                                    == intent.transaction_store_code
-                                                {
-                                                    match intent.transaction_type {
-                                                        TransactionType::In => {
-                                                            stock.quantity.quantity_sellable +=
-                                                                intent.quantity_to_transact
-                                                        }
-                                                        TransactionType::Out => {
-                                                            stock.quantity.quantity_sellable -=
-                                                                intent.quantity_to_transact
-                                                        }
-                                                        TransactionType::PendingIn => {
-                                                            stock.quantity.quantity_on_order +=
-                                                                intent.quantity_to_transact
-                                                        }
-                                                        TransactionType::PendingOut => {
-                                                            stock.quantity.quantity_allocated +=
-                                                                intent.quantity_to_transact
-                                                        }
-                                                        TransactionType::Saved => {
-                                                            // A saved transaction should not be processed, but should be shifted into a specified IN or OUT variant.
-                                                            // As this should never happen, the modified changes are left alone.
-                                                            stock.quantity.quantity_allocated += 0.0
-                                                        }
-                                                        TransactionType::Quote => {
-                                                            // A saved transaction should not be processed, but should be shifted into a specified IN or OUT variant.
-                                                            // As this should never happen, the modified changes are left alone.
-                                                            stock.quantity.quantity_allocated += 0.0
-                                                        }
-                                                    }
-                                                }
-
-                                                stock.clone()
-                                            })
-                                            .collect::<Vec<Stock>>()
-                                    } else {
-                                        var.stock.clone()
-                                    };
-
-                                    var.stock = stock_info;
-                                    var.clone()
-                                })
-                                .collect::<Vec<VariantInformation>>();
-
-                            val.variants = variants;
-
-                            // Possible chance for an alternate client to have a modification during this time-frame, try implementing a queued solution.
-                            match Product::update(val, session_clone, &intent.product_sku, &db_)
-                                .await
-                            {
-                                Ok(val) => Ok(val),
-                                Err(_) => Err(DbErr::Custom(String::new())),
-                            }
-                        }
-                        Err(_) => Err(DbErr::Custom(String::new())),
-                    }
-                    .unwrap()
+    ) -> Result<Vec<Product>, Error> {
+        let mut batches: Vec<(String, Vec<QuantityAlterationIntent>)> = vec![];
+
+        for intent in intents {
+            match batches
+                .iter_mut()
+                .find(|(sku, _)| *sku == intent.product_sku)
+            {
+                Some((_, batch)) => batch.push(intent),
+                None => batches.push((intent.product_sku.clone(), vec![intent])),
+            }
+        }
+
+        let batch_processor = batches.into_iter().map(|(sku, batch)| {
+            let database = db.clone();
+            let session = session.clone();
+
+            debug!(
+                "Processing {} stock intent(s) for {}, employee={} tenant={}",
+                batch.len(),
+                sku,
+                session.employee.id,
+                session.tenant_id
+            );
+
+            let pending = PendingIntent::begin();
+
+            async move {
+                let task_sku = sku.clone();
+                let result = tokio::spawn(async move {
+                    let _pending = pending;
+                    Self::apply_intents(&task_sku, &batch, session, &database).await
                 })
                 .await
-            })
-            .collect::<Vec<_>>();
+                .unwrap_or_else(|err| {
+                    Err(ErrorResponse::create_error(&format!(
+                        "Stock alteration was interrupted: {}",
+                        err
+                    )))
+                });
+
+                if let Err(err) = &result {
+                    warn!("Unable to alter stock of {}: {:?}", sku, err);
+                }
+
+                result
+            }
+        });
 
-        futures::future::join_all(intent_processor).await
+        futures::future::join_all(batch_processor)
+            .await
+            .into_iter()
+            .collect()
     }
 
-    pub async fn delete(id: &str, session: Session, db: &DbConn) -> Result<DeleteResult, Error> {
-        Transactions::delete(transactions::ActiveModel {
-            id: Set(id.to_string()),
-            tenant_id: Set(session.tenant_id),
-            ..Default::default()
+    /// Applies every intent of a single product to its stock, writing it once.
+    /// The product is locked whilst altered, and left unaltered should any intent
+    /// take its stock below zero, see [`Transaction::alter_stock`].
+    async fn apply_intents(
+        sku: &str,
+        intents: &[QuantityAlterationIntent],
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Product, Error> {
+        Product::alter(sku, session, db, |product| {
+            let shortfalls = Self::alter_stock(product, intents, false);
+
+            if !shortfalls.is_empty() {
+                return Err(Self::insufficient_stock(&shortfalls));
+            }
+
+            Ok(())
         })
-        .exec(db)
         .await
-        .map_err(|e| e.into())
+    }
+
+    /// Inserts a sale and applies its stock alterations as one, such that of two
+    /// concurrent sales of the last unit, only one succeeds. Should any alteration
+    /// take stock below zero, neither the sale nor any stock is written, unless
+    /// the sale carries a [`StockOverride`]. Likewise the gift cards paid with are
+    /// debited as the sale is written, which is refused should any card not cover it.
+    pub async fn insert_with_stock(
+        tsn: TransactionInit,
+        intents: &[QuantityAlterationIntent],
+        session: Session,
+        db: &DbConn,
+    ) -> Result<InsertResult<transactions::ActiveModel>, Error> {
+        let allow_negative = tsn.stock_override.is_some();
+
+        let txn = db.begin().await?;
+        let skus = Self::alter_stock_within(intents, allow_negative, &session, &txn).await?;
+
+        let gift_cards = gift_card_payments(&tsn.payment);
+        let res = Self::insert(tsn, session.clone(), &txn).await?;
+
+        for (code, amount) in gift_cards {
+            let redemption = RedemptionInput {
+                amount,
+                transaction_id: Some(res.last_insert_id.clone()),
+            };
+
+            GiftCard::redeem(&code, redemption, session.clone(), &txn).await?;
+        }
+
+        txn.commit().await?;
+
+        for sku in skus.iter() {
+            PRODUCT_CACHE.invalidate(&session.tenant_id, sku);
+        }
+
+        Ok(res)
+    }
+
+    /// Applies the stock alterations as part of `txn`, a database transaction which the
+    /// caller commits alongside its own writes, returning the skus of the products
+    /// altered. Once committed, the caller must invalidate them in the [`PRODUCT_CACHE`].
+    /// Should any alteration take stock below zero, nothing is written and the error
+    /// names each sku short. Stock of a product which cannot be found is left unaltered.
+    pub(crate) async fn alter_stock_within<C: ConnectionTrait>(
+        intents: &[QuantityAlterationIntent],
+        allow_negative: bool,
+        session: &Session,
+        txn: &C,
+    ) -> Result<Vec<String>, Error> {
+        // Products are locked in a consistent order, such that concurrent alterations
+        // of the same products cannot deadlock one another.
+        let mut skus: Vec<String> = intents.iter().map(|i| i.product_sku.clone()).collect();
+        skus.sort();
+        skus.dedup();
+
+        let mut products = vec![];
+        let mut shortfalls = vec![];
+
+        for sku in skus.iter() {
+            match Product::select_for_update(sku, &session.tenant_id)
+                .one(txn)
+                .await?
+            {
+                Some(product) => {
+                    let mut product: Product = product.into();
+                    shortfalls.extend(Self::alter_stock(&mut product, intents, allow_negative));
+                    products.push(product);
+                }
+                None => warn!("Unable to alter stock of {}: product not found", sku),
+            }
+        }
+
+        if !shortfalls.is_empty() {
+            return Err(Self::insufficient_stock(&shortfalls));
+        }
+
+        let mut altered = vec![];
+
+        for product in products {
+            altered.push(product.sku.clone());
+            product.into_active(session.clone()).update(txn).await?;
+        }
+
+        Ok(altered)
+    }
+
+    /// Writes the transaction, as fetched and since changed, together with the stock
+    /// alterations the change requires, such that neither is written without the other.
+    /// Rejected should the transaction have been modified since it was fetched, or any
+    /// alteration take stock below zero.
+    async fn update_with_stock(
+        tsn: Transaction,
+        intents: &[QuantityAlterationIntent],
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Transaction, Error> {
+        let id = tsn.id.clone();
+        let version = tsn.version;
+        let mut model = tsn.into_active(session.tenant_id.clone());
+        model.updated_at = Set(Utc::now().naive_utc());
+
+        let txn = db.begin().await?;
+
+        if !Self::write_versioned(model, version, &id, &session.tenant_id, &txn).await? {
+            return Err(ErrorResponse::conflict(
+                "Transaction was modified by another request, fetch it again before updating.",
+            ));
+        }
+
+        let skus = Self::alter_stock_within(intents, false, &session, &txn).await?;
+        txn.commit().await?;
+
+        for sku in skus.iter() {
+            PRODUCT_CACHE.invalidate(&session.tenant_id, sku);
+        }
+
+        Self::fetch_by_id(&id, session, db).await
+    }
+
+    /// Applies the intents of the product to its stock, returning those refused for
+    /// taking stock below zero, which are left unapplied. Non-diminishing variants,
+    /// i.e. services, are not held in stock so are left unaltered, whilst variants
+    /// which are always available, or any when `allow_negative`, may fall below zero.
+    pub fn alter_stock(
+        product: &mut Product,
+        intents: &[QuantityAlterationIntent],
+        allow_negative: bool,
+    ) -> Vec<StockShortfall> {
+        let mut shortfalls = vec![];
+
+        for intent in intents.iter().filter(|i| i.product_sku == product.sku) {
+            for variant in product
+                .variants
+                .iter_mut()
+                .filter(|v| v.barcode == intent.variant_code)
+                .filter(|v| !v.stock_information.non_diminishing)
+            {
+                let unit = variant.stock_information.unit;
+                let guarded = !allow_negative && !variant.always_available();
+
+                for stock in variant
+                    .stock
+                    .iter_mut()
+                    .filter(|s| s.store.store_code == intent.transaction_store_code)
+                {
+                    match intent.shortfall(&stock.quantity, unit) {
+                        Some(shortfall) if guarded => shortfalls.push(StockShortfall {
+                            product_sku: intent.product_sku.clone(),
+                            variant_code: intent.variant_code.clone(),
+                            store_code: intent.transaction_store_code.clone(),
+                            required: intent.amount(unit).unwrap_or_default(),
+                            shortfall,
+                        }),
+                        _ => intent.apply(&mut stock.quantity, unit),
+                    }
+                }
+            }
+        }
+
+        shortfalls
+    }
+
+    fn insufficient_stock(shortfalls: &[StockShortfall]) -> Error {
+        ErrorResponse::custom_input_error(&format!(
+            "Insufficient stock for {}.",
+            shortfalls
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+                .join("; ")
+        ))
+    }
+
+    /// Marks the transaction as deleted, it remains in the database so that its
+    /// history is retained, but is excluded from all fetches.
+    pub async fn delete(id: &str, session: Session, db: &DbConn) -> Result<(), Error> {
+        let tsn = Self::fetch_by_id(id, session.clone(), db).await?;
+
+        Transactions::update_many()
+            .col_expr(
+                transactions::Column::DeletedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .col_expr(
+                transactions::Column::UpdatedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .col_expr(transactions::Column::Version, Expr::value(tsn.version + 1))
+            .filter(transactions::Column::Id.eq(id))
+            .filter(transactions::Column::TenantId.eq(session.tenant_id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The transaction with the given id whether or not it has been deleted, as
+    /// read by [`Transaction::restore`].
+    pub fn select_with_deleted(id: &str, tenant_id: &str) -> Select<Transactions> {
+        Transactions::find_by_id(id.to_string())
+            .filter(transactions::Column::TenantId.eq(tenant_id))
+    }
+
+    /// Clears the deletion of a transaction, returning it to regular fetches.
+    /// Restoring a transaction which was never deleted leaves it unchanged.
+    pub async fn restore(id: &str, session: Session, db: &DbConn) -> Result<Transaction, Error> {
+        let tsn = Self::select_with_deleted(id, &session.tenant_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ErrorResponse::not_found("Transaction", id))?;
+
+        if tsn.deleted_at.is_none() {
+            return Ok(tsn.into());
+        }
+
+        Transactions::update_many()
+            .col_expr(
+                transactions::Column::DeletedAt,
+                Expr::value(Option::<NaiveDateTime>::None),
+            )
+            .col_expr(
+                transactions::Column::UpdatedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .col_expr(transactions::Column::Version, Expr::value(tsn.version + 1))
+            .filter(transactions::Column::Id.eq(id))
+            .filter(transactions::Column::TenantId.eq(session.tenant_id.clone()))
+            .exec(db)
+            .await?;
+
+        Self::fetch_by_id(id, session, db).await
+    }
+
+    /// Permanently removes the transaction, regardless of whether it has been deleted.
+    pub async fn purge(id: &str, session: Session, db: &DbConn) -> Result<DeleteResult, Error> {
+        let res = Self::delete_purged(id, &session.tenant_id).exec(db).await?;
+
+        if res.rows_affected == 0 {
+            return Err(ErrorResponse::not_found("Transaction", id));
+        }
+
+        Ok(res)
+    }
+
+    /// The deletion of [`Transaction::purge`], of the tenant's transaction only.
+    pub fn delete_purged(id: &str, tenant_id: &str) -> DeleteMany<Transactions> {
+        Transactions::delete_many()
+            .filter(transactions::Column::Id.eq(id))
+            .filter(transactions::Column::TenantId.eq(tenant_id))
     }
 }
 
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::methods::{Id, Order, OrderStatus, RoundingMode, Store, Transaction, TransactionType};
+
+/// The trade of a store over a single day, see [`Transaction::daily_summary`].
+///
+/// Days are bounded by the store's local midnight, such that a sale made late in
+/// the evening counts toward the day it was made on locally rather than in UTC.
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DailySummary {
+    pub store_id: Id,
+    /// The day summarised, in the store's local time.
+    pub date: NaiveDate,
+    /// The instant the day began.
+    pub from: DateTime<Utc>,
+    /// The instant the day ended, exclusive.
+    pub to: DateTime<Utc>,
+    pub sales: usize,
+    pub refunds: usize,
+    /// Money taken over the day, less that refunded.
+    pub takings: f32,
+}
+
+impl DailySummary {
+    /// Summarises those of `transactions` made on the local `date` of `store`,
+    /// ignoring any made on other days.
+    pub fn new(store: &Store, date: NaiveDate, transactions: &[Transaction]) -> Self {
+        let (from, to) = Self::bounds(store, date);
+
+        let on_day: Vec<&Transaction> = transactions
+            .iter()
+            .filter(|t| store.local_time(t.order_date).date() == date)
+            .collect();
+
+        DailySummary {
+            store_id: store.id.clone(),
+            date,
+            from,
+            to,
+            sales: on_day
+                .iter()
+                .filter(|t| matches!(t.transaction_type, TransactionType::Out))
+                .count(),
+            refunds: on_day
+                .iter()
+                .filter(|t| matches!(t.transaction_type, TransactionType::In))
+                .count(),
+            takings: on_day
+                .iter()
+                .filter(|t| {
+                    matches!(
+                        t.transaction_type,
+                        TransactionType::Out | TransactionType::In
+                    )
+                })
+                .flat_map(|t| t.payment.iter())
+                .map(|p| p.amount.quantity)
+                .sum(),
+        }
+    }
+
+    /// The instants at which the local `date` of `store` begins and ends.
+    pub fn bounds(store: &Store, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+
+        // Days on which clocks change are an hour longer or shorter than others.
+        (
+            store.to_utc(midnight),
+            store.to_utc(midnight + Duration::days(1)),
+        )
+    }
+}
+
+/// The sales of a single product over a period, see [`Transaction::top_products`].
+#[cfg(feature = "types")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProductSales {
+    pub product_sku: String,
+    pub product_name: String,
+    pub quantity: f32,
+    /// Money taken for the product after line discounts, before those of the order.
+    pub revenue: f32,
+}
+
+/// Tallies the sales of each product as orders are added, such that a period can
+/// be summarised in a single pass over its transactions.
+#[cfg(feature = "types")]
+#[derive(Debug, Default)]
+pub struct ProductSalesTally {
+    sales: HashMap<String, ProductSales>,
+}
+
+impl ProductSalesTally {
+    /// Adds the lines sold in `order`. Returned lines and cancelled orders are ignored.
+    pub fn add(&mut self, order: &Order, rounding: RoundingMode) {
+        if matches!(order.status.status, OrderStatus::Failed(_)) {
+            return;
+        }
+
+        let breakdown = order.breakdown(rounding);
+
+        for (product, line) in order.products.iter().zip(breakdown.lines.iter()) {
+            if !matches!(product.transaction_type, TransactionType::Out) {
+                continue;
+            }
+
+            let sales = self
+                .sales
+                .entry(product.product_sku.clone())
+                .or_insert_with(|| ProductSales {
+                    product_sku: product.product_sku.clone(),
+                    product_name: product.product_name.clone(),
+                    quantity: 0.0,
+                    revenue: 0.0,
+                });
+
+            sales.quantity += product.quantity;
+            sales.revenue += line.total;
+        }
+    }
+
+    /// The `limit` best selling products by quantity, ties broken by revenue.
+    pub fn ranked(self, limit: usize) -> Vec<ProductSales> {
+        let mut sales: Vec<ProductSales> = self.sales.into_values().collect();
+
+        sales.sort_by(|a, b| {
+            b.quantity
+                .total_cmp(&a.quantity)
+                .then(b.revenue.total_cmp(&a.revenue))
+        });
+        sales.truncate(limit);
+
+        sales
+    }
+}
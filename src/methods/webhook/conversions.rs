@@ -0,0 +1,48 @@
+use crate::entities::webhook::{ActiveModel, Model};
+use crate::entities::webhook_delivery;
+use crate::{Webhook, WebhookDelivery, WebhookEvent, WebhookInput};
+use chrono::{DateTime, Utc};
+use sea_orm::ActiveValue::Set;
+use serde_json::json;
+
+impl From<Model> for Webhook {
+    fn from(val: Model) -> Self {
+        Webhook {
+            id: val.id,
+            url: val.url,
+            events: serde_json::from_value::<Vec<WebhookEvent>>(val.events).unwrap(),
+            created_at: DateTime::from_naive_utc_and_offset(val.created_at, Utc),
+            updated_at: DateTime::from_naive_utc_and_offset(val.updated_at, Utc),
+        }
+    }
+}
+
+impl WebhookInput {
+    pub(crate) fn into_active(self, id: String, tenant_id: String) -> ActiveModel {
+        ActiveModel {
+            id: Set(id),
+            tenant_id: Set(tenant_id),
+            url: Set(self.url),
+            events: Set(json!(self.events)),
+            secret: Set(self.secret),
+            created_at: Set(Utc::now().naive_utc()),
+            updated_at: Set(Utc::now().naive_utc()),
+        }
+    }
+}
+
+impl From<webhook_delivery::Model> for WebhookDelivery {
+    fn from(val: webhook_delivery::Model) -> Self {
+        WebhookDelivery {
+            id: val.id,
+            webhook_id: val.webhook_id,
+            event: serde_json::from_value::<WebhookEvent>(json!(val.event)).unwrap(),
+            payload_id: val.payload_id,
+            attempt: val.attempt,
+            status_code: val.status_code,
+            error: val.error,
+            succeeded: val.succeeded,
+            timestamp: DateTime::from_naive_utc_and_offset(val.timestamp, Utc),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use super::{Webhook, WebhookDelivery, WebhookInput};
+use crate::catchers::Validated;
+use crate::guards::Convert;
+use crate::methods::employee::Action;
+use crate::pool::InternalDb;
+use crate::{check_permissions, Session};
+use okapi::openapi3::OpenApi;
+use rocket::serde::json::Json;
+use rocket::{get, post};
+use rocket_okapi::settings::OpenApiSettings;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
+
+pub fn documented_routes(settings: &OpenApiSettings) -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![settings: get_all, create, get_deliveries]
+}
+
+#[openapi(tag = "Webhook")]
+#[get("/")]
+pub async fn get_all(db: InternalDb, session: Session) -> Convert<Vec<Webhook>> {
+    check_permissions!(session.clone(), Action::FetchWebhook);
+    Webhook::fetch_all(session, &db.0).await.into()
+}
+
+#[openapi(tag = "Webhook")]
+#[post("/", data = "<input_data>")]
+pub async fn create(
+    db: InternalDb,
+    session: Session,
+    input_data: Validated<Json<WebhookInput>>,
+) -> Convert<Webhook> {
+    check_permissions!(session.clone(), Action::CreateWebhook);
+    Webhook::insert(input_data.data(), session, &db.0)
+        .await
+        .into()
+}
+
+#[openapi(tag = "Webhook")]
+#[get("/<id>/deliveries")]
+pub async fn get_deliveries(
+    db: InternalDb,
+    session: Session,
+    id: &str,
+) -> Convert<Vec<WebhookDelivery>> {
+    check_permissions!(session.clone(), Action::FetchWebhook);
+    Webhook::fetch_deliveries(id, session, &db.0).await.into()
+}
@@ -0,0 +1,298 @@
+use std::borrow::Cow;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::{debug, error, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{validate_url, Validate, ValidationError};
+
+#[cfg(feature = "process")]
+use crate::entities::prelude::{Webhook as WebhookEntity, WebhookDelivery as DeliveryEntity};
+#[cfg(feature = "process")]
+use crate::entities::{webhook, webhook_delivery};
+use crate::methods::{Error, Id};
+use crate::{ErrorResponse, Session};
+#[cfg(feature = "process")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "process")]
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DbConn, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+#[cfg(feature = "process")]
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// Header carrying the hex encoded HMAC-SHA256 of the request body, keyed by the webhook's secret.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// Attempts made at delivering a payload before it is abandoned.
+const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+/// Delay before the first retry, doubling with each subsequent attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const DELIVERY_LIMIT: u64 = 100;
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub enum WebhookEvent {
+    TransactionCreated,
+    TransactionVoided,
+    OrderStatusChanged,
+}
+
+/// A registered receiver of events. The secret is write-only and never returned.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct Webhook {
+    pub id: Id,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Validate)]
+pub struct WebhookInput {
+    #[validate(custom = "validate_webhook_url")]
+    pub url: String,
+    #[validate(length(min = 1))]
+    pub events: Vec<WebhookEvent>,
+    /// Used to sign each payload, see [`SIGNATURE_HEADER`].
+    #[validate(length(min = 16))]
+    pub secret: String,
+}
+
+/// The body POSTed to a webhook.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct WebhookPayload<T> {
+    /// Identical across retries, allowing receivers to discard duplicates.
+    pub id: Id,
+    pub event: WebhookEvent,
+    pub timestamp: DateTime<Utc>,
+    pub data: T,
+}
+
+/// A single attempt at delivering a payload.
+#[cfg(feature = "types")]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct WebhookDelivery {
+    pub id: Id,
+    pub webhook_id: Id,
+    pub event: WebhookEvent,
+    pub payload_id: Id,
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+pub fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if (url.starts_with("https://") || url.starts_with("http://")) && validate_url(url) {
+        Ok(())
+    } else {
+        let mut err = ValidationError::new("invalid_webhook_url");
+        err.message = Some(Cow::from(format!(
+            "Webhook URL {} must be an absolute http(s) URL",
+            url
+        )));
+        Err(err)
+    }
+}
+
+#[cfg(feature = "methods")]
+impl Webhook {
+    pub async fn insert(
+        input: WebhookInput,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Webhook, Error> {
+        let id = Uuid::new_v4().to_string();
+
+        WebhookEntity::insert(input.into_active(id.clone(), session.tenant_id.clone()))
+            .exec(db)
+            .await?;
+
+        Self::fetch_by_id(&id, session, db).await
+    }
+
+    pub async fn fetch_by_id(id: &str, session: Session, db: &DbConn) -> Result<Webhook, Error> {
+        let hook = WebhookEntity::find_by_id(id.to_string())
+            .filter(webhook::Column::TenantId.eq(session.tenant_id))
+            .one(db)
+            .await?;
+
+        match hook {
+            Some(h) => Ok(h.into()),
+            None => Err(ErrorResponse::not_found("Webhook", id)),
+        }
+    }
+
+    pub async fn fetch_all(session: Session, db: &DbConn) -> Result<Vec<Webhook>, Error> {
+        let hooks = WebhookEntity::find()
+            .filter(webhook::Column::TenantId.eq(session.tenant_id))
+            .all(db)
+            .await?;
+
+        Ok(hooks.into_iter().map(|h| h.into()).collect())
+    }
+
+    /// Fetches the most recent delivery attempts made to the webhook.
+    pub async fn fetch_deliveries(
+        id: &str,
+        session: Session,
+        db: &DbConn,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        Self::fetch_by_id(id, session.clone(), db).await?;
+
+        let deliveries = DeliveryEntity::find()
+            .filter(webhook_delivery::Column::WebhookId.eq(id))
+            .filter(webhook_delivery::Column::TenantId.eq(session.tenant_id))
+            .order_by_desc(webhook_delivery::Column::Timestamp)
+            .limit(DELIVERY_LIMIT)
+            .all(db)
+            .await?;
+
+        Ok(deliveries.into_iter().map(|d| d.into()).collect())
+    }
+
+    /// Sends `data` to every webhook of the tenant subscribed to `event`.
+    ///
+    /// Deliveries happen in the background so as not to hold up the request,
+    /// each being retried with exponential backoff until it is acknowledged
+    /// with a 2xx response or [`MAX_DELIVERY_ATTEMPTS`] is reached.
+    pub async fn dispatch<T: Serialize>(
+        event: WebhookEvent,
+        data: &T,
+        session: &Session,
+        db: &DbConn,
+    ) {
+        let hooks = match WebhookEntity::find()
+            .filter(webhook::Column::TenantId.eq(session.tenant_id.clone()))
+            .all(db)
+            .await
+        {
+            Ok(hooks) => hooks,
+            Err(err) => {
+                error!("Unable to fetch webhooks for {:?}: {}", event, err);
+                return;
+            }
+        };
+
+        let payload = WebhookPayload {
+            id: Uuid::new_v4().to_string(),
+            event,
+            timestamp: Utc::now(),
+            data,
+        };
+
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(err) => {
+                error!("Unable to serialize {:?} webhook payload: {}", event, err);
+                return;
+            }
+        };
+
+        for hook in hooks {
+            let subscribed = serde_json::from_value::<Vec<WebhookEvent>>(hook.events.clone())
+                .map(|events| events.contains(&event))
+                .unwrap_or(false);
+
+            if subscribed {
+                let db = db.clone();
+                let body = body.clone();
+                let payload_id = payload.id.clone();
+
+                rocket::tokio::spawn(async move {
+                    deliver(hook, event, payload_id, body, db).await;
+                });
+            }
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+async fn deliver(
+    hook: webhook::Model,
+    event: WebhookEvent,
+    payload_id: String,
+    body: String,
+    db: DbConn,
+) {
+    let signature = sign(&hook.secret, &body);
+    let mut backoff = INITIAL_BACKOFF;
+
+    for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+        let request = ureq::AgentBuilder::new()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .post(&hook.url)
+            .set("Content-Type", "application/json")
+            .set(SIGNATURE_HEADER, &signature)
+            .set(EVENT_HEADER, &format!("{:?}", event))
+            .set(DELIVERY_HEADER, &payload_id);
+
+        let sent = body.clone();
+        let (status_code, error) =
+            rocket::tokio::task::spawn_blocking(move || match request.send_string(&sent) {
+                Ok(response) => (Some(response.status()), None),
+                Err(ureq::Error::Status(code, _)) => (Some(code), None),
+                Err(err) => (None, Some(err.to_string())),
+            })
+            .await
+            .unwrap_or_else(|err| (None, Some(err.to_string())));
+
+        let succeeded = matches!(status_code, Some(200..=299));
+
+        let record = webhook_delivery::ActiveModel {
+            id: Set(Uuid::new_v4().to_string()),
+            webhook_id: Set(hook.id.clone()),
+            tenant_id: Set(hook.tenant_id.clone()),
+            event: Set(format!("{:?}", event)),
+            payload_id: Set(payload_id.clone()),
+            attempt: Set(attempt),
+            status_code: Set(status_code.map(i32::from)),
+            error: Set(error),
+            succeeded: Set(succeeded),
+            timestamp: Set(Utc::now().naive_utc()),
+        };
+
+        if let Err(err) = DeliveryEntity::insert(record).exec(&db).await {
+            error!("Unable to record webhook delivery {}: {}", payload_id, err);
+        }
+
+        if succeeded {
+            debug!(
+                "Delivered {:?} webhook {} to {} on attempt {}",
+                event, payload_id, hook.id, attempt
+            );
+            return;
+        }
+
+        if attempt < MAX_DELIVERY_ATTEMPTS {
+            rocket::tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+
+    warn!(
+        "Abandoned {:?} webhook {} to {} after {} attempts",
+        event, payload_id, hook.id, MAX_DELIVERY_ATTEMPTS
+    );
+}
+
+/// Computes the hex encoded HMAC-SHA256 of `body`, prefixed `sha256=`.
+#[cfg(feature = "process")]
+fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
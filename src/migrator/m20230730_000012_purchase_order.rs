@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000012_purchase_order"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PurchaseOrder::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(PurchaseOrder::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PurchaseOrder::TenantId).string().not_null())
+                    .col(
+                        ColumnDef::new(PurchaseOrder::SupplierId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PurchaseOrder::Store).json().not_null())
+                    .col(ColumnDef::new(PurchaseOrder::Items).json().not_null())
+                    .col(ColumnDef::new(PurchaseOrder::Status).json().not_null())
+                    .col(
+                        ColumnDef::new(PurchaseOrder::ExpectedDelivery)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PurchaseOrder::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PurchaseOrder::UpdatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PurchaseOrder::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum PurchaseOrder {
+    #[iden = "PurchaseOrder"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "supplier_id"]
+    SupplierId,
+    #[iden = "store"]
+    Store,
+    #[iden = "items"]
+    Items,
+    #[iden = "status"]
+    Status,
+    #[iden = "expected_delivery"]
+    ExpectedDelivery,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "created_at"]
+    CreatedAt,
+    #[iden = "updated_at"]
+    UpdatedAt,
+}
@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000013_products_barcode_index"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Variant barcodes are nested within the `variants` JSON, a multi-valued
+        // index allows `MEMBER OF` lookups against them without a table scan.
+        db.execute_unprepared(
+            "ALTER TABLE `Products` ADD INDEX barcode_indx((CAST(`variants`->'$[*].barcode' AS CHAR(64) ARRAY)))",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("ALTER TABLE `Products` DROP INDEX barcode_indx")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000014_record_version"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            Versioned::Store,
+            Versioned::Supplier,
+            Versioned::Transactions,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(
+                            ColumnDef::new(Versioned::Version)
+                                .integer()
+                                .not_null()
+                                .default(0),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            Versioned::Store,
+            Versioned::Supplier,
+            Versioned::Transactions,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(Versioned::Version)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+pub enum Versioned {
+    #[iden = "Store"]
+    Store,
+    #[iden = "Supplier"]
+    Supplier,
+    #[iden = "Transactions"]
+    Transactions,
+    #[iden = "version"]
+    Version,
+}
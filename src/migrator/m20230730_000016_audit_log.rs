@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000016_audit_log"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::TenantId).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EmployeeId).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityType).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityId).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Action).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Diff).json().not_null())
+                    .col(ColumnDef::new(AuditLog::Timestamp).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("audit_log_entity_indx")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::TenantId)
+                    .col(AuditLog::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum AuditLog {
+    #[iden = "AuditLog"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "employee_id"]
+    EmployeeId,
+    #[iden = "entity_type"]
+    EntityType,
+    #[iden = "entity_id"]
+    EntityId,
+    #[iden = "action"]
+    Action,
+    #[iden = "diff"]
+    Diff,
+    #[iden = "timestamp"]
+    Timestamp,
+}
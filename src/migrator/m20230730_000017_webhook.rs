@@ -0,0 +1,149 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000017_webhook"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhook::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(Webhook::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Webhook::TenantId).string().not_null())
+                    .col(ColumnDef::new(Webhook::Url).text().not_null())
+                    .col(ColumnDef::new(Webhook::Events).json().not_null())
+                    .col(ColumnDef::new(Webhook::Secret).string().not_null())
+                    .col(ColumnDef::new(Webhook::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(Webhook::UpdatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::WebhookId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::TenantId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::Event).string().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::PayloadId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempt)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::StatusCode).integer().null())
+                    .col(ColumnDef::new(WebhookDelivery::Error).text().null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Succeeded)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Timestamp)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("webhook_delivery_webhook_indx")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::WebhookId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Webhook::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Webhook {
+    #[iden = "Webhook"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "url"]
+    Url,
+    #[iden = "events"]
+    Events,
+    #[iden = "secret"]
+    Secret,
+    #[iden = "created_at"]
+    CreatedAt,
+    #[iden = "updated_at"]
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum WebhookDelivery {
+    #[iden = "WebhookDelivery"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "webhook_id"]
+    WebhookId,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "event"]
+    Event,
+    #[iden = "payload_id"]
+    PayloadId,
+    #[iden = "attempt"]
+    Attempt,
+    #[iden = "status_code"]
+    StatusCode,
+    #[iden = "error"]
+    Error,
+    #[iden = "succeeded"]
+    Succeeded,
+    #[iden = "timestamp"]
+    Timestamp,
+}
@@ -0,0 +1,103 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+use crate::methods::ContactInformation;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000018_supplier_phone"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Supplier::Table)
+                    .add_column(
+                        ColumnDef::new(Supplier::Phone)
+                            .string_len(32)
+                            .not_null()
+                            .default(""),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("supplier_phone_indx")
+                    .table(Supplier::Table)
+                    .col(Supplier::TenantId)
+                    .col(Supplier::Phone)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Normalisation follows the rules of `verify_phone_number_*`, which are
+        // not expressible in SQL, so existing suppliers are backfilled here.
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        let rows = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT `id`, `contact` FROM `Supplier`".to_string(),
+            ))
+            .await?;
+
+        for row in rows {
+            let id: String = row.try_get("", "id")?;
+            let contact: serde_json::Value = row.try_get("", "contact")?;
+
+            let phone = match serde_json::from_value::<ContactInformation>(contact) {
+                Ok(contact) => contact.mobile.normalized(),
+                Err(_) => continue,
+            };
+
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "UPDATE `Supplier` SET `phone` = ? WHERE `id` = ?",
+                [phone.into(), id.into()],
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("supplier_phone_indx")
+                    .table(Supplier::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Supplier::Table)
+                    .drop_column(Supplier::Phone)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Supplier {
+    #[iden = "Supplier"]
+    Table,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "phone"]
+    Phone,
+}
@@ -0,0 +1,156 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000023_gift_card"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GiftCard::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(GiftCard::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(GiftCard::TenantId).string().not_null())
+                    .col(ColumnDef::new(GiftCard::Code).string().not_null())
+                    .col(ColumnDef::new(GiftCard::Balance).big_integer().not_null())
+                    .col(ColumnDef::new(GiftCard::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(GiftCard::UpdatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("gift_card_code_indx")
+                    .table(GiftCard::Table)
+                    .col(GiftCard::TenantId)
+                    .col(GiftCard::Code)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(GiftCardRedemption::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::TenantId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::GiftCardId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::TransactionId)
+                            .string()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::EmployeeId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::Amount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::BalanceAfter)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(GiftCardRedemption::Timestamp)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("gift_card_redemption_card_indx")
+                    .table(GiftCardRedemption::Table)
+                    .col(GiftCardRedemption::GiftCardId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GiftCardRedemption::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(GiftCard::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum GiftCard {
+    #[iden = "GiftCard"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "code"]
+    Code,
+    #[iden = "balance"]
+    Balance,
+    #[iden = "created_at"]
+    CreatedAt,
+    #[iden = "updated_at"]
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+pub enum GiftCardRedemption {
+    #[iden = "GiftCardRedemption"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "gift_card_id"]
+    GiftCardId,
+    #[iden = "transaction_id"]
+    TransactionId,
+    #[iden = "employee_id"]
+    EmployeeId,
+    #[iden = "amount"]
+    Amount,
+    #[iden = "balance_after"]
+    BalanceAfter,
+    #[iden = "timestamp"]
+    Timestamp,
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000026_stocktake"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Stocktake::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(Stocktake::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Stocktake::TenantId).string().not_null())
+                    .col(ColumnDef::new(Stocktake::StoreId).string().not_null())
+                    .col(ColumnDef::new(Stocktake::Counts).json().not_null())
+                    .col(ColumnDef::new(Stocktake::Report).json().null())
+                    .col(ColumnDef::new(Stocktake::StartedBy).string().not_null())
+                    .col(ColumnDef::new(Stocktake::StartedAt).date_time().not_null())
+                    .col(ColumnDef::new(Stocktake::AppliedAt).date_time().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("stocktake_store_indx")
+                    .table(Stocktake::Table)
+                    .col(Stocktake::TenantId)
+                    .col(Stocktake::StoreId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Stocktake::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Stocktake {
+    #[iden = "Stocktake"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "store_id"]
+    StoreId,
+    #[iden = "counts"]
+    Counts,
+    #[iden = "report"]
+    Report,
+    #[iden = "started_by"]
+    StartedBy,
+    #[iden = "started_at"]
+    StartedAt,
+    #[iden = "applied_at"]
+    AppliedAt,
+}
@@ -0,0 +1,40 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000027_session_key_hash"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keys are stored as their hash, see `Session::hash_key`, which existing
+        // sessions are brought in line with so that they remain signed in.
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                manager.get_database_backend(),
+                "UPDATE `Session` SET `key` = SHA2(`key`, 256)".to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // A hashed key cannot be recovered, so every session is signed out.
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                manager.get_database_backend(),
+                "DELETE FROM `Session`".to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
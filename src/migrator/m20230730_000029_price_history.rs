@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m20230730_000029_price_history"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PriceHistory::Table)
+                    .engine("InnoDB".to_string())
+                    .col(
+                        ColumnDef::new(PriceHistory::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PriceHistory::TenantId).string().not_null())
+                    .col(ColumnDef::new(PriceHistory::ProductSku).string().not_null())
+                    .col(
+                        ColumnDef::new(PriceHistory::VariantCode)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceHistory::OldPrice)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PriceHistory::NewPrice)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PriceHistory::ChangedBy).string().not_null())
+                    .col(
+                        ColumnDef::new(PriceHistory::ChangedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("price_history_product_indx")
+                    .table(PriceHistory::Table)
+                    .col(PriceHistory::TenantId)
+                    .col(PriceHistory::ProductSku)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PriceHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum PriceHistory {
+    #[iden = "PriceHistory"]
+    Table,
+    #[iden = "id"]
+    Id,
+    #[iden = "tenant_id"]
+    TenantId,
+    #[iden = "product_sku"]
+    ProductSku,
+    #[iden = "variant_code"]
+    VariantCode,
+    #[iden = "old_price"]
+    OldPrice,
+    #[iden = "new_price"]
+    NewPrice,
+    #[iden = "changed_by"]
+    ChangedBy,
+    #[iden = "changed_at"]
+    ChangedAt,
+}
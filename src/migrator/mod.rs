@@ -11,6 +11,24 @@ mod m20230730_000008_promotion;
 mod m20230730_000009_kiosk;
 mod m20230730_000010_authrec;
 mod m20230730_000011_tenants;
+mod m20230730_000012_purchase_order;
+mod m20230730_000013_products_barcode_index;
+mod m20230730_000014_record_version;
+mod m20230730_000015_transaction_deleted_at;
+mod m20230730_000016_audit_log;
+mod m20230730_000017_webhook;
+mod m20230730_000018_supplier_phone;
+mod m20230730_000019_transaction_attachments;
+mod m20230730_000020_employee_stores;
+mod m20230730_000021_store_hours;
+mod m20230730_000022_store_timezone;
+mod m20230730_000023_gift_card;
+mod m20230730_000024_transaction_change;
+mod m20230730_000025_store_layout;
+mod m20230730_000026_stocktake;
+mod m20230730_000027_session_key_hash;
+mod m20230730_000028_session_created_at;
+mod m20230730_000029_price_history;
 
 pub struct Migrator;
 
@@ -29,6 +47,24 @@ impl MigratorTrait for Migrator {
             Box::new(m20230730_000009_kiosk::Migration),
             Box::new(m20230730_000010_authrec::Migration),
             Box::new(m20230730_000011_tenants::Migration),
+            Box::new(m20230730_000012_purchase_order::Migration),
+            Box::new(m20230730_000013_products_barcode_index::Migration),
+            Box::new(m20230730_000014_record_version::Migration),
+            Box::new(m20230730_000015_transaction_deleted_at::Migration),
+            Box::new(m20230730_000016_audit_log::Migration),
+            Box::new(m20230730_000017_webhook::Migration),
+            Box::new(m20230730_000018_supplier_phone::Migration),
+            Box::new(m20230730_000019_transaction_attachments::Migration),
+            Box::new(m20230730_000020_employee_stores::Migration),
+            Box::new(m20230730_000021_store_hours::Migration),
+            Box::new(m20230730_000022_store_timezone::Migration),
+            Box::new(m20230730_000023_gift_card::Migration),
+            Box::new(m20230730_000024_transaction_change::Migration),
+            Box::new(m20230730_000025_store_layout::Migration),
+            Box::new(m20230730_000026_stocktake::Migration),
+            Box::new(m20230730_000027_session_key_hash::Migration),
+            Box::new(m20230730_000028_session_created_at::Migration),
+            Box::new(m20230730_000029_price_history::Migration),
         ]
     }
 }
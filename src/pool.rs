@@ -9,6 +9,7 @@ use async_trait::async_trait;
 use chrono::{Days, Duration as ChronoDuration, Utc};
 #[cfg(feature = "process")]
 use dotenv::dotenv;
+use log::{debug, error, info, warn};
 use rocket::request;
 use rocket::request::{FromRequest, Outcome};
 #[cfg(feature = "process")]
@@ -24,7 +25,6 @@ use sea_orm_migration::prelude::*;
 #[cfg(feature = "process")]
 use sea_orm_rocket::rocket::figment::Figment;
 use std::{env, fs, sync::Arc, time::Duration};
-use serde::de::Error;
 #[cfg(feature = "process")]
 use tokio::sync::Mutex;
 
@@ -33,6 +33,87 @@ use tokio::sync::Mutex;
 #[database("stock")]
 pub struct Db(RocketDbPool);
 
+/// Sizing and timeouts of the database connection pool, each read from the
+/// environment when the pool is created:
+///
+/// - `DATABASE_MAX_CONNECTIONS`, connections held at most, default `10`.
+/// - `DATABASE_MIN_CONNECTIONS`, connections kept open when idle, default `1`.
+/// - `DATABASE_ACQUIRE_TIMEOUT`, seconds a request waits for a free connection
+///   before failing with `503 Service Unavailable`, default `5`.
+/// - `DATABASE_IDLE_TIMEOUT`, seconds an unused connection is kept, default `600`.
+/// - `DATABASE_CONNECT_TIMEOUT`, seconds allowed to open a connection, default `10`.
+#[cfg(feature = "process")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout: Duration,
+    pub idle_timeout: Duration,
+    pub connect_timeout: Duration,
+}
+
+#[cfg(feature = "process")]
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            min_connections: 1,
+            acquire_timeout: Duration::from_secs(5),
+            idle_timeout: Duration::from_secs(600),
+            connect_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+impl PoolConfig {
+    /// Reads the configuration from the environment, falling back to the default
+    /// of any value which is absent or malformed.
+    pub fn from_env() -> Self {
+        fn read<T: std::str::FromStr>(key: &str) -> Option<T> {
+            let value = env::var(key).ok()?;
+
+            match value.parse() {
+                Ok(parsed) => Some(parsed),
+                Err(_) => {
+                    warn!("Ignoring malformed {}={}, using the default", key, value);
+                    None
+                }
+            }
+        }
+
+        let default = Self::default();
+        let max_connections = read("DATABASE_MAX_CONNECTIONS")
+            .unwrap_or(default.max_connections)
+            .max(1);
+
+        Self {
+            max_connections,
+            min_connections: read("DATABASE_MIN_CONNECTIONS")
+                .unwrap_or(default.min_connections)
+                .min(max_connections),
+            acquire_timeout: read("DATABASE_ACQUIRE_TIMEOUT")
+                .map(Duration::from_secs)
+                .unwrap_or(default.acquire_timeout),
+            idle_timeout: read("DATABASE_IDLE_TIMEOUT")
+                .map(Duration::from_secs)
+                .unwrap_or(default.idle_timeout),
+            connect_timeout: read("DATABASE_CONNECT_TIMEOUT")
+                .map(Duration::from_secs)
+                .unwrap_or(default.connect_timeout),
+        }
+    }
+
+    pub fn apply(&self, options: &mut ConnectOptions) {
+        options
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+            .connect_timeout(self.connect_timeout);
+    }
+}
+
 #[rocket::async_trait]
 impl<'a> FromRequest<'a> for RocketDbPool {
     type Error = &'static str;
@@ -102,13 +183,11 @@ impl rocket_db_pools::Pool for RocketDbPool {
             }
         };
 
-        println!("Database URL: {}", database_url);
+        let config = PoolConfig::from_env();
+        info!("Connecting to database with {:?}", config);
 
         let mut options = ConnectOptions::new(database_url);
-        options.idle_timeout(Duration::new(3600, 0));
-        options.acquire_timeout(Duration::new(3600, 0));
-        options.connect_timeout(Duration::new(3600, 0));
-        options.min_connections(1);
+        config.apply(&mut options);
         options.sqlx_logging(false);
 
         let conn = sea_orm::Database::connect(options).await?;
@@ -171,7 +250,7 @@ pub async fn session_ingress_worker(db: &DbConn) {
                                 // As we don't want to infinitely ingest the file,
                                 // if it cannot be deleted we shall preserve it as
                                 // continually being in the "currently_ingesting" state.
-                                println!("Failed to remove file after ingest; {}", error)
+                                error!("Failed to remove file {} after ingest: {}", file, error)
                             }
                         }
                     });
@@ -187,7 +266,7 @@ pub async fn ingest_file(db: &DbConn, file_path: String) {
     let to_ingest = fs::read_to_string(file_path.clone());
 
     if let Err(error) = to_ingest {
-        println!("Failed to ingest file, {}", error);
+        error!("Failed to ingest file {}: {}", file_path, error);
         return;
     }
 
@@ -200,7 +279,7 @@ pub async fn ingest_file(db: &DbConn, file_path: String) {
     ) = match serde_json::from_str(&to_ingest.unwrap()) {
         Ok(v) => v,
         Err(e) => {
-            eprintln!("Caught error, {}", e);
+            error!("Failed to parse ingest file {}: {}", file_path, e);
             return;
         }
     };
@@ -262,16 +341,16 @@ pub async fn session_garbage_collector(db: &DbConn) {
                     // Delete all model instances of sessions which have surpassed their existence time-frame.
                     match session::Entity::delete_by_id(model.id).exec(db).await {
                         Ok(data) => {
-                            println!("[log]: Culled session {:?}", data)
+                            debug!("Culled {} expired session(s)", data.rows_affected)
                         }
                         Err(err) => {
-                            println!("[err]: Error in scheduled cron task: {:?}", err)
+                            error!("Error in scheduled cron task: {:?}", err)
                         }
                     }
                 }
             }
             Err(err) => {
-                println!("[err]: Error in scheduled cron task: {:?}", err)
+                error!("Error in scheduled cron task: {:?}", err)
             }
         };
 
@@ -290,21 +369,21 @@ pub async fn session_garbage_collector(db: &DbConn) {
                             // Delete all model instances of sessions which have surpassed their existence time-frame.
                             match transactions::Entity::delete_by_id(model.id).exec(db).await {
                                 Ok(_data) => {
-                                    println!("[log]: Culled transaction")
+                                    debug!("Culled saved transaction")
                                 }
                                 Err(err) => {
-                                    println!("[err]: Error in scheduled cron task: {:?}", err)
+                                    error!("Error in scheduled cron task: {:?}", err)
                                 }
                             }
                         }
                     }
                     Err(err) => {
-                        println!("[err]: Error in scheduled cron task: {:?}", err)
+                        error!("Error in scheduled cron task: {:?}", err)
                     }
                 };
             }
             None => {
-                println!("[err]: Error in cron task: Unable to format DateTime")
+                error!("Error in scheduled cron task: Unable to format DateTime")
             }
         };
     }
@@ -0,0 +1,247 @@
+mod common;
+
+#[cfg(feature = "process")]
+mod verification {
+    use crate::common::{self, read};
+    use open_stock::{
+        Address, AddressProvider, AddressVerification, AddressVerifier, Error, NoopAddressProvider,
+        Order,
+    };
+    use serde_json::json;
+
+    fn order(order_type: &str) -> Order {
+        let contact = json!({
+            "name": "Carl Kennith",
+            "mobile": { "number": "+6421212120", "valid": true },
+            "email": { "root": "carl", "domain": "kennith.com", "full": "carl@kennith.com" },
+            "landline": "",
+            "address": {
+                "street": "9 carbine rd",
+                "street2": "",
+                "city": "auckland",
+                "country": "New Zealand",
+                "po_code": "1060",
+                "lat": 0.0,
+                "lon": 0.0
+            }
+        });
+
+        let mut order = common::order("TOR-19592", vec![]);
+        order["destination"] = json!({ "store_code": "", "store_id": "", "contact": contact });
+        order["order_type"] = json!(order_type);
+
+        read(order)
+    }
+
+    /// Corrects the street of every address, with a fixed confidence.
+    struct Corrector(f32);
+
+    #[rocket::async_trait]
+    impl AddressProvider for Corrector {
+        async fn standardize(&self, address: &Address) -> Result<AddressVerification, Error> {
+            Ok(AddressVerification {
+                address: Address {
+                    street: "9 Carbine Road".into(),
+                    ..address.clone()
+                },
+                confidence: self.0,
+            })
+        }
+    }
+
+    #[rocket::async_test]
+    async fn noop_provider_trusts_addresses() {
+        let verifier = AddressVerifier::new(Box::new(NoopAddressProvider), 0.8);
+        let mut orders = vec![order("shipment")];
+        let given = orders[0].destination.contact.address.clone();
+
+        verifier.verify(&mut orders).await;
+
+        assert_eq!(orders[0].destination.contact.address, given);
+        assert_eq!(orders[0].address_confidence, Some(1.0));
+        assert!(!orders[0].address_flagged);
+    }
+
+    #[rocket::async_test]
+    async fn confident_corrections_are_applied() {
+        let verifier = AddressVerifier::new(Box::new(Corrector(0.95)), 0.8);
+        let mut orders = vec![order("shipment")];
+
+        verifier.verify(&mut orders).await;
+
+        assert_eq!(
+            orders[0].destination.contact.address.street,
+            "9 Carbine Road"
+        );
+        assert!(!orders[0].address_flagged);
+    }
+
+    #[rocket::async_test]
+    async fn doubtful_addresses_are_flagged() {
+        let verifier = AddressVerifier::new(Box::new(Corrector(0.4)), 0.8);
+        let mut orders = vec![order("shipment"), order("pickup")];
+
+        verifier.verify(&mut orders).await;
+
+        assert_eq!(orders[0].destination.contact.address.street, "9 carbine rd");
+        assert_eq!(orders[0].address_confidence, Some(0.4));
+        assert!(orders[0].address_flagged);
+
+        // Only shipments are delivered, so only their destinations are checked.
+        assert_eq!(orders[1].address_confidence, None);
+        assert!(!orders[1].address_flagged);
+    }
+}
+
+#[cfg(feature = "process")]
+mod coordinates {
+    use crate::common::{self, read};
+    use open_stock::{Address, Store};
+    use serde_json::json;
+    use validator::Validate;
+
+    fn address(lat: f64, lon: f64) -> Address {
+        let mut address = common::contact()["address"].clone();
+        address["lat"] = json!(lat);
+        address["lon"] = json!(lon);
+
+        read(address)
+    }
+
+    fn store(id: &str, lat: f64, lon: f64) -> Store {
+        let mut store = common::store(id, "UTC");
+        store["contact"]["address"] = json!(address(lat, lon));
+
+        read(store)
+    }
+
+    fn rejected(address: Address) -> Vec<&'static str> {
+        let mut fields: Vec<&str> = address
+            .validate()
+            .err()
+            .map(|errors| errors.field_errors().into_keys().collect())
+            .unwrap_or_default();
+        fields.sort();
+
+        fields
+    }
+
+    #[test]
+    fn out_of_range_coordinates_are_rejected() {
+        assert_eq!(rejected(address(90.5, 174.8)), vec!["lat"]);
+        assert_eq!(rejected(address(-36.9, -180.5)), vec!["lon"]);
+        assert_eq!(rejected(address(-91.0, 181.0)), vec!["lat", "lon"]);
+    }
+
+    #[test]
+    fn antimeridian_coordinates_are_accepted() {
+        assert!(rejected(address(-16.5, 180.0)).is_empty());
+        assert!(rejected(address(-16.5, -180.0)).is_empty());
+    }
+
+    #[test]
+    fn null_island_is_not_geocoded() {
+        assert!(!address(0.0, 0.0).is_geocoded());
+        assert!(address(0.0, 174.8).is_geocoded());
+    }
+
+    #[test]
+    fn distances_wrap_the_antimeridian() {
+        let stores = [
+            store("WEST", -16.5, 170.0),
+            store("EAST", -16.5, -179.9),
+            store("UNKNOWN", 0.0, 0.0),
+        ];
+
+        let distances = Store::by_distance(&stores, -16.5, 179.9);
+        let ids: Vec<&str> = distances.iter().map(|d| d.store_id.as_str()).collect();
+
+        assert_eq!(ids, vec!["EAST", "WEST"]);
+        assert!(
+            distances[0].distance < 25_000.0,
+            "{}",
+            distances[0].distance
+        );
+    }
+}
+
+#[cfg(feature = "process")]
+mod nearest_store {
+    use crate::common::{self, contact, product, purchase, quantity, read};
+    use open_stock::{Address, Product, ProductPurchaseList, Store};
+    use serde_json::{json, Value};
+
+    const STORES: [(&str, f64, f64); 3] = [
+        ("AKL", -36.85, 174.76),
+        ("WLG", -41.29, 174.78),
+        ("CHC", -43.53, 172.64),
+    ];
+
+    fn stores() -> Vec<Store> {
+        STORES
+            .iter()
+            .map(|(code, lat, lon)| {
+                let mut store = common::store(code, "UTC");
+                store["code"] = json!(code);
+                store["contact"]["address"]["lat"] = json!(lat);
+                store["contact"]["address"]["lon"] = json!(lon);
+
+                read(store)
+            })
+            .collect()
+    }
+
+    /// A kayak stocked with the given quantity at each store, by code.
+    fn catalogue(stock: &[(&str, f32)]) -> Vec<Product> {
+        let mut variant = common::variant("51890723908812");
+        variant["stock"] = stock
+            .iter()
+            .map(|(code, sellable)| {
+                json!({
+                    "store": { "store_code": code, "store_id": code, "contact": contact() },
+                    "quantity": quantity(*sellable)
+                })
+            })
+            .collect::<Vec<Value>>()
+            .into();
+
+        vec![read(product("132522", vec![variant]))]
+    }
+
+    /// Hamilton, nearest to Auckland then Wellington.
+    fn hamilton() -> Address {
+        let mut address = contact()["address"].clone();
+        address["lat"] = json!(-37.79);
+        address["lon"] = json!(175.28);
+
+        read(address)
+    }
+
+    fn nearest(stock: &[(&str, f32)]) -> Option<String> {
+        let order: ProductPurchaseList = vec![read(purchase("51890723908812", "132522", 2.0))];
+
+        Store::nearest_supplying(&stores(), &hamilton(), &order, &catalogue(stock))
+            .map(|store| store.code.clone())
+    }
+
+    #[test]
+    fn the_nearest_stocked_store_supplies() {
+        assert_eq!(
+            nearest(&[("AKL", 2.0), ("WLG", 2.0), ("CHC", 2.0)]),
+            Some("AKL".into())
+        );
+    }
+
+    #[test]
+    fn stores_short_of_stock_are_passed_over() {
+        assert_eq!(
+            nearest(&[("AKL", 1.0), ("WLG", 0.0), ("CHC", 5.0)]),
+            Some("CHC".into())
+        );
+    }
+
+    #[test]
+    fn no_store_supplies_more_than_it_holds() {
+        assert_eq!(nearest(&[("AKL", 1.0)]), None);
+    }
+}
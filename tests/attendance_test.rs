@@ -0,0 +1,29 @@
+#[cfg(feature = "types")]
+mod history {
+    use open_stock::{Attendance, TrackType};
+    use serde_json::json;
+
+    #[test]
+    fn skips_malformed_entries() {
+        let history = Attendance::history_from_value(json!([
+            {
+                "item": { "track_type": "In", "kiosk": "kiosk-1" },
+                "reason": "OpenStock - Log",
+                "timestamp": "2024-03-01T08:00:00Z"
+            },
+            { "item": { "track_type": "Sideways" }, "reason": "", "timestamp": "yesterday" },
+            "not-an-entry"
+        ]));
+
+        assert_eq!(history.len(), 1);
+        assert!(matches!(history[0].item.track_type, TrackType::In));
+        assert_eq!(history[0].item.kiosk, "kiosk-1");
+    }
+
+    #[test]
+    fn reads_missing_history_as_empty() {
+        assert!(Attendance::history_from_value(json!(null)).is_empty());
+        assert!(Attendance::history_from_value(json!({})).is_empty());
+        assert!(Attendance::history_from_value(json!([])).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod product_cache {
+    use std::time::Duration;
+
+    use crate::common::{self, read};
+    use open_stock::{Product, ProductCache};
+    use serde_json::json;
+
+    const TENANT: &str = "TENANT";
+
+    fn kayak(name: &str) -> Product {
+        let mut product = common::product("KAYAK-SKU", vec![common::variant("KAYAK")]);
+        product["name"] = json!(name);
+
+        read(product)
+    }
+
+    #[test]
+    fn updates_are_read_once_invalidated() {
+        let cache = ProductCache::new(Some(Duration::from_secs(60)));
+        cache.put(TENANT, &kayak("Nippers Kayak"));
+
+        assert_eq!(
+            cache.get(TENANT, "KAYAK-SKU").unwrap().name,
+            "Nippers Kayak"
+        );
+
+        // As done on every write of the product, before it is next fetched.
+        cache.invalidate(TENANT, "KAYAK-SKU");
+
+        assert!(cache.get(TENANT, "KAYAK-SKU").is_none());
+        assert!(cache.get_by_barcode(TENANT, "KAYAK").is_none());
+
+        cache.put(TENANT, &kayak("Nippers Kayak & Paddle"));
+
+        assert_eq!(
+            cache.get_by_barcode(TENANT, "KAYAK").unwrap().name,
+            "Nippers Kayak & Paddle"
+        );
+    }
+
+    #[test]
+    fn tenants_do_not_share_entries() {
+        let cache = ProductCache::new(Some(Duration::from_secs(60)));
+        cache.put(TENANT, &kayak("Nippers Kayak"));
+
+        assert!(cache.get("OTHER", "KAYAK-SKU").is_none());
+        assert!(cache.get_by_barcode("OTHER", "KAYAK").is_none());
+    }
+
+    #[test]
+    fn disabled_cache_holds_nothing() {
+        let cache = ProductCache::new(None);
+        cache.put(TENANT, &kayak("Nippers Kayak"));
+
+        assert!(!cache.is_enabled());
+        assert!(cache.get(TENANT, "KAYAK-SKU").is_none());
+    }
+}
+
+#[cfg(feature = "process")]
+mod entity_tags {
+    use crate::common::{read, store, STORE};
+    use open_stock::guards::Tagged;
+    use open_stock::Store;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    /// Stands in for the single-resource `get` routes, which answer alike.
+    #[get("/store")]
+    fn get_store() -> Tagged<Store> {
+        Ok(read(store(STORE, "Pacific/Auckland"))).into()
+    }
+
+    fn client() -> Client {
+        Client::untracked(rocket::build().mount("/", routes![get_store]))
+            .expect("valid rocket instance")
+    }
+
+    #[test]
+    fn a_matching_tag_is_not_modified() {
+        let client = client();
+        let first = client.get("/store").dispatch();
+        let tag = first.headers().get_one("ETag").unwrap().to_string();
+
+        assert_eq!(first.status(), Status::Ok);
+
+        let second = client
+            .get("/store")
+            .header(Header::new("If-None-Match", tag.clone()))
+            .dispatch();
+
+        assert_eq!(second.status(), Status::NotModified);
+        assert_eq!(second.headers().get_one("ETag"), Some(tag.as_str()));
+        assert!(second.into_string().unwrap_or_default().is_empty());
+    }
+
+    #[test]
+    fn a_stale_tag_gets_the_body() {
+        let client = client();
+        let response = client
+            .get("/store")
+            .header(Header::new("If-None-Match", "\"stale\""))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.into_string().unwrap().contains(STORE));
+    }
+}
@@ -0,0 +1,136 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod scheme {
+    use open_stock::{CommissionScheme, CommissionTier, RoundingMode, TenantSettings};
+
+    #[test]
+    fn flat_rate_applies_to_all_sales() {
+        let scheme = CommissionScheme::Flat(0.05);
+
+        assert_eq!(scheme.commission(1234.50, RoundingMode::HalfUp), 61.73);
+    }
+
+    #[test]
+    fn tiers_apply_marginally() {
+        let scheme = CommissionScheme::Tiered(vec![
+            CommissionTier {
+                threshold: 5000.0,
+                rate: 0.05,
+            },
+            CommissionTier {
+                threshold: 0.0,
+                rate: 0.02,
+            },
+        ]);
+
+        // 2% of the first 5000, then 5% of the 1000 above it.
+        assert_eq!(scheme.commission(6000.0, RoundingMode::HalfUp), 150.0);
+        assert_eq!(scheme.commission(2000.0, RoundingMode::HalfUp), 40.0);
+    }
+
+    #[test]
+    fn nothing_is_earned_on_a_net_loss() {
+        let scheme = CommissionScheme::Flat(0.05);
+
+        assert_eq!(scheme.commission(-200.0, RoundingMode::HalfUp), 0.0);
+    }
+
+    #[test]
+    fn tenants_pay_no_commission_by_default() {
+        let settings: TenantSettings = serde_json::from_str("{}").expect("default settings");
+
+        assert_eq!(settings.commission, CommissionScheme::Flat(0.0));
+    }
+}
+
+#[cfg(feature = "types")]
+mod tally {
+    use crate::common::{order, purchase, read};
+    use chrono::Utc;
+    use open_stock::{
+        CommissionReport, CommissionScheme, CommissionTally, Order, RoundingMode,
+        TransactionCustomer, TransactionType,
+    };
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    fn kayaks(quantity: f32) -> Vec<Order> {
+        vec![read(order(
+            "TOR-19592",
+            vec![purchase("KAYAK", "KAYAK-SKU", quantity)],
+        ))]
+    }
+
+    fn customer(customer_type: &str, customer_id: &str) -> TransactionCustomer {
+        read(json!({ "customer_type": customer_type, "customer_id": customer_id }))
+    }
+
+    /// A tally of two kayaks sold by `EMPLOYEE`, for a tenant supplied by `SUPPLIER`.
+    fn two_sold() -> CommissionTally {
+        let mut tally = CommissionTally::new(HashSet::from(["SUPPLIER".to_string()]));
+
+        tally.add(
+            "EMPLOYEE",
+            &TransactionType::Out,
+            &customer("Individual", "CUSTOMER"),
+            &kayaks(2.0),
+            RoundingMode::HalfUp,
+        );
+
+        tally
+    }
+
+    fn report(tally: CommissionTally) -> CommissionReport {
+        tally.report(
+            Utc::now(),
+            Utc::now(),
+            CommissionScheme::Flat(0.05),
+            RoundingMode::HalfUp,
+        )
+    }
+
+    #[test]
+    fn purchase_orders_are_not_refunds() {
+        let mut tally = two_sold();
+
+        tally.add(
+            "EMPLOYEE",
+            &TransactionType::In,
+            &customer("Commercial", "SUPPLIER"),
+            &kayaks(5.0),
+            RoundingMode::HalfUp,
+        );
+        tally.add(
+            "EMPLOYEE",
+            &TransactionType::In,
+            &customer("Store", "628f74d7-de00-4956-a5b6-2031e0c72128"),
+            &kayaks(3.0),
+            RoundingMode::HalfUp,
+        );
+
+        let salesperson = &report(tally).salespeople[0];
+
+        assert!(salesperson.sales > 0.0);
+        assert_eq!(salesperson.refunds, 0.0);
+        assert_eq!(salesperson.net_sales, salesperson.sales);
+    }
+
+    #[test]
+    fn customer_refunds_are_deducted() {
+        let mut tally = two_sold();
+
+        tally.add(
+            "EMPLOYEE",
+            &TransactionType::In,
+            &customer("Individual", "CUSTOMER"),
+            &kayaks(1.0),
+            RoundingMode::HalfUp,
+        );
+
+        let salesperson = &report(tally).salespeople[0];
+
+        assert!((salesperson.refunds * 2.0 - salesperson.sales).abs() < 0.01);
+        assert!((salesperson.net_sales - salesperson.refunds).abs() < 0.01);
+    }
+}
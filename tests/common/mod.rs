@@ -0,0 +1,240 @@
+//! Fixtures shared by the integration tests. Each is built as JSON, such that a
+//! test may adjust the fields it concerns before reading it with [`read`].
+#![allow(dead_code)]
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// The store stock is held at and sold from, coded `002`.
+pub const STORE: &str = "c4a1d88b-e8a0-4dcd-ade2-1eea82254816";
+/// A second store of the tenant, coded `001`.
+pub const OTHER_STORE: &str = "628f74d7-de00-4956-a5b6-2031e0c72128";
+
+/// Reads a fixture as `T`, panicking should it not fit.
+pub fn read<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).expect("fixture reads as its type")
+}
+
+pub fn contact() -> Value {
+    json!({
+        "name": "Torpedo7 Mt Wellington",
+        "mobile": { "number": "+6421212120", "valid": true },
+        "email": { "root": "order", "domain": "torpedo7.com", "full": "order@torpedo7.com" },
+        "landline": "",
+        "address": {
+            "street": "315-375 Mount Wellington Highway",
+            "street2": "Mount Wellington",
+            "city": "Auckland",
+            "country": "New Zealand",
+            "po_code": "1060",
+            "lat": -36.915501,
+            "lon": 174.838745
+        }
+    })
+}
+
+/// The [`Location`](open_stock::Location) of `store_id`, either [`STORE`] or [`OTHER_STORE`].
+pub fn location(store_id: &str) -> Value {
+    let store_code = if store_id == STORE { "002" } else { "001" };
+
+    json!({ "store_code": store_code, "store_id": store_id, "contact": contact() })
+}
+
+/// A store trading in the IANA `timezone`, i.e. `Pacific/Auckland`.
+pub fn store(store_id: &str, timezone: &str) -> Value {
+    json!({
+        "id": store_id,
+        "name": "Torpedo7 Mt Wellington",
+        "contact": contact(),
+        "code": location(store_id)["store_code"],
+        "timezone": timezone,
+        "created_at": "2022-11-27T06:54:11.665651300Z",
+        "updated_at": "2022-11-27T06:54:11.665651300Z"
+    })
+}
+
+pub fn quantity(sellable: f32) -> Value {
+    json!({
+        "quantity_sellable": sellable,
+        "quantity_unsellable": 0.0,
+        "quantity_on_order": 0.0,
+        "quantity_allocated": 0.0
+    })
+}
+
+pub fn stock(store_id: &str, sellable: f32) -> Value {
+    json!({ "store": location(store_id), "quantity": quantity(sellable) })
+}
+
+/// A kayak selling at 399.99 and costing 250.00, with no stock held.
+pub fn variant(barcode: &str) -> Value {
+    json!({
+        "id": barcode,
+        "name": "1.83m Beaches",
+        "stock": [],
+        "stock_information": {
+            "stock_group": "RANDOM",
+            "sales_group": "RANDOM",
+            "value_stream": "RANDOM",
+            "min_stock_before_alert": 1.0,
+            "min_stock_level": 0.0,
+            "brand": "Torpedo7",
+            "colli": "",
+            "size_x": 0.0,
+            "size_y": 0.0,
+            "size_z": 0.0,
+            "size_x_unit": "cm",
+            "size_y_unit": "cm",
+            "size_z_unit": "cm",
+            "size_override_unit": "",
+            "tax_code": "GST",
+            "weight": "5.8",
+            "volume": "0",
+            "max_volume": "10",
+            "back_order": false,
+            "discontinued": false,
+            "non_diminishing": false,
+            "shippable": true
+        },
+        "images": [],
+        "retail_price": 399.99,
+        "marginal_price": 250.0,
+        "buy_min": 1.0,
+        "buy_max": -1.0,
+        "loyalty_discount": { "Absolute": 0 },
+        "variant_code": [],
+        "order_history": [],
+        "barcode": barcode,
+        "identification": {
+            "sku": "",
+            "ean": "",
+            "hs_code": "",
+            "article_code": "",
+            "isbn": ""
+        },
+        "stock_tracking": true
+    })
+}
+
+pub fn product(sku: &str, variants: Vec<Value>) -> Value {
+    json!({
+        "name": "Torpedo7 Nippers Kids Kayak & Paddle",
+        "name_long": "",
+        "company": "Torpedo7",
+        "variant_groups": [],
+        "variants": variants,
+        "sku": sku,
+        "identification": {
+            "sku": sku,
+            "ean": "",
+            "hs_code": "",
+            "article_code": "",
+            "isbn": ""
+        },
+        "images": [],
+        "tags": [],
+        "description": "",
+        "description_long": "",
+        "specifications": [],
+        "visible": "AlwaysShown",
+        "created_at": "2022-11-27T06:54:11.665651300Z",
+        "updated_at": "2022-11-27T06:54:11.665651300Z"
+    })
+}
+
+/// A line of an order selling `quantity` of the variant `barcode` at 399.99 each.
+pub fn purchase(barcode: &str, sku: &str, quantity: f32) -> Value {
+    json!({
+        "id": barcode,
+        "product_code": barcode,
+        "product_sku": sku,
+        "discount": { "Absolute": 0 },
+        "product_name": "Torpedo7 Nippers Kids Kayak & Paddle",
+        "product_variant_name": "1.83m Beaches",
+        "product_cost": 399.99,
+        "quantity": quantity,
+        "tags": [],
+        "transaction_type": "Out"
+    })
+}
+
+/// A shipment from [`STORE`] to [`OTHER_STORE`], queued to be picked.
+pub fn order(reference: &str, products: Vec<Value>) -> Value {
+    json!({
+        "id": reference,
+        "destination": location(OTHER_STORE),
+        "origin": location(STORE),
+        "products": products,
+        "status": {
+            "status": { "type": "queued", "value": "2022-11-27T06:54:11.665676300Z" },
+            "assigned_products": [],
+            "timestamp": "2022-11-27T06:54:11.665676300Z"
+        },
+        "status_history": [],
+        "order_history": [],
+        "previous_failed_fulfillment_attempts": [],
+        "order_notes": [],
+        "reference": reference,
+        "creation_date": "2022-11-27T06:54:11.665651300Z",
+        "discount": { "Absolute": 0 },
+        "order_type": "shipment"
+    })
+}
+
+/// A payment of `amount` made by `method`, such as `"Card"` or `{ "GiftCard": code }`.
+pub fn payment(method: Value, amount: f32) -> Value {
+    json!({
+        "id": "e6a2d5a0-1f54-4b8e-9c0e-2a3c5d7f9b11",
+        "payment_method": method,
+        "fulfillment_date": "2022-11-27T06:54:11.665651300Z",
+        "amount": { "quantity": amount, "currency": "NZD" },
+        "processing_fee": { "quantity": 0.0, "currency": "NZD" },
+        "status": { "Complete": { "Anonymous": "Kiosk" } },
+        "processor": {
+            "location": STORE,
+            "employee": "EMPLOYEE",
+            "software_version": "k0.5.2",
+            "token": ""
+        },
+        "order_ids": [],
+        "delay_action": "Cancel",
+        "delay_duration": "PT12H"
+    })
+}
+
+/// A sale of `orders` made at `order_date`, paid by `payments`.
+pub fn transaction(order_date: &str, orders: Vec<Value>, payments: Vec<Value>) -> Value {
+    json!({
+        "id": "b2b4f0a6-54a5-4b53-9f0a-8e3f1e6c2d44",
+        "customer": { "customer_type": "Individual", "customer_id": "CUSTOMER" },
+        "transaction_type": "Out",
+        "products": orders,
+        "order_total": 0,
+        "payment": payments,
+        "order_date": order_date,
+        "order_notes": [],
+        "salesperson": "EMPLOYEE",
+        "kiosk": "KIOSK",
+        "created_at": order_date,
+        "updated_at": order_date
+    })
+}
+
+/// Connects to the MySQL database named by `DATABASE_URL`, migrated to the latest
+/// schema, for tests which exercise the database itself. Such tests are ignored
+/// unless run with `cargo test -- --ignored`.
+#[cfg(feature = "process")]
+pub async fn database() -> sea_orm::DbConn {
+    use open_stock::Migrator;
+    use sea_orm_migration::MigratorTrait;
+
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL names a MySQL database");
+    let db = sea_orm::Database::connect(url)
+        .await
+        .expect("connects to DATABASE_URL");
+
+    Migrator::up(&db, None)
+        .await
+        .expect("migrates the database");
+    db
+}
@@ -0,0 +1,218 @@
+#[cfg(feature = "process")]
+mod cookies {
+    use chrono::{Duration, Utc};
+    use open_stock::{add_session_cookies, CSRF_COOKIE, SESSION_COOKIE};
+    use rocket::http::{CookieJar, SameSite};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/login")]
+    fn login(cookies: &CookieJar<'_>) {
+        add_session_cookies(
+            cookies,
+            "session-key".to_string(),
+            Utc::now() + Duration::minutes(10),
+        );
+    }
+
+    #[test]
+    fn login_sets_secure_cookies() {
+        let client = Client::untracked(rocket::build().mount("/", routes![login]))
+            .expect("valid rocket instance");
+        let response = client.get("/login").dispatch();
+
+        let session = response
+            .cookies()
+            .get(SESSION_COOKIE)
+            .expect("a session cookie");
+
+        assert_eq!(session.value(), "session-key");
+        assert_eq!(session.http_only(), Some(true));
+        assert_eq!(session.secure(), Some(true));
+        assert_eq!(session.same_site(), Some(SameSite::Lax));
+        assert_eq!(session.path(), Some("/"));
+
+        let max_age = session.max_age().expect("a max-age").whole_seconds();
+        assert!((590..=600).contains(&max_age), "max-age of {}s", max_age);
+
+        let csrf = response.cookies().get(CSRF_COOKIE).expect("a CSRF cookie");
+
+        // Read by the client's scripts, so as to be echoed back in a header.
+        assert_ne!(csrf.http_only(), Some(true));
+        assert_eq!(csrf.secure(), Some(true));
+        assert!(!csrf.value().is_empty());
+    }
+}
+
+#[cfg(feature = "process")]
+mod csrf {
+    use open_stock::guards::CsrfProtected;
+    use open_stock::{CSRF_COOKIE, CSRF_HEADER};
+    use rocket::http::{Cookie, Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, post, routes};
+
+    #[post("/sale")]
+    fn sale(_csrf: CsrfProtected) {}
+
+    #[get("/sale")]
+    fn sales(_csrf: CsrfProtected) {}
+
+    fn client() -> Client {
+        Client::untracked(rocket::build().mount("/", routes![sale, sales]))
+            .expect("valid rocket instance")
+    }
+
+    const ORIGIN: &str = "https://pos.example.com";
+
+    #[test]
+    fn passes_matching_token() {
+        let client = client();
+        let response = client
+            .post("/sale")
+            .header(Header::new("Origin", ORIGIN))
+            .header(Header::new(CSRF_HEADER, "token"))
+            .cookie(Cookie::new(CSRF_COOKIE, "token"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn rejects_missing_token() {
+        let client = client();
+        let response = client
+            .post("/sale")
+            .header(Header::new("Origin", ORIGIN))
+            .cookie(Cookie::new(CSRF_COOKIE, "token"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn rejects_mismatched_token() {
+        let client = client();
+        let response = client
+            .post("/sale")
+            .header(Header::new("Origin", ORIGIN))
+            .header(Header::new(CSRF_HEADER, "forged"))
+            .cookie(Cookie::new(CSRF_COOKIE, "token"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn exempts_safe_methods() {
+        let client = client();
+        let response = client
+            .get("/sale")
+            .header(Header::new("Origin", ORIGIN))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
+
+#[cfg(feature = "process")]
+mod session_keys {
+    use open_stock::Session;
+
+    #[test]
+    fn keys_are_long_and_url_safe() {
+        let key = Session::generate_key();
+
+        assert_eq!(key.len(), 64);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(key, Session::generate_key());
+    }
+
+    #[test]
+    fn presented_keys_match_their_stored_hash() {
+        let key = Session::generate_key();
+        let stored = Session::hash_key(&key);
+
+        // Sessions are looked up by the hash of the cookie presented.
+        assert_ne!(stored, key);
+        assert_eq!(Session::hash_key(&key), stored);
+        assert_ne!(Session::hash_key(&Session::generate_key()), stored);
+    }
+
+    #[test]
+    fn hashes_match_mysql() {
+        // SELECT SHA2('abc', 256)
+        assert_eq!(
+            Session::hash_key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
+
+#[cfg(feature = "process")]
+mod remember_me {
+    use chrono::{Duration, Utc};
+    use open_stock::{remember_duration, session_duration, session_expiry};
+
+    #[test]
+    fn remembered_sessions_last_longer() {
+        let remembered = session_expiry(true) - Utc::now();
+        let ordinary = session_expiry(false) - Utc::now();
+
+        assert!(remembered > Duration::days(13), "lasts {}", remembered);
+        assert!(remembered <= remember_duration());
+        assert!(ordinary <= session_duration());
+        assert!(ordinary < remembered);
+    }
+}
+
+#[cfg(feature = "process")]
+mod concurrent_sessions {
+    use chrono::{Duration, Utc};
+    use open_stock::{Session, SessionRaw, SessionVariant};
+
+    fn session(id: &str, minutes_ago: i64, variant: SessionVariant) -> SessionRaw {
+        SessionRaw {
+            id: id.to_string(),
+            key: Session::hash_key(id),
+            employee_id: "EMPLOYEE".to_string(),
+            expiry: Utc::now() + Duration::minutes(10),
+            variant,
+            tenant_id: "TENANT".to_string(),
+            created_at: Utc::now() - Duration::minutes(minutes_ago),
+        }
+    }
+
+    #[test]
+    fn oldest_session_is_evicted_beyond_the_limit() {
+        let sessions = vec![
+            session("SECOND", 20, SessionVariant::AccessToken),
+            session("FIRST", 30, SessionVariant::AccessToken),
+            session("THIRD", 10, SessionVariant::AccessToken),
+            session(
+                "FIRST-REFRESH",
+                30,
+                SessionVariant::RefreshToken("FIRST".to_string()),
+            ),
+        ];
+
+        assert_eq!(
+            Session::sessions_to_evict(&sessions, 3),
+            vec!["FIRST", "FIRST-REFRESH"]
+        );
+        assert_eq!(
+            Session::sessions_to_evict(&sessions, 2),
+            vec!["FIRST", "SECOND", "FIRST-REFRESH"]
+        );
+    }
+
+    #[test]
+    fn nothing_is_evicted_within_the_limit() {
+        let sessions = vec![
+            session("FIRST", 30, SessionVariant::AccessToken),
+            session("SECOND", 20, SessionVariant::AccessToken),
+        ];
+
+        assert!(Session::sessions_to_evict(&sessions, 3).is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(feature = "process")]
+mod cors {
+    use open_stock::guards::Cors;
+    use rocket::http::{Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::{get, routes};
+
+    #[get("/store")]
+    fn store() -> &'static str {
+        "{}"
+    }
+
+    fn client() -> Client {
+        let cors = Cors::new(vec!["https://pos.example.com".to_string()]);
+
+        Client::untracked(rocket::build().mount("/", routes![store]).attach(cors))
+            .expect("valid rocket instance")
+    }
+
+    #[test]
+    fn answers_preflight() {
+        let client = client();
+        let response = client
+            .options("/store")
+            .header(Header::new("Origin", "https://pos.example.com"))
+            .header(Header::new("Access-Control-Request-Method", "POST"))
+            .dispatch();
+
+        let headers = response.headers();
+
+        assert_eq!(response.status(), Status::NoContent);
+        assert_eq!(
+            headers.get_one("Access-Control-Allow-Origin"),
+            Some("https://pos.example.com")
+        );
+        assert_eq!(
+            headers.get_one("Access-Control-Allow-Credentials"),
+            Some("true")
+        );
+        assert!(headers
+            .get_one("Access-Control-Allow-Methods")
+            .is_some_and(|methods| methods.contains("POST")));
+    }
+
+    #[test]
+    fn allows_listed_origin() {
+        let client = client();
+        let response = client
+            .get("/store")
+            .header(Header::new("Origin", "https://pos.example.com"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.headers().get_one("Access-Control-Allow-Origin"),
+            Some("https://pos.example.com")
+        );
+    }
+
+    #[test]
+    fn ignores_other_origins() {
+        let client = client();
+        let response = client
+            .get("/store")
+            .header(Header::new("Origin", "https://elsewhere.example.com"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response
+            .headers()
+            .get_one("Access-Control-Allow-Origin")
+            .is_none());
+    }
+}
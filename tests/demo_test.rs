@@ -0,0 +1,44 @@
+#[cfg(feature = "process")]
+mod demo_mode {
+    use open_stock::guards::Convert;
+    use open_stock::{check_demo, DemoMode};
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use rocket::{post, routes, State};
+
+    /// Stands in for the `generate` routes, which check the demo mode alike.
+    #[post("/generate")]
+    fn generate(demo: &State<DemoMode>) -> Convert<String> {
+        check_demo!(demo);
+        Ok("Generated".to_string()).into()
+    }
+
+    fn client(enabled: bool) -> Client {
+        Client::untracked(
+            rocket::build()
+                .manage(DemoMode { enabled })
+                .mount("/", routes![generate]),
+        )
+        .expect("valid rocket instance")
+    }
+
+    #[test]
+    fn generation_is_blocked_outside_demo_mode() {
+        let client = client(false);
+        let response = client.post("/generate").dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+        assert_eq!(
+            response.into_string().as_deref(),
+            Some("OpenStock is not in DEMO mode.")
+        );
+    }
+
+    #[test]
+    fn generation_is_allowed_in_demo_mode() {
+        let client = client(true);
+        let response = client.post("/generate").dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+#[cfg(feature = "process")]
+mod passwords {
+    use chrono::{Duration, Utc};
+    use open_stock::{EmployeeAuth, PASSWORD_MAX_AGE_DAYS};
+
+    fn auth(password: &str, must_rotate: bool) -> EmployeeAuth {
+        let mut auth = EmployeeAuth::default();
+        auth.set_password(password, must_rotate).unwrap();
+        auth
+    }
+
+    #[test]
+    fn the_password_set_verifies() {
+        let auth = auth("hunter22", false);
+
+        assert!(auth.verify_password("hunter22"));
+        assert_ne!(auth.hash, "hunter22");
+        assert!(auth.ensure_current().is_ok());
+    }
+
+    #[test]
+    fn other_passwords_do_not_verify() {
+        let auth = auth("hunter22", false);
+
+        assert!(!auth.verify_password("hunter23"));
+        assert!(!auth.verify_password(""));
+        assert!(!EmployeeAuth::default().verify_password(""));
+    }
+
+    #[test]
+    fn issued_passwords_must_be_rotated() {
+        let mut auth = auth("temporary", true);
+
+        assert!(auth.verify_password("temporary"));
+        assert!(auth.needs_rotation());
+        assert!(auth.ensure_current().is_err());
+
+        auth.set_password("chosen-by-me", false).unwrap();
+
+        assert!(!auth.needs_rotation());
+        assert!(!auth.verify_password("temporary"));
+    }
+
+    #[test]
+    fn aged_passwords_must_be_rotated() {
+        let mut auth = auth("hunter22", false);
+
+        auth.last_changed = Some(Utc::now() - Duration::days(PASSWORD_MAX_AGE_DAYS - 1));
+        assert!(auth.ensure_current().is_ok());
+
+        auth.last_changed = Some(Utc::now() - Duration::days(PASSWORD_MAX_AGE_DAYS + 1));
+        assert!(auth.ensure_current().is_err());
+    }
+}
+
+#[cfg(feature = "process")]
+mod store_access {
+    use crate::common::{OTHER_STORE, STORE};
+    use open_stock::{Session, StoreAccess};
+    use rocket::http::Status;
+
+    fn session(stores: StoreAccess) -> Session {
+        let mut session = Session::default_with_tenant("DEFAULT_TENANT".into());
+        session.employee.stores = stores;
+        session
+    }
+
+    #[test]
+    fn an_assigned_store_is_permitted() {
+        let session = session(StoreAccess::Only(vec![STORE.into()]));
+
+        assert!(session.ensure_store(STORE).is_ok());
+    }
+
+    #[test]
+    fn another_store_is_denied() {
+        let session = session(StoreAccess::Only(vec![STORE.into()]));
+        let error = session.ensure_store(OTHER_STORE).unwrap_err();
+
+        assert_eq!(error.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn unrestricted_employees_may_act_on_any_store() {
+        let session = session(StoreAccess::All);
+
+        assert!(session.ensure_store(STORE).is_ok());
+        assert!(session.ensure_store(OTHER_STORE).is_ok());
+    }
+}
@@ -0,0 +1,191 @@
+#[cfg(feature = "process")]
+mod parity {
+    use open_stock::guards::Convert;
+    use open_stock::{Error, ErrorResponse};
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::Json;
+    use rocket::{get, routes};
+    use sea_orm::{ConnAcquireErr, DbErr};
+
+    fn error(kind: &str) -> Error {
+        match kind {
+            "input" => ErrorResponse::custom_input_error("Invalid input."),
+            "unauthorized" => ErrorResponse::custom_unauthorized("No session."),
+            "forbidden" => ErrorResponse::forbidden("Missing CSRF token."),
+            "not-found" => ErrorResponse::not_found("Store", "x"),
+            "missing-record" => DbErr::RecordNotFound("x".to_string()).into(),
+            "conflict" => ErrorResponse::conflict("Already exists."),
+            "unavailable" => DbErr::ConnectionAcquire(ConnAcquireErr::Timeout).into(),
+            _ => ErrorResponse::create_error("Failed."),
+        }
+    }
+
+    #[get("/convert/<kind>")]
+    fn convert(kind: &str) -> Convert<String> {
+        Err(error(kind)).into()
+    }
+
+    #[get("/result/<kind>")]
+    fn result(kind: &str) -> Result<Json<String>, Error> {
+        Err(error(kind))
+    }
+
+    #[test]
+    fn handlers_respond_alike() {
+        let client = Client::untracked(rocket::build().mount("/", routes![convert, result]))
+            .expect("valid rocket instance");
+
+        for (kind, status) in [
+            ("input", Status::BadRequest),
+            ("unauthorized", Status::Unauthorized),
+            ("forbidden", Status::Forbidden),
+            ("not-found", Status::NotFound),
+            ("missing-record", Status::NotFound),
+            ("conflict", Status::Conflict),
+            ("unavailable", Status::ServiceUnavailable),
+            ("standard", Status::InternalServerError),
+        ] {
+            let converted = client.get(format!("/convert/{}", kind)).dispatch();
+            let converted_status = converted.status();
+            let converted_body = converted.into_string();
+
+            let returned = client.get(format!("/result/{}", kind)).dispatch();
+
+            assert_eq!(converted_status, status, "{} via Convert", kind);
+            assert_eq!(returned.status(), status, "{} via Result", kind);
+            assert_eq!(
+                returned.into_string(),
+                converted_body,
+                "{} bodies differ",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn missing_records_are_not_server_errors() {
+        for entity in ["Supplier", "Store", "Transaction", "Promotion"] {
+            let error = ErrorResponse::not_found(entity, "MISSING");
+            assert_eq!(error.status(), Status::NotFound, "{}", entity);
+
+            let body = serde_json::to_value(error.into_body()).unwrap();
+            assert_eq!(body["code"], "error.not_found");
+            assert_eq!(
+                body["message"],
+                format!("{} with id MISSING does not exist.", entity)
+            );
+        }
+    }
+
+    #[test]
+    fn refused_requests_are_forbidden() {
+        let error = ErrorResponse::forbidden("Missing or mismatched CSRF token.");
+        assert_eq!(error.status(), Status::Forbidden);
+
+        // Matches the body of the 403 catcher, so clients see one code either way.
+        let body = serde_json::to_value(error.into_body()).unwrap();
+        assert_eq!(body["code"], "error.forbidden");
+    }
+
+    #[test]
+    fn duplicate_suppliers_conflict() {
+        for (detail, message) in [
+            (
+                "Duplicate entry 'SUPPLIER' for key 'Supplier.PRIMARY'",
+                "A record with the same id already exists.",
+            ),
+            (
+                "Duplicate entry '6421212120' for key 'Supplier.phone'",
+                "A record with the same phone already exists.",
+            ),
+        ] {
+            let error = ErrorResponse::duplicate(detail);
+            assert_eq!(error.status(), Status::Conflict, "{}", detail);
+
+            let body = serde_json::to_value(error.into_body()).unwrap();
+            assert_eq!(body["message"], message);
+        }
+    }
+
+    #[test]
+    fn database_errors_map_by_kind() {
+        for (err, status) in [
+            (DbErr::RecordNotFound("x".to_string()), Status::NotFound),
+            (
+                DbErr::ConnectionAcquire(ConnAcquireErr::Timeout),
+                Status::ServiceUnavailable,
+            ),
+            (
+                DbErr::ConnectionAcquire(ConnAcquireErr::ConnectionClosed),
+                Status::InternalServerError,
+            ),
+            (DbErr::Custom("x".to_string()), Status::InternalServerError),
+        ] {
+            let description = err.to_string();
+            let error: Error = err.into();
+
+            assert_eq!(error.status(), status, "{}", description);
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+mod fields {
+    use open_stock::catchers::{general_catcher, Validated};
+    use open_stock::ContactInformation;
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::{json, Json, Value};
+    use rocket::{catchers, post, routes};
+
+    #[post("/contact", data = "<contact>")]
+    fn contact(contact: Validated<Json<ContactInformation>>) -> String {
+        contact.data().name
+    }
+
+    #[test]
+    fn rejection_lists_offending_fields() {
+        let client = Client::untracked(
+            rocket::build()
+                .mount("/", routes![contact])
+                .register("/", catchers![general_catcher]),
+        )
+        .expect("valid rocket instance");
+
+        let body = json!({
+            "name": "Torpedo7",
+            "mobile": { "number": "12", "valid": true },
+            "email": { "root": "order", "domain": "", "full": "order-at-torpedo7" },
+            "landline": "",
+            "address": {
+                "street": "9 Carbine Road",
+                "street2": "",
+                "city": "Auckland",
+                "country": "New Zealand",
+                "po_code": "1060",
+                "lat": 95.0,
+                "lon": 174.838745
+            }
+        });
+
+        let response = client
+            .post("/contact")
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let error: Value = response.into_json().expect("an error body");
+        let fields: Vec<&str> = error["errors"]
+            .as_array()
+            .expect("a list of field errors")
+            .iter()
+            .filter_map(|e| e["field"].as_str())
+            .collect();
+
+        assert_eq!(error["code"], "error.input.fields");
+        assert_eq!(fields, vec!["address.lat", "email", "mobile"]);
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(feature = "process")]
+#[test]
+fn documented_routes_generate_spec() {
+    use open_stock::methods;
+    use rocket_okapi::settings::OpenApiSettings;
+
+    let settings = OpenApiSettings::default();
+
+    let specs = vec![
+        ("/store", methods::store::documented_routes(&settings)),
+        ("/audit", methods::audit::documented_routes(&settings)),
+        ("/kiosk", methods::kiosk::documented_routes(&settings)),
+        ("/ingress", methods::ingress::documented_routes(&settings)),
+        ("/product", methods::product::documented_routes(&settings)),
+        ("/customer", methods::customer::documented_routes(&settings)),
+        ("/employee", methods::employee::documented_routes(&settings)),
+        (
+            "/giftcard",
+            methods::gift_card::documented_routes(&settings),
+        ),
+        ("/supplier", methods::supplier::documented_routes(&settings)),
+        ("/helpers", methods::helpers::documented_routes(&settings)),
+        ("/reports", methods::report::documented_routes(&settings)),
+        ("/search", methods::search::documented_routes(&settings)),
+        (
+            "/stocktake",
+            methods::stocktake::documented_routes(&settings),
+        ),
+        (
+            "/transaction",
+            methods::transaction::documented_routes(&settings),
+        ),
+        ("/webhook", methods::webhook::documented_routes(&settings)),
+    ];
+
+    for (base, (routes, spec)) in specs {
+        assert!(!routes.is_empty(), "{} mounts no routes", base);
+        assert!(!spec.paths.is_empty(), "{} documents no paths", base);
+
+        let spec = serde_json::to_value(&spec)
+            .unwrap_or_else(|err| panic!("{} spec failed to serialize: {}", base, err));
+
+        assert!(spec["paths"].is_object(), "{} spec has no paths", base);
+    }
+
+    // Order statuses are documented by variant, such that clients can match on each.
+    let (_, spec) = methods::transaction::documented_routes(&settings);
+    let spec = serde_json::to_string(&spec).unwrap();
+
+    for status in [
+        "queued",
+        "transit",
+        "processing",
+        "instore",
+        "partiallyfulfilled",
+        "fulfilled",
+        "failed",
+    ] {
+        assert!(
+            spec.contains(&format!("\"{}\"", status)),
+            "order status {} is missing from the spec",
+            status
+        );
+    }
+}
+
+#[cfg(feature = "process")]
+#[test]
+fn documented_routes_do_not_collide() {
+    use open_stock::methods;
+    use rocket::error::ErrorKind;
+    use rocket::local::blocking::Client;
+    use rocket_okapi::settings::OpenApiSettings;
+
+    let settings = OpenApiSettings::default();
+
+    let rocket = rocket::build()
+        .mount("/store", methods::store::documented_routes(&settings).0)
+        .mount("/audit", methods::audit::documented_routes(&settings).0)
+        .mount("/kiosk", methods::kiosk::documented_routes(&settings).0)
+        .mount("/ingress", methods::ingress::documented_routes(&settings).0)
+        .mount("/product", methods::product::documented_routes(&settings).0)
+        .mount(
+            "/customer",
+            methods::customer::documented_routes(&settings).0,
+        )
+        .mount(
+            "/employee",
+            methods::employee::documented_routes(&settings).0,
+        )
+        .mount(
+            "/giftcard",
+            methods::gift_card::documented_routes(&settings).0,
+        )
+        .mount(
+            "/supplier",
+            methods::supplier::documented_routes(&settings).0,
+        )
+        .mount("/helpers", methods::helpers::documented_routes(&settings).0)
+        .mount("/reports", methods::report::documented_routes(&settings).0)
+        .mount("/search", methods::search::documented_routes(&settings).0)
+        .mount(
+            "/stocktake",
+            methods::stocktake::documented_routes(&settings).0,
+        )
+        .mount(
+            "/transaction",
+            methods::transaction::documented_routes(&settings).0,
+        )
+        .mount("/webhook", methods::webhook::documented_routes(&settings).0);
+
+    // Without a database the routes' sentinels abort launch, but only once
+    // the routes have been checked for collisions, which would refuse it first.
+    if let Err(err) = Client::untracked(rocket) {
+        assert!(
+            !matches!(err.kind(), ErrorKind::Collisions(_)),
+            "routes collide: {:?}",
+            err.kind()
+        );
+    }
+}
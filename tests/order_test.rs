@@ -0,0 +1,342 @@
+mod common;
+
+#[cfg(feature = "types")]
+#[test]
+fn order_round_trip() {
+    use open_stock::{Order, OrderStatus};
+    use serde_json::json;
+
+    let mut line = common::purchase("PDT-KAYAK-PURCHASE-ID-1", "", 2.0);
+    line["product_code"] = json!("54897443288214");
+    line["tags"] = json!(["Kayak"]);
+
+    let mut input = common::order("TOR-19592", vec![line]);
+    input["status"] = json!({
+        "status": { "type": "partiallyfulfilled", "value": ["PDT-KAYAK-PURCHASE-ID-1"] },
+        "assigned_products": ["PDT-KAYAK-PURCHASE-ID-1"],
+        "timestamp": "2022-11-27T06:54:11.665676300Z"
+    });
+
+    let order: Order = serde_json::from_value(input).expect("order is accepted as input");
+
+    // Instances and fulfilment are filled in when absent from the input.
+    assert_eq!(order.products[0].instances.len(), 2);
+    assert_eq!(order.products[0].remaining_quantity, 2.0);
+    assert_eq!(
+        order.status.status,
+        OrderStatus::PartiallyFulfilled(vec!["PDT-KAYAK-PURCHASE-ID-1".to_string()])
+    );
+
+    let written = serde_json::to_value(&order).unwrap();
+    let read: Order = serde_json::from_value(written.clone()).expect("order reads as written");
+
+    assert_eq!(serde_json::to_value(&read).unwrap(), written);
+}
+
+#[cfg(feature = "types")]
+#[test]
+fn created_orders_begin_their_status_history() {
+    use common::read;
+    use open_stock::{Order, OrderStatus};
+
+    let mut order: Order = read(common::order("TOR-19592", vec![]));
+
+    order.seed_status_history();
+    order.seed_status_history();
+
+    assert_eq!(order.status_history.len(), 1);
+    assert_eq!(order.status_history[0].item.status, order.status.status);
+    assert_eq!(order.status_history[0].timestamp, order.status.timestamp);
+
+    // Later statuses are appended to, rather than replace, the initial status.
+    let processing = order
+        .next_status(OrderStatus::Processing(chrono::Utc::now()))
+        .unwrap();
+    order.assign_status(processing, "Picking".to_string());
+
+    assert_eq!(order.status_history.len(), 2);
+    assert!(matches!(
+        order.status_history[0].item.status,
+        OrderStatus::Queued(_)
+    ));
+}
+
+#[cfg(feature = "process")]
+mod tracking {
+    use crate::common::{contact, order, purchase, read, transaction};
+    use open_stock::{Carrier, OrderStatus, Transaction, TransitInformation};
+    use serde_json::json;
+
+    fn transit(carrier: Option<Carrier>, query_url: &str) -> TransitInformation {
+        read(json!({
+            "shipping_company": contact(),
+            "carrier": carrier,
+            "query_url": query_url,
+            "tracking_code": "NZ1523123",
+            "assigned_products": []
+        }))
+    }
+
+    #[test]
+    fn known_carriers_link_to_their_tracking_page() {
+        assert_eq!(
+            TransitInformation::tracking_url(Carrier::NzPost, "NZ1523123").as_deref(),
+            Some("https://www.nzpost.co.nz/tools/tracking/item/NZ1523123")
+        );
+        assert_eq!(
+            TransitInformation::tracking_url(Carrier::FedEx, "1523123").as_deref(),
+            Some("https://www.fedex.com/fedextrack/?trknbr=1523123")
+        );
+    }
+
+    #[test]
+    fn other_carriers_keep_the_url_given() {
+        assert_eq!(
+            TransitInformation::tracking_url(Carrier::Other, "1523123"),
+            None
+        );
+
+        let mut info = transit(Some(Carrier::Other), "https://track.example.com/1523123");
+        info.populate_query_url();
+        assert_eq!(info.query_url, "https://track.example.com/1523123");
+
+        let mut info = transit(None, "https://track.example.com/1523123");
+        info.populate_query_url();
+        assert_eq!(info.query_url, "https://track.example.com/1523123");
+    }
+
+    #[test]
+    fn shipping_an_order_links_its_tracking_page() {
+        let mut sale: Transaction = read(transaction(
+            "2022-11-27T06:54:11.665651300Z",
+            vec![order(
+                "TOR-19592",
+                vec![purchase("KAYAK", "KAYAK-SKU", 1.0)],
+            )],
+            vec![],
+        ));
+
+        sale.apply_order_status(
+            "TOR-19592",
+            OrderStatus::Transit(Box::new(transit(Some(Carrier::NzPost), ""))),
+        )
+        .unwrap();
+
+        match &sale.products[0].status.status {
+            OrderStatus::Transit(info) => assert_eq!(
+                info.query_url,
+                "https://www.nzpost.co.nz/tools/tracking/item/NZ1523123"
+            ),
+            status => panic!("order is {}", status),
+        }
+    }
+}
+
+#[cfg(feature = "types")]
+mod notes {
+    use crate::common::{order, purchase, read};
+    use open_stock::{note_thread, thread_notes, Note, NoteInput, Order};
+    use serde_json::json;
+
+    fn input(message: &str, parent_id: Option<&str>) -> NoteInput {
+        NoteInput {
+            message: message.into(),
+            parent_id: parent_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn replies_thread_two_levels_deep() {
+        let mut order: Order = read(order(
+            "#ORD-1",
+            vec![purchase("51890723908812", "654321", 1.0)],
+        ));
+
+        let question = order
+            .add_note(input("Can this ship Friday?", None), "CUSTOMER".into())
+            .unwrap()
+            .id
+            .clone();
+        let answer = order
+            .add_note(input("It can.", Some(&question)), "EMPLOYEE".into())
+            .unwrap()
+            .id
+            .clone();
+        order
+            .add_note(input("Thanks!", Some(&answer)), "CUSTOMER".into())
+            .unwrap();
+        order
+            .add_note(input("Left at the back door.", None), "EMPLOYEE".into())
+            .unwrap();
+
+        let threads = order.threaded_notes();
+
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].note.message, "Can this ship Friday?");
+        assert_eq!(threads[0].replies.len(), 1);
+        assert_eq!(threads[0].replies[0].note.message, "It can.");
+        assert_eq!(threads[0].replies[0].replies[0].note.message, "Thanks!");
+        assert_eq!(threads[0].replies[0].replies[0].note.author, "CUSTOMER");
+        assert!(threads[1].replies.is_empty());
+    }
+
+    #[test]
+    fn replies_to_unknown_notes_are_refused() {
+        let mut order: Order = read(order("#ORD-1", vec![]));
+
+        assert!(order
+            .add_note(input("Orphan", Some("missing")), "EMPLOYEE".into())
+            .is_none());
+        assert!(order.order_notes.is_empty());
+    }
+
+    /// A note `id` replying to `parent_id`, written `minute` minutes past the hour.
+    fn note(id: &str, parent_id: &str, minute: u32) -> Note {
+        read(json!({
+            "id": id,
+            "parent_id": parent_id,
+            "message": id,
+            "author": "EMPLOYEE",
+            "timestamp": format!("2023-03-01T09:{minute:02}:00Z")
+        }))
+    }
+
+    #[test]
+    fn a_note_replying_to_itself_is_threaded_once() {
+        let notes = vec![note("SELF", "SELF", 0)];
+
+        let threads = thread_notes(&notes);
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].note.id, "SELF");
+        assert!(threads[0].replies.is_empty());
+
+        let thread = note_thread(&notes, "SELF").unwrap();
+        assert!(thread.replies.is_empty());
+    }
+
+    #[test]
+    fn notes_replying_to_one_another_are_threaded_from_the_oldest() {
+        let notes = vec![note("B", "A", 5), note("A", "B", 1)];
+
+        let threads = thread_notes(&notes);
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].note.id, "A");
+        assert_eq!(threads[0].replies.len(), 1);
+        assert_eq!(threads[0].replies[0].note.id, "B");
+        assert!(threads[0].replies[0].replies.is_empty());
+
+        assert!(note_thread(&notes, "B").is_some());
+    }
+}
+
+#[cfg(feature = "process")]
+mod partial_fulfillment {
+    use crate::common::{order, purchase, read};
+    use chrono::Utc;
+    use open_stock::{Order, OrderStatus};
+
+    /// An order of a kayak and a paddle, being picked.
+    fn processing() -> Order {
+        let mut order: Order = read(order(
+            "TOR-19592",
+            vec![
+                purchase("KAYAK", "654321", 1.0),
+                purchase("PADDLE", "654322", 1.0),
+            ],
+        ));
+
+        let processing = order
+            .next_status(OrderStatus::Processing(Utc::now()))
+            .unwrap();
+        order.assign_status(processing, "Picking".into());
+        order
+    }
+
+    fn fulfil(order: &mut Order, line: &str) {
+        let status = order
+            .next_status(OrderStatus::PartiallyFulfilled(vec![line.into()]))
+            .unwrap();
+        order.assign_status(status, "Shipped".into());
+    }
+
+    #[test]
+    fn lines_are_fulfilled_one_at_a_time() {
+        let mut order = processing();
+
+        fulfil(&mut order, "KAYAK");
+
+        assert_eq!(
+            order.status.status,
+            OrderStatus::PartiallyFulfilled(vec!["KAYAK".into()])
+        );
+        assert!(order.products[0].is_fully_fulfilled());
+        assert!(!order.is_fully_fulfilled());
+
+        fulfil(&mut order, "PADDLE");
+
+        assert!(matches!(order.status.status, OrderStatus::Fulfilled(_)));
+        assert!(order.is_fully_fulfilled());
+        assert_eq!(order.status_history.len(), 3);
+    }
+
+    #[test]
+    fn unknown_lines_are_refused() {
+        let order = processing();
+
+        assert!(order
+            .next_status(OrderStatus::PartiallyFulfilled(vec!["CANOE".into()]))
+            .is_err());
+    }
+}
+
+#[cfg(feature = "process")]
+mod picking {
+    use crate::common::{order, purchase, read};
+    use open_stock::{Order, OrderStatus, PickStatus};
+
+    fn instance(order: &Order, n: usize) -> String {
+        order.products[0].instances[n].id.clone()
+    }
+
+    #[test]
+    fn picking_two_of_five_leaves_three_remaining() {
+        let mut order: Order = read(order("TOR-19592", vec![purchase("PADDLE", "654322", 5.0)]));
+
+        for n in 0..2 {
+            let id = instance(&order, n);
+            order.pick("PADDLE", &id, PickStatus::Picked);
+        }
+
+        assert_eq!(order.products[0].fulfilled_quantity, 2.0);
+        assert_eq!(order.products[0].remaining_quantity, 3.0);
+        assert!(!order.is_fully_fulfilled());
+        assert!(matches!(order.status.status, OrderStatus::Queued(_)));
+    }
+
+    #[test]
+    fn un_picking_returns_the_quantity() {
+        let mut order: Order = read(order("TOR-19592", vec![purchase("PADDLE", "654322", 5.0)]));
+        let id = instance(&order, 0);
+
+        order.pick("PADDLE", &id, PickStatus::Picked);
+        order.pick("PADDLE", &id, PickStatus::Picked);
+        assert_eq!(order.products[0].remaining_quantity, 4.0);
+
+        order.pick("PADDLE", &id, PickStatus::Failed);
+        assert_eq!(order.products[0].remaining_quantity, 5.0);
+    }
+
+    #[test]
+    fn picking_every_unit_fulfils_the_order() {
+        let mut order: Order = read(order("TOR-19592", vec![purchase("PADDLE", "654322", 5.0)]));
+        order.status.status = OrderStatus::Processing(chrono::Utc::now());
+
+        for n in 0..5 {
+            let id = instance(&order, n);
+            order.pick("PADDLE", &id, PickStatus::Picked);
+        }
+
+        assert_eq!(order.products[0].remaining_quantity, 0.0);
+        assert!(matches!(order.status.status, OrderStatus::Fulfilled(_)));
+    }
+}
@@ -0,0 +1,128 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod consolidation {
+    use std::collections::HashMap;
+
+    use crate::common::{self, purchase, read, STORE};
+    use open_stock::{Order, PickList};
+    use serde_json::{json, Value};
+
+    fn line(barcode: &str, sku: &str, quantity: f32, fulfilled: f32) -> Value {
+        let mut line = purchase(barcode, sku, quantity);
+        line["fulfilled_quantity"] = json!(fulfilled);
+        line
+    }
+
+    fn order(reference: &str, order_type: &str, status: Value, products: Vec<Value>) -> Order {
+        let mut order = common::order(reference, products);
+        order["status"]["status"] = status;
+        order["order_type"] = json!(order_type);
+
+        read(order)
+    }
+
+    fn queued() -> Value {
+        json!({ "type": "queued", "value": "2022-11-27T06:54:11.665676300Z" })
+    }
+
+    #[test]
+    fn consolidates_unpicked_lines_by_bin() {
+        let orders = vec![
+            order(
+                "TOR-1",
+                "shipment",
+                queued(),
+                vec![
+                    line("KAYAK", "KAYAK-SKU", 2.0, 0.0),
+                    line("PADDLE", "PADDLE-SKU", 1.0, 0.0),
+                ],
+            ),
+            order(
+                "TOR-2",
+                "pickup",
+                queued(),
+                vec![line("KAYAK", "KAYAK-SKU", 3.0, 1.0)],
+            ),
+            // Already picked, in-store sales and completed orders are left off the list.
+            order(
+                "TOR-3",
+                "shipment",
+                queued(),
+                vec![line("HELMET", "HELMET-SKU", 1.0, 1.0)],
+            ),
+            order(
+                "TOR-4",
+                "direct",
+                queued(),
+                vec![line("HELMET", "HELMET-SKU", 1.0, 0.0)],
+            ),
+            order(
+                "TOR-5",
+                "shipment",
+                json!({ "type": "fulfilled", "value": "2022-11-28T06:54:11.665676300Z" }),
+                vec![line("HELMET", "HELMET-SKU", 1.0, 0.0)],
+            ),
+        ];
+
+        let bins = HashMap::from([
+            ("KAYAK".to_string(), "B-04".to_string()),
+            ("PADDLE".to_string(), "A-12".to_string()),
+        ]);
+
+        let list = PickList::from_orders(STORE, &orders, &bins);
+
+        assert_eq!(list.lines.len(), 2);
+
+        assert_eq!(list.lines[0].barcode, "PADDLE");
+        assert_eq!(list.lines[0].bin_location.as_deref(), Some("A-12"));
+
+        assert_eq!(list.lines[1].barcode, "KAYAK");
+        assert_eq!(list.lines[1].quantity, 4.0);
+        assert_eq!(list.lines[1].orders, vec!["TOR-1", "TOR-2"]);
+    }
+
+    #[test]
+    fn lines_without_a_bin_come_last() {
+        let orders = vec![order(
+            "TOR-1",
+            "shipment",
+            queued(),
+            vec![
+                line("KAYAK", "KAYAK-SKU", 1.0, 0.0),
+                line("PADDLE", "PADDLE-SKU", 1.0, 0.0),
+            ],
+        )];
+
+        let bins = HashMap::from([("PADDLE".to_string(), "Z-99".to_string())]);
+        let list = PickList::from_orders(STORE, &orders, &bins);
+
+        assert_eq!(list.lines[0].barcode, "PADDLE");
+        assert_eq!(list.lines[1].barcode, "KAYAK");
+        assert_eq!(list.lines[1].bin_location, None);
+    }
+}
+
+#[cfg(feature = "types")]
+mod layout {
+    use crate::common::{self, read, STORE};
+    use open_stock::{Stock, StoreLayout};
+
+    #[test]
+    fn bins_match_regardless_of_case() {
+        let layout = StoreLayout {
+            bins: vec!["A1-03".to_string(), "B2-01".to_string()],
+        };
+
+        assert!(layout.contains("a1-03"));
+        assert!(layout.contains(" B2-01 "));
+        assert!(!layout.contains("C1-01"));
+    }
+
+    #[test]
+    fn stock_without_a_bin_is_read() {
+        let stock: Stock = read(common::stock(STORE, 4.0));
+
+        assert_eq!(stock.bin_location, None);
+    }
+}
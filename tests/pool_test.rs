@@ -0,0 +1,39 @@
+#[cfg(feature = "process")]
+mod exhaustion {
+    use open_stock::{Error, PoolConfig};
+    use rocket::http::Status;
+    use sea_orm::{ConnectOptions, ConnectionTrait, Database, Statement, TransactionTrait};
+    use std::time::{Duration, Instant};
+
+    #[rocket::async_test]
+    async fn an_exhausted_pool_fails_promptly() {
+        let config = PoolConfig {
+            max_connections: 1,
+            min_connections: 1,
+            acquire_timeout: Duration::from_millis(200),
+            ..PoolConfig::default()
+        };
+
+        let mut options = ConnectOptions::new("sqlite::memory:".to_string());
+        config.apply(&mut options);
+        let db = Database::connect(options).await.unwrap();
+
+        // Holds the only connection for as long as the transaction is open.
+        let held = db.begin().await.unwrap();
+
+        let started = Instant::now();
+        let result = db
+            .execute(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT 1",
+            ))
+            .await;
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let error: Error = result.unwrap_err().into();
+        assert_eq!(error.status(), Status::ServiceUnavailable);
+
+        held.rollback().await.unwrap();
+    }
+}
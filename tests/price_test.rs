@@ -0,0 +1,303 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod scheduled {
+    use crate::common::{self, read};
+    use chrono::{Duration, Utc};
+    use open_stock::VariantInformation;
+    use serde_json::{json, Value};
+
+    fn variant(scheduled_prices: Value) -> VariantInformation {
+        let mut variant = common::variant("KAYAK");
+        variant["price_tiers"] = json!({ "Trade": 350.0 });
+        variant["scheduled_prices"] = scheduled_prices;
+
+        read(variant)
+    }
+
+    fn change(retail_price: f32, days: i64) -> Value {
+        json!({
+            "id": format!("CHANGE-{}", days),
+            "retail_price": retail_price,
+            "effective_at": Utc::now() + Duration::days(days),
+            "scheduled_by": "EMPLOYEE",
+            "scheduled_at": Utc::now()
+        })
+    }
+
+    #[test]
+    fn future_changes_do_not_affect_todays_price() {
+        let kayak = variant(json!([change(449.99, 7)]));
+
+        assert_eq!(kayak.current_price(), 399.99);
+        assert_eq!(kayak.price_for(None), 399.99);
+        assert_eq!(
+            kayak.retail_price_at(Utc::now() + Duration::days(8)),
+            449.99
+        );
+    }
+
+    #[test]
+    fn latest_change_to_take_effect_applies() {
+        let kayak = variant(json!([
+            change(429.99, -2),
+            change(419.99, -5),
+            change(449.99, 7)
+        ]));
+
+        assert_eq!(kayak.price_for(None), 429.99);
+        // Group pricing is unaffected by changes to the retail price.
+        assert_eq!(kayak.price_for(Some("Trade")), 350.0);
+    }
+
+    #[test]
+    fn due_changes_are_settled_into_the_retail_price() {
+        let mut kayak = variant(json!([change(429.99, -2), change(449.99, 7)]));
+
+        kayak.apply_due_prices(Utc::now());
+
+        assert_eq!(kayak.retail_price, 429.99);
+        assert_eq!(kayak.scheduled_prices.len(), 1);
+        assert_eq!(kayak.scheduled_prices[0].retail_price, 449.99);
+    }
+}
+
+#[cfg(feature = "types")]
+mod bulk {
+    use crate::common::{self, read};
+    use chrono::Utc;
+    use open_stock::{BulkPriceUpdate, Product};
+    use serde_json::json;
+
+    fn product(sku: &str, tags: Vec<&str>) -> Product {
+        let mut product = common::product(sku, vec![common::variant(sku)]);
+        product["tags"] = json!(tags);
+
+        read(product)
+    }
+
+    fn update(field: &str, adjustment: serde_json::Value) -> BulkPriceUpdate {
+        read(json!({ "tags": ["Kayaks"], "field": field, "adjustment": adjustment }))
+    }
+
+    #[test]
+    fn tagged_products_rise_by_five_percent() {
+        let mut products = [
+            product("KAYAK", vec!["Kayaks", "Water"]),
+            product("PADDLE", vec!["Water"]),
+            product("SIT-ON", vec!["Kayaks"]),
+        ];
+        let rise = update("Retail", json!({ "Percentage": 5.0 }));
+
+        let repriced: usize = products
+            .iter_mut()
+            .filter(|p| p.tagged_any(&rise.tags))
+            .map(|p| p.adjust_prices(&rise, Utc::now()).unwrap())
+            .sum();
+
+        assert_eq!(repriced, 2);
+        // 399.99 * 1.05 = 419.9895, rounded to the cent.
+        assert_eq!(products[0].variants[0].retail_price, 419.99);
+        assert_eq!(products[1].variants[0].retail_price, 399.99);
+        assert_eq!(products[2].variants[0].retail_price, 419.99);
+        // Only the retail price is adjusted.
+        assert_eq!(products[0].variants[0].marginal_price, 250.0);
+    }
+
+    #[test]
+    fn prices_may_not_fall_below_zero() {
+        let mut kayak = product("KAYAK", vec!["Kayaks"]);
+
+        assert!(kayak
+            .adjust_prices(
+                &update("Marginal", json!({ "Absolute": -300.0 })),
+                Utc::now()
+            )
+            .is_err());
+        assert_eq!(kayak.variants[0].marginal_price, 250.0);
+
+        assert!(kayak
+            .adjust_prices(
+                &update("Marginal", json!({ "Absolute": -250.0 })),
+                Utc::now()
+            )
+            .is_ok());
+        assert_eq!(kayak.variants[0].marginal_price, 0.0);
+    }
+}
+
+#[cfg(feature = "types")]
+mod history {
+    use crate::common::{self, read};
+    use chrono::{DateTime, Duration, Utc};
+    use open_stock::{PriceHistoryEntry, Product};
+    use serde_json::json;
+
+    fn kayak() -> Product {
+        read(common::product("KAYAK", vec![common::variant("KAYAK")]))
+    }
+
+    #[test]
+    fn two_updates_leave_two_entries() {
+        let start = Utc::now() - Duration::days(2);
+        let original = kayak();
+
+        let mut first = original.clone();
+        first.variants[0].retail_price = 379.99;
+        let mut history = PriceHistoryEntry::between(&original, &first, "EMPLOYEE", start);
+
+        let mut second = first.clone();
+        second.variants[0].retail_price = 359.99;
+        second.variants[0].marginal_price = 200.0;
+        history.append(&mut PriceHistoryEntry::between(
+            &first,
+            &second,
+            "MANAGER",
+            start + Duration::days(1),
+        ));
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            (history[0].old_price, history[0].new_price),
+            (399.99, 379.99)
+        );
+        assert_eq!(history[0].changed_by, "EMPLOYEE");
+        assert_eq!(
+            (history[1].old_price, history[1].new_price),
+            (379.99, 359.99)
+        );
+        assert_eq!(history[1].changed_by, "MANAGER");
+
+        // The price each sold at is found from the history, for a backdated return.
+        let variant = &second.variants[0];
+        let at = |hours| start + Duration::hours(hours);
+        assert_eq!(
+            PriceHistoryEntry::price_at(variant, &history, at(-1)),
+            399.99
+        );
+        assert_eq!(
+            PriceHistoryEntry::price_at(variant, &history, at(1)),
+            379.99
+        );
+        assert_eq!(
+            PriceHistoryEntry::price_at(variant, &history, at(25)),
+            359.99
+        );
+    }
+
+    #[test]
+    fn settled_scheduled_changes_are_credited_to_their_scheduler() {
+        let effective_at = Utc::now() - Duration::hours(1);
+        let mut before = kayak();
+        before.variants[0].scheduled_prices = read(json!([{
+            "id": "CHANGE",
+            "retail_price": 449.99,
+            "effective_at": effective_at,
+            "scheduled_by": "MANAGER",
+            "scheduled_at": effective_at - Duration::days(7)
+        }]));
+
+        let mut after = before.clone();
+        after.variants[0].apply_due_prices(Utc::now());
+
+        let history = PriceHistoryEntry::between(&before, &after, "EMPLOYEE", Utc::now());
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].new_price, 449.99);
+        assert_eq!(history[0].changed_by, "MANAGER");
+        assert_eq!(history[0].changed_at, effective_at);
+    }
+
+    fn scheduled(product: &mut Product, retail_price: f32, effective_at: DateTime<Utc>) {
+        product.variants[0].scheduled_prices = read(json!([{
+            "id": "CHANGE",
+            "retail_price": retail_price,
+            "effective_at": effective_at,
+            "scheduled_by": "MANAGER",
+            "scheduled_at": effective_at - Duration::days(7)
+        }]));
+    }
+
+    #[test]
+    fn an_update_settles_changes_which_have_taken_effect() {
+        let mut before = kayak();
+        scheduled(&mut before, 449.99, Utc::now() - Duration::hours(1));
+
+        let mut update = before.clone();
+        update.name = "Touring Kayak".into();
+        update.settle_due_prices(&before, Utc::now());
+
+        assert_eq!(update.variants[0].retail_price, 449.99);
+        assert!(update.variants[0].scheduled_prices.is_empty());
+
+        let history = PriceHistoryEntry::between(&before, &update, "EMPLOYEE", Utc::now());
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].changed_by, "MANAGER");
+    }
+
+    #[test]
+    fn a_price_edited_in_the_update_follows_the_settled_change() {
+        let mut before = kayak();
+        scheduled(&mut before, 449.99, Utc::now() - Duration::hours(1));
+
+        let mut update = before.clone();
+        update.variants[0].retail_price = 429.99;
+        update.settle_due_prices(&before, Utc::now());
+
+        assert_eq!(update.variants[0].retail_price, 429.99);
+        assert!(update.variants[0].scheduled_prices.is_empty());
+
+        let history = PriceHistoryEntry::between(&before, &update, "EMPLOYEE", Utc::now());
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            (history[0].old_price, history[0].new_price),
+            (399.99, 449.99)
+        );
+        assert_eq!(
+            (history[1].old_price, history[1].new_price),
+            (449.99, 429.99)
+        );
+        assert_eq!(history[1].changed_by, "EMPLOYEE");
+    }
+
+    #[test]
+    fn pending_changes_survive_an_update() {
+        let mut before = kayak();
+        scheduled(&mut before, 449.99, Utc::now() + Duration::days(7));
+
+        let mut update = before.clone();
+        update.settle_due_prices(&before, Utc::now());
+
+        assert_eq!(update.variants[0].retail_price, 399.99);
+        assert_eq!(update.variants[0].scheduled_prices.len(), 1);
+    }
+}
+
+#[cfg(feature = "types")]
+mod currency {
+    use open_stock::{format_price, CurrencyFormat};
+
+    #[test]
+    fn euros_follow_the_amount_with_a_decimal_comma() {
+        let eur = CurrencyFormat::from_code("EUR").unwrap();
+
+        assert_eq!(eur.format(4.5), "4,50 €");
+        assert_eq!(eur.format(-1234.567), "-1234,57 €");
+    }
+
+    #[test]
+    fn yen_have_no_minor_units() {
+        let jpy = CurrencyFormat::from_code("jpy").unwrap();
+
+        assert_eq!(jpy.format(450.0), "¥450");
+        assert_eq!(jpy.format(449.6), "¥450");
+        assert_eq!(jpy.format(-450.0), "-¥450");
+    }
+
+    #[test]
+    fn dollars_are_the_default() {
+        assert_eq!(format_price(4.5), "$4.50");
+        assert_eq!(format_price(-0.001), "$0.00");
+        assert!(CurrencyFormat::from_code("XYZ").is_none());
+    }
+}
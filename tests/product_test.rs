@@ -0,0 +1,86 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod discontinued {
+    use crate::common::{self, read, stock, STORE};
+    use open_stock::VariantInformation;
+    use serde_json::json;
+
+    /// A discontinued kayak, which had been back-orderable, with `sellable` on hand.
+    fn kayak(sellable: f32) -> VariantInformation {
+        let mut variant = common::variant("51890723908812");
+        variant["stock"] = json!([stock(STORE, sellable)]);
+        variant["stock_information"]["back_order"] = json!(true);
+        variant["stock_information"]["discontinued"] = json!(true);
+
+        read(variant)
+    }
+
+    #[test]
+    fn stock_on_hand_still_sells() {
+        assert!(kayak(2.0).available_at("002", 2.0));
+    }
+
+    #[test]
+    fn no_more_may_be_ordered_once_sold_out() {
+        assert!(!kayak(2.0).available_at("002", 3.0));
+        assert!(!kayak(0.0).available_at("002", 1.0));
+    }
+
+    #[test]
+    fn reinstated_variants_may_be_back_ordered() {
+        let mut variant = kayak(0.0);
+        variant.stock_information.discontinued = false;
+
+        assert!(variant.available_at("002", 1.0));
+    }
+}
+
+#[cfg(feature = "process")]
+mod units {
+    use crate::common::{self, order, purchase, read, stock, STORE};
+    use open_stock::{
+        Order, Product, QuantityAlterationIntent, Transaction, TransactionType, UnitOfMeasure,
+    };
+    use serde_json::json;
+
+    /// Coffee beans stocked by the kilogram, 2kg on hand.
+    fn beans() -> Product {
+        let mut variant = common::variant("COFFEE");
+        variant["stock"] = json!([stock(STORE, 2.0)]);
+        variant["stock_information"]["unit"] = json!("Kilogram");
+
+        read(common::product("COFFEE-SKU", vec![variant]))
+    }
+
+    /// A sale of 250 grams of beans.
+    fn sale() -> Order {
+        let mut line = purchase("COFFEE", "COFFEE-SKU", 250.0);
+        line["unit"] = json!("Gram");
+
+        read(order("TOR-19592", vec![line]))
+    }
+
+    #[test]
+    fn grams_sold_are_kilograms_of_stock() {
+        let sale = sale();
+
+        assert_eq!(
+            sale.products[0].stock_quantity(UnitOfMeasure::Kilogram),
+            Some(0.25)
+        );
+        assert_eq!(sale.products[0].stock_quantity(UnitOfMeasure::Litre), None);
+    }
+
+    #[test]
+    fn a_250g_sale_draws_a_quarter_kilogram() {
+        let mut product = beans();
+        let intents = QuantityAlterationIntent::for_order(&sale(), TransactionType::Out, None);
+
+        assert!(Transaction::alter_stock(&mut product, &intents, false).is_empty());
+        assert_eq!(
+            product.variants[0].stock[0].quantity.quantity_sellable,
+            1.75
+        );
+    }
+}
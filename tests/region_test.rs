@@ -0,0 +1,225 @@
+#[cfg(feature = "types")]
+mod po_codes {
+    use open_stock::{Address, Region};
+
+    fn address(country: &str, po_code: &str) -> Address {
+        Address {
+            street: "1 George Street".into(),
+            street2: "".into(),
+            city: "".into(),
+            country: country.into(),
+            po_code: po_code.into(),
+            lat: 0.0,
+            lon: 0.0,
+        }
+    }
+
+    #[test]
+    fn australian_default_expects_four_digits() {
+        assert!(address("", "2000").has_valid_po_code(Region::Australia));
+        assert!(!address("", "90210").has_valid_po_code(Region::Australia));
+        assert!(!address("", "200").has_valid_po_code(Region::Australia));
+    }
+
+    #[test]
+    fn american_default_expects_zip_codes() {
+        assert!(address("", "90210").has_valid_po_code(Region::UnitedStates));
+        assert!(address("", "90210-1234").has_valid_po_code(Region::UnitedStates));
+        assert!(address("", "902101234").has_valid_po_code(Region::UnitedStates));
+        assert!(!address("", "2000").has_valid_po_code(Region::UnitedStates));
+    }
+
+    #[test]
+    fn british_codes_are_alphanumeric() {
+        for valid in ["SW1A 1AA", "M1 1AE", "EC1A 1BB", "b33 8th"] {
+            assert!(
+                address("United Kingdom", valid).has_valid_po_code(Region::UnitedStates),
+                "{}",
+                valid
+            );
+        }
+
+        for invalid in ["90210", "SW1A", "1AA SW1A"] {
+            assert!(
+                !address("UK", invalid).has_valid_po_code(Region::UnitedStates),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn named_country_overrides_default() {
+        assert!(address("Australia", "2000").has_valid_po_code(Region::UnitedStates));
+        assert!(address("US", "90210").has_valid_po_code(Region::Australia));
+        assert!(!address("AU", "90210").has_valid_po_code(Region::UnitedStates));
+    }
+
+    #[test]
+    fn unlisted_countries_and_blank_codes_pass() {
+        assert!(address("Germany", "10115").has_valid_po_code(Region::Australia));
+        assert!(address("", "").has_valid_po_code(Region::Australia));
+    }
+}
+
+#[cfg(feature = "types")]
+mod phone_numbers {
+    use open_stock::{MobileNumber, Region};
+
+    #[test]
+    fn national_numbers_follow_region() {
+        assert!(MobileNumber::from_region("0412 345 678".into(), Region::Australia).valid);
+        assert!(!MobileNumber::from_region("0412 345 678".into(), Region::UnitedStates).valid);
+        assert!(MobileNumber::from_region("(555) 123-4567".into(), Region::UnitedStates).valid);
+        assert!(!MobileNumber::from_region("1 (555) 123-4567".into(), Region::NewZealand).valid);
+    }
+
+    #[test]
+    fn international_numbers_ignore_region() {
+        let number = MobileNumber::from_region("+64 21 212 120".into(), Region::UnitedStates);
+
+        assert!(number.valid);
+        assert_eq!(
+            number.international(Region::UnitedStates).as_deref(),
+            Some("+6421212120")
+        );
+    }
+
+    #[test]
+    fn national_numbers_gain_calling_code() {
+        let number = MobileNumber::from_region("021 212 120".into(), Region::NewZealand);
+
+        assert_eq!(
+            number.international(Region::NewZealand).as_deref(),
+            Some("+6421212120")
+        );
+        assert_eq!(
+            Region::UnitedStates
+                .international("555.123.4567")
+                .as_deref(),
+            Some("+15551234567")
+        );
+        assert_eq!(
+            Region::NewZealand
+                .international("+64 (0)21 212 120")
+                .as_deref(),
+            Some("+6421212120")
+        );
+    }
+}
+
+#[cfg(feature = "types")]
+mod international_numbers {
+    use open_stock::{verify_phone_number_with_country_code, MobileNumber, Region};
+
+    #[test]
+    fn british_numbers_are_valid() {
+        assert!(MobileNumber::from("+44 20 7946 0958".into()).valid);
+        assert!(MobileNumber::from("+44 7700 900123".into()).valid);
+        assert!(MobileNumber::from_region("07700 900123".into(), Region::UnitedKingdom).valid);
+        assert!(!MobileNumber::from("+44 20 7946".into()).valid);
+    }
+
+    #[test]
+    fn australian_numbers_are_valid() {
+        assert!(MobileNumber::from("+61 412 345 678".into()).valid);
+        assert!(MobileNumber::from("+61 2 9876 5432".into()).valid);
+        assert!(!MobileNumber::from("+61 412 345 6789".into()).valid);
+    }
+
+    #[test]
+    fn new_zealand_numbers_are_valid() {
+        assert!(MobileNumber::from("+64 21 212 120".into()).valid);
+        assert!(MobileNumber::from("+64 (0)21 212 120".into()).valid);
+        assert!(MobileNumber::from("+64 9 379 0123".into()).valid);
+        assert!(!MobileNumber::from("+64 21 21".into()).valid);
+    }
+
+    #[test]
+    fn north_american_check_is_unchanged() {
+        assert!(verify_phone_number_with_country_code("+1 (555) 123-4567"));
+        assert!(!verify_phone_number_with_country_code("+61 412 345 678"));
+    }
+}
+
+#[cfg(feature = "process")]
+mod tenant_region {
+    use open_stock::{
+        validate_mobile_number, Address, ContactInformationInput, MobileNumber, Region,
+        TenantSettings, DEFAULT_REGION,
+    };
+    use rocket::http::Status;
+    use serde_json::json;
+    use validator::Validate;
+
+    fn contact(mobile: &str, po_code: &str) -> ContactInformationInput {
+        ContactInformationInput {
+            name: "Torpedo7".into(),
+            mobile: mobile.into(),
+            email: "".into(),
+            landline: "".into(),
+            address: Address {
+                street: "1 George Street".into(),
+                street2: "".into(),
+                city: "Sydney".into(),
+                country: "".into(),
+                po_code: po_code.into(),
+                lat: 0.0,
+                lon: 0.0,
+            },
+        }
+    }
+
+    #[test]
+    fn tenants_without_a_region_use_the_default() {
+        let settings: TenantSettings = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(settings.region(), *DEFAULT_REGION);
+
+        let settings: TenantSettings =
+            serde_json::from_value(json!({ "region": "Australia" })).unwrap();
+        assert_eq!(settings.region(), Region::Australia);
+    }
+
+    #[test]
+    fn requests_are_not_refused_by_the_default_region() {
+        let input = contact("0412 345 678", "2000");
+
+        assert!(input.validate().is_ok());
+        assert!(validate_mobile_number(&MobileNumber::from_region(
+            input.mobile,
+            Region::UnitedStates
+        ))
+        .is_ok());
+        assert!(validate_mobile_number(&MobileNumber::from("12".into())).is_err());
+    }
+
+    #[test]
+    fn contacts_are_checked_in_the_tenants_region() {
+        let input = contact("0412 345 678", "2000");
+
+        assert!(input.ensure_valid("contact", Region::Australia).is_ok());
+
+        let error = input
+            .ensure_valid("contact", Region::UnitedStates)
+            .unwrap_err();
+        assert_eq!(error.status(), Status::BadRequest);
+
+        let body = serde_json::to_value(error.into_body()).unwrap();
+        let fields: Vec<&str> = body["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, vec!["contact.address.po_code", "contact.mobile"]);
+    }
+
+    #[test]
+    fn mobile_numbers_are_read_in_the_tenants_region() {
+        let australian = contact("0412 345 678", "2000").into_major_in(Region::Australia);
+        let american = contact("0412 345 678", "2000").into_major_in(Region::UnitedStates);
+
+        assert!(australian.mobile.valid);
+        assert!(!american.mobile.valid);
+    }
+}
@@ -0,0 +1,94 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod local_day {
+    use chrono::NaiveDate;
+
+    use crate::common::{self, payment, read, STORE};
+    use open_stock::{compute_tax, DailySummary, Receipt, Store, TenantSettings, Transaction};
+    use serde_json::json;
+
+    /// A store five hours behind UTC in March, such that 23:30 locally is 04:30 UTC
+    /// the next day.
+    fn store() -> Store {
+        read(common::store(STORE, "America/New_York"))
+    }
+
+    fn sale(order_date: &str) -> Transaction {
+        read(common::transaction(
+            order_date,
+            vec![],
+            vec![payment(json!("Card"), 20.0)],
+        ))
+    }
+
+    #[test]
+    fn late_sales_count_toward_the_local_day() {
+        let late = sale("2024-03-02T04:30:00Z");
+        let local_day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let utc_day = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+
+        let summary = DailySummary::new(&store(), local_day, std::slice::from_ref(&late));
+
+        assert_eq!(summary.sales, 1);
+        assert_eq!(summary.takings, 20.0);
+        assert!(summary.from <= late.order_date && late.order_date < summary.to);
+
+        assert_eq!(DailySummary::new(&store(), utc_day, &[late]).sales, 0);
+    }
+
+    #[test]
+    fn receipts_print_the_local_time_of_sale() {
+        let late = sale("2024-03-02T04:30:00Z");
+        let settings = TenantSettings::default();
+        let tax = compute_tax(&late.products, &settings, |_| String::new());
+
+        let mut receipt = Receipt::new(&late, &tax, settings.rounding);
+        receipt.localise(&store());
+
+        assert!(receipt.to_string().contains("01/03/2024 23:30"));
+    }
+
+    /// A store in Auckland, whose clocks go back an hour at 3am on 7 April 2024,
+    /// from 13 hours ahead of UTC to 12.
+    fn auckland() -> Store {
+        read(common::store(STORE, "Pacific/Auckland"))
+    }
+
+    #[test]
+    fn late_sales_count_toward_the_local_day_either_side_of_daylight_saving() {
+        let auckland = auckland();
+
+        for (order_date, day) in [
+            (
+                "2024-04-06T10:30:00Z",
+                NaiveDate::from_ymd_opt(2024, 4, 6).unwrap(),
+            ),
+            (
+                "2024-04-08T11:30:00Z",
+                NaiveDate::from_ymd_opt(2024, 4, 8).unwrap(),
+            ),
+        ] {
+            let late = sale(order_date);
+
+            assert_eq!(
+                auckland.local_time(late.order_date),
+                day.and_hms_opt(23, 30, 0).unwrap()
+            );
+
+            let summary = DailySummary::new(&auckland, day, std::slice::from_ref(&late));
+            assert_eq!(summary.sales, 1, "{order_date}");
+
+            let next_day = day.succ_opt().unwrap();
+            assert_eq!(DailySummary::new(&auckland, next_day, &[late]).sales, 0);
+        }
+    }
+
+    #[test]
+    fn the_day_clocks_go_back_is_25_hours_long() {
+        let day = NaiveDate::from_ymd_opt(2024, 4, 7).unwrap();
+        let (from, to) = DailySummary::bounds(&auckland(), day);
+
+        assert_eq!((to - from).num_hours(), 25);
+    }
+}
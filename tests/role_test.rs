@@ -0,0 +1,68 @@
+#[cfg(feature = "types")]
+mod templates {
+    use open_stock::{Access, Action, Role};
+
+    #[test]
+    fn cashier_grants_exactly_the_front_line_actions() {
+        let mut granted = Role::Cashier.actions();
+        granted.sort_by_key(|action| format!("{:?}", action));
+
+        let mut expected = vec![
+            Action::FetchProduct,
+            Action::FetchCustomer,
+            Action::CreateCustomer,
+            Action::ModifyCustomer,
+            Action::FetchTransaction,
+            Action::CreateTransaction,
+            Action::FetchStore,
+            Action::FetchKiosk,
+            Action::FetchSupplier,
+            Action::FetchGeoLocation,
+            Action::FetchGiftCard,
+            Action::RedeemGiftCard,
+        ];
+        expected.sort_by_key(|action| format!("{:?}", action));
+
+        assert_eq!(granted, expected);
+    }
+
+    #[test]
+    fn cashier_level_lists_every_action_granting_only_its_own() {
+        let level = Role::Cashier.level();
+        let granted: Vec<Action> = level
+            .iter()
+            .filter(|access| access.authority == 1)
+            .map(|access| access.action.clone())
+            .collect();
+
+        assert_eq!(level.len(), Role::Admin.actions().len());
+        assert_eq!(granted.len(), 12);
+        assert!(!granted.contains(&Action::DeleteTransaction));
+        assert!(!granted.contains(&Action::BulkUpdatePrices));
+    }
+
+    #[test]
+    fn overrides_apply_on_top_of_the_role() {
+        let level = Role::Cashier.level_with(&[Access {
+            action: Action::FetchReports,
+            authority: 1,
+        }]);
+
+        let reports = level
+            .iter()
+            .find(|access| access.action == Action::FetchReports)
+            .unwrap();
+        assert_eq!(reports.authority, 1);
+    }
+
+    #[test]
+    fn managers_may_do_all_a_cashier_may() {
+        let manager = Role::Manager.actions();
+
+        assert!(Role::Cashier
+            .actions()
+            .iter()
+            .all(|action| manager.contains(action)));
+        assert!(manager.contains(&Action::BulkUpdatePrices));
+    }
+}
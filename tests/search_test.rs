@@ -0,0 +1,68 @@
+#[cfg(feature = "types")]
+mod fuzzy {
+    use open_stock::{edit_distance, fuzzy_rank, DEFAULT_FUZZY_DISTANCE};
+
+    fn rank(query: &str, names: &[&str]) -> Vec<String> {
+        fuzzy_rank(
+            query,
+            DEFAULT_FUZZY_DISTANCE,
+            names.iter().map(|n| (n.to_string(), n.to_string())),
+        )
+    }
+
+    #[test]
+    fn transposed_characters_are_one_edit() {
+        assert_eq!(edit_distance("jhonson", "johnson"), 1);
+        assert_eq!(rank("Jhonson", &["Carl Johnson"]), vec!["Carl Johnson"]);
+    }
+
+    #[test]
+    fn missing_characters_are_one_edit_each() {
+        assert_eq!(edit_distance("jonson", "johnson"), 1);
+        assert_eq!(edit_distance("jnson", "johnson"), 2);
+        assert_eq!(rank("Jonson", &["Carl Johnson"]), vec!["Carl Johnson"]);
+    }
+
+    #[test]
+    fn names_beyond_the_distance_are_dropped() {
+        assert!(rank("Jsn", &["Johnson"]).is_empty());
+        assert!(rank("Kayak", &["Torpedo7", "Paddle"]).is_empty());
+    }
+
+    #[test]
+    fn exact_matches_rank_first() {
+        let ranked = rank("Johnson", &["Jonson", "Johnsons", "johnson"]);
+
+        assert_eq!(ranked, vec!["johnson", "Johnsons", "Jonson"]);
+    }
+}
+
+#[cfg(feature = "types")]
+mod phone {
+    use open_stock::normalize_phone_number;
+
+    #[test]
+    fn formatting_does_not_matter() {
+        for formatted in [
+            "5551234567",
+            "(555) 123-4567",
+            "555.123.4567",
+            "555 123 4567",
+            "+1 555 123 4567",
+            "1 (555) 123-4567",
+        ] {
+            assert_eq!(
+                normalize_phone_number(formatted),
+                "5551234567",
+                "{}",
+                formatted
+            );
+        }
+    }
+
+    #[test]
+    fn other_numbers_keep_every_digit() {
+        assert_eq!(normalize_phone_number("+64 21 212 120"), "6421212120");
+        assert_eq!(normalize_phone_number("12-34"), "1234");
+    }
+}
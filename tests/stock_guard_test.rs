@@ -0,0 +1,382 @@
+mod common;
+
+#[cfg(feature = "process")]
+mod guard {
+    use crate::common::{self, order, purchase, read, stock, transaction, OTHER_STORE, STORE};
+    use futures::future::join_all;
+    use open_stock::{
+        prelude::{Products, Transactions},
+        products, transactions, Product, Quantity, QuantityAlterationIntent, Reservation, Session,
+        StockShortfall, Transaction, TransactionInit, TransactionType, UnitOfMeasure,
+    };
+    use rocket::http::Status;
+    use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn on_hand(sellable: f32, reserved: f32) -> Quantity {
+        Quantity {
+            quantity_sellable: sellable,
+            quantity_unsellable: 0.0,
+            quantity_on_order: 0.0,
+            quantity_allocated: 0.0,
+            quantity_reserved: reserved,
+        }
+    }
+
+    fn sale(quantity: f32, reservation: Option<Reservation>) -> QuantityAlterationIntent {
+        QuantityAlterationIntent {
+            variant_code: "51890723908812".to_string(),
+            product_sku: "132522".to_string(),
+            transaction_store_code: "001".to_string(),
+            transaction_store_id: "c4a1d88b-e8a0-4dcd-ade2-1eea82254816".to_string(),
+            transaction_type: TransactionType::Out,
+            quantity_to_transact: quantity,
+            unit: None,
+            reservation,
+        }
+    }
+
+    #[test]
+    fn sales_may_not_exceed_stock_on_hand() {
+        let stock = on_hand(2.0, 0.0);
+
+        assert_eq!(sale(2.0, None).shortfall(&stock, UnitOfMeasure::Each), None);
+        assert_eq!(
+            sale(3.0, None).shortfall(&stock, UnitOfMeasure::Each),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn reservations_may_not_exceed_stock_available() {
+        let stock = on_hand(2.0, 1.0);
+
+        assert_eq!(
+            sale(1.0, Some(Reservation::Reserve)).shortfall(&stock, UnitOfMeasure::Each),
+            None
+        );
+        assert_eq!(
+            sale(2.0, Some(Reservation::Reserve)).shortfall(&stock, UnitOfMeasure::Each),
+            Some(1.0)
+        );
+        assert_eq!(
+            sale(5.0, Some(Reservation::Release)).shortfall(&stock, UnitOfMeasure::Each),
+            None
+        );
+    }
+
+    #[test]
+    fn shortfall_names_the_sku() {
+        let shortfall = StockShortfall {
+            product_sku: "132522".to_string(),
+            variant_code: "51890723908812".to_string(),
+            store_code: "001".to_string(),
+            required: 3.0,
+            shortfall: 1.0,
+        };
+
+        assert_eq!(
+            shortfall.to_string(),
+            "132522 (51890723908812) at 001: required 3, short by 1"
+        );
+    }
+
+    fn kayak(sellable: f32) -> Product {
+        let mut variant = common::variant("51890723908812");
+        variant["stock"] = json!([stock(OTHER_STORE, sellable)]);
+
+        read(common::product("132522", vec![variant]))
+    }
+
+    fn sellable(product: &Product) -> f32 {
+        product.variants[0].stock[0].quantity.quantity_sellable
+    }
+
+    #[test]
+    fn a_second_sale_of_the_last_unit_is_refused() {
+        let mut product = kayak(1.0);
+
+        assert!(Transaction::alter_stock(&mut product, &[sale(1.0, None)], false).is_empty());
+
+        let shortfalls = Transaction::alter_stock(&mut product, &[sale(1.0, None)], false);
+        assert_eq!(shortfalls.len(), 1);
+        assert_eq!(shortfalls[0].product_sku, "132522");
+        assert_eq!(shortfalls[0].shortfall, 1.0);
+        assert_eq!(sellable(&product), 0.0);
+    }
+
+    #[rocket::async_test]
+    #[ignore = "requires a MySQL database named by DATABASE_URL"]
+    async fn simultaneous_sales_of_the_last_unit_are_recorded_once() {
+        let db = common::database().await;
+        let session = Session::default_with_tenant(Uuid::new_v4().to_string());
+
+        let mut variant = common::variant("51890723908812");
+        variant["stock"] = json!([stock(STORE, 1.0)]);
+        let product: Product = read(common::product("132522", vec![variant]));
+
+        Product::insert(product, session.clone(), &db)
+            .await
+            .unwrap();
+
+        let sales = (0..2).map(|_| {
+            let db = db.clone();
+            let session = session.clone();
+
+            let sale: TransactionInit = read(transaction(
+                "2023-03-01T09:00:00Z",
+                vec![order(
+                    "TOR-19592",
+                    vec![purchase("51890723908812", "132522", 1.0)],
+                )],
+                vec![],
+            ));
+            let intents =
+                QuantityAlterationIntent::for_order(&sale.products[0], TransactionType::Out, None);
+
+            rocket::tokio::spawn(async move {
+                Transaction::insert_with_stock(sale, &intents, session, &db).await
+            })
+        });
+
+        let results: Vec<_> = join_all(sales)
+            .await
+            .into_iter()
+            .map(|sale| sale.unwrap())
+            .collect();
+
+        let recorded = Transactions::find()
+            .filter(transactions::Column::TenantId.eq(session.tenant_id.clone()))
+            .count(&db)
+            .await
+            .unwrap();
+
+        Transactions::delete_many()
+            .filter(transactions::Column::TenantId.eq(session.tenant_id.clone()))
+            .exec(&db)
+            .await
+            .unwrap();
+        Products::delete_many()
+            .filter(products::Column::TenantId.eq(session.tenant_id))
+            .exec(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(results.iter().filter(|sale| sale.is_ok()).count(), 1);
+        assert_eq!(recorded, 1);
+
+        let refused = results.into_iter().find_map(Result::err).unwrap();
+        assert_eq!(refused.status(), Status::BadRequest);
+
+        let body = serde_json::to_value(refused.into_body()).unwrap();
+        assert!(body["message"].as_str().unwrap().contains("132522"));
+    }
+
+    #[test]
+    fn an_override_may_take_stock_below_zero() {
+        let mut product = kayak(1.0);
+
+        assert!(Transaction::alter_stock(&mut product, &[sale(3.0, None)], true).is_empty());
+        assert_eq!(sellable(&product), -2.0);
+    }
+}
+
+#[cfg(feature = "process")]
+mod locking {
+    use crate::common::{self, read, stock, OTHER_STORE};
+    use futures::future::join_all;
+    use open_stock::{
+        prelude::Products, products, Product, QuantityAlterationIntent, Session, Transaction,
+        TransactionType,
+    };
+    use sea_orm::{ColumnTrait, DbBackend, EntityTrait, QueryFilter, QueryTrait};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn product(sellable: f32, back_order: bool) -> Product {
+        let mut variant = common::variant("KAYAK");
+        variant["stock"] = json!([stock(OTHER_STORE, sellable)]);
+        variant["stock_information"]["back_order"] = json!(back_order);
+
+        read(common::product("KAYAK-SKU", vec![variant]))
+    }
+
+    fn sale() -> QuantityAlterationIntent {
+        QuantityAlterationIntent {
+            variant_code: "KAYAK".to_string(),
+            product_sku: "KAYAK-SKU".to_string(),
+            transaction_store_code: "001".to_string(),
+            transaction_store_id: OTHER_STORE.to_string(),
+            transaction_type: TransactionType::Out,
+            quantity_to_transact: 1.0,
+            unit: None,
+            reservation: None,
+        }
+    }
+
+    /// Sells one unit `sales` times from the product, one after another, returning
+    /// how many succeeded and the stock remaining.
+    fn sell_in_turn(mut product: Product, sales: usize) -> (usize, f32) {
+        let succeeded = (0..sales)
+            .filter(|_| Transaction::alter_stock(&mut product, &[sale()], false).is_empty())
+            .count();
+
+        (
+            succeeded,
+            product.variants[0].stock[0].quantity.quantity_sellable,
+        )
+    }
+
+    #[test]
+    fn stock_is_read_under_a_row_lock() {
+        let sql = Product::select_for_update("KAYAK-SKU", "TENANT")
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert!(sql.contains("`Products`.`sku` = 'KAYAK-SKU'"), "{sql}");
+        assert!(sql.contains("`Products`.`tenant_id` = 'TENANT'"), "{sql}");
+        assert!(sql.ends_with("FOR UPDATE"), "{sql}");
+    }
+
+    #[test]
+    fn only_the_stock_on_hand_is_sold() {
+        assert_eq!(sell_in_turn(product(1.0, false), 8), (1, 0.0));
+    }
+
+    #[rocket::async_test]
+    #[ignore = "requires a MySQL database named by DATABASE_URL"]
+    async fn concurrent_sales_of_the_last_unit_succeed_once() {
+        let db = common::database().await;
+        let session = Session::default_with_tenant(Uuid::new_v4().to_string());
+
+        Product::insert(product(1.0, false), session.clone(), &db)
+            .await
+            .unwrap();
+
+        let sales = (0..8).map(|_| {
+            let db = db.clone();
+            let session = session.clone();
+
+            rocket::tokio::spawn(async move {
+                Transaction::process_intents(session, &db, vec![sale()]).await
+            })
+        });
+
+        let succeeded = join_all(sales)
+            .await
+            .into_iter()
+            .filter(|sold| matches!(sold, Ok(Ok(_))))
+            .count();
+
+        let remaining = Product::fetch_by_id("KAYAK-SKU", session.clone(), &db)
+            .await
+            .unwrap();
+
+        Products::delete_many()
+            .filter(products::Column::TenantId.eq(session.tenant_id))
+            .exec(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(succeeded, 1);
+        assert_eq!(
+            remaining.variants[0].stock[0].quantity.quantity_sellable,
+            0.0
+        );
+    }
+
+    #[test]
+    fn back_orderable_stock_may_fall_below_zero() {
+        assert_eq!(sell_in_turn(product(1.0, true), 8), (8, -7.0));
+    }
+}
+
+#[cfg(feature = "process")]
+mod reservations {
+    use chrono::Utc;
+
+    use crate::common::{self, order, purchase, read, stock, transaction, STORE};
+    use open_stock::{
+        OrderStatus, Product, QuantityAlterationIntent, Reservation, Transaction, TransactionType,
+    };
+    use serde_json::json;
+
+    fn kayak() -> Product {
+        let mut variant = common::variant("KAYAK");
+        variant["stock"] = json!([stock(STORE, 5.0)]);
+
+        read(common::product("KAYAK-SKU", vec![variant]))
+    }
+
+    /// A sale of two kayaks queued to be picked, whose stock is held by `product`.
+    fn reserved_sale(product: &mut Product) -> Transaction {
+        let mut queued = order("TOR-19592", vec![purchase("KAYAK", "KAYAK-SKU", 2.0)]);
+        queued["stock_reserved"] = json!(true);
+
+        let sale: Transaction = read(transaction(
+            "2022-11-27T06:54:11.665651300Z",
+            vec![queued],
+            vec![],
+        ));
+
+        let intents = QuantityAlterationIntent::for_order(
+            &sale.products[0],
+            TransactionType::Out,
+            Some(Reservation::Reserve),
+        );
+        assert!(Transaction::alter_stock(product, &intents, false).is_empty());
+
+        sale
+    }
+
+    /// The stock on hand, reserved and available to sell.
+    fn held(product: &Product) -> (f32, f32, f32) {
+        let quantity = &product.variants[0].stock[0].quantity;
+
+        (
+            quantity.quantity_sellable,
+            quantity.quantity_reserved,
+            quantity.available(),
+        )
+    }
+
+    fn move_to(sale: &mut Transaction, product: &mut Product, status: OrderStatus) {
+        let intents = sale.apply_order_status("TOR-19592", status).unwrap();
+        assert!(Transaction::alter_stock(product, &intents, false).is_empty());
+    }
+
+    #[test]
+    fn reserving_holds_stock_on_hand() {
+        let mut product = kayak();
+        reserved_sale(&mut product);
+
+        assert_eq!(held(&product), (5.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn fulfilling_removes_the_held_stock() {
+        let mut product = kayak();
+        let mut sale = reserved_sale(&mut product);
+
+        move_to(&mut sale, &mut product, OrderStatus::Fulfilled(Utc::now()));
+
+        assert_eq!(held(&product), (3.0, 0.0, 3.0));
+        assert!(!sale.products[0].stock_reserved);
+    }
+
+    #[test]
+    fn cancelling_releases_the_held_stock() {
+        let mut product = kayak();
+        let mut sale = reserved_sale(&mut product);
+
+        move_to(
+            &mut sale,
+            &mut product,
+            OrderStatus::Failed("Cancelled".into()),
+        );
+
+        assert_eq!(held(&product), (5.0, 0.0, 5.0));
+        assert!(!sale.products[0].stock_reserved);
+    }
+}
@@ -0,0 +1,89 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod variance {
+    use crate::common::{self, location, read, stock, OTHER_STORE, STORE};
+    use open_stock::{
+        Location, StockCount, StockVariance, Stocktake, StocktakeReport, VariantInformation,
+    };
+    use serde_json::json;
+
+    fn variant(barcode: &str, sellable: f32, cost: f32) -> VariantInformation {
+        let mut variant = common::variant(barcode);
+        variant["stock"] = json!([stock(STORE, sellable)]);
+        variant["marginal_price"] = json!(cost);
+
+        read(variant)
+    }
+
+    fn store() -> Location {
+        read(location(STORE))
+    }
+
+    #[test]
+    fn over_and_under_counts_set_stock_to_the_count() {
+        let mut over = variant("KAYAK", 4.0, 250.0);
+        let mut under = variant("PADDLE", 10.0, 20.0);
+
+        let found = StockVariance::apply("KAYAK-SKU", "Kayak", &mut over, &store(), 6.0);
+        let missing = StockVariance::apply("PADDLE-SKU", "Paddle", &mut under, &store(), 7.0);
+
+        assert_eq!(found.recorded, 4.0);
+        assert_eq!(found.variance, 2.0);
+        assert_eq!(found.variance_value, 500.0);
+        assert_eq!(over.stock[0].quantity.quantity_sellable, 6.0);
+
+        assert_eq!(missing.recorded, 10.0);
+        assert_eq!(missing.variance, -3.0);
+        assert_eq!(missing.variance_value, -60.0);
+        assert_eq!(under.stock[0].quantity.quantity_sellable, 7.0);
+
+        // Shrinkage is reported ahead of the smaller surplus, however it is valued.
+        let report = StocktakeReport::new(STORE, vec![found, missing]);
+
+        assert_eq!(report.reason, "Stocktake adjustment");
+        assert_eq!(report.lines[0].variant_code, "PADDLE");
+        assert_eq!(report.lines[1].variant_code, "KAYAK");
+        assert_eq!(report.total_variance_value, 440.0);
+    }
+
+    #[test]
+    fn count_at_an_unstocked_store_adds_its_stock() {
+        let mut kayak = variant("KAYAK", 4.0, 250.0);
+        let elsewhere: Location = read(location(OTHER_STORE));
+
+        let found = StockVariance::apply("KAYAK-SKU", "Kayak", &mut kayak, &elsewhere, 3.0);
+
+        assert_eq!(found.recorded, 0.0);
+        assert_eq!(found.variance, 3.0);
+        assert_eq!(kayak.stock.len(), 2);
+        assert_eq!(kayak.stock[0].quantity.quantity_sellable, 4.0);
+    }
+
+    #[test]
+    fn recounting_a_variant_replaces_its_count() {
+        let mut stocktake: Stocktake = serde_json::from_value(json!({
+            "id": "d3f0c0b8-8d4e-4b8e-a8f4-6c2b1f1c9e11",
+            "store_id": STORE,
+            "counts": [],
+            "report": null,
+            "started_by": "e2d8d5a4-3b6c-4b7e-9f3e-9b1c6d4e2a10",
+            "started_at": "2024-01-01T00:00:00Z",
+            "applied_at": null
+        }))
+        .unwrap();
+
+        let count = |variant_code: &str, counted: f32| StockCount {
+            product_sku: "KAYAK-SKU".to_string(),
+            variant_code: variant_code.to_string(),
+            counted,
+        };
+
+        stocktake.record(vec![count("KAYAK", 4.0), count("KAYAK-RED", 1.0)]);
+        stocktake.record(vec![count("KAYAK", 6.0)]);
+
+        assert_eq!(stocktake.counts.len(), 2);
+        assert_eq!(stocktake.counts[0].counted, 6.0);
+        assert_eq!(stocktake.counts[1].counted, 1.0);
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+
+#[cfg(feature = "process")]
+mod receipt {
+    use crate::common::{self, location, product, read, stock, STORE};
+    use open_stock::{Location, Product, PurchaseOrderItem};
+    use serde_json::json;
+
+    const SUPPLIER: &str = "SUPPLIER";
+
+    fn kayak(supplier_id: Option<&str>) -> Product {
+        let mut variant = common::variant("51890723908812");
+        variant["stock"] = json!([stock(STORE, 2.0)]);
+        variant["stock_information"]["supplier_id"] = json!(supplier_id);
+        variant["stock_information"]["supplier_cost"] = json!(250.0);
+
+        read(product("SKU-KAYAK", vec![variant]))
+    }
+
+    fn line(quantity: f32, cost: f32) -> PurchaseOrderItem {
+        read(json!({
+            "product_sku": "SKU-KAYAK",
+            "product_code": "51890723908812",
+            "quantity": quantity,
+            "cost": cost
+        }))
+    }
+
+    fn store() -> Location {
+        read(location(STORE))
+    }
+
+    #[test]
+    fn receiving_a_line_records_its_cost() {
+        let mut product = kayak(Some(SUPPLIER));
+
+        line(3.0, 235.5)
+            .receive_into(&mut product, SUPPLIER, store())
+            .unwrap();
+
+        let variant = &product.variants[0];
+        assert_eq!(variant.stock[0].quantity.quantity_sellable, 5.0);
+        assert_eq!(variant.stock_information.supplier_cost, 235.5);
+        assert_eq!(variant.marginal_price, 235.5);
+    }
+
+    #[test]
+    fn another_suppliers_cost_is_not_recorded() {
+        let mut product = kayak(Some("ANOTHER"));
+
+        line(3.0, 235.5)
+            .receive_into(&mut product, SUPPLIER, store())
+            .unwrap();
+
+        let variant = &product.variants[0];
+        assert_eq!(variant.stock[0].quantity.quantity_sellable, 5.0);
+        assert_eq!(variant.stock_information.supplier_cost, 250.0);
+        assert_eq!(variant.marginal_price, 250.0);
+    }
+
+    #[test]
+    fn unknown_variants_cannot_be_received() {
+        let mut product = kayak(Some(SUPPLIER));
+        let mut item = line(3.0, 235.5);
+        item.product_code = "UNKNOWN".into();
+
+        assert!(item.receive_into(&mut product, SUPPLIER, store()).is_err());
+        assert_eq!(product.variants[0].marginal_price, 250.0);
+    }
+}
@@ -0,0 +1,260 @@
+mod common;
+
+#[cfg(feature = "process")]
+mod soft_delete {
+    use open_stock::Transaction;
+    use sea_orm::{DbBackend, QueryTrait};
+
+    #[test]
+    fn deleted_transactions_are_not_listed_as_saved() {
+        let sql = Transaction::select_saved("TENANT")
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert!(sql.contains("`Transactions`.`deleted_at` IS NULL"), "{sql}");
+        assert!(
+            sql.contains("`Transactions`.`tenant_id` = 'TENANT'"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn deleted_transactions_may_be_restored() {
+        let sql = Transaction::select_with_deleted("TRANSACTION", "TENANT")
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert!(!sql.contains("deleted_at` IS NULL"), "{sql}");
+        assert!(sql.contains("`Transactions`.`id` = 'TRANSACTION'"), "{sql}");
+        assert!(
+            sql.contains("`Transactions`.`tenant_id` = 'TENANT'"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn only_the_tenants_transactions_may_be_purged() {
+        let sql = Transaction::delete_purged("TRANSACTION", "TENANT")
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert!(sql.starts_with("DELETE FROM `Transactions`"), "{sql}");
+        assert!(sql.contains("`Transactions`.`id` = 'TRANSACTION'"), "{sql}");
+        assert!(
+            sql.contains("`Transactions`.`tenant_id` = 'TENANT'"),
+            "{sql}"
+        );
+    }
+}
+
+#[cfg(feature = "types")]
+mod price_tiers {
+    use crate::common::{self, order, purchase, read};
+    use open_stock::{Order, RoundingMode, VariantInformation};
+    use serde_json::json;
+
+    fn kayak() -> VariantInformation {
+        let mut variant = common::variant("51890723908812");
+        variant["price_tiers"] = json!({ "wholesale": 299.99 });
+
+        read(variant)
+    }
+
+    /// A cart of two kayaks, priced for a customer of `group`.
+    fn cart(group: Option<&str>) -> Order {
+        let mut cart: Order = read(order(
+            "TOR-19592",
+            vec![purchase("51890723908812", "132522", 2.0)],
+        ));
+        cart.products[0].product_cost = kayak().price_for(group);
+
+        cart
+    }
+
+    #[test]
+    fn wholesale_customers_pay_the_tier_price() {
+        let total = cart(Some("wholesale"))
+            .breakdown(RoundingMode::HalfUp)
+            .total;
+
+        assert_eq!(total, 599.98);
+    }
+
+    #[test]
+    fn other_customers_pay_the_retail_price() {
+        for group in [None, Some("staff")] {
+            let total = cart(group).breakdown(RoundingMode::HalfUp).total;
+
+            assert_eq!(total, 799.98, "{:?}", group);
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+mod tax {
+    use crate::common::{order, payment, purchase, read};
+    use open_stock::{compute_tax, settle_tender, Order, Payment, ProductPurchase, TenantSettings};
+    use serde_json::json;
+
+    /// A cart of a kayak, rated GST at 15%, and a book, rated zero.
+    fn cart(kayak: f32, book: f32) -> Vec<Order> {
+        let mut cart: Order = read(order(
+            "TOR-19592",
+            vec![
+                purchase("KAYAK", "132522", 1.0),
+                purchase("BOOK", "132523", 1.0),
+            ],
+        ));
+        cart.products[0].product_cost = kayak;
+        cart.products[1].product_cost = book;
+
+        vec![cart]
+    }
+
+    fn settings(tax_inclusive: bool) -> TenantSettings {
+        read(json!({
+            "tax_rates": [
+                { "code": "GST", "rate": 0.15 },
+                { "code": "ZERO", "rate": 0.15, "exempt": true }
+            ],
+            "tax_inclusive": tax_inclusive
+        }))
+    }
+
+    fn tax_code(purchase: &ProductPurchase) -> String {
+        match purchase.product_code.as_str() {
+            "BOOK" => "ZERO".to_string(),
+            _ => "GST".to_string(),
+        }
+    }
+
+    #[test]
+    fn only_standard_rated_lines_are_taxed() {
+        let tax = compute_tax(&cart(100.0, 50.0), &settings(false), tax_code);
+
+        assert_eq!(tax.lines[0].tax, 15.0);
+        assert_eq!(tax.lines[1].tax, 0.0);
+        assert_eq!((tax.net, tax.total_tax, tax.gross), (150.0, 15.0, 165.0));
+    }
+
+    #[test]
+    fn unknown_codes_are_not_taxed() {
+        let tax = compute_tax(&cart(100.0, 50.0), &settings(false), |_| "GST-B".into());
+
+        assert_eq!((tax.net, tax.total_tax, tax.gross), (150.0, 0.0, 150.0));
+    }
+
+    fn card(amount: f32) -> Vec<Payment> {
+        vec![read(payment(json!("Card"), amount))]
+    }
+
+    #[test]
+    fn inclusive_prices_contain_their_tax() {
+        let tax = compute_tax(&cart(115.0, 50.0), &settings(true), tax_code);
+
+        assert_eq!((tax.net, tax.total_tax, tax.gross), (150.0, 15.0, 165.0));
+        assert_eq!(settle_tender(&card(165.0), tax.gross).ok(), Some(0.0));
+    }
+
+    #[test]
+    fn exclusive_prices_are_taxed_on_top() {
+        let tax = compute_tax(&cart(115.0, 50.0), &settings(false), tax_code);
+
+        assert_eq!((tax.net, tax.total_tax, tax.gross), (165.0, 17.25, 182.25));
+        assert_eq!(settle_tender(&card(182.25), tax.gross).ok(), Some(0.0));
+        assert!(settle_tender(&card(165.0), tax.gross).is_err());
+    }
+}
+
+#[cfg(feature = "process")]
+mod rounding {
+    use crate::common::{order, payment, purchase, read};
+    use open_stock::{settle_tender, Order, Payment, RoundingMode};
+    use serde_json::json;
+
+    /// A cart whose lines come to fractions of a cent.
+    fn cart() -> Order {
+        let mut cart: Order = read(order(
+            "TOR-19592",
+            vec![
+                purchase("BOLT", "132522", 1.0),
+                purchase("NUT", "132523", 1.0),
+            ],
+        ));
+        cart.products[0].product_cost = 1.125;
+        cart.products[1].product_cost = 0.129;
+
+        cart
+    }
+
+    #[test]
+    fn each_mode_rounds_the_cart_its_own_way() {
+        for (mode, total) in [
+            (RoundingMode::HalfUp, 1.26),
+            (RoundingMode::HalfEven, 1.25),
+            (RoundingMode::Down, 1.24),
+        ] {
+            assert_eq!(cart().breakdown(mode).total, total, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn payments_settle_the_cart_under_any_mode() {
+        let paid: Vec<Payment> = vec![read(payment(json!("Card"), 1.26))];
+
+        for mode in [
+            RoundingMode::HalfUp,
+            RoundingMode::HalfEven,
+            RoundingMode::Down,
+        ] {
+            let total = cart().breakdown(mode).total;
+
+            assert_eq!(settle_tender(&paid, total).ok(), Some(0.0), "{:?}", mode);
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+mod tender {
+    use crate::common::{payment, read};
+    use open_stock::{settle_tender, Payment};
+    use rocket::http::Status;
+    use serde_json::json;
+
+    fn paid(payments: &[(&str, f32)]) -> Vec<Payment> {
+        payments
+            .iter()
+            .map(|(method, amount)| read(payment(json!(method), *amount)))
+            .collect()
+    }
+
+    #[test]
+    fn cash_overpayment_gives_change() {
+        let payments = paid(&[("Card", 20.0), ("Cash", 50.0)]);
+
+        assert_eq!(settle_tender(&payments, 59.5).ok(), Some(10.5));
+    }
+
+    #[test]
+    fn change_is_not_given_from_a_card() {
+        let payments = paid(&[("Card", 70.0), ("Cash", 5.0)]);
+        let error = settle_tender(&payments, 59.5).unwrap_err();
+
+        assert_eq!(error.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn negative_payments_on_a_sale_are_invalid() {
+        let payments = paid(&[("Cash", 80.0), ("Card", -20.5)]);
+        let error = settle_tender(&payments, 59.5).unwrap_err();
+
+        assert_eq!(error.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn short_payments_are_refused() {
+        let payments = paid(&[("Cash", 50.0)]);
+
+        assert!(settle_tender(&payments, 59.5).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+mod common;
+
+#[cfg(feature = "types")]
+mod rejected_fields {
+    use crate::common::{self, contact};
+    use open_stock::{
+        BinAssignment, BulkOrderStatusUpdate, ContactInformation, NoteInput, NoteUpdate,
+        ProductPurchase, SalespersonReassignment, SupplierInput,
+    };
+    use serde_json::{json, Value};
+    use validator::Validate;
+
+    fn supplier() -> Value {
+        json!({
+            "name": { "first": "Torpedo7", "middle": "", "last": "" },
+            "contact": contact(),
+            "transaction_history": []
+        })
+    }
+
+    fn purchase() -> Value {
+        common::purchase("54897443288214", "", 1.0)
+    }
+
+    /// The fields of `value`, read as `T`, which fail validation.
+    fn rejected<T: Validate + serde::de::DeserializeOwned>(value: Value) -> Vec<String> {
+        let input: T = serde_json::from_value(value).expect("well-formed input");
+
+        match input.validate() {
+            Ok(_) => vec![],
+            Err(errors) => errors.errors().keys().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_input() {
+        assert!(rejected::<SupplierInput>(supplier()).is_empty());
+        assert!(rejected::<ContactInformation>(contact()).is_empty());
+        assert!(rejected::<ProductPurchase>(purchase()).is_empty());
+    }
+
+    #[test]
+    fn accepts_absent_contact_details() {
+        let mut input = contact();
+        input["mobile"]["number"] = json!("");
+        input["email"]["full"] = json!("");
+
+        assert!(rejected::<ContactInformation>(input).is_empty());
+    }
+
+    #[test]
+    fn rejects_blank_supplier_name() {
+        let mut input = supplier();
+        input["name"]["first"] = json!("  ");
+
+        assert_eq!(rejected::<SupplierInput>(input), vec!["name"]);
+    }
+
+    #[test]
+    fn rejects_malformed_mobile() {
+        let mut input = contact();
+        input["mobile"]["number"] = json!("12");
+
+        assert_eq!(rejected::<ContactInformation>(input), vec!["mobile"]);
+    }
+
+    #[test]
+    fn rejects_malformed_email() {
+        let mut input = contact();
+        input["email"]["full"] = json!("order-at-torpedo7");
+
+        assert_eq!(rejected::<ContactInformation>(input), vec!["email"]);
+    }
+
+    #[test]
+    fn rejects_malformed_po_code() {
+        let mut input = contact();
+        input["address"]["po_code"] = json!("10600");
+
+        assert_eq!(rejected::<ContactInformation>(input), vec!["address"]);
+    }
+
+    #[test]
+    fn rejects_negative_cost() {
+        let mut input = purchase();
+        input["product_cost"] = json!(-1.0);
+
+        assert_eq!(rejected::<ProductPurchase>(input), vec!["product_cost"]);
+    }
+
+    #[test]
+    fn rejects_blank_reassignment() {
+        let input = json!({ "employee_id": "", "reason": "" });
+        let mut fields = rejected::<SalespersonReassignment>(input);
+        fields.sort();
+
+        assert_eq!(fields, vec!["employee_id", "reason"]);
+    }
+
+    #[test]
+    fn rejects_empty_bulk_status_update() {
+        let status = json!({ "type": "processing", "value": "2024-01-01T00:00:00Z" });
+        let input = json!({ "references": [], "status": status });
+
+        assert_eq!(rejected::<BulkOrderStatusUpdate>(input), vec!["references"]);
+
+        let references = (0..101).map(|i| format!("#{}", i)).collect::<Vec<_>>();
+        let input = json!({ "references": references, "status": status });
+
+        assert_eq!(rejected::<BulkOrderStatusUpdate>(input), vec!["references"]);
+    }
+
+    #[test]
+    fn rejects_blank_bin() {
+        let input = json!({ "store_id": common::STORE, "bin_location": "" });
+
+        assert_eq!(rejected::<BinAssignment>(input), vec!["bin_location"]);
+    }
+
+    #[test]
+    fn rejects_blank_notes() {
+        assert!(rejected::<NoteInput>(json!({ "message": "Left at the door" })).is_empty());
+
+        assert_eq!(
+            rejected::<NoteInput>(json!({ "message": "" })),
+            vec!["message"]
+        );
+        assert_eq!(
+            rejected::<NoteInput>(json!({ "message": " \n " })),
+            vec!["message"]
+        );
+        assert_eq!(
+            rejected::<NoteUpdate>(json!({ "message": "\t" })),
+            vec!["message"]
+        );
+    }
+}
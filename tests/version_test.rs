@@ -0,0 +1,26 @@
+#[cfg(feature = "process")]
+mod stale_updates {
+    use open_stock::entities::supplier;
+    use open_stock::Supplier;
+    use sea_orm::{ActiveValue::Set, DbBackend, QueryTrait};
+
+    fn renamed(name: &str) -> supplier::ActiveModel {
+        supplier::ActiveModel {
+            name: Set(serde_json::json!({ "first": name, "middle": "", "last": "" })),
+            ..Default::default()
+        }
+    }
+
+    /// Of two edits reading version 3, the first written moves the supplier to
+    /// version 4, such that the second matches no row and is refused as a conflict.
+    #[test]
+    fn updates_match_only_the_version_read() {
+        let sql = Supplier::update_at_version(renamed("Torpedo7"), 3, "SUPPLIER", "TENANT")
+            .build(DbBackend::MySql)
+            .to_string();
+
+        assert!(sql.contains("`version` = 4"), "{sql}");
+        assert!(sql.ends_with("`Supplier`.`version` = 3"), "{sql}");
+        assert!(sql.contains("`Supplier`.`tenant_id` = 'TENANT'"), "{sql}");
+    }
+}